@@ -1,38 +1,141 @@
 //! # EVO System Supervisor (Watchdog)
 //!
-//! Spawns HAL→CU in order, monitors via waitpid, restarts with exponential
-//! backoff, and performs graceful shutdown with SHM cleanup.
+//! Spawns the modules listed in `config.toml` in dependency order,
+//! monitors via waitpid, restarts them per their restart policy with
+//! exponential backoff, and performs graceful shutdown with SHM cleanup.
 //!
 //! # Usage
 //!
 //! ```bash
 //! evo --config-dir config/
 //! evo --config-dir config/ --verbose
+//! evo --config-dir config/ --instance b   # second stack, segments `evo-b_*`
+//! evo ctl status                           # query the running supervisor
 //! ```
 //!
 //! # Startup sequence
 //!
-//! 1. Load `config.toml` → `WatchdogConfig`
-//! 2. Clean up orphan SHM segments (`/dev/shm/evo_*`)
-//! 3. Spawn HAL (`evo_hal --config-dir <DIR> --simulate`)
-//! 4. Wait for `evo_hal_cu` segment with heartbeat > 0
-//! 5. Spawn CU (`evo_control_unit --config-dir <DIR>`)
-//! 6. Enter monitoring loop (waitpid + optional heartbeat check)
+//! 1. Load `config.toml` → `WatchdogConfig` + `[[modules]]`
+//! 2. Clean up orphan SHM segments of this instance (`/dev/shm/evo_*`)
+//! 3. For each enabled module in dependency order: spawn
+//!    `<binary> <args> [--instance <ID>]`, then wait for its
+//!    `ready_segment` with heartbeat > 0 (if any)
+//! 4. Enter monitoring loop (waitpid + outbound heartbeat check)
+//!
+//! Without `[[modules]]` the list is HAL (ready on `evo_hal_cu`) then CU
+//! (ready on `evo_cu_hal`).
+//! `--simulate` is forwarded to `evo_hal`.
+//!
+//! ```toml
+//! [[modules]]
+//! name = "re"
+//! binary = "evo_recipe_executor"
+//! depends_on = ["cu"]
+//! ready_segment = "re_cu"
+//! ```
 //!
 //! # Shutdown
 //!
-//! On SIGTERM/SIGINT: send SIGTERM to CU first, then HAL (reverse order).
+//! On SIGTERM/SIGINT: send SIGTERM to each module in reverse start order.
 //! Wait up to `sigterm_timeout_s`, then escalate to SIGKILL.
 //! Clean up all `evo_*` SHM segments.
+//!
+//! # Restarts
+//!
+//! A module that exits, hangs or is not ready in time is handled by its
+//! `restart` policy:
+//!
+//! | `restart`            | Restarted after                      | Also restarted              |
+//! |----------------------|--------------------------------------|-----------------------------|
+//! | `always`             | any exit                             | `restart_with` modules      |
+//! | `on-failure`         | non-zero exit, signal, hang, timeout | `restart_with` modules      |
+//! | `never`              | — (stays down until `evo ctl start`) | —                           |
+//! | `restart-dependents` | any exit (default)                   | `restart_with` + dependents |
+//!
+//! Modules outside the restart set keep running, e.g. MQTT restarts
+//! without touching the HAL/CU pair. The restart waits for the module's
+//! backoff (doubling from `initial_backoff_ms` to `max_backoff_s` while
+//! it fails within `stable_run_s`) and for its dependencies to run.
+//!
+//! Each module may restart `max_restarts` times within
+//! `restart_window_s`. One more failure of a module with
+//! `fatal_on_exhaustion` (default: HAL and CU) puts the supervisor in its
+//! fatal state: all modules are stopped and the namespace cleaned up, so
+//! no stale commands reach the hardware; nothing restarts until
+//! `evo ctl reload`, and `evo ctl status` reports the reason. The
+//! supervisor exits non-zero when shut down in that state. Other modules
+//! (the liaisons) are only left down as failed until `evo ctl start`; the
+//! RT pair keeps running.
+//!
+//! # Hang detection
+//!
+//! For modules running a managed binary (`ManagedModule`), the supervisor
+//! probes the heartbeats of the segments the module writes via
+//! `SegmentDiscovery`. If none advances for `hang_timeout_s` (per module,
+//! default `watchdog.hang_timeout_s`; 0 disables), the module is reported
+//! `HealthStatus::Stale`, killed (SIGTERM, then SIGKILL) and handled like
+//! a crash. Disable it for stacks driven in lockstep, whose heartbeats
+//! pause between steps.
+//!
+//! # Post-mortem bundles
+//!
+//! Before a failed module's restart set is stopped and its dead segments
+//! unlinked, the supervisor writes a bundle to `postmortem_dir`
+//! (`<UTC time>_<namespace>_<module>/`): `report.json` with the failure,
+//! exit code or signal and the decoded segment headers, raw copies of
+//! the namespace's `/dev/shm` files, the last lines of the module's
+//! stdout/stderr, and a copy of the config directory. A hung module is
+//! captured while still running. Only the newest `postmortem_keep`
+//! bundles are kept (0 disables them); clean exits leave none. Module
+//! output is piped through the supervisor for this and echoed unchanged.
+//!
+//! # Instances
+//!
+//! `--instance <ID>` (or `instance` in `config.toml`) selects the SHM
+//! namespace: segments are named `evo-<ID>_*`, the ID is forwarded to
+//! every child, and orphan/shutdown cleanup only touches that namespace,
+//! so several supervisors can run side by side.
+//!
+//! # Control socket
+//!
+//! The supervisor serves a Unix-domain socket (`/tmp/evo_ctl.sock`,
+//! `/tmp/evo-<ID>_ctl.sock` for an instance; `--socket` overrides it)
+//! polled with the monitoring loop. `evo ctl` talks to it:
+//!
+//! ```bash
+//! evo ctl status            # PID, uptime, restart count, health per module
+//! evo ctl --json status     # same, as JSON for scripts
+//! evo ctl stop cu           # stop cu and its dependents; kept stopped
+//! evo ctl start cu          # start cu (and stopped dependencies)
+//! evo ctl restart hal       # restart hal with its restart set
+//! evo ctl reload            # re-read config.toml and restart all modules
+//! evo ctl shutdown          # same as SIGTERM
+//! ```
+//!
+//! `reload` keeps the running stack if the new configuration is invalid,
+//! and leaves the fatal state otherwise.
+//! The SHM namespace is fixed for the supervisor's lifetime.
+
+mod restart;
 
-use clap::Parser;
-use evo_common::config::{load_config_dir, WatchdogConfig};
+use clap::{Parser, Subcommand};
+use evo_common::config::{load_config_dir, ModuleConfig, WatchdogConfig};
+use evo_common::shm::p2p::{SegmentDiscovery, ShmNamespace};
+use evo_common::watchdog::control::{
+    self, ControlSocket, CtlRequest, CtlResponse, ModuleState, ModuleStatus, SupervisorStatus,
+};
+use evo_common::watchdog::postmortem::{CrashReport, OutputTail, PostMortem, OUTPUT_TAIL_LINES};
+use evo_common::watchdog::{
+    HealthStatus, HeartbeatProbe, ManagedModule, Watchdog, WatchdogError,
+};
 use nix::sys::signal::{self, Signal};
 use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 use nix::unistd::Pid;
+use restart::{Decision, Failure, RestartBudget};
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
-use std::path::PathBuf;
-use std::process::{Child, Command};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn, Level};
@@ -43,13 +146,22 @@ use tracing_subscriber::EnvFilter;
 #[command(name = "evo")]
 #[command(author = "RTS007")]
 #[command(version)]
-#[command(about = "EVO System Supervisor: spawns, monitors, and restarts HAL + CU")]
+#[command(about = "EVO System Supervisor: spawns, monitors, and restarts the EVO modules")]
 struct Args {
     /// Path to unified config directory.
-    #[arg(long, value_name = "DIR", default_value = "config")]
+    #[arg(long, value_name = "DIR", default_value = "config", global = true)]
     config_dir: PathBuf,
 
-    /// Force simulation mode for HAL.
+    /// EVO instance id selecting the SHM namespace (overrides `instance`
+    /// in config.toml; default: `evo_*` segments).
+    #[arg(long, value_name = "ID", global = true)]
+    instance: Option<String>,
+
+    /// Control socket path (default: derived from the SHM namespace).
+    #[arg(long, value_name = "PATH", global = true)]
+    socket: Option<PathBuf>,
+
+    /// Force simulation mode for HAL (`--simulate` to `evo_hal`).
     #[arg(short = 's', long)]
     simulate: bool,
 
@@ -60,6 +172,51 @@ struct Args {
     /// Output logs in JSON format.
     #[arg(long)]
     json: bool,
+
+    #[command(subcommand)]
+    command: Option<Cmd>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Send a command to the running supervisor.
+    Ctl {
+        /// Print the supervisor's JSON response.
+        #[arg(long)]
+        json: bool,
+
+        #[command(subcommand)]
+        action: CtlAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CtlAction {
+    /// Show PID, uptime, restart count and health of each module.
+    Status,
+    /// Start a stopped module (and its stopped dependencies).
+    Start { module: String },
+    /// Stop a module and the modules depending on it.
+    Stop { module: String },
+    /// Restart a module and its running dependents.
+    Restart { module: String },
+    /// Re-read the config directory and restart all modules with it.
+    Reload,
+    /// Stop all modules and exit the supervisor.
+    Shutdown,
+}
+
+impl CtlAction {
+    fn request(&self) -> CtlRequest {
+        match self {
+            Self::Status => CtlRequest::Status,
+            Self::Start { module } => CtlRequest::Start { module: module.clone() },
+            Self::Stop { module } => CtlRequest::Stop { module: module.clone() },
+            Self::Restart { module } => CtlRequest::Restart { module: module.clone() },
+            Self::Reload => CtlRequest::Reload,
+            Self::Shutdown => CtlRequest::Shutdown,
+        }
+    }
 }
 
 // ─── Signal handling ────────────────────────────────────────────────
@@ -83,6 +240,9 @@ extern "C" fn signal_handler(_sig: libc::c_int) {
 
 fn main() {
     let args = Args::parse();
+    if let Some(Cmd::Ctl { json, action }) = &args.command {
+        std::process::exit(run_ctl(&args, *json, action));
+    }
     setup_tracing(&args);
 
     info!("EVO System Supervisor v{} starting...", env!("CARGO_PKG_VERSION"));
@@ -100,110 +260,400 @@ fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
     // 1. Load config.
     let full_config = load_config_dir(&args.config_dir)?;
     let wd = &full_config.system.watchdog;
-    info!("Watchdog config: max_restarts={}, backoff={}ms→{}s, hal_ready_timeout={}s",
-        wd.max_restarts, wd.initial_backoff_ms, wd.max_backoff_s, wd.hal_ready_timeout_s);
+    info!("Watchdog config: max_restarts={} per {}s, backoff={}ms→{}s, hal_ready_timeout={}s, hang_timeout={}s",
+        wd.max_restarts, wd.restart_window_s, wd.initial_backoff_ms, wd.max_backoff_s,
+        wd.hal_ready_timeout_s, wd.hang_timeout_s);
+    let ns = match args.instance.as_deref() {
+        Some(id) => ShmNamespace::new(id)?,
+        None => full_config.system.shm_namespace()?,
+    };
+    info!("SHM namespace: {ns} (segments /dev/shm/{}*)", ns.file_prefix());
 
-    // 2. Clean up orphan SHM segments.
-    cleanup_orphan_shm();
+    let order = full_config.system.start_order()?;
+    let names: Vec<&str> = order.iter().map(|m| m.name.as_str()).collect();
+    info!("Modules (start order): {}", names.join(" → "));
 
-    // 3. Enter the supervisor loop.
-    let mut restart_count: u32 = 0;
-    let mut backoff_ms: u64 = wd.initial_backoff_ms;
+    let socket = args.socket.clone().unwrap_or_else(|| control::socket_path(&ns));
+    let ctl = ControlSocket::bind(&socket)?;
+    info!("Control socket: {}", socket.display());
 
+    let mut sup = Supervisor {
+        modules: order.into_iter().cloned().collect(),
+        config_dir: args.config_dir.clone(),
+        simulate: args.simulate,
+        ns: ns.clone(),
+        wd: wd.clone(),
+        running: Vec::new(),
+        started: Instant::now(),
+        spawns: HashMap::new(),
+        stopped: HashSet::new(),
+        failed: HashMap::new(),
+        restart_at: HashMap::new(),
+        budgets: HashMap::new(),
+        last_failure: HashMap::new(),
+        fatal: None,
+        ctl: Some(ctl),
+    };
+
+    // 2. Clean up orphan SHM segments.
+    cleanup_orphan_shm(&ns);
+
+    // 3. Start the modules in dependency order and supervise them.
     loop {
-        if SHUTDOWN.load(Ordering::SeqCst) {
-            info!("Shutdown requested before spawn");
-            return Ok(());
+        info!("Starting {} module(s)", sup.modules.len());
+        match monitor_children(&mut sup) {
+            MonitorResult::Shutdown => {
+                info!("Shutdown signal received, stopping modules...");
+                sup.shutdown_all()?;
+                return match sup.fatal.take() {
+                    Some(reason) => Err(format!("shut down in fatal state: {reason}").into()),
+                    None => Ok(()),
+                };
+            }
+            MonitorResult::Reload { modules, wd } => {
+                info!("Reloading config, stopping modules...");
+                sup.stop_all();
+                cleanup_all_shm(&ns);
+                sup.reset(modules, wd);
+            }
         }
+    }
+}
+
+// ─── Supervisor (Watchdog) ─────────────────────────────────────────
 
-        // Spawn HAL.
-        info!("Spawning HAL (attempt {})", restart_count + 1);
-        let mut hal = spawn_hal(&args.config_dir, args.simulate)?;
-        let hal_pid = hal.id();
-        info!("HAL spawned (PID={})", hal_pid);
-
-        // Wait for HAL to create evo_hal_cu segment.
-        if !wait_for_segment("hal_cu", wd.hal_ready_timeout_s) {
-            warn!("HAL did not create evo_hal_cu within {}s, killing", wd.hal_ready_timeout_s);
-            let _ = terminate_child(&mut hal, wd.sigterm_timeout_s);
-            restart_count += 1;
-            if restart_count >= wd.max_restarts {
-                error!("CRITICAL: max restarts ({}) exhausted", wd.max_restarts);
-                return Err("max restarts exhausted".into());
+/// A spawned module.
+struct RunningModule {
+    config: ModuleConfig,
+    child: Child,
+    /// Exit status once reaped by `waitpid`.
+    exit: Option<Option<i32>>,
+    /// Outbound heartbeat tracker, if hang detection applies.
+    probe: Option<HeartbeatProbe>,
+    hang_timeout: Duration,
+    /// Spawn time, for uptime.
+    started: Instant,
+    /// Recent stdout/stderr lines, for post-mortem bundles.
+    output: OutputTail,
+}
+
+impl RunningModule {
+    fn health(&self, now: Instant) -> HealthStatus {
+        if let Some(exit_code) = self.exit {
+            return HealthStatus::Dead { exit_code };
+        }
+        if let Some(probe) = &self.probe {
+            let age = probe.age(now);
+            if age > self.hang_timeout {
+                return HealthStatus::Stale { age_secs: age.as_secs() };
             }
-            std::thread::sleep(Duration::from_millis(backoff_ms));
-            backoff_ms = (backoff_ms * 2).min(wd.max_backoff_s * 1000);
-            continue;
         }
-        info!("HAL ready (evo_hal_cu segment active)");
+        HealthStatus::Healthy
+    }
+}
 
-        // Spawn CU.
-        info!("Spawning CU");
-        let mut cu = spawn_cu(&args.config_dir)?;
-        let cu_pid = cu.id();
-        info!("CU spawned (PID={})", cu_pid);
+enum StartError {
+    /// The binary could not be spawned (fatal).
+    Spawn(String),
+    /// A module did not become ready in time (restart).
+    NotReady(String),
+}
 
-        // Monitor both processes.
-        let stable_start = Instant::now();
-        let result = monitor_children(&mut hal, &mut cu, wd);
+impl StartError {
+    fn into_message(self) -> String {
+        match self {
+            Self::Spawn(e) | Self::NotReady(e) => e,
+        }
+    }
+}
 
-        match result {
-            MonitorResult::Shutdown => {
-                info!("Shutdown signal received, stopping children...");
-                graceful_shutdown(&mut cu, &mut hal, wd.sigterm_timeout_s);
-                cleanup_all_shm();
-                return Ok(());
+/// Process supervisor for the configured module list.
+struct Supervisor {
+    /// Enabled modules in start order.
+    modules: Vec<ModuleConfig>,
+    config_dir: PathBuf,
+    simulate: bool,
+    ns: ShmNamespace,
+    wd: WatchdogConfig,
+    /// Spawned modules in start order.
+    running: Vec<RunningModule>,
+    /// Supervisor start time, for uptime.
+    started: Instant,
+    /// Spawns per module name (restart count + 1).
+    spawns: HashMap<String, u32>,
+    /// Modules stopped via `evo ctl stop` or by a clean exit under
+    /// `on-failure`; not restarted.
+    stopped: HashSet<String>,
+    /// Modules left down by their restart policy, with the failure.
+    failed: HashMap<String, String>,
+    /// Earliest restart time of modules waiting out a backoff.
+    restart_at: HashMap<String, Instant>,
+    /// Restart budget and backoff per module name.
+    budgets: HashMap<String, RestartBudget>,
+    /// Most recent exit, hang or readiness timeout per module name.
+    last_failure: HashMap<String, String>,
+    /// Why the supervisor stopped everything and restarts nothing.
+    fatal: Option<String>,
+    /// `evo ctl` socket, polled by the monitoring loop.
+    ctl: Option<ControlSocket>,
+}
+
+impl Supervisor {
+    /// Spawn `module` and set up its heartbeat probe.
+    fn spawn(&mut self, module: &ModuleConfig, config_dir: &Path) -> Result<RunningModule, String> {
+        let mut cmd = Command::new(resolve_bin_path(&module.binary));
+        cmd.args(module.resolved_args(config_dir));
+        if self.simulate && module.binary == "evo_hal" {
+            cmd.arg("--simulate");
+        }
+        if module.forward_instance {
+            instance_args(&mut cmd, &self.ns);
+        }
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("failed to spawn {}: {e}", module.binary))?;
+        info!("{} spawned (PID={})", module.name, child.id());
+        let output = OutputTail::capture(&mut child, OUTPUT_TAIL_LINES);
+        *self.spawns.entry(module.name.clone()).or_default() += 1;
+
+        let hang_timeout_s = module.hang_timeout_s.unwrap_or(self.wd.hang_timeout_s);
+        let probe = ManagedModule::from_binary(&module.binary)
+            .filter(|_| hang_timeout_s > 0.0)
+            .map(|m| HeartbeatProbe::new(m.abbrev(), Instant::now()));
+        Ok(RunningModule {
+            config: module.clone(),
+            child,
+            exit: None,
+            probe,
+            hang_timeout: Duration::from_secs_f64(hang_timeout_s),
+            started: Instant::now(),
+            output,
+        })
+    }
+
+    /// Wait for the module's `ready_segment`, if any.
+    fn wait_ready(&self, module: &ModuleConfig) -> Result<(), StartError> {
+        let Some(segment) = &module.ready_segment else {
+            return Ok(());
+        };
+        let timeout_s = self.ready_timeout_s(module);
+        if !wait_for_segment(&self.ns, segment, timeout_s) {
+            return Err(StartError::NotReady(format!(
+                "{} did not create {}{segment} within {timeout_s}s",
+                module.name,
+                self.ns.file_prefix()
+            )));
+        }
+        info!("{} ready ({}{segment} segment active)", module.name, self.ns.file_prefix());
+        Ok(())
+    }
+
+    fn ready_timeout_s(&self, module: &ModuleConfig) -> f64 {
+        module.ready_timeout_s.unwrap_or(self.wd.hal_ready_timeout_s)
+    }
+
+    /// Spawn `module` and wait for its readiness. It is no longer
+    /// considered stopped or failed, and stays in `running` if not ready.
+    fn start_one(&mut self, module: &ModuleConfig) -> Result<(), StartError> {
+        let config_dir = self.config_dir.clone();
+        self.stopped.remove(&module.name);
+        self.failed.remove(&module.name);
+        self.restart_at.remove(&module.name);
+        let running = self.spawn(module, &config_dir).map_err(StartError::Spawn)?;
+        self.insert_running(running);
+        self.wait_ready(module)
+    }
+
+    /// Start the modules of `names` that are not running, in start order
+    /// and each after the previous one is ready. Returns the started names.
+    fn start_set(&mut self, names: &HashSet<String>) -> Result<Vec<String>, StartError> {
+        let mut started = Vec::new();
+        for i in 0..self.modules.len() {
+            let module = self.modules[i].clone();
+            if !names.contains(&module.name) || self.is_running(&module.name) {
+                continue;
             }
-            MonitorResult::HalDied(status) => {
-                warn!("HAL died ({status:?}), stopping CU and restarting...");
-                let _ = terminate_child(&mut cu, wd.sigterm_timeout_s);
+            self.start_one(&module)?;
+            started.push(module.name);
+        }
+        Ok(started)
+    }
+
+    /// Stop the running modules of `names` in reverse start order.
+    /// Returns the stopped names.
+    fn stop_set(&mut self, names: &HashSet<String>) -> Vec<String> {
+        let mut stopped = Vec::new();
+        while let Some(i) = self
+            .running
+            .iter()
+            .rposition(|m| names.contains(&m.config.name))
+        {
+            let mut module = self.running.remove(i);
+            if module.exit.is_none() {
+                info!("Stopping {} (PID={})", module.config.name, module.child.id());
+                let _ = terminate_child(&mut module.child, self.wd.sigterm_timeout_s);
             }
-            MonitorResult::CuDied(status) => {
-                warn!("CU died ({status:?}), stopping HAL and restarting...");
-                let _ = terminate_child(&mut hal, wd.sigterm_timeout_s);
+            stopped.push(module.config.name);
+        }
+        SegmentDiscovery::cleanup_dead_in(&self.ns);
+        stopped
+    }
+
+    /// Restart `name` with the running modules of its restart set (see
+    /// [`restart::restart_set`]). Returns the started names.
+    fn restart(&mut self, name: &str) -> Result<Vec<String>, StartError> {
+        let names: HashSet<String> = restart::restart_set(&self.modules, name)
+            .into_iter()
+            .filter(|n| n == name || self.is_running(n))
+            .collect();
+        self.stop_set(&names);
+        self.start_set(&names)
+    }
+
+    fn is_running(&self, name: &str) -> bool {
+        self.running.iter().any(|m| m.config.name == name)
+    }
+
+    /// Add a spawned module to `running`, keeping start order.
+    fn insert_running(&mut self, module: RunningModule) {
+        let rank = |name: &str| self.modules.iter().position(|m| m.name == name);
+        let own = rank(&module.config.name);
+        let index = self
+            .running
+            .iter()
+            .position(|m| rank(&m.config.name) > own)
+            .unwrap_or(self.running.len());
+        self.running.insert(index, module);
+    }
+
+    /// Reap exited modules and probe heartbeats; the first failure found.
+    fn poll(&mut self) -> Option<(String, Failure)> {
+        for module in self.running.iter_mut().filter(|m| m.exit.is_none()) {
+            let pid = Pid::from_raw(module.child.id() as i32);
+            let (status, failure) = match waitpid(pid, Some(WaitPidFlag::WNOHANG)) {
+                Ok(WaitStatus::Exited(_, code)) => (code, Failure::Exited(code)),
+                Ok(WaitStatus::Signaled(_, sig, core_dumped)) => {
+                    (128 + sig as i32, Failure::Signaled(sig, core_dumped))
+                }
+                _ => continue,
+            };
+            module.exit = Some(Some(status));
+            return Some((module.config.name.clone(), failure));
+        }
+
+        if self.running.iter().all(|m| m.probe.is_none()) {
+            return None;
+        }
+        let segments = SegmentDiscovery::list_segments_in(&self.ns);
+        let now = Instant::now();
+        for module in &mut self.running {
+            if let Some(probe) = &mut module.probe {
+                probe.update(&segments, now);
+                let age = probe.age(now);
+                if age > module.hang_timeout {
+                    return Some((module.config.name.clone(), Failure::Hung(age)));
+                }
             }
         }
+        None
+    }
 
-        // Check if we were stable long enough to reset backoff.
-        if stable_start.elapsed() >= Duration::from_secs(wd.stable_run_s) {
-            restart_count = 0;
-            backoff_ms = wd.initial_backoff_ms;
-            debug!("Stable run reset: backoff→{}ms", backoff_ms);
-        } else {
-            restart_count += 1;
-            if restart_count >= wd.max_restarts {
-                error!("CRITICAL: max restarts ({}) exhausted", wd.max_restarts);
-                cleanup_all_shm();
-                return Err("max restarts exhausted".into());
+    /// Stop all running modules in reverse start order.
+    fn stop_all(&mut self) {
+        while let Some(mut module) = self.running.pop() {
+            if module.exit.is_some() {
+                continue;
             }
-            info!("Restart {}/{}, backoff {}ms", restart_count, wd.max_restarts, backoff_ms);
-            std::thread::sleep(Duration::from_millis(backoff_ms));
-            backoff_ms = (backoff_ms * 2).min(wd.max_backoff_s * 1000);
+            info!("Stopping {} (PID={})", module.config.name, module.child.id());
+            let _ = terminate_child(&mut module.child, self.wd.sigterm_timeout_s);
         }
+    }
 
-        cleanup_all_shm();
+    /// Adopt a reloaded module list and watchdog config, forgetting
+    /// stopped modules, budgets and the fatal state.
+    fn reset(&mut self, modules: Vec<ModuleConfig>, wd: WatchdogConfig) {
+        self.modules = modules;
+        self.wd = wd;
+        self.stopped.clear();
+        self.failed.clear();
+        self.restart_at.clear();
+        self.budgets.clear();
+        self.last_failure.clear();
+        self.fatal = None;
+    }
+
+    /// Index in `running` of the module running `module`'s binary.
+    fn find(&self, module: ManagedModule) -> Option<usize> {
+        self.running
+            .iter()
+            .position(|m| m.config.binary == module.binary())
     }
 }
 
-// ─── Process Spawning (T059) ────────────────────────────────────────
+impl Watchdog for Supervisor {
+    fn spawn_module(
+        &mut self,
+        module: ManagedModule,
+        config_dir: &Path,
+    ) -> Result<u32, WatchdogError> {
+        let config = self
+            .modules
+            .iter()
+            .find(|m| m.binary == module.binary())
+            .cloned()
+            .ok_or_else(|| WatchdogError::SpawnFailed {
+                module,
+                reason: "not an enabled module in config.toml".to_string(),
+            })?;
+        let running = self
+            .spawn(&config, config_dir)
+            .map_err(|reason| WatchdogError::SpawnFailed { module, reason })?;
+        let pid = running.child.id();
+        self.insert_running(running);
+        Ok(pid)
+    }
+
+    fn health_check(&self, module: ManagedModule) -> HealthStatus {
+        self.find(module)
+            .map_or(HealthStatus::Unknown, |i| self.running[i].health(Instant::now()))
+    }
+
+    /// Restarts the module together with the running modules of its
+    /// restart set.
+    fn restart_module(&mut self, module: ManagedModule) -> Result<u32, WatchdogError> {
+        let index = self
+            .find(module)
+            .ok_or_else(|| WatchdogError::Other(format!("{module:?} is not running")))?;
+        let config = self.running[index].config.clone();
+        self.restart(&config.name).map_err(|e| match e {
+            StartError::Spawn(reason) => WatchdogError::SpawnFailed { module, reason },
+            StartError::NotReady(_) => WatchdogError::ReadyTimeout {
+                module,
+                timeout_s: self.ready_timeout_s(&config),
+            },
+        })?;
+        self.find(module)
+            .map(|i| self.running[i].child.id())
+            .ok_or_else(|| WatchdogError::Other(format!("{module:?} is not running")))
+    }
 
-fn spawn_hal(config_dir: &PathBuf, simulate: bool) -> Result<Child, Box<dyn std::error::Error>> {
-    let mut cmd = Command::new(resolve_bin_path("evo_hal"));
-    cmd.arg("--config-dir").arg(config_dir);
-    if simulate {
-        cmd.arg("--simulate");
+    fn shutdown_all(&mut self) -> Result<(), WatchdogError> {
+        self.stop_all();
+        cleanup_all_shm(&self.ns);
+        Ok(())
     }
-    let child = cmd.spawn().map_err(|e| format!("failed to spawn evo_hal: {e}"))?;
-    Ok(child)
 }
 
-fn spawn_cu(config_dir: &PathBuf) -> Result<Child, Box<dyn std::error::Error>> {
-    let child = Command::new(resolve_bin_path("evo_control_unit"))
-        .arg("--config-dir")
-        .arg(config_dir)
-        .spawn()
-        .map_err(|e| format!("failed to spawn evo_control_unit: {e}"))?;
-    Ok(child)
+// ─── Process Spawning (T059) ────────────────────────────────────────
+
+/// Forward the instance id so children use the supervisor's namespace
+/// even when it came from the command line rather than `config.toml`.
+fn instance_args(cmd: &mut Command, ns: &ShmNamespace) {
+    if let Some(id) = ns.instance() {
+        cmd.arg("--instance").arg(id);
+    }
 }
 
 /// Resolve child executable path.
@@ -225,8 +675,8 @@ fn resolve_bin_path(bin_name: &str) -> OsString {
 // ─── Ordered Startup (T060) ────────────────────────────────────────
 
 /// Wait for an SHM segment to appear with heartbeat > 0.
-fn wait_for_segment(segment_name: &str, timeout_s: f64) -> bool {
-    let path = format!("/dev/shm/evo_{segment_name}");
+fn wait_for_segment(ns: &ShmNamespace, segment_name: &str, timeout_s: f64) -> bool {
+    let path = format!("/dev/shm/{}{segment_name}", ns.file_prefix());
     let deadline = Instant::now() + Duration::from_secs_f64(timeout_s);
 
     while Instant::now() < deadline {
@@ -264,61 +714,424 @@ fn check_heartbeat(path: &str) -> bool {
 
 enum MonitorResult {
     Shutdown,
-    HalDied(Option<i32>),
-    CuDied(Option<i32>),
+    /// `evo ctl reload` with a valid new configuration.
+    Reload {
+        modules: Vec<ModuleConfig>,
+        wd: WatchdogConfig,
+    },
 }
 
-fn monitor_children(hal: &mut Child, cu: &mut Child, _wd: &WatchdogConfig) -> MonitorResult {
-    let hal_pid = Pid::from_raw(hal.id() as i32);
-    let cu_pid = Pid::from_raw(cu.id() as i32);
-
+fn monitor_children(sup: &mut Supervisor) -> MonitorResult {
     loop {
         if SHUTDOWN.load(Ordering::SeqCst) {
             return MonitorResult::Shutdown;
         }
+        sup.reconcile();
+        while let Some((name, failure)) = sup.poll() {
+            sup.handle_failure(&name, failure);
+        }
+        if let Some(result) = sup.serve_ctl() {
+            return result;
+        }
 
-        // Check HAL.
-        match waitpid(hal_pid, Some(WaitPidFlag::WNOHANG)) {
-            Ok(WaitStatus::Exited(_, code)) => {
-                return MonitorResult::HalDied(Some(code));
+        // Sleep between polls (100ms).
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+// ─── Control Socket ─────────────────────────────────────────────────
+
+/// Bound on reading a request / writing a reply on the server side.
+const CTL_IO_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Client wait for a reply; `start`/`restart` wait for module readiness.
+const CTL_REPLY_TIMEOUT: Duration = Duration::from_secs(300);
+
+impl Supervisor {
+    /// Answer pending `evo ctl` requests. Returns a result for requests
+    /// that end the current monitoring run (shutdown, reload).
+    fn serve_ctl(&mut self) -> Option<MonitorResult> {
+        while let Some((request, conn)) = self.ctl.as_ref()?.accept(CTL_IO_TIMEOUT) {
+            info!("Control request: {request:?}");
+            let (response, result) = self.handle(request);
+            if let CtlResponse::Error { message } = &response {
+                warn!("Control request failed: {message}");
             }
-            Ok(WaitStatus::Signaled(_, sig, _)) => {
-                return MonitorResult::HalDied(Some(128 + sig as i32));
+            conn.reply(&response);
+            if result.is_some() {
+                return result;
             }
-            _ => {}
         }
+        None
+    }
 
-        // Check CU.
-        match waitpid(cu_pid, Some(WaitPidFlag::WNOHANG)) {
-            Ok(WaitStatus::Exited(_, code)) => {
-                return MonitorResult::CuDied(Some(code));
+    fn handle(&mut self, request: CtlRequest) -> (CtlResponse, Option<MonitorResult>) {
+        let result = match request {
+            CtlRequest::Status => return (CtlResponse::Status(self.status()), None),
+            CtlRequest::Shutdown => {
+                let response = CtlResponse::Ok {
+                    message: "shutting down".to_string(),
+                };
+                return (response, Some(MonitorResult::Shutdown));
             }
-            Ok(WaitStatus::Signaled(_, sig, _)) => {
-                return MonitorResult::CuDied(Some(128 + sig as i32));
+            CtlRequest::Reload => match self.load_modules() {
+                Ok((modules, wd)) => {
+                    let response = CtlResponse::Ok {
+                        message: format!("reloading {} module(s)", modules.len()),
+                    };
+                    return (response, Some(MonitorResult::Reload { modules, wd }));
+                }
+                Err(e) => Err(e),
+            },
+            CtlRequest::Start { .. } | CtlRequest::Stop { .. } | CtlRequest::Restart { .. }
+                if self.fatal.is_some() =>
+            {
+                Err(format!(
+                    "fatal state: {}; reload to recover",
+                    self.fatal.as_deref().unwrap_or_default()
+                ))
             }
-            _ => {}
+            CtlRequest::Start { module } => self.ctl_start(&module),
+            CtlRequest::Stop { module } => self.ctl_stop(&module),
+            CtlRequest::Restart { module } => self.ctl_restart(&module),
+        };
+        let response = match result {
+            Ok(message) => CtlResponse::Ok { message },
+            Err(message) => CtlResponse::Error { message },
+        };
+        (response, None)
+    }
+
+    fn status(&self) -> SupervisorStatus {
+        let now = Instant::now();
+        let modules = self
+            .modules
+            .iter()
+            .map(|m| {
+                let running = self.running.iter().find(|r| r.config.name == m.name);
+                let state = if running.is_some() {
+                    ModuleState::Running
+                } else if self.fatal.is_some() || self.failed.contains_key(&m.name) {
+                    ModuleState::Failed
+                } else if self.stopped.contains(&m.name) {
+                    ModuleState::Stopped
+                } else {
+                    ModuleState::Pending
+                };
+                ModuleStatus {
+                    name: m.name.clone(),
+                    binary: m.binary.clone(),
+                    state,
+                    pid: running.map(|r| r.child.id()),
+                    uptime_s: running.map(|r| now.duration_since(r.started).as_secs_f64()),
+                    restarts: self.spawns.get(&m.name).map_or(0, |n| n.saturating_sub(1)),
+                    restart: m.restart,
+                    health: running.map_or(HealthStatus::Unknown, |r| r.health(now)),
+                    last_failure: self.last_failure.get(&m.name).cloned(),
+                }
+            })
+            .collect();
+        SupervisorStatus {
+            namespace: self.ns.to_string(),
+            pid: std::process::id(),
+            uptime_s: now.duration_since(self.started).as_secs_f64(),
+            fatal: self.fatal.clone(),
+            modules,
         }
+    }
 
-        // Sleep between polls (100ms).
-        std::thread::sleep(Duration::from_millis(100));
+    /// Enabled module list and watchdog settings from the config directory.
+    fn load_modules(&self) -> Result<(Vec<ModuleConfig>, WatchdogConfig), String> {
+        let config = load_config_dir(&self.config_dir)
+            .map_err(|e| format!("config not reloaded: {e}"))?;
+        let order = config
+            .system
+            .start_order()
+            .map_err(|e| format!("config not reloaded: {e}"))?;
+        Ok((
+            order.into_iter().cloned().collect(),
+            config.system.watchdog.clone(),
+        ))
+    }
+
+    fn check_module(&self, name: &str) -> Result<(), String> {
+        if self.modules.iter().any(|m| m.name == name) {
+            return Ok(());
+        }
+        let names: Vec<&str> = self.modules.iter().map(|m| m.name.as_str()).collect();
+        Err(format!("unknown module '{name}' (enabled: {})", names.join(", ")))
+    }
+
+    fn ctl_start(&mut self, name: &str) -> Result<String, String> {
+        self.check_module(name)?;
+        if self.is_running(name) {
+            return Ok(format!("{name} already running"));
+        }
+        let names = restart::dependencies(&self.modules, name);
+        let started = self.start_set(&names).map_err(StartError::into_message)?;
+        Ok(format!("started {}", started.join(", ")))
+    }
+
+    fn ctl_stop(&mut self, name: &str) -> Result<String, String> {
+        self.check_module(name)?;
+        let names = restart::dependents(&self.modules, name);
+        let stopped = self.stop_set(&names);
+        self.stopped.extend(names);
+        if stopped.is_empty() {
+            return Ok(format!("{name} already stopped"));
+        }
+        Ok(format!("stopped {}", stopped.join(", ")))
+    }
+
+    fn ctl_restart(&mut self, name: &str) -> Result<String, String> {
+        self.check_module(name)?;
+        if !self.is_running(name) {
+            return Err(format!("{name} is not running (use start)"));
+        }
+        let started = self.restart(name).map_err(StartError::into_message)?;
+        Ok(format!("restarted {}", started.join(", ")))
+    }
+}
+
+/// `evo ctl`: send one request and print the answer. Returns the exit code
+/// (0 = ok, 1 = rejected by the supervisor, 2 = no answer).
+fn run_ctl(args: &Args, json: bool, action: &CtlAction) -> i32 {
+    let socket = match &args.socket {
+        Some(path) => path.clone(),
+        None => {
+            let ns = match args.instance.as_deref() {
+                Some(id) => ShmNamespace::new(id),
+                // Without a readable config.toml, address the default namespace.
+                None => load_config_dir(&args.config_dir)
+                    .ok()
+                    .and_then(|c| c.system.shm_namespace().ok())
+                    .map_or(Ok(ShmNamespace::default()), Ok),
+            };
+            match ns {
+                Ok(ns) => control::socket_path(&ns),
+                Err(e) => {
+                    eprintln!("evo ctl: {e}");
+                    return 2;
+                }
+            }
+        }
+    };
+
+    let response = match control::send(&socket, &action.request(), CTL_REPLY_TIMEOUT) {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("evo ctl: {e}");
+            return 2;
+        }
+    };
+    if json {
+        println!("{}", response.to_json_pretty());
+    } else {
+        print_response(&response);
+    }
+    i32::from(matches!(response, CtlResponse::Error { .. }))
+}
+
+fn print_response(response: &CtlResponse) {
+    match response {
+        CtlResponse::Ok { message } => println!("{message}"),
+        CtlResponse::Error { message } => eprintln!("error: {message}"),
+        CtlResponse::Status(status) => {
+            println!(
+                "supervisor PID {} (namespace {}), up {}",
+                status.pid,
+                status.namespace,
+                format_uptime(status.uptime_s)
+            );
+            println!(
+                "{:<12} {:<8} {:>8} {:>9} {:>8}  HEALTH",
+                "MODULE", "STATE", "PID", "UPTIME", "RESTARTS"
+            );
+            for m in &status.modules {
+                let state = match m.state {
+                    ModuleState::Running => "running",
+                    ModuleState::Pending => "pending",
+                    ModuleState::Stopped => "stopped",
+                    ModuleState::Failed => "failed",
+                };
+                let health = match m.health {
+                    HealthStatus::Healthy => "healthy".to_string(),
+                    HealthStatus::Stale { age_secs } => format!("stale ({age_secs}s)"),
+                    HealthStatus::Dead { exit_code } => format!("dead ({exit_code:?})"),
+                    HealthStatus::Unknown => "-".to_string(),
+                };
+                println!(
+                    "{:<12} {:<8} {:>8} {:>9} {:>8}  {health}",
+                    m.name,
+                    state,
+                    m.pid.map_or("-".to_string(), |p| p.to_string()),
+                    m.uptime_s.map_or("-".to_string(), format_uptime),
+                    m.restarts,
+                );
+            }
+            for m in &status.modules {
+                if let Some(failure) = &m.last_failure {
+                    println!("last failure of {}: {failure}", m.name);
+                }
+            }
+            if let Some(reason) = &status.fatal {
+                println!("FATAL: {reason}");
+            }
+        }
+    }
+}
+
+/// `"42s"`, `"3m07s"` or `"2h05m"`.
+fn format_uptime(secs: f64) -> String {
+    let s = secs as u64;
+    match s {
+        0..60 => format!("{s}s"),
+        60..3600 => format!("{}m{:02}s", s / 60, s % 60),
+        _ => format!("{}h{:02}m", s / 3600, s % 3600 / 60),
     }
 }
 
 // ─── Restart Logic (T062) ──────────────────────────────────────────
-// Integrated into the main run() loop above with exponential backoff.
+//
+// Decisions live in `restart.rs`; the supervisor applies them.
 
-// ─── Graceful Shutdown (T063) ───────────────────────────────────────
+impl Supervisor {
+    /// Start every module that should run: not stopped or failed, past
+    /// its restart delay, and with all dependencies running. Nothing is
+    /// started in the fatal state.
+    fn reconcile(&mut self) {
+        let now = Instant::now();
+        for i in 0..self.modules.len() {
+            if self.fatal.is_some() || SHUTDOWN.load(Ordering::SeqCst) {
+                return;
+            }
+            let module = self.modules[i].clone();
+            let name = module.name.as_str();
+            if self.is_running(name)
+                || self.stopped.contains(name)
+                || self.failed.contains_key(name)
+                || self.restart_at.get(name).is_some_and(|t| *t > now)
+                || !module.depends_on.iter().all(|d| self.is_running(d))
+            {
+                continue;
+            }
+            match self.start_one(&module) {
+                Ok(()) => {}
+                Err(StartError::Spawn(e)) => self.enter_fatal(e),
+                Err(StartError::NotReady(_)) if SHUTDOWN.load(Ordering::SeqCst) => return,
+                Err(StartError::NotReady(_)) => {
+                    let timeout_s = self.ready_timeout_s(&module);
+                    self.handle_failure(name, Failure::NotReady(timeout_s));
+                }
+            }
+        }
+    }
+
+    /// Apply the restart policy of `name` after `failure`: leave it down,
+    /// schedule it with its restart set after its backoff, or enter the
+    /// fatal state once its restart budget is used up.
+    fn handle_failure(&mut self, name: &str, failure: Failure) {
+        let Some(module) = self.modules.iter().find(|m| m.name == name).cloned() else {
+            return;
+        };
+        warn!("{name} {failure}");
+        if !failure.is_clean() {
+            self.write_postmortem(name, &failure);
+        }
+        let now = Instant::now();
+        let ran = self
+            .running
+            .iter()
+            .find(|m| m.config.name == name)
+            .map_or(Duration::ZERO, |m| now.duration_since(m.started));
+        self.last_failure.insert(name.to_string(), failure.to_string());
+
+        let budget = self.budgets.entry(name.to_string()).or_default();
+        match restart::decide(&self.modules, &self.wd, budget, name, &failure, ran, now) {
+            Decision::Down { stopped } => {
+                self.stop_set(&HashSet::from([name.to_string()]));
+                info!("{name} not restarted (restart = \"{}\")", module.restart.as_str());
+                if stopped {
+                    self.stopped.insert(name.to_string());
+                } else {
+                    self.failed.insert(name.to_string(), failure.to_string());
+                }
+            }
+            Decision::Fatal(reason) => self.enter_fatal(reason),
+            Decision::Exhausted(reason) => {
+                self.stop_set(&HashSet::from([name.to_string()]));
+                error!("{reason}; {name} left down until `evo ctl start`");
+                self.failed.insert(name.to_string(), reason);
+            }
+            Decision::Restart { set, delay, count, max, window_s } => {
+                let mut stopped = self.stop_set(&set);
+                stopped.reverse();
+                for n in set {
+                    self.restart_at.insert(n, now + delay);
+                }
+                info!(
+                    "Restarting {} in {delay:?} (restart {count}/{max} of {name} within {window_s}s)",
+                    stopped.join(", ")
+                );
+            }
+        }
+    }
 
-fn graceful_shutdown(cu: &mut Child, hal: &mut Child, timeout_s: f64) {
-    // CU first (reverse of startup order).
-    info!("Stopping CU (PID={})", cu.id());
-    let _ = terminate_child(cu, timeout_s);
+    /// Record a post-mortem bundle of `name`'s failure, before its
+    /// restart set is stopped and dead segments are unlinked. A bundle
+    /// that cannot be written is logged and skipped.
+    fn write_postmortem(&self, name: &str, failure: &Failure) {
+        if self.wd.postmortem_keep == 0 {
+            return;
+        }
+        let Some(module) = self.running.iter().find(|m| m.config.name == name) else {
+            return;
+        };
+        // Let the readers collect the last lines of an exited child.
+        if module.exit.is_some() {
+            module.output.drain(Duration::from_millis(200));
+        }
+        let (exit_code, signal, core_dumped) = match failure {
+            Failure::Exited(code) => (Some(*code), None, false),
+            Failure::Signaled(sig, core) => (None, Some(sig.as_str().to_string()), *core),
+            Failure::Hung(_) | Failure::NotReady(_) => (None, None, false),
+        };
+        let report = CrashReport {
+            module: name.to_string(),
+            binary: module.config.binary.clone(),
+            namespace: self.ns.to_string(),
+            pid: Some(module.child.id()),
+            failure: failure.to_string(),
+            exit_code,
+            signal,
+            core_dumped,
+            uptime_s: Some(module.started.elapsed().as_secs_f64()),
+            time: String::new(),
+            segments: Vec::new(),
+        };
+        let writer = PostMortem::new(&self.wd.postmortem_dir, self.wd.postmortem_keep as usize);
+        match writer.write(&self.ns, &self.config_dir, report, &module.output.lines()) {
+            Ok(path) => info!("Post-mortem bundle of {name}: {}", path.display()),
+            Err(e) => warn!(
+                "Cannot write post-mortem bundle of {name} to {}: {e}",
+                writer.dir().display()
+            ),
+        }
+    }
 
-    // Then HAL.
-    info!("Stopping HAL (PID={})", hal.id());
-    let _ = terminate_child(hal, timeout_s);
+    /// Stop all modules and restart nothing until `evo ctl reload`;
+    /// `reason` is reported by `evo ctl status`.
+    fn enter_fatal(&mut self, reason: String) {
+        error!("CRITICAL: {reason}; stopping all modules, awaiting operator");
+        self.stop_all();
+        cleanup_all_shm(&self.ns);
+        self.restart_at.clear();
+        self.fatal = Some(reason);
+    }
 }
 
+// ─── Graceful Shutdown (T063) ───────────────────────────────────────
+
 /// Send SIGTERM, wait up to timeout_s, then escalate to SIGKILL.
 fn terminate_child(child: &mut Child, timeout_s: f64) -> Result<(), String> {
     let pid = Pid::from_raw(child.id() as i32);
@@ -350,9 +1163,9 @@ fn terminate_child(child: &mut Child, timeout_s: f64) -> Result<(), String> {
 
 // ─── Orphan SHM Cleanup (T064) ─────────────────────────────────────
 
-/// Clean up orphan SHM segments left from a previous crash.
-fn cleanup_orphan_shm() {
-    let segments = list_evo_segments();
+/// Clean up orphan SHM segments of this instance left from a previous crash.
+fn cleanup_orphan_shm(ns: &ShmNamespace) {
+    let segments = list_evo_segments(ns);
     if segments.is_empty() {
         debug!("No orphan SHM segments found");
         return;
@@ -399,9 +1212,9 @@ fn cleanup_orphan_shm() {
     }
 }
 
-/// Remove all evo_* SHM segments (used during shutdown).
-fn cleanup_all_shm() {
-    for name in list_evo_segments() {
+/// Remove all SHM segments of this instance (used during shutdown).
+fn cleanup_all_shm(ns: &ShmNamespace) {
+    for name in list_evo_segments(ns) {
         let shm_name = format!("/{name}");
         match nix::sys::mman::shm_unlink(shm_name.as_str()) {
             Ok(()) => debug!("Unlinked SHM segment: {name}"),
@@ -413,13 +1226,17 @@ fn cleanup_all_shm() {
     }
 }
 
-/// List all `evo_*` files in `/dev/shm/`.
-fn list_evo_segments() -> Vec<String> {
+/// List all files of namespace `ns` in `/dev/shm/` (`evo_*` by default).
+///
+/// Other instances' files never match: instance ids cannot contain `_`,
+/// so no namespace prefix is a prefix of another.
+fn list_evo_segments(ns: &ShmNamespace) -> Vec<String> {
+    let prefix = ns.file_prefix();
     let mut segments = Vec::new();
     if let Ok(entries) = std::fs::read_dir("/dev/shm") {
         for entry in entries.flatten() {
             if let Some(name) = entry.file_name().to_str() {
-                if name.starts_with("evo_") {
+                if name.starts_with(&prefix) {
                     segments.push(name.to_string());
                 }
             }
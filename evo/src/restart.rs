@@ -0,0 +1,448 @@
+//! Restart decisions of the supervisor (T062).
+//!
+//! Pure policy: which modules restart together, how the restart budget
+//! and backoff evolve, and whether a failure leaves a module down,
+//! restarts it or puts the supervisor in its fatal state (only modules
+//! with `fatal_on_exhaustion`, by default the HAL/CU pair). The
+//! [`Supervisor`](crate) applies the [`Decision`] to the processes.
+
+use evo_common::config::{ModuleConfig, RestartPolicy, WatchdogConfig};
+use nix::sys::signal::Signal;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Why a module went down.
+#[derive(Debug, Clone)]
+pub enum Failure {
+    /// Exited with this status.
+    Exited(i32),
+    /// Killed by this signal; whether the kernel dumped core.
+    Signaled(Signal, bool),
+    /// Outbound heartbeats frozen for this long.
+    Hung(Duration),
+    /// Not ready within this many seconds.
+    NotReady(f64),
+}
+
+impl Failure {
+    /// Exit status 0, which `on-failure` does not restart.
+    pub fn is_clean(&self) -> bool {
+        matches!(self, Self::Exited(0))
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exited(status) => write!(f, "exited with status {status}"),
+            Self::Signaled(sig, false) => write!(f, "killed by {}", sig.as_str()),
+            Self::Signaled(sig, true) => write!(f, "killed by {} (core dumped)", sig.as_str()),
+            Self::Hung(age) => write!(f, "hung (outbound heartbeats frozen for {age:.1?})"),
+            Self::NotReady(timeout_s) => write!(f, "not ready within {timeout_s}s"),
+        }
+    }
+}
+
+/// Restart history and backoff of one module.
+#[derive(Debug, Default)]
+pub struct RestartBudget {
+    /// Restart times within the current window.
+    history: VecDeque<Instant>,
+    /// Delay of the last restart (0 = none yet).
+    backoff_ms: u64,
+}
+
+/// What the supervisor does after a module failed.
+#[derive(Debug, PartialEq)]
+pub enum Decision {
+    /// Leave the module down. `stopped`: kept stopped as after
+    /// `evo ctl stop` (clean exit), otherwise reported as failed.
+    Down { stopped: bool },
+    /// Stop the running modules of `set` and start them again after
+    /// `delay`; this is restart `count` of `max` within `window_s`.
+    Restart {
+        set: HashSet<String>,
+        delay: Duration,
+        count: usize,
+        max: u32,
+        window_s: u64,
+    },
+    /// Restart budget used up: stop everything, restart nothing.
+    Fatal(String),
+    /// Restart budget used up by a module with `fatal_on_exhaustion`
+    /// off: leave only it down, reported as failed.
+    Exhausted(String),
+}
+
+/// Decide what follows `failure` of `name` after it ran for `ran`.
+///
+/// Restarts are charged to `budget`; the backoff doubles from
+/// `initial_backoff_ms` up to `max_backoff_s` while the module fails
+/// before `stable_run_s`. Unknown modules are left down.
+pub fn decide(
+    modules: &[ModuleConfig],
+    wd: &WatchdogConfig,
+    budget: &mut RestartBudget,
+    name: &str,
+    failure: &Failure,
+    ran: Duration,
+    now: Instant,
+) -> Decision {
+    let Some(module) = modules.iter().find(|m| m.name == name) else {
+        return Decision::Down { stopped: false };
+    };
+    let restart = match module.restart {
+        RestartPolicy::Never => false,
+        RestartPolicy::OnFailure => !failure.is_clean(),
+        RestartPolicy::Always | RestartPolicy::RestartDependents => true,
+    };
+    if !restart {
+        return Decision::Down {
+            stopped: failure.is_clean(),
+        };
+    }
+
+    let max = module.max_restarts.unwrap_or(wd.max_restarts);
+    let window_s = module.restart_window_s.unwrap_or(wd.restart_window_s);
+    budget
+        .history
+        .retain(|t| now.duration_since(*t) < Duration::from_secs(window_s));
+    if budget.history.len() >= max as usize {
+        let reason =
+            format!("{name} {failure}; restart budget exhausted ({max} restarts within {window_s}s)");
+        return if module.exhaustion_is_fatal() {
+            Decision::Fatal(reason)
+        } else {
+            Decision::Exhausted(reason)
+        };
+    }
+    budget.history.push_back(now);
+    let stable = ran >= Duration::from_secs(wd.stable_run_s);
+    budget.backoff_ms = if budget.backoff_ms == 0 || stable {
+        wd.initial_backoff_ms
+    } else {
+        (budget.backoff_ms * 2).min(wd.max_backoff_s * 1000)
+    };
+
+    Decision::Restart {
+        set: restart_set(modules, name),
+        delay: Duration::from_millis(budget.backoff_ms),
+        count: budget.history.len(),
+        max,
+        window_s,
+    }
+}
+
+/// Modules restarted with `name`: itself, its `restart_with` modules,
+/// and the dependents of those among them with `restart-dependents`.
+pub fn restart_set(modules: &[ModuleConfig], name: &str) -> HashSet<String> {
+    let mut names = HashSet::from([name.to_string()]);
+    if let Some(module) = modules.iter().find(|m| m.name == name) {
+        names.extend(module.restart_with.iter().cloned());
+    }
+    let roots: Vec<String> = modules
+        .iter()
+        .filter(|m| m.restart == RestartPolicy::RestartDependents && names.contains(&m.name))
+        .map(|m| m.name.clone())
+        .collect();
+    for root in roots {
+        names.extend(dependents(modules, &root));
+    }
+    names
+}
+
+/// `name` and all modules depending on it, directly or not.
+///
+/// `modules` is in start order, which lists dependencies first.
+pub fn dependents(modules: &[ModuleConfig], name: &str) -> HashSet<String> {
+    let mut names = HashSet::from([name.to_string()]);
+    for m in modules {
+        if m.depends_on.iter().any(|d| names.contains(d)) {
+            names.insert(m.name.clone());
+        }
+    }
+    names
+}
+
+/// `name` and all modules it depends on, directly or not.
+pub fn dependencies(modules: &[ModuleConfig], name: &str) -> HashSet<String> {
+    let mut names = HashSet::from([name.to_string()]);
+    for m in modules.iter().rev() {
+        if names.contains(&m.name) {
+            names.extend(m.depends_on.iter().cloned());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_common::config::default_modules;
+
+    fn watchdog() -> WatchdogConfig {
+        WatchdogConfig {
+            max_restarts: 3,
+            restart_window_s: 60,
+            initial_backoff_ms: 100,
+            max_backoff_s: 1,
+            stable_run_s: 10,
+            sigterm_timeout_s: 2.0,
+            hal_ready_timeout_s: 5.0,
+            hang_timeout_s: 0.0,
+            postmortem_dir: "/tmp".into(),
+            postmortem_keep: 0,
+        }
+    }
+
+    /// HAL → CU (default pair) plus an MQTT liaison on the CU.
+    fn stack() -> Vec<ModuleConfig> {
+        let mut modules = default_modules();
+        let mut mqtt = ModuleConfig::new("mqtt", "evo_mqtt");
+        mqtt.depends_on = vec!["cu".to_string()];
+        modules.push(mqtt);
+        modules
+    }
+
+    fn set(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn crash() -> Failure {
+        Failure::Signaled(Signal::SIGSEGV, false)
+    }
+
+    #[test]
+    fn liaison_restarts_alone() {
+        let (modules, wd) = (stack(), watchdog());
+        let mut budget = RestartBudget::default();
+        let now = Instant::now();
+        let decision = decide(
+            &modules,
+            &wd,
+            &mut budget,
+            "mqtt",
+            &crash(),
+            Duration::ZERO,
+            now,
+        );
+        assert_eq!(
+            decision,
+            Decision::Restart {
+                set: set(&["mqtt"]),
+                delay: Duration::from_millis(100),
+                count: 1,
+                max: 3,
+                window_s: 60,
+            }
+        );
+    }
+
+    #[test]
+    fn cu_restarts_with_hal_and_dependents() {
+        let (modules, wd) = (stack(), watchdog());
+        let mut budget = RestartBudget::default();
+        let failure = Failure::Exited(1);
+        let now = Instant::now();
+        let Decision::Restart { set: names, .. } = decide(
+            &modules,
+            &wd,
+            &mut budget,
+            "cu",
+            &failure,
+            Duration::ZERO,
+            now,
+        ) else {
+            panic!("cu must restart");
+        };
+        assert_eq!(names, set(&["hal", "cu", "mqtt"]));
+
+        // Without `restart-dependents` on the HAL only the pair restarts.
+        let mut modules = stack();
+        modules[0].restart = RestartPolicy::Always;
+        modules[1].restart = RestartPolicy::Always;
+        assert_eq!(restart_set(&modules, "cu"), set(&["hal", "cu"]));
+    }
+
+    #[test]
+    fn budget_exhaustion_is_fatal() {
+        let (modules, wd) = (stack(), watchdog());
+        let mut budget = RestartBudget::default();
+        let start = Instant::now();
+        let mut delays = Vec::new();
+        for i in 0..3 {
+            let now = start + Duration::from_secs(i);
+            match decide(
+                &modules,
+                &wd,
+                &mut budget,
+                "hal",
+                &crash(),
+                Duration::ZERO,
+                now,
+            ) {
+                Decision::Restart { delay, count, .. } => {
+                    assert_eq!(count, i as usize + 1);
+                    delays.push(delay.as_millis());
+                }
+                other => panic!("restart {i}: {other:?}"),
+            }
+        }
+        assert_eq!(delays, [100, 200, 400], "backoff doubles while unstable");
+
+        let now = start + Duration::from_secs(3);
+        let Decision::Fatal(reason) = decide(
+            &modules,
+            &wd,
+            &mut budget,
+            "hal",
+            &crash(),
+            Duration::ZERO,
+            now,
+        ) else {
+            panic!("fourth failure within the window must be fatal");
+        };
+        assert!(reason.starts_with("hal killed by SIGSEGV"), "{reason}");
+        assert!(reason.contains("3 restarts within 60s"), "{reason}");
+
+        // Outside the window the budget is available again.
+        let later = start + Duration::from_secs(120);
+        let decision = decide(
+            &modules,
+            &wd,
+            &mut budget,
+            "hal",
+            &crash(),
+            Duration::ZERO,
+            later,
+        );
+        assert!(
+            matches!(decision, Decision::Restart { count: 1, .. }),
+            "{decision:?}"
+        );
+    }
+
+    #[test]
+    fn liaison_exhaustion_is_not_fatal() {
+        let (mut modules, wd) = (stack(), watchdog());
+        modules[2].max_restarts = Some(1);
+        let mut budget = RestartBudget::default();
+        let now = Instant::now();
+        let decide_mqtt = |budget: &mut RestartBudget| {
+            decide(&modules, &wd, budget, "mqtt", &crash(), Duration::ZERO, now)
+        };
+        assert!(matches!(decide_mqtt(&mut budget), Decision::Restart { .. }));
+        let Decision::Exhausted(reason) = decide_mqtt(&mut budget) else {
+            panic!("liaison exhaustion must leave only the liaison down");
+        };
+        assert!(reason.contains("1 restarts within 60s"), "{reason}");
+
+        // Opting in makes it fatal like the RT pair.
+        modules[2].fatal_on_exhaustion = Some(true);
+        let mut budget = RestartBudget::default();
+        let decide_mqtt = |budget: &mut RestartBudget| {
+            decide(&modules, &wd, budget, "mqtt", &crash(), Duration::ZERO, now)
+        };
+        decide_mqtt(&mut budget);
+        assert!(matches!(decide_mqtt(&mut budget), Decision::Fatal(_)));
+    }
+
+    #[test]
+    fn stable_run_resets_backoff() {
+        let (modules, wd) = (stack(), watchdog());
+        let mut budget = RestartBudget::default();
+        let now = Instant::now();
+        let mut delay = |ran: u64| match decide(
+            &modules,
+            &wd,
+            &mut budget,
+            "mqtt",
+            &crash(),
+            Duration::from_secs(ran),
+            now,
+        ) {
+            Decision::Restart { delay, .. } => delay.as_millis(),
+            other => panic!("{other:?}"),
+        };
+        assert_eq!([delay(0), delay(0), delay(10)], [100, 200, 100]);
+    }
+
+    #[test]
+    fn on_failure_keeps_clean_exit_stopped() {
+        let (mut modules, wd) = (stack(), watchdog());
+        modules[2].restart = RestartPolicy::OnFailure;
+        let mut budget = RestartBudget::default();
+        let now = Instant::now();
+        let clean = Failure::Exited(0);
+        assert_eq!(
+            decide(
+                &modules,
+                &wd,
+                &mut budget,
+                "mqtt",
+                &clean,
+                Duration::ZERO,
+                now
+            ),
+            Decision::Down { stopped: true }
+        );
+        assert!(budget.history.is_empty(), "no restart charged");
+
+        let hung = Failure::Hung(Duration::from_secs(2));
+        let decision = decide(
+            &modules,
+            &wd,
+            &mut budget,
+            "mqtt",
+            &hung,
+            Duration::ZERO,
+            now,
+        );
+        assert!(
+            matches!(decision, Decision::Restart { count: 1, .. }),
+            "{decision:?}"
+        );
+    }
+
+    #[test]
+    fn never_leaves_module_down() {
+        let (mut modules, wd) = (stack(), watchdog());
+        modules[2].restart = RestartPolicy::Never;
+        let mut budget = RestartBudget::default();
+        let now = Instant::now();
+        assert_eq!(
+            decide(
+                &modules,
+                &wd,
+                &mut budget,
+                "mqtt",
+                &crash(),
+                Duration::ZERO,
+                now
+            ),
+            Decision::Down { stopped: false }
+        );
+        assert_eq!(
+            decide(
+                &modules,
+                &wd,
+                &mut budget,
+                "mqtt",
+                &Failure::Exited(0),
+                Duration::ZERO,
+                now
+            ),
+            Decision::Down { stopped: true }
+        );
+        assert!(budget.history.is_empty());
+    }
+
+    #[test]
+    fn dependency_closure() {
+        let modules = stack();
+        assert_eq!(dependents(&modules, "hal"), set(&["hal", "cu", "mqtt"]));
+        assert_eq!(dependents(&modules, "mqtt"), set(&["mqtt"]));
+        assert_eq!(dependencies(&modules, "mqtt"), set(&["hal", "cu", "mqtt"]));
+        assert_eq!(dependencies(&modules, "hal"), set(&["hal"]));
+    }
+}
@@ -8,11 +8,15 @@
 //! - Heartbeat checking via raw file reads
 //! - Orphan detection via flock probing
 //! - WatchdogTrait and associated types
+//! - Hang detection via outbound heartbeat probing
 
-use evo_common::shm::p2p::{TypedP2pWriter, ModuleAbbrev};
-use evo_common::shm::segments::HalToCuSegment;
-use evo_common::watchdog::{HealthStatus, ManagedModule, Watchdog, WatchdogError};
+use evo_common::shm::p2p::{SegmentDiscovery, TypedP2pWriter, ModuleAbbrev, ShmNamespace};
+use evo_common::shm::segments::{CuToHalSegment, HalToCuSegment};
+use evo_common::watchdog::{
+    HealthStatus, HeartbeatProbe, ManagedModule, Watchdog, WatchdogError,
+};
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 // ─── Helpers ────────────────────────────────────────────────────────
 
@@ -48,7 +52,7 @@ fn test_list_evo_segments_finds_created_segments() {
     );
 
     // List all evo_* segments.
-    let segments = list_evo_segments();
+    let segments = list_evo_segments(&ShmNamespace::default());
     assert!(
         segments.contains(&file_name),
         "list_evo_segments should find our segment; got: {segments:?}"
@@ -57,9 +61,33 @@ fn test_list_evo_segments_finds_created_segments() {
     drop(writer);
 }
 
+#[test]
+fn test_list_evo_segments_scoped_per_instance() {
+    // Same logical segment name in the default namespace and two instances.
+    let name = test_seg_name("inst");
+    let ns_a = ShmNamespace::new(&format!("wda{}", std::process::id())).unwrap();
+    let ns_ab = ShmNamespace::new(&format!("wda{}-b", std::process::id())).unwrap();
+    let _default = TypedP2pWriter::<HalToCuSegment>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+        .expect("create default");
+    let _a = TypedP2pWriter::<HalToCuSegment>::create_in(&ns_a, &name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+        .expect("create instance a");
+    let _ab = TypedP2pWriter::<HalToCuSegment>::create_in(&ns_ab, &name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+        .expect("create instance a-b");
+
+    let default = list_evo_segments(&ShmNamespace::default());
+    assert!(default.contains(&format!("evo_{name}")));
+    assert!(default.iter().all(|s| s.starts_with("evo_")), "{default:?}");
+
+    let a = list_evo_segments(&ns_a);
+    let prefix_a = ns_a.file_prefix();
+    assert!(a.contains(&format!("{prefix_a}{name}")));
+    assert!(a.iter().all(|s| s.starts_with(&prefix_a)), "{a:?}");
+    assert!(!a.iter().any(|s| s.starts_with(&ns_ab.file_prefix())), "{a:?}");
+}
+
 #[test]
 fn test_list_evo_segments_ignores_non_evo() {
-    let segments = list_evo_segments();
+    let segments = list_evo_segments(&ShmNamespace::default());
     for seg in &segments {
         assert!(
             seg.starts_with("evo_"),
@@ -259,16 +287,69 @@ fn test_watchdog_trait_is_object_safe() {
     assert!(wd.shutdown_all().is_ok());
 }
 
+#[test]
+fn test_managed_module_binaries() {
+    for module in ManagedModule::ALL {
+        assert_eq!(ManagedModule::from_binary(module.binary()), Some(module));
+    }
+    assert_eq!(ManagedModule::from_binary("evo_api"), None);
+    assert_eq!(ManagedModule::Cu.abbrev(), ModuleAbbrev::Cu);
+    assert_eq!(ManagedModule::Grpc.abbrev(), ModuleAbbrev::Rpc);
+}
+
+// ─── Hang detection ─────────────────────────────────────────────────
+
+#[test]
+fn test_heartbeat_probe_detects_frozen_writer() {
+    let ns = ShmNamespace::new(&format!("wdh{}", std::process::id())).unwrap();
+    let name = test_seg_name("hang");
+    let mut writer = TypedP2pWriter::<CuToHalSegment>::create_in(
+        &ns,
+        &name,
+        ModuleAbbrev::Cu,
+        ModuleAbbrev::Hal,
+    )
+    .expect("create writer");
+    writer.commit(&CuToHalSegment::default()).expect("commit");
+
+    let t0 = Instant::now();
+    let mut cu = HeartbeatProbe::new(ModuleAbbrev::Cu, t0);
+    let mut hal = HeartbeatProbe::new(ModuleAbbrev::Hal, t0);
+    cu.update(&SegmentDiscovery::list_segments_in(&ns), t0);
+
+    // Heartbeat advances → age resets.
+    let t1 = t0 + Duration::from_secs(1);
+    writer.commit(&CuToHalSegment::default()).expect("commit");
+    cu.update(&SegmentDiscovery::list_segments_in(&ns), t1);
+    assert_eq!(cu.age(t1), Duration::ZERO);
+
+    // Heartbeat frozen → age grows.
+    let t2 = t1 + Duration::from_secs(3);
+    cu.update(&SegmentDiscovery::list_segments_in(&ns), t2);
+    assert_eq!(cu.age(t2), Duration::from_secs(3));
+
+    // A module without outbound segments never ages.
+    hal.update(&SegmentDiscovery::list_segments_in(&ns), t2);
+    assert_eq!(hal.age(t2), Duration::ZERO);
+
+    // Writer gone → nothing to probe → age resets.
+    drop(writer);
+    let t3 = t2 + Duration::from_secs(1);
+    cu.update(&SegmentDiscovery::list_segments_in(&ns), t3);
+    assert_eq!(cu.age(t3), Duration::ZERO);
+}
+
 // ─── Reimplemented helpers (matching evo/src/main.rs logic) ─────────
 // We reimplement the pure functions here rather than importing from the
 // binary crate, since evo is a binary (not a lib).
 
-fn list_evo_segments() -> Vec<String> {
+fn list_evo_segments(ns: &ShmNamespace) -> Vec<String> {
+    let prefix = ns.file_prefix();
     let mut segments = Vec::new();
     if let Ok(entries) = std::fs::read_dir("/dev/shm") {
         for entry in entries.flatten() {
             if let Some(name) = entry.file_name().to_str() {
-                if name.starts_with("evo_") {
+                if name.starts_with(&prefix) {
                     segments.push(name.to_string());
                 }
             }
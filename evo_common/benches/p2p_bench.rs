@@ -2,10 +2,16 @@
 //!
 //! Measures single-writer / single-reader latency for various segment sizes.
 //! Target: write ≤ 5µs, read ≤ 2µs for segments ≤ 8KB.
+//!
+//! The `*_with` benchmarks compare the copying `commit()` / `read()` path
+//! against the in-place `commit_with()` / `read_with()` API on the large
+//! `HalToReSegment` and `CuToRpcSegment` payloads.
 
 use criterion::{Criterion, criterion_group, criterion_main};
 use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
-use evo_common::shm::segments::{HalToCuSegment, CuToHalSegment, CuToMqtSegment};
+use evo_common::shm::segments::{
+    CuToHalSegment, CuToMqtSegment, CuToRpcSegment, HalToCuSegment, HalToReSegment,
+};
 use std::hint::black_box;
 use std::sync::atomic::{AtomicU32, Ordering};
 
@@ -100,6 +106,94 @@ fn bench_roundtrip_hal_cu(c: &mut Criterion) {
     });
 }
 
+fn bench_commit_vs_commit_with_hal_to_re(c: &mut Criterion) {
+    let name = bench_seg("cw_hal_re");
+    let mut writer = TypedP2pWriter::<HalToReSegment>::create(
+        &name, ModuleAbbrev::Hal, ModuleAbbrev::Re,
+    ).expect("create writer");
+
+    let mut payload = HalToReSegment::default();
+
+    c.bench_function("p2p_commit_HalToReSegment", |b| {
+        b.iter(|| {
+            payload.axes[0].position += 1.0;
+            writer.commit(black_box(&payload)).unwrap();
+        });
+    });
+
+    c.bench_function("p2p_commit_with_HalToReSegment", |b| {
+        b.iter(|| {
+            writer.commit_with(|seg| seg.axes[0].position += black_box(1.0));
+        });
+    });
+}
+
+fn bench_read_vs_read_with_hal_to_re(c: &mut Criterion) {
+    let name = bench_seg("rw_hal_re");
+    let mut writer = TypedP2pWriter::<HalToReSegment>::create(
+        &name, ModuleAbbrev::Hal, ModuleAbbrev::Re,
+    ).expect("create writer");
+    writer.commit(&HalToReSegment::default()).unwrap();
+
+    let mut reader = TypedP2pReader::<HalToReSegment>::attach(&name, u32::MAX).expect("attach reader");
+
+    c.bench_function("p2p_read_HalToReSegment", |b| {
+        b.iter(|| {
+            let _pos = black_box(reader.read().map(|seg| seg.axes[0].position));
+        });
+    });
+
+    c.bench_function("p2p_read_with_HalToReSegment", |b| {
+        b.iter(|| {
+            let _pos = black_box(reader.read_with(|seg| seg.axes[0].position));
+        });
+    });
+}
+
+fn bench_commit_vs_commit_with_cu_to_rpc(c: &mut Criterion) {
+    let name = bench_seg("cw_cu_rpc");
+    let mut writer = TypedP2pWriter::<CuToRpcSegment>::create(
+        &name, ModuleAbbrev::Cu, ModuleAbbrev::Rpc,
+    ).expect("create writer");
+
+    let mut payload = CuToRpcSegment::default();
+
+    c.bench_function("p2p_commit_CuToRpcSegment", |b| {
+        b.iter(|| {
+            payload.error_flags = payload.error_flags.wrapping_add(1);
+            writer.commit(black_box(&payload)).unwrap();
+        });
+    });
+
+    c.bench_function("p2p_commit_with_CuToRpcSegment", |b| {
+        b.iter(|| {
+            writer.commit_with(|seg| seg.error_flags = seg.error_flags.wrapping_add(black_box(1)));
+        });
+    });
+}
+
+fn bench_read_vs_read_with_cu_to_rpc(c: &mut Criterion) {
+    let name = bench_seg("rw_cu_rpc");
+    let mut writer = TypedP2pWriter::<CuToRpcSegment>::create(
+        &name, ModuleAbbrev::Cu, ModuleAbbrev::Rpc,
+    ).expect("create writer");
+    writer.commit(&CuToRpcSegment::default()).unwrap();
+
+    let mut reader = TypedP2pReader::<CuToRpcSegment>::attach(&name, u32::MAX).expect("attach reader");
+
+    c.bench_function("p2p_read_CuToRpcSegment", |b| {
+        b.iter(|| {
+            let _flags = black_box(reader.read().map(|seg| seg.error_flags));
+        });
+    });
+
+    c.bench_function("p2p_read_with_CuToRpcSegment", |b| {
+        b.iter(|| {
+            let _flags = black_box(reader.read_with(|seg| seg.error_flags));
+        });
+    });
+}
+
 criterion_group!(
     benches,
     bench_write_hal_to_cu,
@@ -107,5 +201,9 @@ criterion_group!(
     bench_write_cu_to_hal,
     bench_write_cu_to_mqt,
     bench_roundtrip_hal_cu,
+    bench_commit_vs_commit_with_hal_to_re,
+    bench_read_vs_read_with_hal_to_re,
+    bench_commit_vs_commit_with_cu_to_rpc,
+    bench_read_vs_read_with_cu_to_rpc,
 );
 criterion_main!(benches);
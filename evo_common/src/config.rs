@@ -20,6 +20,7 @@ use crate::consts::{
     MAX_LAG_ERROR, MAX_OUT_MAX, MAX_POSITION_RANGE, MAX_SAFE_DECEL, MAX_VELOCITY, MIN_KD,
     MIN_KI, MIN_KP,
 };
+use crate::shm::p2p::ShmNamespace;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -186,6 +187,8 @@ impl<T: serde::de::DeserializeOwned> ConfigLoader for T {}
 pub const DEFAULT_CONFIG_PATH: &str = "/etc/evo/config";
 /// Default state file name (HAL persistent state).
 pub const DEFAULT_STATE_FILE: &str = "hal_state";
+/// Default directory of crash post-mortem bundles.
+pub const DEFAULT_POSTMORTEM_DIR: &str = "/var/lib/evo/postmortem";
 
 /// Default cycle time in microseconds for TOML-loaded configs.
 pub const DEFAULT_CYCLE_TIME_US: u32 = 1000;
@@ -202,35 +205,70 @@ fn default_max_backoff_s() -> u64 {
 fn default_stable_run_s() -> u64 {
     60
 }
+fn default_restart_window_s() -> u64 {
+    60
+}
 fn default_sigterm_timeout_s() -> f64 {
     2.0
 }
 fn default_hal_ready_timeout_s() -> f64 {
     5.0
 }
+fn default_hang_timeout_s() -> f64 {
+    2.0
+}
+fn default_postmortem_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_POSTMORTEM_DIR)
+}
+fn default_postmortem_keep() -> u32 {
+    20
+}
+
+/// `hang_timeout_s` is 0 (disabled) or within 0.1..=60.0 seconds.
+fn hang_timeout_valid(t: f64) -> bool {
+    t == 0.0 || (0.1..=60.0).contains(&t)
+}
 
 /// Watchdog configuration — how `evo` binary manages child processes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct WatchdogConfig {
-    /// Maximum consecutive restarts before degraded state (1..=100).
+    /// Maximum restarts of one module within `restart_window_s` before
+    /// the supervisor enters its fatal state (1..=100).
     #[serde(default = "default_max_restarts")]
     pub max_restarts: u32,
+    /// Sliding window for `max_restarts` in seconds (1..=86400).
+    #[serde(default = "default_restart_window_s")]
+    pub restart_window_s: u64,
     /// Initial restart delay in milliseconds (10..=10_000).
     #[serde(default = "default_initial_backoff_ms")]
     pub initial_backoff_ms: u64,
     /// Maximum restart delay in seconds (1..=300).
     #[serde(default = "default_max_backoff_s")]
     pub max_backoff_s: u64,
-    /// Successful run duration to reset backoff counter in seconds (10..=3600).
+    /// Successful run duration to reset a module's backoff in seconds (10..=3600).
     #[serde(default = "default_stable_run_s")]
     pub stable_run_s: u64,
     /// Timeout before escalating to SIGKILL in seconds (0.5..=30.0).
     #[serde(default = "default_sigterm_timeout_s")]
     pub sigterm_timeout_s: f64,
-    /// Timeout waiting for `evo_hal_cu` segment in seconds (1.0..=60.0).
+    /// Default readiness timeout in seconds (1.0..=60.0) for modules
+    /// without `ready_timeout_s`, e.g. HAL creating `evo_hal_cu`.
     #[serde(default = "default_hal_ready_timeout_s")]
     pub hal_ready_timeout_s: f64,
+    /// Outbound segment heartbeats frozen this long mean the module hangs
+    /// and is killed and restarted, in seconds (0 = disabled, else
+    /// 0.1..=60.0).
+    #[serde(default = "default_hang_timeout_s")]
+    pub hang_timeout_s: f64,
+    /// Directory of the crash post-mortem bundles written when a module
+    /// fails.
+    #[serde(default = "default_postmortem_dir")]
+    pub postmortem_dir: PathBuf,
+    /// Bundles kept in `postmortem_dir`, oldest removed first
+    /// (0 = no bundles, else 1..=1000).
+    #[serde(default = "default_postmortem_keep")]
+    pub postmortem_keep: u32,
 }
 
 impl WatchdogConfig {
@@ -242,6 +280,12 @@ impl WatchdogConfig {
                 self.max_restarts
             )));
         }
+        if !(1..=86_400).contains(&self.restart_window_s) {
+            return Err(ConfigError::ValidationError(format!(
+                "watchdog.restart_window_s={} out of range [1, 86400]",
+                self.restart_window_s
+            )));
+        }
         if !(10..=10_000).contains(&self.initial_backoff_ms) {
             return Err(ConfigError::ValidationError(format!(
                 "watchdog.initial_backoff_ms={} out of range [10, 10000]",
@@ -272,6 +316,23 @@ impl WatchdogConfig {
                 self.hal_ready_timeout_s
             )));
         }
+        if !hang_timeout_valid(self.hang_timeout_s) {
+            return Err(ConfigError::ValidationError(format!(
+                "watchdog.hang_timeout_s={} out of range (0 or [0.1, 60.0])",
+                self.hang_timeout_s
+            )));
+        }
+        if self.postmortem_keep > 1000 {
+            return Err(ConfigError::ValidationError(format!(
+                "watchdog.postmortem_keep={} out of range [0, 1000]",
+                self.postmortem_keep
+            )));
+        }
+        if self.postmortem_keep > 0 && self.postmortem_dir.as_os_str().is_empty() {
+            return Err(ConfigError::ValidationError(
+                "watchdog.postmortem_dir must not be empty".to_string(),
+            ));
+        }
         Ok(())
     }
 }
@@ -282,6 +343,11 @@ impl WatchdogConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct SystemConfig {
+    /// EVO instance identifier selecting the SHM namespace (`None` =
+    /// default `evo_*` segments). Must precede the first `[section]`;
+    /// a `--instance` CLI argument takes precedence.
+    #[serde(default)]
+    pub instance: Option<String>,
     /// Watchdog process management configuration.
     pub watchdog: WatchdogConfig,
     /// HAL program configuration (placeholder).
@@ -308,6 +374,275 @@ pub struct SystemConfig {
     /// Diagnostic configuration (placeholder).
     #[serde(default)]
     pub diagnostic: Option<toml::Value>,
+    /// Modules started by the `evo` supervisor (`[[modules]]`, default:
+    /// HAL then CU).
+    #[serde(default = "default_modules")]
+    pub modules: Vec<ModuleConfig>,
+}
+
+impl SystemConfig {
+    /// SHM namespace of the configured instance.
+    ///
+    /// # Errors
+    /// - `ConfigError::ValidationError` if `instance` is not a valid id.
+    pub fn shm_namespace(&self) -> Result<ShmNamespace, ConfigError> {
+        ShmNamespace::from_instance(self.instance.as_deref())
+            .map_err(|e| ConfigError::ValidationError(format!("instance: {e}")))
+    }
+
+    /// Enabled modules in start order: every module after all of its
+    /// `depends_on`, otherwise in declaration order. Stop in reverse.
+    ///
+    /// # Errors
+    /// - `ConfigError::ValidationError` for duplicate or empty names, an
+    ///   empty `binary`, an unknown dependency, an enabled module
+    ///   depending on a disabled one, or a dependency cycle.
+    pub fn start_order(&self) -> Result<Vec<&ModuleConfig>, ConfigError> {
+        let err = |msg: String| Err(ConfigError::ValidationError(msg));
+        let index = |name: &str| self.modules.iter().position(|m| m.name == name);
+
+        for (i, module) in self.modules.iter().enumerate() {
+            if module.name.is_empty() {
+                return err(format!("modules[{i}]: empty name"));
+            }
+            if index(&module.name) != Some(i) {
+                return err(format!("modules: duplicate name '{}'", module.name));
+            }
+            if module.binary.is_empty() {
+                return err(format!("modules.{}: empty binary", module.name));
+            }
+            for dep in &module.depends_on {
+                match index(dep) {
+                    None => {
+                        return err(format!("modules.{}: unknown dependency '{dep}'", module.name));
+                    }
+                    Some(d) if module.enabled && !self.modules[d].enabled => {
+                        return err(format!(
+                            "modules.{}: depends on disabled module '{dep}'",
+                            module.name
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+            for other in &module.restart_with {
+                match index(other) {
+                    None => {
+                        return err(format!(
+                            "modules.{}: unknown restart_with module '{other}'",
+                            module.name
+                        ));
+                    }
+                    Some(o) if module.enabled && !self.modules[o].enabled => {
+                        return err(format!(
+                            "modules.{}: restart_with disabled module '{other}'",
+                            module.name
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+            if let Some(n) = module.max_restarts
+                && n > 100
+            {
+                return err(format!(
+                    "modules.{}.max_restarts={n} out of range [0, 100]",
+                    module.name
+                ));
+            }
+            if let Some(t) = module.restart_window_s
+                && !(1..=86_400).contains(&t)
+            {
+                return err(format!(
+                    "modules.{}.restart_window_s={t} out of range [1, 86400]",
+                    module.name
+                ));
+            }
+            if let Some(t) = module.ready_timeout_s
+                && !(1.0..=60.0).contains(&t)
+            {
+                return err(format!(
+                    "modules.{}.ready_timeout_s={t} out of range [1.0, 60.0]",
+                    module.name
+                ));
+            }
+            if let Some(t) = module.hang_timeout_s
+                && !hang_timeout_valid(t)
+            {
+                return err(format!(
+                    "modules.{}.hang_timeout_s={t} out of range (0 or [0.1, 60.0])",
+                    module.name
+                ));
+            }
+        }
+
+        // Repeatedly take the first enabled module whose dependencies
+        // have all started (stable topological order).
+        let enabled: Vec<&ModuleConfig> = self.modules.iter().filter(|m| m.enabled).collect();
+        let mut order: Vec<&ModuleConfig> = Vec::with_capacity(enabled.len());
+        while order.len() < enabled.len() {
+            let next = enabled.iter().find(|m| {
+                !order.iter().any(|o| o.name == m.name)
+                    && m.depends_on.iter().all(|d| order.iter().any(|o| &o.name == d))
+            });
+            match next {
+                Some(m) => order.push(m),
+                None => {
+                    let cycle: Vec<&str> = enabled
+                        .iter()
+                        .filter(|m| !order.iter().any(|o| o.name == m.name))
+                        .map(|m| m.name.as_str())
+                        .collect();
+                    return err(format!("modules: dependency cycle among {cycle:?}"));
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+// ─── ModuleConfig ──────────────────────────────────────────────────
+
+fn default_true() -> bool {
+    true
+}
+
+/// What the supervisor does when a module exits or hangs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Restart after any exit, including exit status 0.
+    Always,
+    /// Restart after a non-zero exit, a signal, a hang or a failed
+    /// readiness wait; a clean exit leaves the module stopped.
+    OnFailure,
+    /// Never restart; the module stays down until `evo ctl start`.
+    Never,
+    /// As `Always`, and restart every module depending on it as well.
+    #[default]
+    RestartDependents,
+}
+
+impl RestartPolicy {
+    /// Name as written in `config.toml`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::OnFailure => "on-failure",
+            Self::Never => "never",
+            Self::RestartDependents => "restart-dependents",
+        }
+    }
+}
+
+/// One supervised module (`[[modules]]` in `config.toml`).
+///
+/// The supervisor starts enabled modules in dependency order (see
+/// [`SystemConfig::start_order`]), waits for each one's readiness, and
+/// stops them in reverse order. Each module has its own restart policy,
+/// backoff and restart budget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleConfig {
+    /// Unique module name, used in `depends_on` and logs.
+    pub name: String,
+    /// Executable name, resolved next to the `evo` binary, else on `PATH`.
+    pub binary: String,
+    /// Command line arguments; `{config_dir}` is replaced by the
+    /// supervisor's config directory.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Start this module (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Modules that must be ready before this one starts.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Segment (without namespace prefix, e.g. `"hal_cu"`) whose first
+    /// heartbeat marks the module ready; `None` = ready once spawned.
+    #[serde(default)]
+    pub ready_segment: Option<String>,
+    /// Readiness timeout in seconds (1.0..=60.0; default:
+    /// `watchdog.hal_ready_timeout_s`).
+    #[serde(default)]
+    pub ready_timeout_s: Option<f64>,
+    /// Hang timeout in seconds (0 = no hang detection; default:
+    /// `watchdog.hang_timeout_s`). Only modules with a managed binary
+    /// (see `ManagedModule`) are probed.
+    #[serde(default)]
+    pub hang_timeout_s: Option<f64>,
+    /// Forward `--instance <ID>` when the supervisor runs a named
+    /// instance (default: true).
+    #[serde(default = "default_true")]
+    pub forward_instance: bool,
+    /// Restart policy (default: `restart-dependents`).
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Modules restarted together with this one, e.g. the HAL for the
+    /// CU, whose segments the HAL only attaches once.
+    #[serde(default)]
+    pub restart_with: Vec<String>,
+    /// Restarts allowed within `restart_window_s` before the fatal state
+    /// (0..=100; 0 = first failure is fatal; default:
+    /// `watchdog.max_restarts`).
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
+    /// Restart budget window in seconds (1..=86400; default:
+    /// `watchdog.restart_window_s`).
+    #[serde(default)]
+    pub restart_window_s: Option<u64>,
+    /// Whether an exhausted restart budget puts the supervisor in its
+    /// fatal state; otherwise only this module is left down as failed
+    /// (default: true for `hal` and `cu`, false for liaisons).
+    #[serde(default)]
+    pub fatal_on_exhaustion: Option<bool>,
+}
+
+impl ModuleConfig {
+    /// Module started with `--config-dir <DIR>` and no dependencies.
+    pub fn new(name: &str, binary: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            binary: binary.to_string(),
+            args: vec!["--config-dir".to_string(), "{config_dir}".to_string()],
+            enabled: true,
+            depends_on: Vec::new(),
+            ready_segment: None,
+            ready_timeout_s: None,
+            hang_timeout_s: None,
+            forward_instance: true,
+            restart: RestartPolicy::default(),
+            restart_with: Vec::new(),
+            max_restarts: None,
+            restart_window_s: None,
+            fatal_on_exhaustion: None,
+        }
+    }
+
+    /// `fatal_on_exhaustion`, defaulting to true for the RT pair only.
+    pub fn exhaustion_is_fatal(&self) -> bool {
+        self.fatal_on_exhaustion
+            .unwrap_or(matches!(self.name.as_str(), "hal" | "cu"))
+    }
+
+    /// Arguments with `{config_dir}` substituted.
+    pub fn resolved_args(&self, config_dir: &Path) -> Vec<String> {
+        let dir = config_dir.display().to_string();
+        self.args.iter().map(|a| a.replace("{config_dir}", &dir)).collect()
+    }
+}
+
+/// Module list used when `config.toml` has no `[[modules]]`: HAL, ready
+/// on `evo_hal_cu`, then CU, ready on `evo_cu_hal`. The two restart
+/// together.
+pub fn default_modules() -> Vec<ModuleConfig> {
+    let mut hal = ModuleConfig::new("hal", "evo_hal");
+    hal.ready_segment = Some("hal_cu".to_string());
+    let mut cu = ModuleConfig::new("cu", "evo_control_unit");
+    cu.depends_on = vec!["hal".to_string()];
+    cu.ready_segment = Some("cu_hal".to_string());
+    cu.restart_with = vec!["hal".to_string()];
+    vec![hal, cu]
 }
 
 // ─── MachineConfig (unified) ───────────────────────────────────────
@@ -355,6 +690,9 @@ pub struct NewMachineConfig {
     pub global_safety: GlobalSafetyConfig,
     /// Service bypass parameters.
     pub service_bypass: ServiceBypassConfig,
+    /// Coordinated axis groups (`[[axis_groups]]`, optional).
+    #[serde(default)]
+    pub axis_groups: Vec<crate::control_unit::config::AxisGroupConfig>,
 }
 
 // ─── Per-Axis Config ───────────────────────────────────────────────
@@ -380,6 +718,9 @@ pub struct KinematicsConfig {
     pub max_velocity: f64,
     /// Maximum acceleration (optional).
     pub max_acceleration: Option<f64>,
+    /// Maximum jerk (optional).
+    #[serde(default)]
+    pub max_jerk: Option<f64>,
     /// Safe reduced speed limit for safety mode.
     pub safe_reduced_speed_limit: f64,
     /// Minimum position.
@@ -389,6 +730,9 @@ pub struct KinematicsConfig {
     /// In-position window (tolerance).
     #[serde(default = "default_in_position_window")]
     pub in_position_window: f64,
+    /// Velocity below which the axis counts as standing still (optional).
+    #[serde(default)]
+    pub standstill_velocity: Option<f64>,
 }
 
 fn default_in_position_window() -> f64 {
@@ -610,6 +954,9 @@ pub struct NewAxisConfig {
     pub guard: Option<GuardConfig>,
     /// Coupling (optional).
     pub coupling: Option<CouplingConfig>,
+    /// Dynamic plant model for `evo_hal --simulate` (optional).
+    #[serde(default)]
+    pub dynamics: Option<crate::hal::config::DynamicsConfig>,
 }
 
 // ─── FullConfig ────────────────────────────────────────────────────
@@ -643,6 +990,8 @@ pub fn load_config_dir(path: &Path) -> Result<FullConfig, ConfigError> {
     let system_path = path.join("config.toml");
     let system: SystemConfig = load_toml_file(&system_path)?;
     system.watchdog.validate()?;
+    system.shm_namespace()?;
+    system.start_order()?;
 
     // 2. Load machine.toml.
     let machine_path = path.join("machine.toml");
@@ -785,6 +1134,9 @@ fn validate_machine_config(cfg: &NewMachineConfig) -> Result<(), ConfigError> {
             "global_safety.safety_stop_timeout must be > 0".to_string(),
         ));
     }
+    for group in &cfg.axis_groups {
+        group.validate().map_err(ConfigError::ValidationError)?;
+    }
     if cfg.service_bypass.max_service_velocity <= 0.0
         || cfg.service_bypass.max_service_velocity > MAX_VELOCITY
     {
@@ -823,6 +1175,11 @@ fn validate_axis_bounds(axis: &NewAxisConfig, fname: &str) -> Result<(), ConfigE
             ));
         }
     }
+    if let Some(sv) = k.standstill_velocity
+        && (sv <= 0.0 || sv > MAX_VELOCITY)
+    {
+        return Err(ctx("kinematics.standstill_velocity", sv, 0.0, MAX_VELOCITY));
+    }
     if k.min_pos >= k.max_pos {
         return Err(ConfigError::ValidationError(format!(
             "{fname}: kinematics.min_pos ({}) must be < max_pos ({})",
@@ -18,6 +18,15 @@ pub const MAX_AI: usize = 1024;
 /// Maximum number of analog outputs.
 pub const MAX_AO: usize = 1024;
 
+/// Maximum number of axes in one coordinated axis group (XYZABC).
+pub const MAX_GROUP_AXES: usize = 6;
+
+/// Maximum number of coordinated axis groups.
+pub const MAX_AXIS_GROUPS: usize = 8;
+
+/// Maximum path look-ahead buffer depth [segments].
+pub const MAX_LOOKAHEAD: usize = 64;
+
 // ─── Immutable validation bounds (FR-054) ──────────────────────────
 
 /// Minimum Kp gain value.
@@ -71,6 +80,7 @@ mod tests {
         assert!(MAX_DO > 0);
         assert!(MAX_AI > 0);
         assert!(MAX_AO > 0);
+        assert!(MAX_GROUP_AXES > 0 && MAX_GROUP_AXES <= MAX_AXES as usize);
         assert!(MIN_KP <= MAX_KP);
         assert!(MIN_KI <= MAX_KI);
         assert!(MIN_KD <= MAX_KD);
@@ -8,7 +8,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::DEFAULT_CYCLE_TIME_US;
 use crate::consts::{
-    MANUAL_TIMEOUT_MAX, MANUAL_TIMEOUT_MIN, MAX_AXES, MAX_CYCLE_TIME_US, MIN_CYCLE_TIME_US,
+    MANUAL_TIMEOUT_MAX, MANUAL_TIMEOUT_MIN, MAX_AXES, MAX_CYCLE_TIME_US, MAX_GROUP_AXES,
+    MAX_LOOKAHEAD, MIN_CYCLE_TIME_US,
 };
 
 use super::command::ServiceBypassConfig;
@@ -57,6 +58,11 @@ pub struct ControlUnitConfig {
     #[serde(default = "default_hal_stale")]
     pub hal_stale_threshold: u32,
 
+    /// HAL commit age limit [µs] (default: none). When set, HAL staleness
+    /// is time-based and replaces `hal_stale_threshold`.
+    #[serde(default)]
+    pub hal_max_age_us: Option<u32>,
+
     /// RE heartbeat staleness threshold [cycles] (default: 1000).
     #[serde(default = "default_non_rt_stale")]
     pub re_stale_threshold: u32,
@@ -110,6 +116,9 @@ impl ControlUnitConfig {
                 self.manual_timeout, MANUAL_TIMEOUT_MIN, MANUAL_TIMEOUT_MAX
             ));
         }
+        if self.hal_max_age_us == Some(0) {
+            return Err("hal_max_age_us must be > 0".to_string());
+        }
         Ok(())
     }
 }
@@ -127,6 +136,9 @@ pub struct CuMachineConfig {
     /// Service mode bypass configuration (FR-001a).
     #[serde(default)]
     pub service_bypass: ServiceBypassConfig,
+    /// Coordinated axis groups for path interpolation.
+    #[serde(default)]
+    pub axis_groups: Vec<AxisGroupConfig>,
 }
 
 impl Default for CuMachineConfig {
@@ -135,10 +147,66 @@ impl Default for CuMachineConfig {
             axes: Vec::new(),
             global_safety: GlobalSafetyConfig::default(),
             service_bypass: ServiceBypassConfig::default(),
+            axis_groups: Vec::new(),
         }
     }
 }
 
+// ─── Axis Group Config ──────────────────────────────────────────────
+
+/// Coordinated axis group (`[[axis_groups]]`).
+///
+/// Linear and circular path segments are interpolated over the group
+/// axes, in the listed order (e.g. X, Y, Z, A, B, C).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AxisGroupConfig {
+    /// Group name (e.g., "XYZABC").
+    pub name: String,
+    /// Member axis IDs, 1..=`MAX_GROUP_AXES` entries.
+    pub axes: Vec<AxisId>,
+    /// Look-ahead buffer depth [segments], 1..=`MAX_LOOKAHEAD`.
+    #[serde(default = "default_lookahead")]
+    pub lookahead: usize,
+    /// Maximum path deviation allowed when blending corners [user units].
+    /// 0 = exact stop at every corner.
+    #[serde(default = "default_corner_tolerance")]
+    pub corner_tolerance: f64,
+}
+
+fn default_lookahead() -> usize {
+    16
+}
+fn default_corner_tolerance() -> f64 {
+    0.01
+}
+
+impl AxisGroupConfig {
+    /// Validate group-local bounds (cross-axis checks live in the CU loader).
+    pub fn validate(&self) -> Result<(), String> {
+        if self.axes.is_empty() || self.axes.len() > MAX_GROUP_AXES {
+            return Err(format!(
+                "axis group '{}': {} axes out of range [1, {MAX_GROUP_AXES}]",
+                self.name,
+                self.axes.len()
+            ));
+        }
+        if self.lookahead == 0 || self.lookahead > MAX_LOOKAHEAD {
+            return Err(format!(
+                "axis group '{}': lookahead={} out of range [1, {MAX_LOOKAHEAD}]",
+                self.name, self.lookahead
+            ));
+        }
+        if !self.corner_tolerance.is_finite() || self.corner_tolerance < 0.0 {
+            return Err(format!(
+                "axis group '{}': corner_tolerance={} must be >= 0",
+                self.name, self.corner_tolerance
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Per-axis configuration (FR-142).
 ///
 /// Peripheral I/O fields use string role names resolved via `IoRegistry` at startup.
@@ -157,6 +225,14 @@ pub struct CuAxisConfig {
     #[serde(default = "default_safe_reduced_speed")]
     pub safe_reduced_speed_limit: f64,
 
+    /// Maximum acceleration for trajectory generation [user units/s²].
+    #[serde(default = "default_max_acceleration")]
+    pub max_acceleration: f64,
+
+    /// Maximum jerk for trajectory generation [user units/s³].
+    #[serde(default = "default_max_jerk")]
+    pub max_jerk: f64,
+
     /// Control engine parameters.
     #[serde(default)]
     pub control: UniversalControlParameters,
@@ -207,6 +283,10 @@ pub struct CuAxisConfig {
     #[serde(default = "default_in_position_window")]
     pub in_position_window: f64,
 
+    /// Standstill velocity threshold for the power sequences [mm/s].
+    #[serde(default = "default_standstill_velocity")]
+    pub standstill_velocity: f64,
+
     /// Per-axis loading config flags (FR-073).
     #[serde(default)]
     pub loading_blocked: bool,
@@ -217,12 +297,21 @@ pub struct CuAxisConfig {
 fn default_safe_reduced_speed() -> f64 {
     50.0
 }
+fn default_max_acceleration() -> f64 {
+    1000.0
+}
+fn default_max_jerk() -> f64 {
+    20_000.0
+}
 fn default_max_pos() -> f64 {
     f64::MAX
 }
 fn default_in_position_window() -> f64 {
     0.1
 }
+fn default_standstill_velocity() -> f64 {
+    0.1
+}
 
 impl CuAxisConfig {
     /// Convert from the new unified `NewAxisConfig` (from `load_config_dir`).
@@ -236,6 +325,11 @@ impl CuAxisConfig {
             name: ax.axis.name.clone(),
             max_velocity: ax.kinematics.max_velocity,
             safe_reduced_speed_limit: ax.kinematics.safe_reduced_speed_limit,
+            max_acceleration: ax
+                .kinematics
+                .max_acceleration
+                .unwrap_or_else(default_max_acceleration),
+            max_jerk: ax.kinematics.max_jerk.unwrap_or_else(default_max_jerk),
             control: UniversalControlParameters {
                 kp: ax.control.kp,
                 ki: ax.control.ki,
@@ -254,6 +348,10 @@ impl CuAxisConfig {
             min_pos: ax.kinematics.min_pos,
             max_pos: ax.kinematics.max_pos,
             in_position_window: ax.kinematics.in_position_window,
+            standstill_velocity: ax
+                .kinematics
+                .standstill_velocity
+                .unwrap_or_else(default_standstill_velocity),
             loading_blocked: false,
             loading_manual: false,
         }
@@ -304,6 +402,7 @@ mod tests {
             io_config_path: "io.toml".to_string(),
             manual_timeout: 30.0,
             hal_stale_threshold: 3,
+            hal_max_age_us: None,
             re_stale_threshold: 1000,
             rpc_stale_threshold: 1000,
             mqt_update_interval: 10,
@@ -327,5 +426,29 @@ mod tests {
             ..valid.clone()
         };
         assert!(bad_timeout.validate().is_err());
+
+        let bad_max_age = ControlUnitConfig {
+            hal_max_age_us: Some(0),
+            ..valid.clone()
+        };
+        assert!(bad_max_age.validate().is_err());
+    }
+
+    #[test]
+    fn axis_group_defaults_and_validate() {
+        let group: AxisGroupConfig =
+            toml::from_str("name = \"XYZABC\"\naxes = [1, 2, 3, 4, 5, 6]").unwrap();
+        assert_eq!(group.lookahead, 16);
+        assert_eq!(group.corner_tolerance, 0.01);
+        assert!(group.validate().is_ok());
+
+        let too_many = AxisGroupConfig { axes: (1..=7).collect(), ..group.clone() };
+        assert!(too_many.validate().is_err());
+        let empty = AxisGroupConfig { axes: Vec::new(), ..group.clone() };
+        assert!(empty.validate().is_err());
+        let no_lookahead = AxisGroupConfig { lookahead: 0, ..group.clone() };
+        assert!(no_lookahead.validate().is_err());
+        let bad_tolerance = AxisGroupConfig { corner_tolerance: -1.0, ..group };
+        assert!(bad_tolerance.validate().is_err());
     }
 }
@@ -2,8 +2,8 @@
 //!
 //! All types shared between the Control Unit and other EVO modules live here.
 //! Organized by domain: state enums, error bitflags, safety types, control
-//! parameters, command types, homing configuration, SHM segment payloads,
-//! and configuration structures.
+//! parameters, command types, homing configuration and configuration
+//! structures. SHM segment payloads live in [`crate::shm::segments`].
 
 pub mod command;
 pub mod config;
@@ -11,5 +11,4 @@ pub mod control;
 pub mod error;
 pub mod homing;
 pub mod safety;
-pub mod shm;
 pub mod state;
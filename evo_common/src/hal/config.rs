@@ -255,6 +255,11 @@ pub struct AxisConfig {
     pub soft_limit_positive: Option<f64>,
     #[serde(default)]
     pub soft_limit_negative: Option<f64>,
+
+    /// Dynamic plant model for simulation (`[dynamics]`).
+    /// `None` = kinematic simulation.
+    #[serde(default)]
+    pub dynamics: Option<DynamicsConfig>,
 }
 
 impl AxisConfig {
@@ -267,6 +272,7 @@ impl AxisConfig {
     /// 4. For `Measurement`: `encoder_resolution` required and > 0
     /// 5. `soft_limit_negative` < `soft_limit_positive` (if both set)
     /// 6. `in_position_window` >= 0
+    /// 7. `dynamics` only for `Positioning`, and valid
     pub fn validate(&self, axis_index: usize, all_axes: &[AxisConfig]) -> Result<(), HalError> {
         // Check name
         if self.name.is_empty() {
@@ -357,6 +363,208 @@ impl AxisConfig {
             )));
         }
 
+        // Check dynamic model
+        if let Some(dynamics) = &self.dynamics {
+            if self.axis_type != AxisType::Positioning {
+                return Err(HalError::ConfigError(format!(
+                    "Axis '{}': dynamics only supported for Positioning type",
+                    self.name
+                )));
+            }
+            dynamics
+                .validate()
+                .map_err(|e| HalError::ConfigError(format!("Axis '{}': {}", self.name, e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Build a simulation axis from a unified `axis_NN_*.toml` config.
+    ///
+    /// Positioning axis with the unified kinematics, lag limit and soft
+    /// limits; the optional `[dynamics]` section selects the dynamic
+    /// model. No referencing is required.
+    pub fn from_new_axis_config(ax: &crate::config::NewAxisConfig) -> Self {
+        let dynamics = ax.dynamics.clone();
+        Self {
+            name: ax.axis.name.clone(),
+            axis_type: AxisType::Positioning,
+            encoder_resolution: Some(
+                dynamics
+                    .as_ref()
+                    .and_then(|d| d.encoder_resolution)
+                    .unwrap_or(DEFAULT_SIM_ENCODER_RESOLUTION),
+            ),
+            max_velocity: Some(ax.kinematics.max_velocity),
+            max_acceleration: Some(
+                ax.kinematics
+                    .max_acceleration
+                    .unwrap_or(DEFAULT_SIM_MAX_ACCELERATION),
+            ),
+            lag_error_limit: Some(ax.control.lag_error_limit),
+            master_axis: None,
+            coupling_offset: None,
+            in_position_window: ax.kinematics.in_position_window,
+            referencing: ReferencingConfig::default(),
+            soft_limit_positive: Some(ax.kinematics.max_pos),
+            soft_limit_negative: Some(ax.kinematics.min_pos),
+            dynamics,
+        }
+    }
+}
+
+/// Encoder resolution of simulated axes built from unified config
+/// [increments per user unit].
+pub const DEFAULT_SIM_ENCODER_RESOLUTION: f64 = 1_000_000.0;
+
+/// Acceleration limit of simulated axes without `max_acceleration`
+/// [user units/s²].
+pub const DEFAULT_SIM_MAX_ACCELERATION: f64 = 10_000.0;
+
+/// Dynamic plant model of a simulated axis.
+///
+/// Torque-driven two-mass model in user units; torque is in the units of
+/// the CU control output:
+///
+/// ```text
+/// J_m·a_m = τ − b·v_m − F_c·sgn(v_m) − k·(x_m − x_l) − c·(v_m − v_l)
+/// J_l·a_l =   − τ_g  + k·(x_m − x_l) + c·(v_m − v_l)
+/// ```
+///
+/// Without `resonance_frequency` both masses are rigidly joined.
+/// The encoder sits on the motor side and is quantized to
+/// `encoder_resolution`. With `drive_loop = "cu"` (default) the CU's
+/// `target_torque` is applied in every drive mode, so the CU gains shape
+/// the response. With `drive_loop = "drive"` the simulated drive closes its
+/// own PI velocity loop (and P position loop) at `drive_bandwidth` in
+/// CSP / CSV; in CST the commanded torque is applied directly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicsConfig {
+    /// Total inertia (motor + load) [torque / (user units/s²)].
+    pub inertia: f64,
+    /// Viscous friction [torque / (user units/s)].
+    #[serde(default)]
+    pub viscous_friction: f64,
+    /// Coulomb friction [torque].
+    #[serde(default)]
+    pub coulomb_friction: f64,
+    /// Constant gravity load on the load side, pulling negative [torque].
+    /// Held by the brake while the axis is disabled.
+    #[serde(default)]
+    pub gravity_load: f64,
+    /// Mechanical resonance frequency [Hz]. `None` = rigid coupling.
+    #[serde(default)]
+    pub resonance_frequency: Option<f64>,
+    /// Damping ratio of the resonance.
+    #[serde(default = "default_resonance_damping")]
+    pub resonance_damping: f64,
+    /// Load share of the total inertia (0 < r < 1), with resonance only.
+    #[serde(default = "default_load_ratio")]
+    pub load_ratio: f64,
+    /// Encoder quantization [increments per user unit].
+    /// Default: the axis `encoder_resolution`.
+    #[serde(default)]
+    pub encoder_resolution: Option<f64>,
+    /// Drive torque limit [torque]. `None` = unlimited.
+    #[serde(default)]
+    pub max_torque: Option<f64>,
+    /// Torque constant for current feedback [torque / A].
+    #[serde(default = "default_torque_constant")]
+    pub torque_constant: f64,
+    /// Velocity loop bandwidth of the simulated drive (CSP / CSV) [Hz].
+    #[serde(default = "default_drive_bandwidth")]
+    pub drive_bandwidth: f64,
+    /// Who closes the position / velocity loop.
+    #[serde(default)]
+    pub drive_loop: DriveLoop,
+}
+
+/// Position / velocity loop owner of a simulated dynamic axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriveLoop {
+    /// The CU runs the loop; its torque output drives the plant in every
+    /// command mode (`torque_offset` is already part of `target_torque`).
+    #[default]
+    Cu,
+    /// The simulated drive runs the cascade in CSP / CSV.
+    Drive,
+}
+
+fn default_resonance_damping() -> f64 {
+    0.05
+}
+
+fn default_load_ratio() -> f64 {
+    0.5
+}
+
+fn default_torque_constant() -> f64 {
+    1.0
+}
+
+fn default_drive_bandwidth() -> f64 {
+    50.0
+}
+
+impl DynamicsConfig {
+    /// Rigid model with the given inertia and default parameters.
+    pub fn rigid(inertia: f64) -> Self {
+        Self {
+            inertia,
+            viscous_friction: 0.0,
+            coulomb_friction: 0.0,
+            gravity_load: 0.0,
+            resonance_frequency: None,
+            resonance_damping: default_resonance_damping(),
+            load_ratio: default_load_ratio(),
+            encoder_resolution: None,
+            max_torque: None,
+            torque_constant: default_torque_constant(),
+            drive_bandwidth: default_drive_bandwidth(),
+            drive_loop: DriveLoop::Cu,
+        }
+    }
+
+    /// Validate the model parameters.
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = [
+            ("inertia", self.inertia),
+            ("torque_constant", self.torque_constant),
+            ("drive_bandwidth", self.drive_bandwidth),
+        ];
+        for (name, value) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(format!("dynamics.{name} must be > 0"));
+            }
+        }
+        let non_negative = [
+            ("viscous_friction", self.viscous_friction),
+            ("coulomb_friction", self.coulomb_friction),
+            ("resonance_damping", self.resonance_damping),
+        ];
+        for (name, value) in non_negative {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(format!("dynamics.{name} must be >= 0"));
+            }
+        }
+        if !self.gravity_load.is_finite() {
+            return Err("dynamics.gravity_load must be finite".into());
+        }
+        let optional_positive = [
+            ("resonance_frequency", self.resonance_frequency),
+            ("encoder_resolution", self.encoder_resolution),
+            ("max_torque", self.max_torque),
+        ];
+        for (name, value) in optional_positive {
+            if value.is_some_and(|v| !(v.is_finite() && v > 0.0)) {
+                return Err(format!("dynamics.{name} must be > 0"));
+            }
+        }
+        if self.resonance_frequency.is_some() && !(self.load_ratio > 0.0 && self.load_ratio < 1.0) {
+            return Err("dynamics.load_ratio must be in (0, 1)".into());
+        }
         Ok(())
     }
 }
@@ -636,6 +844,7 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: Some(1000.0),
             soft_limit_negative: Some(-1000.0),
+            dynamics: None,
         };
         assert!(axis.validate(0, &[]).is_ok());
     }
@@ -655,6 +864,7 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         };
         assert!(axis.validate(0, &[]).is_err());
     }
@@ -674,6 +884,7 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         };
 
         let slave = AxisConfig {
@@ -689,6 +900,7 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         };
 
         assert!(slave.validate(1, &[master]).is_ok());
@@ -709,7 +921,74 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: Some(100.0),
             soft_limit_negative: Some(200.0), // Invalid: neg > pos
+            dynamics: None,
         };
         assert!(axis.validate(0, &[]).is_err());
     }
+
+    #[test]
+    fn test_dynamics_config_parse_defaults() {
+        let dynamics: DynamicsConfig = toml::from_str("inertia = 0.01").unwrap();
+        assert_eq!(dynamics.inertia, 0.01);
+        assert_eq!(dynamics.viscous_friction, 0.0);
+        assert!(dynamics.resonance_frequency.is_none());
+        assert_eq!(dynamics.torque_constant, 1.0);
+        assert_eq!(dynamics.drive_loop, DriveLoop::Cu);
+        assert!(dynamics.validate().is_ok());
+
+        assert!(toml::from_str::<DynamicsConfig>("inertia = 0.01\nmass = 1.0").is_err());
+        assert!(toml::from_str::<DynamicsConfig>("inertia = 0.01\ndrive_loop = \"re\"").is_err());
+    }
+
+    #[test]
+    fn test_dynamics_config_validate() {
+        assert!(DynamicsConfig::rigid(0.0).validate().is_err());
+        let negative_friction = DynamicsConfig {
+            coulomb_friction: -1.0,
+            ..DynamicsConfig::rigid(0.01)
+        };
+        assert!(negative_friction.validate().is_err());
+        let bad_ratio = DynamicsConfig {
+            resonance_frequency: Some(100.0),
+            load_ratio: 1.0,
+            ..DynamicsConfig::rigid(0.01)
+        };
+        assert!(bad_ratio.validate().is_err());
+        let rigid_ratio_ignored = DynamicsConfig {
+            load_ratio: 1.0,
+            ..DynamicsConfig::rigid(0.01)
+        };
+        assert!(rigid_ratio_ignored.validate().is_ok());
+    }
+
+    #[test]
+    fn test_axis_config_dynamics_positioning_only() {
+        let mut axis = AxisConfig {
+            name: "test".to_string(),
+            axis_type: AxisType::Simple,
+            encoder_resolution: None,
+            max_velocity: None,
+            max_acceleration: None,
+            lag_error_limit: None,
+            master_axis: None,
+            coupling_offset: None,
+            in_position_window: 0.01,
+            referencing: ReferencingConfig::default(),
+            soft_limit_positive: None,
+            soft_limit_negative: None,
+            dynamics: Some(DynamicsConfig::rigid(0.01)),
+        };
+        assert!(axis.validate(0, &[]).is_err());
+
+        axis.axis_type = AxisType::Positioning;
+        axis.encoder_resolution = Some(1000.0);
+        axis.max_velocity = Some(100.0);
+        axis.max_acceleration = Some(500.0);
+        axis.lag_error_limit = Some(1.0);
+        assert!(axis.validate(0, &[]).is_ok());
+
+        axis.dynamics = Some(DynamicsConfig::rigid(-1.0));
+        let err = axis.validate(0, &[]).unwrap_err();
+        assert!(err.to_string().contains("dynamics.inertia"));
+    }
 }
@@ -7,7 +7,7 @@
 //! - `DriverDiagnostics` struct - Optional driver diagnostics
 
 use crate::hal::config::{AxisConfig, MachineConfig};
-use crate::hal::types::{HalCommands, HalStatus};
+use crate::hal::types::{CommandMode, HalCommands, HalStatus};
 use std::time::Duration;
 use thiserror::Error;
 
@@ -104,7 +104,8 @@ pub trait HalDriver: Send + Sync {
     /// Called every `cycle_time_us` microseconds by HAL Core's RT loop.
     /// Driver should:
     /// - Read hardware inputs (or simulate)
-    /// - Process commands from `HalCommands`
+    /// - Process commands from `HalCommands`, closing each axis loop on the
+    ///   setpoint selected by `AxisCommand::mode` plus feedforward offsets
     /// - Update internal state
     /// - Return status in `HalStatus`
     ///
@@ -117,7 +118,8 @@ pub trait HalDriver: Send + Sync {
     /// * `dt` - Actual elapsed time since last cycle (for physics/interpolation)
     ///
     /// # Returns
-    /// `HalStatus` containing current state of all axes and I/O.
+    /// `HalStatus` containing current state of all axes and I/O, including
+    /// drive torque / current feedback where available.
     fn cycle(&mut self, commands: &HalCommands, dt: Duration) -> HalStatus;
 
     /// Graceful shutdown of the driver.
@@ -143,6 +145,21 @@ pub trait HalDriver: Send + Sync {
         // Default: no-op
     }
 
+    /// Check if the driver can run axes in the given drive mode.
+    ///
+    /// HAL Core disables axes commanded in an unsupported mode.
+    /// Default: CSP only.
+    fn supports_command_mode(&self, mode: CommandMode) -> bool {
+        mode == CommandMode::CyclicSyncPosition
+    }
+
+    /// Check if the driver can run a specific axis in the given drive mode.
+    ///
+    /// Default: `supports_command_mode` for every axis.
+    fn supports_axis_command_mode(&self, _axis: usize, mode: CommandMode) -> bool {
+        self.supports_command_mode(mode)
+    }
+
     /// Check if driver supports hot-swap (runtime replacement).
     /// Default: false
     fn supports_hot_swap(&self) -> bool {
@@ -203,6 +220,16 @@ mod tests {
         assert!(err.to_string().contains("simulation"));
     }
 
+    #[test]
+    fn test_default_command_modes() {
+        let driver = TestDriver { initialized: false };
+        assert!(driver.supports_command_mode(CommandMode::CyclicSyncPosition));
+        assert!(!driver.supports_command_mode(CommandMode::CyclicSyncVelocity));
+        assert!(!driver.supports_command_mode(CommandMode::CyclicSyncTorque));
+        assert!(driver.supports_axis_command_mode(3, CommandMode::CyclicSyncPosition));
+        assert!(!driver.supports_axis_command_mode(3, CommandMode::CyclicSyncTorque));
+    }
+
     #[test]
     fn test_driver_diagnostics_default() {
         let diag = DriverDiagnostics::default();
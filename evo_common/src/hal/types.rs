@@ -4,6 +4,7 @@
 //! - `HalCommands` - Commands from Control Unit to HAL
 //! - `HalStatus` - Status from HAL to Control Unit
 //! - `AxisCommand` / `AxisStatus` - Per-axis data
+//! - `CommandMode` - Cyclic synchronous drive mode (CSP / CSV / CST)
 //! - `AnalogValue` - Dual representation for analog I/O
 
 use crate::consts::{MAX_AI, MAX_AO, MAX_AXES, MAX_DI, MAX_DO};
@@ -29,11 +30,50 @@ impl Default for HalCommands {
     }
 }
 
+/// Cyclic synchronous drive mode of an axis (CiA 402 CSP / CSV / CST).
+///
+/// Selects which setpoint of `AxisCommand` the drive closes its loop on.
+/// Feedforward offsets are added on top in every mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum CommandMode {
+    /// Cyclic synchronous position: drive tracks `target_position`.
+    #[default]
+    CyclicSyncPosition = 0,
+    /// Cyclic synchronous velocity: drive tracks `target_velocity`.
+    CyclicSyncVelocity = 1,
+    /// Cyclic synchronous torque: drive applies `target_torque`.
+    CyclicSyncTorque = 2,
+}
+
+impl CommandMode {
+    /// Decode from the SHM `u8` representation.
+    #[inline]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::CyclicSyncPosition),
+            1 => Some(Self::CyclicSyncVelocity),
+            2 => Some(Self::CyclicSyncTorque),
+            _ => None,
+        }
+    }
+}
+
 /// Per-axis command structure.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct AxisCommand {
-    /// Target position in user units
+    /// Drive mode selecting the active setpoint
+    pub mode: CommandMode,
+    /// Target position in user units (CSP)
     pub target_position: f64,
+    /// Target velocity in user units/sec (CSV)
+    pub target_velocity: f64,
+    /// Target torque in % of rated (CST)
+    pub target_torque: f64,
+    /// Velocity feedforward in user units/sec (CSP)
+    pub velocity_offset: f64,
+    /// Torque feedforward in % of rated (all modes)
+    pub torque_offset: f64,
     /// Enable axis
     pub enable: bool,
     /// Reset error
@@ -72,6 +112,12 @@ pub struct AxisStatus {
     pub actual_velocity: f64,
     /// Current lag error
     pub lag_error: f64,
+    /// Actual torque in % of rated (0 if the drive has no torque feedback)
+    pub actual_torque: f64,
+    /// Actual motor current in A (0 if the drive has no current feedback)
+    pub actual_current: f64,
+    /// Drive mode in effect
+    pub mode: CommandMode,
     /// Axis ready for motion
     pub ready: bool,
     /// Axis in error state
@@ -137,7 +183,10 @@ mod tests {
     #[test]
     fn test_axis_command_default() {
         let cmd = AxisCommand::default();
+        assert_eq!(cmd.mode, CommandMode::CyclicSyncPosition);
         assert_eq!(cmd.target_position, 0.0);
+        assert_eq!(cmd.target_velocity, 0.0);
+        assert_eq!(cmd.target_torque, 0.0);
         assert!(!cmd.enable);
         assert!(!cmd.reset);
         assert!(!cmd.reference);
@@ -149,6 +198,8 @@ mod tests {
         assert_eq!(status.actual_position, 0.0);
         assert_eq!(status.actual_velocity, 0.0);
         assert_eq!(status.lag_error, 0.0);
+        assert_eq!(status.actual_torque, 0.0);
+        assert_eq!(status.actual_current, 0.0);
         assert!(!status.ready);
         assert!(!status.error);
         assert!(!status.referenced);
@@ -158,6 +209,14 @@ mod tests {
         assert_eq!(status.error_code, ERROR_NONE);
     }
 
+    #[test]
+    fn test_command_mode_roundtrip() {
+        for v in 0..=2u8 {
+            assert_eq!(CommandMode::from_u8(v).unwrap() as u8, v);
+        }
+        assert!(CommandMode::from_u8(3).is_none());
+    }
+
     #[test]
     fn test_analog_value() {
         let v = AnalogValue::from_normalized(0.5);
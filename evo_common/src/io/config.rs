@@ -177,6 +177,39 @@ impl CurvePreset {
     }
 }
 
+// ─── Initial Output Value ───────────────────────────────────────────
+
+/// Initial output state: logical state for DO, engineering value for AO.
+///
+/// `init = true` and `init = 2.5` are both accepted; integer values are
+/// read as analog.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum InitValue {
+    /// DO logical state (before inversion).
+    Digital(bool),
+    /// AO value in engineering units.
+    Analog(f64),
+}
+
+impl InitValue {
+    /// Logical state: `Analog` is ON when nonzero.
+    pub fn as_bool(self) -> bool {
+        match self {
+            Self::Digital(v) => v,
+            Self::Analog(v) => v != 0.0,
+        }
+    }
+
+    /// Engineering value: `Digital` maps to 0.0 / 1.0.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Self::Digital(v) => f64::from(u8::from(v)),
+            Self::Analog(v) => v,
+        }
+    }
+}
+
 // ─── IoPoint ────────────────────────────────────────────────────────
 
 /// A single I/O point definition from `io.toml`.
@@ -225,9 +258,10 @@ pub struct IoPoint {
 
     // ── DO-specific ─────────────────────────────────────────────────
 
-    /// Initial logical state (before inversion). Default: false.
+    /// Initial state: DO logical state (before inversion), AO value in
+    /// engineering units. Default: false / 0.0.
     #[serde(default)]
-    pub init: Option<bool>,
+    pub init: Option<InitValue>,
 
     /// Invert logic-to-pin mapping. Default: false.
     #[serde(default)]
@@ -379,12 +413,29 @@ io = [
         let config = IoConfig::from_toml(toml_str).unwrap();
         let point = &config.groups["Outputs"].io[0];
         assert_eq!(point.io_type, IoPointType::Do);
-        assert_eq!(point.init, Some(true));
+        assert_eq!(point.init, Some(InitValue::Digital(true)));
         assert_eq!(point.inverted, Some(true));
         assert_eq!(point.keep_estop, Some(true));
         assert_eq!(point.pulse, Some(500));
     }
 
+    #[test]
+    fn parse_ao_init_value() {
+        let toml_str = r#"
+[Outputs]
+io = [
+    { type = "ao", pin = 0, max = 10.0, init = 2.5 },
+    { type = "ao", pin = 1, max = 10.0, init = 4 },
+]
+"#;
+        let config = IoConfig::from_toml(toml_str).unwrap();
+        let io = &config.groups["Outputs"].io;
+        assert_eq!(io[0].init, Some(InitValue::Analog(2.5)));
+        assert_eq!(io[1].init.map(InitValue::as_f64), Some(4.0));
+        assert!(io[1].init.unwrap().as_bool());
+        assert_eq!(InitValue::Digital(true).as_f64(), 1.0);
+    }
+
     #[test]
     fn analog_curve_evaluate() {
         let linear = AnalogCurve::default();
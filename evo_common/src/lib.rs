@@ -8,6 +8,7 @@
 //! - [`shm`] - Shared memory constants and configuration
 //! - [`hal`] - Hardware abstraction layer constants and configuration
 //! - [`config`] - Configuration loading traits and types
+//! - [`lockstep`] - Virtual-time lockstep execution of the RT modules
 //! - [`prelude`] - Common re-exports for convenience
 //!
 //! # Usage
@@ -29,6 +30,7 @@ pub mod consts;
 pub mod control_unit;
 pub mod hal;
 pub mod io;
+pub mod lockstep;
 pub mod prelude;
 pub mod shm;
 pub mod watchdog;
@@ -0,0 +1,187 @@
+//! # Lockstep Simulation
+//!
+//! Deterministic virtual-time execution of the RT modules.
+//!
+//! Instead of pacing themselves on the wall clock, HAL Core and the CU
+//! `CycleRunner` implement [`LockstepModule`] and are advanced one cycle at
+//! a time on a shared [`VirtualClock`]. Every tick runs each module exactly
+//! once, in registration order (HAL before CU), so SHM hand-over is always
+//! complete and hours of machine time run in seconds with bit-identical
+//! results.
+//!
+//! ```rust, ignore
+//! let mut clock = VirtualClock::new(Duration::from_millis(1));
+//! clock.run(&mut [&mut hal, &mut cu], 3_600_000)?; // one machine hour
+//! ```
+
+use std::time::Duration;
+use thiserror::Error;
+
+// ─── Error Type ─────────────────────────────────────────────────────
+
+/// A module failed to execute its lockstep cycle.
+#[derive(Debug, Error)]
+#[error("{module} failed at tick {tick}: {message}")]
+pub struct LockstepError {
+    /// Name of the failing module.
+    pub module: String,
+    /// Tick at which the module failed.
+    pub tick: u64,
+    /// Module error description.
+    pub message: String,
+}
+
+// ─── Module Trait ───────────────────────────────────────────────────
+
+/// An RT module that can be advanced by a [`VirtualClock`].
+pub trait LockstepModule {
+    /// Module name (for error reporting).
+    fn name(&self) -> &str;
+
+    /// Execute exactly one cycle at the clock's current virtual time.
+    ///
+    /// Implementations must not sleep or read the wall clock.
+    fn step(&mut self, clock: &VirtualClock) -> Result<(), String>;
+}
+
+// ─── Virtual Clock ──────────────────────────────────────────────────
+
+/// Shared simulated clock advancing in fixed cycle increments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualClock {
+    /// Duration of one tick.
+    cycle_time: Duration,
+    /// Ticks elapsed since start.
+    ticks: u64,
+}
+
+impl VirtualClock {
+    /// Create a clock at virtual time 0.
+    ///
+    /// # Panics
+    /// Panics if `cycle_time` is zero.
+    pub fn new(cycle_time: Duration) -> Self {
+        assert!(!cycle_time.is_zero(), "lockstep cycle time must be > 0");
+        Self { cycle_time, ticks: 0 }
+    }
+
+    /// Duration of one tick.
+    pub fn cycle_time(&self) -> Duration {
+        self.cycle_time
+    }
+
+    /// Ticks elapsed since start.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Virtual time elapsed since start.
+    pub fn now(&self) -> Duration {
+        let nanos = self.cycle_time.as_nanos() * u128::from(self.ticks);
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+
+    /// Advance one tick and step every module once, in order.
+    ///
+    /// # Errors
+    /// Returns the first module failure; later modules are not stepped.
+    pub fn tick(&mut self, modules: &mut [&mut dyn LockstepModule]) -> Result<(), LockstepError> {
+        self.ticks += 1;
+        for module in modules.iter_mut() {
+            module.step(self).map_err(|message| LockstepError {
+                module: module.name().to_string(),
+                tick: self.ticks,
+                message,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Run `ticks` ticks.
+    ///
+    /// # Errors
+    /// Stops at the first module failure.
+    pub fn run(
+        &mut self,
+        modules: &mut [&mut dyn LockstepModule],
+        ticks: u64,
+    ) -> Result<(), LockstepError> {
+        for _ in 0..ticks {
+            self.tick(modules)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the virtual time and the global step order.
+    struct Recorder {
+        name: &'static str,
+        seen: Vec<(u64, Duration)>,
+        fail_at: Option<u64>,
+    }
+
+    impl Recorder {
+        fn new(name: &'static str) -> Self {
+            Self { name, seen: Vec::new(), fail_at: None }
+        }
+    }
+
+    impl LockstepModule for Recorder {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn step(&mut self, clock: &VirtualClock) -> Result<(), String> {
+            if self.fail_at == Some(clock.ticks()) {
+                return Err("boom".into());
+            }
+            self.seen.push((clock.ticks(), clock.now()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn clock_advances_every_module_once_per_tick() {
+        let mut clock = VirtualClock::new(Duration::from_millis(1));
+        let mut a = Recorder::new("a");
+        let mut b = Recorder::new("b");
+        clock.run(&mut [&mut a, &mut b], 3).unwrap();
+
+        assert_eq!(clock.ticks(), 3);
+        assert_eq!(clock.now(), Duration::from_millis(3));
+        let expected: Vec<_> = (1..=3).map(|t| (t, Duration::from_millis(t))).collect();
+        assert_eq!(a.seen, expected);
+        assert_eq!(b.seen, expected);
+    }
+
+    #[test]
+    fn failure_stops_the_tick() {
+        let mut clock = VirtualClock::new(Duration::from_micros(500));
+        let mut a = Recorder::new("hal");
+        let mut b = Recorder::new("cu");
+        a.fail_at = Some(2);
+
+        let err = clock.run(&mut [&mut a, &mut b], 5).unwrap_err();
+        assert_eq!(err.module, "hal");
+        assert_eq!(err.tick, 2);
+        assert_eq!(err.to_string(), "hal failed at tick 2: boom");
+        assert_eq!(b.seen.len(), 1, "later modules not stepped");
+    }
+
+    #[test]
+    fn virtual_time_beyond_u32_ticks() {
+        let mut clock = VirtualClock::new(Duration::from_millis(1));
+        clock.ticks = 5_000_000_000;
+        assert_eq!(clock.now(), Duration::from_secs(5_000_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle time")]
+    fn zero_cycle_time_rejected() {
+        let _ = VirtualClock::new(Duration::ZERO);
+    }
+}
@@ -4,6 +4,10 @@
 //! - `p2p`: The P2P lock-free segment writer/reader (sole SHM transport).
 //! - `consts`: SHM size limits and cache line constants.
 //! - `io_helpers`: Bit-packed digital I/O bank helpers.
+//! - `layout`: Field-level payload layout hashing (`ShmLayout`).
+//! - `schema`: JSON segment schemas for decoding outside Rust.
+//! - `recorder`: Segment recorder / replayer for post-mortem analysis.
+//! - `request`: Request/response channels over paired P2P segments.
 //!
 //! Future submodules (added when implementing US7):
 //! - `segments`: All 15 typed SHM segment structs.
@@ -12,5 +16,9 @@
 pub mod consts;
 pub mod conversions;
 pub mod io_helpers;
+pub mod layout;
 pub mod p2p;
+pub mod recorder;
+pub mod request;
+pub mod schema;
 pub mod segments;
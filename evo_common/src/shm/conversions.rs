@@ -12,7 +12,7 @@
 
 use crate::consts::{MAX_AI, MAX_AO, MAX_AXES};
 use crate::hal::types::{
-    AnalogValue, AxisCommand, AxisStatus, HalCommands, HalStatus,
+    AnalogValue, AxisCommand, AxisStatus, CommandMode, HalCommands, HalStatus,
 };
 use crate::shm::io_helpers::{pack_bools, unpack_bools};
 use crate::shm::segments::{
@@ -23,8 +23,7 @@ use crate::shm::segments::{
 
 /// Convert `HalStatus` into `HalToCuSegment` for SHM write.
 ///
-/// - Axis feedback: position, velocity, torque (lag_error maps to torque
-///   estimate), boolean flags packed as `u8`.
+/// - Axis feedback: see [`hal_axis_feedback`].
 /// - DI bank: `[bool; 1024]` → `[u64; 16]` bit-packed.
 /// - AI values: extracts `.scaled` from each `AnalogValue`.
 ///
@@ -39,16 +38,7 @@ pub fn hal_status_to_segment(status: &HalStatus, axis_count: u8) -> HalToCuSegme
     // Per-axis feedback.
     let count = (axis_count as usize).min(MAX_AXES as usize);
     for i in 0..count {
-        let src = &status.axes[i];
-        seg.axes[i] = HalAxisFeedback {
-            position: src.actual_position,
-            velocity: src.actual_velocity,
-            torque_estimate: src.lag_error, // Best available torque proxy.
-            drive_ready: src.ready as u8,
-            drive_fault: src.error as u8,
-            referenced: src.referenced as u8,
-            active: (src.ready || src.moving || src.referencing) as u8,
-        };
+        seg.axes[i] = hal_axis_feedback(&status.axes[i]);
     }
 
     // DI bank: bool[] → bit-packed u64[].
@@ -62,11 +52,31 @@ pub fn hal_status_to_segment(status: &HalStatus, axis_count: u8) -> HalToCuSegme
     seg
 }
 
+/// Pack one axis status into its SHM feedback form.
+///
+/// Position, velocity, drive torque / current and the active drive mode
+/// are copied as reported; boolean flags are packed as `u8`.
+pub fn hal_axis_feedback(src: &AxisStatus) -> HalAxisFeedback {
+    HalAxisFeedback {
+        position: src.actual_position,
+        velocity: src.actual_velocity,
+        torque_estimate: src.actual_torque,
+        current: src.actual_current,
+        drive_ready: src.ready as u8,
+        drive_fault: src.error as u8,
+        referenced: src.referenced as u8,
+        active: (src.ready || src.moving || src.referencing) as u8,
+        command_mode: src.mode as u8,
+    }
+}
+
 // ─── CuToHalSegment → HalCommands ──────────────────────────────────
 
 /// Convert `CuToHalSegment` from SHM read into `HalCommands`.
 ///
-/// - Axis commands: position, enable, brake (mapped to reset/reference defaults).
+/// - Axis commands: drive mode, position / velocity / torque setpoints and
+///   feedforward offsets, enable. In CSP the CU target velocity becomes the
+///   velocity feedforward. An unknown mode disables the axis.
 /// - DO bank: `[u64; 16]` → `[bool; 1024]`.
 /// - AO values: direct copy.
 ///
@@ -80,9 +90,18 @@ pub fn segment_to_hal_commands(seg: &CuToHalSegment) -> HalCommands {
     let count = (seg.axis_count as usize).min(MAX_AXES as usize);
     for i in 0..count {
         let src = &seg.axes[i];
+        let mode = CommandMode::from_u8(src.command_mode);
         cmds.axes[i] = AxisCommand {
+            mode: mode.unwrap_or_default(),
             target_position: src.target_position,
-            enable: src.enable != 0,
+            target_velocity: src.target_velocity,
+            target_torque: src.calculated_torque,
+            velocity_offset: match mode {
+                Some(CommandMode::CyclicSyncPosition) => src.target_velocity,
+                _ => 0.0,
+            },
+            torque_offset: src.torque_offset,
+            enable: src.enable != 0 && mode.is_some(),
             reset: false,     // Not transmitted via SHM — CU uses state machine.
             reference: false, // Not transmitted via SHM — CU uses state machine.
         };
@@ -114,11 +133,12 @@ pub fn hal_commands_to_segment(cmds: &HalCommands, axis_count: u8) -> CuToHalSeg
         let src = &cmds.axes[i];
         seg.axes[i] = CuAxisCommand {
             target_position: src.target_position,
-            target_velocity: 0.0, // Not in legacy AxisCommand.
-            calculated_torque: 0.0,
-            torque_offset: 0.0,
+            target_velocity: src.target_velocity,
+            calculated_torque: src.target_torque,
+            torque_offset: src.torque_offset,
             enable: src.enable as u8,
             brake_release: 0,
+            command_mode: src.mode as u8,
         };
     }
 
@@ -144,7 +164,10 @@ pub fn segment_to_hal_status(seg: &HalToCuSegment) -> HalStatus {
         status.axes[i] = AxisStatus {
             actual_position: src.position,
             actual_velocity: src.velocity,
-            lag_error: src.torque_estimate,
+            lag_error: 0.0, // Not transmitted via SHM.
+            actual_torque: src.torque_estimate,
+            actual_current: src.current,
+            mode: CommandMode::from_u8(src.command_mode).unwrap_or_default(),
             ready: src.drive_ready != 0,
             error: src.drive_fault != 0,
             referenced: src.referenced != 0,
@@ -184,6 +207,9 @@ mod tests {
                 actual_position: 100.0 + i as f64,
                 actual_velocity: 10.0 + i as f64,
                 lag_error: 0.01 * (i + 1) as f64,
+                actual_torque: 5.0 * i as f64,
+                actual_current: 0.5 * i as f64,
+                mode: CommandMode::CyclicSyncPosition,
                 ready: i % 2 == 0,
                 error: false,
                 referenced: true,
@@ -222,7 +248,9 @@ mod tests {
         // Verify axis 0.
         assert_eq!(seg.axes[0].position, 100.0);
         assert_eq!(seg.axes[0].velocity, 10.0);
-        assert!((seg.axes[0].torque_estimate - 0.01).abs() < 1e-10);
+        assert_eq!(seg.axes[0].torque_estimate, 0.0);
+        assert_eq!(seg.axes[2].torque_estimate, 10.0); // Drive torque, not lag.
+        assert_eq!(seg.axes[2].current, 1.0);
         assert_eq!(seg.axes[0].drive_ready, 1); // i=0, even → ready
         assert_eq!(seg.axes[0].drive_fault, 0);
         assert_eq!(seg.axes[0].referenced, 1);
@@ -297,6 +325,54 @@ mod tests {
         assert_eq!(cmds2.analog_outputs[99], 2.71);
     }
 
+    #[test]
+    fn command_modes_and_setpoints_reach_driver() {
+        const MODES: [CommandMode; 3] = [
+            CommandMode::CyclicSyncPosition,
+            CommandMode::CyclicSyncVelocity,
+            CommandMode::CyclicSyncTorque,
+        ];
+        let mut seg = CuToHalSegment {
+            axis_count: 3,
+            ..CuToHalSegment::default()
+        };
+        for (i, mode) in MODES.into_iter().enumerate() {
+            seg.axes[i] = CuAxisCommand {
+                target_position: 1.0,
+                target_velocity: 2.0,
+                calculated_torque: 3.0,
+                torque_offset: 4.0,
+                enable: 1,
+                brake_release: 1,
+                command_mode: mode as u8,
+            };
+        }
+
+        let cmds = segment_to_hal_commands(&seg);
+        for (i, mode) in MODES.into_iter().enumerate() {
+            let ax = &cmds.axes[i];
+            assert_eq!(ax.mode, mode);
+            assert_eq!(ax.target_position, 1.0);
+            assert_eq!(ax.target_velocity, 2.0);
+            assert_eq!(ax.target_torque, 3.0);
+            assert_eq!(ax.torque_offset, 4.0);
+            assert!(ax.enable);
+        }
+        // Velocity feedforward only in CSP.
+        assert_eq!(cmds.axes[0].velocity_offset, 2.0);
+        assert_eq!(cmds.axes[1].velocity_offset, 0.0);
+
+        // Round-trip keeps mode and setpoints.
+        let back = hal_commands_to_segment(&cmds, 3);
+        assert_eq!(back.axes[2].command_mode, CommandMode::CyclicSyncTorque as u8);
+        assert_eq!(back.axes[2].calculated_torque, 3.0);
+        assert_eq!(back.axes[1].target_velocity, 2.0);
+
+        // Unknown mode: axis is not enabled.
+        seg.axes[0].command_mode = 9;
+        assert!(!segment_to_hal_commands(&seg).axes[0].enable);
+    }
+
     #[test]
     fn empty_status_converts_cleanly() {
         let status = HalStatus::default();
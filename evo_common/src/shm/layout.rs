@@ -0,0 +1,570 @@
+//! Field-level layout descriptors for P2P segment payloads.
+//!
+//! [`ShmLayout`] describes a `#[repr(C)]` payload at compile time: field
+//! names, offsets, sizes and the layout of every field type, recursively.
+//! The resulting [`ShmLayout::LAYOUT_HASH`] is what `TypedP2pWriter` stores
+//! in the header `version_hash` and `TypedP2pReader` checks on first read,
+//! so swapping two equal-size fields or changing a field type is detected
+//! even when `size_of` / `align_of` are unchanged.
+//!
+//! Payload structs implement the trait with [`impl_shm_layout!`]; the macro
+//! refuses to compile if a field is missing from the list. Integer fields
+//! that carry a `#[repr(uN)]` enum are tagged `field as Enum`, with the enum
+//! described by [`impl_shm_enum!`], so schemas can name discriminants
+//! (see `shm::schema`).
+//!
+//! ## Layout Table
+//!
+//! The writer also publishes the flattened field list after the payload
+//! (`[P2pSegmentHeader][T][layout table]`), so a reader that hits a hash
+//! mismatch can report which fields differ instead of two opaque hashes.
+
+use core::mem::{align_of, size_of};
+
+// ─── Trait ──────────────────────────────────────────────────────────
+
+/// Compile-time layout description of a SHM payload type.
+///
+/// Implemented for the numeric primitives, fixed-size arrays, and (via
+/// [`impl_shm_layout!`]) for every `#[repr(C)]` segment struct.
+///
+/// # Safety
+///
+/// Every bit pattern of `size_of::<Self>()` bytes must be a valid `Self`.
+/// `TypedP2pReader::read_with` and `TypedP2pWriter::commit_with` hand out
+/// `&Self` / `&mut Self` directly over the mapped segment, whose bytes
+/// another process writes, and readers start from an all-zero payload.
+/// This holds for the integer and float primitives, for arrays of valid
+/// types, and for `#[repr(C)]` structs whose fields all implement
+/// `ShmLayout`; `bool`, `char`, references and enums must be stored as
+/// raw integers instead.
+pub unsafe trait ShmLayout: Copy {
+    /// Type name shown in layout diffs (element type for arrays).
+    const TYPE_NAME: &'static str;
+
+    /// Fields in declaration order; empty for primitives.
+    const FIELDS: &'static [FieldLayout];
+
+    /// Hash over size, alignment, and every field's name, offset and
+    /// layout hash.
+    const LAYOUT_HASH: u32;
+
+    /// Element count: array length (product for nested arrays), 1 otherwise.
+    const COUNT: usize = 1;
+}
+
+/// A `#[repr(uN)]` enum stored as a raw integer in SHM payloads.
+///
+/// Implemented with [`impl_shm_enum!`].
+pub trait ShmEnum {
+    /// Enum type name.
+    const ENUM_NAME: &'static str;
+
+    /// Every variant with its discriminant.
+    const VARIANTS: &'static [EnumVariant];
+}
+
+/// One variant of a [`ShmEnum`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnumVariant {
+    /// Variant name.
+    pub name: &'static str,
+    /// Discriminant value.
+    pub value: u64,
+}
+
+/// Enum carried by an integer field.
+#[derive(Debug, Clone, Copy)]
+pub struct EnumLayout {
+    /// `ShmEnum::ENUM_NAME`.
+    pub name: &'static str,
+    /// `ShmEnum::VARIANTS`.
+    pub variants: &'static [EnumVariant],
+}
+
+/// One field of a [`ShmLayout`] struct.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldLayout {
+    /// Field name.
+    pub name: &'static str,
+    /// Byte offset within the parent struct.
+    pub offset: usize,
+    /// Field size in bytes.
+    pub size: usize,
+    /// `ShmLayout::TYPE_NAME` of the field type.
+    pub type_name: &'static str,
+    /// `ShmLayout::LAYOUT_HASH` of the field type.
+    pub hash: u32,
+    /// Nested fields of the field type (element fields for arrays).
+    pub fields: &'static [FieldLayout],
+    /// `ShmLayout::COUNT` of the field type.
+    pub count: usize,
+    /// Enum decoded from the field's integer elements, if tagged.
+    pub enumeration: Option<EnumLayout>,
+}
+
+impl FieldLayout {
+    /// Describe field `name` at `offset`; the field type is inferred from
+    /// the `access` projection (e.g. `|s: &Seg| &s.value`).
+    pub const fn of<T, F: ShmLayout>(
+        name: &'static str,
+        offset: usize,
+        _access: fn(&T) -> &F,
+    ) -> Self {
+        Self {
+            name,
+            offset,
+            size: size_of::<F>(),
+            type_name: F::TYPE_NAME,
+            hash: F::LAYOUT_HASH,
+            fields: F::FIELDS,
+            count: F::COUNT,
+            enumeration: None,
+        }
+    }
+
+    /// Tag the field's integer elements as discriminants of `E`.
+    ///
+    /// Fails const evaluation if `E` is not the size of one element.
+    pub const fn with_enum<E: ShmEnum>(mut self) -> Self {
+        assert!(
+            size_of::<E>() * self.count == self.size,
+            "enum size differs from field element size"
+        );
+        self.enumeration = Some(EnumLayout {
+            name: E::ENUM_NAME,
+            variants: E::VARIANTS,
+        });
+        self
+    }
+}
+
+// ─── Hashing ────────────────────────────────────────────────────────
+
+const FNV_OFFSET: u32 = 0x811C_9DC5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+const fn fnv_bytes(mut hash: u32, bytes: &[u8]) -> u32 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+const fn fnv_usize(hash: u32, value: usize) -> u32 {
+    fnv_bytes(hash, &(value as u64).to_le_bytes())
+}
+
+/// Layout hash of a primitive: type name and size.
+pub const fn primitive_layout_hash<T>(type_name: &str) -> u32 {
+    fnv_usize(fnv_bytes(FNV_OFFSET, type_name.as_bytes()), size_of::<T>())
+}
+
+/// Layout hash of a struct from its size, alignment and fields.
+pub const fn struct_layout_hash<T>(fields: &[FieldLayout]) -> u32 {
+    let mut hash = fnv_usize(FNV_OFFSET, size_of::<T>());
+    hash = fnv_usize(hash, align_of::<T>());
+    let mut i = 0;
+    while i < fields.len() {
+        hash = fnv_bytes(hash, fields[i].name.as_bytes());
+        hash = fnv_usize(hash, fields[i].offset);
+        hash = fnv_usize(hash, fields[i].hash as usize);
+        i += 1;
+    }
+    hash
+}
+
+// ─── Implementations ────────────────────────────────────────────────
+
+macro_rules! impl_primitive_layout {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            // SAFETY: every bit pattern is a valid integer or float.
+            unsafe impl ShmLayout for $ty {
+                const TYPE_NAME: &'static str = stringify!($ty);
+                const FIELDS: &'static [FieldLayout] = &[];
+                const LAYOUT_HASH: u32 = primitive_layout_hash::<$ty>(stringify!($ty));
+            }
+        )*
+    };
+}
+
+impl_primitive_layout!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+// SAFETY: an array is valid when each element is.
+unsafe impl<T: ShmLayout, const N: usize> ShmLayout for [T; N] {
+    const TYPE_NAME: &'static str = T::TYPE_NAME;
+    const FIELDS: &'static [FieldLayout] = T::FIELDS;
+    const LAYOUT_HASH: u32 = fnv_usize(fnv_usize(FNV_OFFSET, T::LAYOUT_HASH as usize), N);
+    const COUNT: usize = N * T::COUNT;
+}
+
+/// Implement [`ShmLayout`] for `#[repr(C)]` structs by listing their fields.
+///
+/// Every field must be listed (checked at compile time by an exhaustive
+/// destructuring pattern) and every field type must implement `ShmLayout`.
+/// `field as Enum` tags an integer field with a [`ShmEnum`]; it does not
+/// change the layout hash.
+///
+/// The macro emits an `unsafe impl`: the struct must be `#[repr(C)]` so
+/// that, with every field a `ShmLayout` type, any bit pattern is a valid
+/// value (see the trait's safety contract).
+///
+/// ```
+/// use evo_common::{impl_shm_enum, impl_shm_layout};
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(u8)]
+/// enum Mode {
+///     Off = 0,
+///     On = 1,
+/// }
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct Sample {
+///     position: f64,
+///     flags: [u8; 8],
+///     mode: u8,
+/// }
+///
+/// impl_shm_enum!(Mode { Off, On });
+/// impl_shm_layout!(Sample { position, flags, mode as Mode });
+/// ```
+///
+/// Omitting a field is a compile error:
+///
+/// ```compile_fail
+/// # use evo_common::impl_shm_layout;
+/// # #[derive(Clone, Copy)]
+/// # #[repr(C)]
+/// # struct Sample {
+/// #     position: f64,
+/// #     flags: [u8; 8],
+/// # }
+/// impl_shm_layout!(Sample { position });
+/// ```
+#[macro_export]
+macro_rules! impl_shm_layout {
+    ($($ty:ident { $($field:ident $(as $enum_ty:ident)?),* $(,)? }),* $(,)?) => {
+        $(
+            // SAFETY: `#[repr(C)]` struct whose fields all implement
+            // `ShmLayout` (required by `FieldLayout::of`).
+            unsafe impl $crate::shm::layout::ShmLayout for $ty {
+                const TYPE_NAME: &'static str = stringify!($ty);
+                const FIELDS: &'static [$crate::shm::layout::FieldLayout] = &[$(
+                    $crate::shm::layout::FieldLayout::of(
+                        stringify!($field),
+                        ::core::mem::offset_of!($ty, $field),
+                        |v: &$ty| &v.$field,
+                    )$(.with_enum::<$enum_ty>())?,
+                )*];
+                const LAYOUT_HASH: u32 =
+                    $crate::shm::layout::struct_layout_hash::<$ty>(Self::FIELDS);
+            }
+
+            const _: () = {
+                // Fails to compile if a field is missing from the list.
+                #[allow(dead_code)]
+                fn exhaustive(v: &$ty) {
+                    let $ty { $($field: _),* } = v;
+                }
+            };
+        )*
+    };
+}
+
+/// Implement [`ShmEnum`] for fieldless `#[repr(uN)]` enums by listing
+/// their variants.
+///
+/// Every variant must be listed (checked at compile time by an exhaustive
+/// `match`).
+#[macro_export]
+macro_rules! impl_shm_enum {
+    ($($ty:ident { $($variant:ident),* $(,)? }),* $(,)?) => {
+        $(
+            impl $crate::shm::layout::ShmEnum for $ty {
+                const ENUM_NAME: &'static str = stringify!($ty);
+                const VARIANTS: &'static [$crate::shm::layout::EnumVariant] = &[$(
+                    $crate::shm::layout::EnumVariant {
+                        name: stringify!($variant),
+                        value: $ty::$variant as u64,
+                    },
+                )*];
+            }
+
+            const _: () = {
+                // Fails to compile if a variant is missing from the list.
+                #[allow(dead_code)]
+                fn exhaustive(v: $ty) {
+                    match v {
+                        $($ty::$variant => {})*
+                    }
+                }
+            };
+        )*
+    };
+}
+
+// ─── Flattened Layout ───────────────────────────────────────────────
+
+/// A primitive (leaf) field with its full path from the payload root.
+///
+/// Nested struct fields are joined with `.` (`axes.position`); array
+/// element fields are described by the first element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutEntry {
+    /// Dotted field path.
+    pub path: String,
+    /// Leaf type name.
+    pub type_name: String,
+    /// Byte offset from the payload start.
+    pub offset: usize,
+    /// Size in bytes (whole array for primitive arrays).
+    pub size: usize,
+}
+
+/// Flatten `T`'s fields into leaf entries.
+pub fn flatten<T: ShmLayout>() -> Vec<LayoutEntry> {
+    let mut out = Vec::new();
+    flatten_into(T::FIELDS, "", 0, &mut out);
+    out
+}
+
+fn flatten_into(fields: &[FieldLayout], prefix: &str, base: usize, out: &mut Vec<LayoutEntry>) {
+    for field in fields {
+        let path = format!("{prefix}{}", field.name);
+        if field.fields.is_empty() {
+            out.push(LayoutEntry {
+                path,
+                type_name: field.type_name.to_string(),
+                offset: base + field.offset,
+                size: field.size,
+            });
+        } else {
+            flatten_into(field.fields, &format!("{path}."), base + field.offset, out);
+        }
+    }
+}
+
+/// Readable differences between the reader's (`expected`) and writer's
+/// (`actual`) layouts, one line per differing field.
+pub fn layout_diff(expected: &[LayoutEntry], actual: &[LayoutEntry]) -> Vec<String> {
+    let describe = |e: &LayoutEntry| format!("{}@{}+{}", e.type_name, e.offset, e.size);
+    let mut diff = Vec::new();
+    for exp in expected {
+        match actual.iter().find(|a| a.path == exp.path) {
+            Some(act) if act != exp => {
+                diff.push(format!("{}: {} → {}", exp.path, describe(exp), describe(act)));
+            }
+            Some(_) => {}
+            None => diff.push(format!("{}: missing in writer", exp.path)),
+        }
+    }
+    for act in actual {
+        if !expected.iter().any(|e| e.path == act.path) {
+            diff.push(format!("{}: only in writer", act.path));
+        }
+    }
+    diff
+}
+
+// ─── SHM Table Encoding ─────────────────────────────────────────────
+//
+// Table layout (little-endian):
+//   [0..4]  magic  b"EVOL"
+//   [4..8]  count  u32
+//   [8..]   count × 64-byte records:
+//             [0..4]   offset    u32
+//             [4..8]   size      u32
+//             [8..48]  path      [u8; 40]  (NUL-padded, truncated)
+//             [48..64] type_name [u8; 16]  (NUL-padded, truncated)
+
+const TABLE_MAGIC: [u8; 4] = *b"EVOL";
+const TABLE_HEADER: usize = 8;
+const RECORD_SIZE: usize = 64;
+const PATH_LEN: usize = 40;
+const TYPE_LEN: usize = 16;
+
+/// Encoded size of a layout table with `count` entries.
+pub(crate) const fn table_size(count: usize) -> usize {
+    TABLE_HEADER + count * RECORD_SIZE
+}
+
+/// Encode entries into the SHM layout table format.
+pub(crate) fn encode_table(entries: &[LayoutEntry]) -> Vec<u8> {
+    let mut buf = vec![0u8; table_size(entries.len())];
+    buf[..4].copy_from_slice(&TABLE_MAGIC);
+    buf[4..8].copy_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (entry, rec) in entries.iter().zip(buf[TABLE_HEADER..].chunks_exact_mut(RECORD_SIZE)) {
+        rec[0..4].copy_from_slice(&(entry.offset as u32).to_le_bytes());
+        rec[4..8].copy_from_slice(&(entry.size as u32).to_le_bytes());
+        copy_truncated(&mut rec[8..8 + PATH_LEN], &entry.path);
+        copy_truncated(&mut rec[8 + PATH_LEN..8 + PATH_LEN + TYPE_LEN], &entry.type_name);
+    }
+    buf
+}
+
+/// Decode a layout table; `None` if `bytes` holds no valid table.
+pub(crate) fn decode_table(bytes: &[u8]) -> Option<Vec<LayoutEntry>> {
+    if bytes.len() < TABLE_HEADER || bytes[..4] != TABLE_MAGIC {
+        return None;
+    }
+    let count = u32::from_le_bytes(bytes[4..8].try_into().ok()?) as usize;
+    let records = bytes.get(TABLE_HEADER..table_size(count))?;
+    let text = |b: &[u8]| {
+        let end = b.iter().position(|&c| c == 0).unwrap_or(b.len());
+        String::from_utf8_lossy(&b[..end]).into_owned()
+    };
+    Some(
+        records
+            .chunks_exact(RECORD_SIZE)
+            .map(|rec| LayoutEntry {
+                offset: u32::from_le_bytes(rec[0..4].try_into().unwrap()) as usize,
+                size: u32::from_le_bytes(rec[4..8].try_into().unwrap()) as usize,
+                path: text(&rec[8..8 + PATH_LEN]),
+                type_name: text(&rec[8 + PATH_LEN..8 + PATH_LEN + TYPE_LEN]),
+            })
+            .collect(),
+    )
+}
+
+/// Copy `s` into `dst`, truncated at a char boundary, NUL-padded.
+fn copy_truncated(dst: &mut [u8], s: &str) {
+    let mut end = s.len().min(dst.len());
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    dst[..end].copy_from_slice(&s.as_bytes()[..end]);
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Inner {
+        a: f64,
+        b: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Swapped {
+        b: f64,
+        a: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Retyped {
+        a: f64,
+        b: u64,
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Outer {
+        count: u8,
+        items: [Inner; 4],
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(u8)]
+    enum Mode {
+        Idle = 0,
+        Run = 4,
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Tagged {
+        mode: u8,
+        modes: [u8; 3],
+        grid: [[u16; 2]; 3],
+    }
+
+    impl_shm_enum!(Mode { Idle, Run });
+
+    impl_shm_layout!(
+        Inner { a, b },
+        Swapped { b, a },
+        Retyped { a, b },
+        Outer { count, items },
+        Tagged { mode as Mode, modes as Mode, grid },
+    );
+
+    #[test]
+    fn equal_size_changes_change_hash() {
+        assert_eq!(size_of::<Inner>(), size_of::<Swapped>());
+        assert_ne!(Inner::LAYOUT_HASH, Swapped::LAYOUT_HASH, "field order");
+        assert_ne!(Inner::LAYOUT_HASH, Retyped::LAYOUT_HASH, "field type");
+        assert_ne!(<[Inner; 4]>::LAYOUT_HASH, <[Inner; 5]>::LAYOUT_HASH, "array length");
+        assert_ne!(u64::LAYOUT_HASH, f64::LAYOUT_HASH);
+        assert_ne!(u64::LAYOUT_HASH, i64::LAYOUT_HASH);
+    }
+
+    #[test]
+    fn flatten_nested_fields() {
+        let flat = flatten::<Outer>();
+        let paths: Vec<_> = flat.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["count", "items.a", "items.b"]);
+        assert_eq!(flat[2].offset, 16);
+        assert_eq!(flat[2].type_name, "f64");
+    }
+
+    #[test]
+    fn counts_and_enum_tags() {
+        let [mode, modes, grid] = Tagged::FIELDS else {
+            panic!("expected 3 fields");
+        };
+        assert_eq!((mode.count, modes.count, grid.count), (1, 3, 6));
+        assert_eq!(Outer::FIELDS[1].count, 4);
+
+        let tag = modes.enumeration.unwrap();
+        assert_eq!(tag.name, "Mode");
+        assert_eq!(
+            tag.variants,
+            [
+                EnumVariant { name: "Idle", value: 0 },
+                EnumVariant { name: "Run", value: 4 },
+            ]
+        );
+        assert!(grid.enumeration.is_none());
+    }
+
+    #[test]
+    fn diff_reports_moved_retyped_and_missing_fields() {
+        let diff = layout_diff(&flatten::<Inner>(), &flatten::<Swapped>());
+        assert_eq!(diff, ["a: f64@0+8 → f64@8+8", "b: f64@8+8 → f64@0+8"]);
+
+        let diff = layout_diff(&flatten::<Inner>(), &flatten::<Retyped>());
+        assert_eq!(diff, ["b: f64@8+8 → u64@8+8"]);
+
+        let diff = layout_diff(&flatten::<Inner>(), &flatten::<Outer>());
+        assert_eq!(diff.len(), 5);
+        assert!(diff.contains(&"a: missing in writer".to_string()));
+        assert!(diff.contains(&"items.b: only in writer".to_string()));
+    }
+
+    #[test]
+    fn table_roundtrip() {
+        let mut entries = flatten::<Outer>();
+        entries[0].path = "x".repeat(60);
+        let bytes = encode_table(&entries);
+        assert_eq!(bytes.len(), table_size(3));
+
+        let decoded = decode_table(&bytes).unwrap();
+        assert_eq!(decoded[0].path, "x".repeat(PATH_LEN));
+        assert_eq!(decoded[1..], entries[1..]);
+
+        assert!(decode_table(&[0u8; 16]).is_none());
+        assert!(decode_table(&bytes[..bytes.len() - 1]).is_none());
+    }
+}
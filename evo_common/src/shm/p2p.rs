@@ -4,6 +4,11 @@
 //! the `ModuleAbbrev` enum, `ShmError`, and the lock-free `TypedP2pWriter<T>`
 //! / `TypedP2pReader<T>` for inter-process communication.
 //!
+//! `TypedP2pWriter` / `TypedP2pReader` carry the latest value only. Event
+//! streams and command queues use the [`P2pRing`] segment instead
+//! (`P2pRingWriter<T, N>` / `P2pRingReader<T, N>`): N slots, per-reader
+//! cursors and overflow detection.
+//!
 //! ## Lock-Free Protocol
 //!
 //! The writer uses an odd/even `write_seq` protocol:
@@ -15,14 +20,34 @@
 //! prevention is handled by an exclusive flock on a separate `.lock` file
 //! under `/dev/shm/`.
 //!
+//! ## Blocking Reads
+//!
+//! Non-RT readers can block on new data with
+//! [`TypedP2pReader::read_wait`] instead of polling. The reader registers
+//! a short lease in the header `wait_until_ms` word and sleeps on a futex
+//! on `write_seq`, renewing the lease until it returns. The writer issues
+//! `FUTEX_WAKE` after `commit()` only while a lease is running, so the RT
+//! write path stays syscall-free without waiters — also once a waiting
+//! reader has died, at most [`WAIT_LEASE`] after its last renewal.
+//!
 //! ## Segment Naming
 //!
 //! All segments are created under `/dev/shm/` with the name `evo_<name>`.
 //! The name follows the convention `<source>_<dest>`, e.g. `hal_cu`.
+//!
+//! ## Instances
+//!
+//! Several EVO stacks can share one host: each `_in` constructor takes a
+//! [`ShmNamespace`], and instance `<id>` uses `evo-<id>_<name>` instead.
+//! [`SegmentDiscovery`] lists and cleans up one namespace at a time, so an
+//! instance never sees (or removes) another instance's segments.
 
+use std::io::Write;
 use std::marker::PhantomData;
 use std::os::unix::io::OwnedFd;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use nix::fcntl::{Flock, FlockArg, OFlag};
 use nix::sys::mman::{self, MapFlags, MmapAdvise, ProtFlags};
@@ -31,6 +56,9 @@ use nix::unistd;
 use static_assertions::const_assert_eq;
 use thiserror::Error;
 
+use crate::shm::layout::{self, ShmLayout};
+use crate::shm::schema;
+
 // ─── Constants ──────────────────────────────────────────────────────
 
 /// Magic bytes identifying a valid P2P segment: `"EVO_P2P\0"`.
@@ -39,8 +67,11 @@ pub const EVO_P2P_MAGIC: [u8; 8] = *b"EVO_P2P\0";
 /// Page size for segment data allocation.
 pub const PAGE_SIZE: usize = 4096;
 
-/// SHM name prefix for all EVO segments.
-const SHM_PREFIX: &str = "/evo_";
+/// SHM name prefix for EVO segments in the default namespace.
+pub(crate) const SHM_PREFIX: &str = "/evo_";
+
+/// Maximum length of an instance identifier.
+pub const MAX_INSTANCE_LEN: usize = 32;
 
 // ─── P2P Header Field Offsets (repr(C) layout) ─────────────────────
 //
@@ -54,10 +85,94 @@ const SHM_PREFIX: &str = "/evo_";
 //   [26..28] _pad:          (implicit padding for u32 align)
 //   [28..32] payload_size:  u32
 //   [32..36] write_seq:     u32
-//   [36..64] _padding:      [u8; 28]
+//   [36..40] wait_until_ms: u32
+//   [40..48] commit_ns:     u64
+//   [48..64] _padding:      [u8; 16]
 
 const HEARTBEAT_OFFSET: usize = 16;
 const WRITE_SEQ_OFFSET: usize = 32;
+const WAIT_UNTIL_OFFSET: usize = 36;
+const COMMIT_NS_OFFSET: usize = 40;
+
+// ─── SHM Namespace ──────────────────────────────────────────────────
+
+/// SHM namespace of one EVO instance.
+///
+/// The default namespace names segments `/evo_<name>`; instance `<id>`
+/// names them `/evo-<id>_<name>`. Instance ids are restricted to ASCII
+/// letters, digits and `-`, so no namespace prefix is a prefix of another
+/// (`evo_` never matches `evo-a_…`, `evo-a_` never matches `evo-ab_…`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ShmNamespace {
+    /// Instance identifier (`None` = default namespace).
+    instance: Option<String>,
+}
+
+impl ShmNamespace {
+    /// Namespace of instance `id`; an empty `id` selects the default namespace.
+    ///
+    /// # Errors
+    /// - `ShmError::InvalidInstance` unless `id` is at most
+    ///   [`MAX_INSTANCE_LEN`] ASCII letters, digits or `-`.
+    pub fn new(id: &str) -> Result<Self, ShmError> {
+        if id.is_empty() {
+            return Ok(Self::default());
+        }
+        if id.len() > MAX_INSTANCE_LEN
+            || !id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        {
+            return Err(ShmError::InvalidInstance {
+                instance: id.to_string(),
+            });
+        }
+        Ok(Self {
+            instance: Some(id.to_string()),
+        })
+    }
+
+    /// Namespace of an optional instance id (`None` = default namespace).
+    ///
+    /// # Errors
+    /// - `ShmError::InvalidInstance` as for [`new`](Self::new).
+    pub fn from_instance(id: Option<&str>) -> Result<Self, ShmError> {
+        id.map_or(Ok(Self::default()), Self::new)
+    }
+
+    /// Instance identifier, `None` for the default namespace.
+    #[inline]
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// File name prefix under `/dev/shm/` (`"evo_"` or `"evo-<id>_"`).
+    pub fn file_prefix(&self) -> String {
+        match &self.instance {
+            None => SHM_PREFIX[1..].to_string(),
+            Some(id) => format!("evo-{id}_"),
+        }
+    }
+
+    /// POSIX SHM name of a segment (e.g. `"hal_cu"` → `"/evo-a_hal_cu"`).
+    pub fn shm_name(&self, name: &str) -> String {
+        format!("/{}{name}", self.file_prefix())
+    }
+
+    /// Lock-file SHM name for writer-exclusivity enforcement.
+    fn lock_name(&self, name: &str) -> String {
+        format!("{}.lock", self.shm_name(name))
+    }
+
+    /// Schema-file SHM name published by `TypedP2pWriter::publish_schema`.
+    fn schema_name(&self, name: &str) -> String {
+        format!("{}{}", self.shm_name(name), schema::SCHEMA_SUFFIX)
+    }
+}
+
+impl std::fmt::Display for ShmNamespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.instance().unwrap_or("default"))
+    }
+}
 
 // ─── Error Type ─────────────────────────────────────────────────────
 
@@ -72,7 +187,10 @@ pub enum ShmError {
     },
 
     /// P2P version hash mismatch (struct layout incompatibility).
-    #[error("version hash mismatch on '{segment}': expected 0x{expected:08X}, got 0x{actual:08X}")]
+    #[error(
+        "version hash mismatch on '{segment}': expected 0x{expected:08X}, got 0x{actual:08X}{}",
+        format_layout_diff(diff)
+    )]
     VersionMismatch {
         /// Segment name.
         segment: String,
@@ -80,6 +198,9 @@ pub enum ShmError {
         expected: u32,
         /// Actual hash read from SHM.
         actual: u32,
+        /// Differing fields, reader → writer (empty if the writer
+        /// published no layout table).
+        diff: Vec<String>,
     },
 
     /// Destination module in header doesn't match expected reader module.
@@ -132,6 +253,28 @@ pub enum ShmError {
         missed_beats: u32,
     },
 
+    /// Last commit older than the reader's `max_age` (time-based staleness).
+    #[error("commit too old on '{segment}': {age:?} > {max_age:?}")]
+    CommitTooOld {
+        /// Segment name.
+        segment: String,
+        /// Time since the writer's last commit.
+        age: Duration,
+        /// Configured maximum age.
+        max_age: Duration,
+    },
+
+    /// Payload length differs from the segment's payload size.
+    #[error("payload size mismatch on '{segment}': expected {expected} bytes, got {actual}")]
+    PayloadSizeMismatch {
+        /// Segment name.
+        segment: String,
+        /// Segment payload size in bytes.
+        expected: usize,
+        /// Supplied payload size in bytes.
+        actual: usize,
+    },
+
     /// Segment data too small for the expected payload type.
     #[error("payload too small on '{segment}': need {expected} bytes, got {actual}")]
     PayloadTooSmall {
@@ -143,6 +286,24 @@ pub enum ShmError {
         actual: usize,
     },
 
+    /// Ring reader fell more than N entries behind the writer.
+    #[error("ring overflow on '{segment}': {lost} entries lost")]
+    RingOverflow {
+        /// Segment name.
+        segment: String,
+        /// Entries overwritten before they were read.
+        lost: u64,
+    },
+
+    /// Instance identifier not usable as an SHM namespace.
+    #[error(
+        "invalid instance id '{instance}': expected at most 32 ASCII letters, digits or '-'"
+    )]
+    InvalidInstance {
+        /// Rejected identifier.
+        instance: String,
+    },
+
     /// OS-level error from nix/libc calls.
     #[error("OS error on '{segment}': {source}")]
     Os {
@@ -153,6 +314,15 @@ pub enum ShmError {
     },
 }
 
+/// Render a `VersionMismatch` field diff as a message suffix.
+fn format_layout_diff(diff: &[String]) -> String {
+    if diff.is_empty() {
+        String::new()
+    } else {
+        format!("; fields (reader → writer): {}", diff.join("; "))
+    }
+}
+
 // ─── Module Abbreviation ────────────────────────────────────────────
 
 /// Module abbreviation identifying source/destination of a P2P segment.
@@ -202,6 +372,12 @@ impl ModuleAbbrev {
 ///
 /// `write_seq` must be accessed atomically (`AtomicU32`) in runtime code.
 /// The struct uses `u32` for FFI/serialization compatibility.
+///
+/// ## Commit Timestamp
+///
+/// `commit_ns` is the writer's `CLOCK_MONOTONIC` time of the last commit,
+/// published together with `heartbeat`. Readers on the same host compare
+/// it with their own clock to get the writer→reader age of each read.
 #[derive(Debug, Clone, Copy)]
 #[repr(C, align(64))]
 pub struct P2pSegmentHeader {
@@ -209,7 +385,7 @@ pub struct P2pSegmentHeader {
     pub magic: [u8; 8],
 
     /// Compile-time hash of the payload struct layout.
-    /// Computed via `struct_version_hash::<T>()`.
+    /// `ShmLayout::LAYOUT_HASH` of the payload type.
     /// Reader refuses to connect if mismatch.
     pub version_hash: u32,
 
@@ -232,13 +408,37 @@ pub struct P2pSegmentHeader {
     /// Must be accessed as `AtomicU32` at runtime.
     pub write_seq: u32,
 
+    /// Wait lease of readers blocked in `read_wait()` on `write_seq`:
+    /// `CLOCK_MONOTONIC` milliseconds (wrapping, see [`lease_running`])
+    /// until which a reader may be asleep; 0 = none.
+    /// Renewed by readers; the writer loads it after `commit()` to decide
+    /// whether to issue `FUTEX_WAKE` and clears it once expired.
+    /// Must be accessed as `AtomicU32` at runtime.
+    pub wait_until_ms: u32,
+
+    /// `CLOCK_MONOTONIC` time of the last commit [ns]; 0 = never committed.
+    pub commit_ns: u64,
+
     /// Padding to fill 64 bytes total.
-    pub _padding: [u8; 28],
+    pub _padding: [u8; 16],
 }
 
 const_assert_eq!(core::mem::size_of::<P2pSegmentHeader>(), 64);
 const_assert_eq!(core::mem::align_of::<P2pSegmentHeader>(), 64);
 
+crate::impl_shm_layout!(P2pSegmentHeader {
+    magic,
+    version_hash,
+    heartbeat,
+    source_module,
+    dest_module,
+    payload_size,
+    write_seq,
+    wait_until_ms,
+    commit_ns,
+    _padding,
+});
+
 impl P2pSegmentHeader {
     /// Create a new header with default values.
     pub const fn new(
@@ -255,7 +455,9 @@ impl P2pSegmentHeader {
             dest_module: dest as u8,
             payload_size,
             write_seq: 0,
-            _padding: [0u8; 28],
+            wait_until_ms: 0,
+            commit_ns: 0,
+            _padding: [0u8; 16],
         }
     }
 
@@ -274,15 +476,11 @@ impl P2pSegmentHeader {
     }
 }
 
-/// Compile-time version hash for struct compatibility detection.
+/// Size/alignment-only hash of a type.
 ///
-/// Computes a hash from `size_of::<T>()` and `align_of::<T>()`.
-/// If the struct layout changes, the hash changes, and reader/writer
-/// refuse to connect.
-///
-/// **Known limitation**: Does not detect field reordering within the
-/// same total size/alignment. This is acceptable because `#[repr(C)]`
-/// structs with explicit padding have deterministic field order.
+/// Computes a hash from `size_of::<T>()` and `align_of::<T>()`. Does not
+/// detect field reordering or type changes of equal size; P2P segments
+/// use the field-level `ShmLayout::LAYOUT_HASH` instead.
 pub const fn struct_version_hash<T>() -> u32 {
     let size = core::mem::size_of::<T>() as u32;
     let align = core::mem::align_of::<T>() as u32;
@@ -300,24 +498,319 @@ pub const fn data_size_for<T>() -> usize {
     pages * PAGE_SIZE
 }
 
-/// Compute the page-aligned mmap size for a P2P segment: header (64 B) + payload T.
+/// Compute the page-aligned mmap size for a P2P segment: header (64 B) +
+/// payload T + `table_len` bytes of layout table.
 ///
-/// The SHM region layout is `[P2pSegmentHeader (64 B)][T payload]`, rounded
-/// up to the nearest page boundary.
-const fn segment_mmap_size<T>() -> usize {
-    let raw = core::mem::size_of::<P2pSegmentHeader>() + core::mem::size_of::<T>();
+/// The SHM region layout is `[P2pSegmentHeader (64 B)][T payload][layout
+/// table]`, rounded up to the nearest page boundary.
+const fn segment_mmap_size<T>(table_len: usize) -> usize {
+    mmap_size_for(core::mem::size_of::<P2pSegmentHeader>() + core::mem::size_of::<T>() + table_len)
+}
+
+/// Round a raw segment size up to the nearest page boundary.
+const fn mmap_size_for(raw: usize) -> usize {
     let pages = (raw + PAGE_SIZE - 1) / PAGE_SIZE;
     pages * PAGE_SIZE
 }
 
-/// Build the POSIX SHM path for a segment name (e.g., `"hal_cu"` → `"/evo_hal_cu"`).
-fn shm_path(name: &str) -> String {
-    format!("{SHM_PREFIX}{name}")
+/// Acquire the exclusive writer flock on the segment's `.lock` SHM file.
+///
+/// Held for the writer's lifetime; readers never touch the lock file.
+fn acquire_writer_lock(ns: &ShmNamespace, name: &str) -> Result<Flock<OwnedFd>, ShmError> {
+    let lock_name = ns.lock_name(name);
+    let lock_fd = mman::shm_open(
+        lock_name.as_str(),
+        OFlag::O_CREAT | OFlag::O_RDWR,
+        Mode::S_IRUSR | Mode::S_IWUSR,
+    )
+    .map_err(|e| ShmError::Os {
+        segment: name.to_string(),
+        source: e,
+    })?;
+
+    Flock::lock(lock_fd, FlockArg::LockExclusiveNonblock).map_err(|(_, errno)| {
+        if errno == nix::errno::Errno::EWOULDBLOCK {
+            ShmError::WriterAlreadyExists {
+                segment: name.to_string(),
+            }
+        } else {
+            ShmError::Os {
+                segment: name.to_string(),
+                source: errno,
+            }
+        }
+    })
+}
+
+/// Open/create the data segment (no flock), size it and map it read-write.
+fn create_data_segment(
+    ns: &ShmNamespace,
+    name: &str,
+    data_size: usize,
+) -> Result<(OwnedFd, NonNull<libc::c_void>), ShmError> {
+    let shm_name = ns.shm_name(name);
+    let data_fd = mman::shm_open(
+        shm_name.as_str(),
+        OFlag::O_CREAT | OFlag::O_RDWR,
+        Mode::S_IRUSR | Mode::S_IWUSR, // 0o600
+    )
+    .map_err(|e| ShmError::Os {
+        segment: name.to_string(),
+        source: e,
+    })?;
+
+    // Set the segment size.
+    unistd::ftruncate(&data_fd, data_size as libc::off_t).map_err(|e| ShmError::Os {
+        segment: name.to_string(),
+        source: e,
+    })?;
+
+    // Memory-map the segment.
+    let map_ptr = unsafe {
+        mman::mmap(
+            None,
+            std::num::NonZeroUsize::new(data_size).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_SHARED,
+            &data_fd,
+            0,
+        )
+        .map_err(|e| ShmError::Os {
+            segment: name.to_string(),
+            source: e,
+        })?
+    };
+    Ok((data_fd, map_ptr))
+}
+
+/// Open an existing data segment read-only. Returns the fd and file size.
+fn open_data_segment(ns: &ShmNamespace, name: &str) -> Result<(OwnedFd, usize), ShmError> {
+    let shm_name = ns.shm_name(name);
+
+    // Open existing SHM segment (read-only — no flock needed).
+    let data_fd = mman::shm_open(shm_name.as_str(), OFlag::O_RDONLY, Mode::empty()).map_err(
+        |e| {
+            if e == nix::errno::Errno::ENOENT {
+                ShmError::SegmentNotFound {
+                    segment: name.to_string(),
+                }
+            } else if e == nix::errno::Errno::EACCES {
+                ShmError::PermissionDenied {
+                    segment: name.to_string(),
+                    reason: "insufficient permissions to open SHM segment".to_string(),
+                }
+            } else {
+                ShmError::Os {
+                    segment: name.to_string(),
+                    source: e,
+                }
+            }
+        },
+    )?;
+
+    // Get segment size from fd.
+    let stat = nix::sys::stat::fstat(&data_fd).map_err(|e| ShmError::Os {
+        segment: name.to_string(),
+        source: e,
+    })?;
+    Ok((data_fd, stat.st_size as usize))
+}
+
+/// Map `map_len` bytes of an opened data segment read-only.
+fn map_read_only(
+    name: &str,
+    data_fd: &OwnedFd,
+    map_len: usize,
+) -> Result<NonNull<libc::c_void>, ShmError> {
+    unsafe {
+        mman::mmap(
+            None,
+            std::num::NonZeroUsize::new(map_len).unwrap(),
+            ProtFlags::PROT_READ,
+            MapFlags::MAP_SHARED,
+            data_fd,
+            0,
+        )
+        .map_err(|e| ShmError::Os {
+            segment: name.to_string(),
+            source: e,
+        })
+    }
+}
+
+/// Map the header page of an existing data segment read-write.
+///
+/// Used by blocking readers to take leases in the `wait_until_ms` word; the fd is
+/// closed on return, the mapping stays valid until `munmap`.
+fn map_header_rw(ns: &ShmNamespace, name: &str) -> Result<NonNull<libc::c_void>, ShmError> {
+    let shm_name = ns.shm_name(name);
+    let fd = mman::shm_open(shm_name.as_str(), OFlag::O_RDWR, Mode::empty()).map_err(|e| {
+        if e == nix::errno::Errno::EACCES {
+            ShmError::PermissionDenied {
+                segment: name.to_string(),
+                reason: "read-write access needed to wait on SHM segment".to_string(),
+            }
+        } else {
+            ShmError::Os {
+                segment: name.to_string(),
+                source: e,
+            }
+        }
+    })?;
+    unsafe {
+        mman::mmap(
+            None,
+            std::num::NonZeroUsize::new(PAGE_SIZE).unwrap(),
+            ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+            MapFlags::MAP_SHARED,
+            &fd,
+            0,
+        )
+        .map_err(|e| ShmError::Os {
+            segment: name.to_string(),
+            source: e,
+        })
+    }
+}
+
+/// View a `u32` header word of a mapped segment as an atomic.
+///
+/// # Safety
+/// `map` must point to a live mapping of at least one P2P header, and
+/// `offset` must be a 4-byte aligned header field offset.
+unsafe fn header_word<'a>(map: *const u8, offset: usize) -> &'a AtomicU32 {
+    unsafe { &*(map.add(offset) as *const AtomicU32) }
+}
+
+/// Longest a blocked reader sleeps before renewing its wait lease.
+///
+/// Bounds how long a reader that died inside `read_wait()` keeps the
+/// writer issuing `FUTEX_WAKE`.
+pub const WAIT_LEASE: Duration = Duration::from_millis(50);
+
+/// `CLOCK_MONOTONIC` milliseconds as a wrapping lease timestamp.
+///
+/// Never 0, which marks "no lease".
+fn lease_ms(now_ns: u64) -> u32 {
+    ((now_ns / 1_000_000) as u32).max(1)
+}
+
+/// Is `lease` still running at `now` (both from [`lease_ms`])?
+///
+/// Wrapping compare: leases lie at most [`WAIT_LEASE`] in the future and
+/// expired ones are cleared by the next commit, so both are far closer
+/// than the 24-day half range of `u32` milliseconds.
+pub fn lease_running(lease: u32, now: u32) -> bool {
+    lease != 0 && (lease.wrapping_sub(now) as i32) >= 0
+}
+
+/// Wake readers blocked in `read_wait()` on the segment at `map`.
+///
+/// No syscall unless a reader's wait lease is running at `now_ns`; an
+/// expired lease (reader gone) is cleared with a single CAS, so the RT
+/// path is syscall-free again once every waiter has died.
+fn wake_waiters(map: *const u8, now_ns: u64) {
+    let lease = unsafe { header_word(map, WAIT_UNTIL_OFFSET) };
+    let until = lease.load(Ordering::Relaxed);
+    if until == 0 {
+        return;
+    }
+    if lease_running(until, lease_ms(now_ns)) {
+        futex_wake_all(unsafe { header_word(map, WRITE_SEQ_OFFSET) });
+    } else {
+        // Fails harmlessly if a reader renewed the lease meanwhile.
+        let _ = lease.compare_exchange(until, 0, Ordering::Relaxed, Ordering::Relaxed);
+    }
+}
+
+/// Extend the wait lease at `map` to at least `until` (see [`lease_ms`]).
+fn renew_lease(map: *const u8, until: u32) {
+    let lease = unsafe { header_word(map, WAIT_UNTIL_OFFSET) };
+    let mut current = lease.load(Ordering::Relaxed);
+    loop {
+        // Keep a later lease of another reader.
+        if current != 0 && (current.wrapping_sub(until) as i32) >= 0 {
+            return;
+        }
+        match lease.compare_exchange_weak(current, until, Ordering::SeqCst, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+/// Wake every process blocked on the futex word.
+///
+/// Shared (non-private) futex: waiters live in other processes and are
+/// keyed by the SHM page, not by the virtual address.
+fn futex_wake_all(word: &AtomicU32) {
+    unsafe {
+        libc::syscall(libc::SYS_futex, word.as_ptr(), libc::FUTEX_WAKE, i32::MAX);
+    }
+}
+
+/// Current `CLOCK_MONOTONIC` time in nanoseconds.
+///
+/// Same clock as [`P2pSegmentHeader::commit_ns`]. Served by the vDSO, no
+/// syscall.
+pub fn monotonic_ns() -> u64 {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: `ts` is a valid out-pointer; CLOCK_MONOTONIC always exists.
+    unsafe {
+        libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts);
+    }
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+/// Sleep while the futex word equals `expected`, at most `timeout`.
+///
+/// Returns on wake-up, value mismatch, timeout or signal — callers must
+/// re-check their condition.
+fn futex_wait(word: &AtomicU32, expected: u32, timeout: Duration) {
+    let ts = libc::timespec {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_nsec: timeout.subsec_nanos() as libc::c_long,
+    };
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            word.as_ptr(),
+            libc::FUTEX_WAIT,
+            expected,
+            &ts as *const libc::timespec,
+        );
+    }
 }
 
-/// Build the lock-file path for writer-exclusivity enforcement.
-fn lock_path(name: &str) -> String {
-    format!("{SHM_PREFIX}{name}.lock")
+/// Diff `entries` (the reader's layout) against the layout table the
+/// writer published after its `payload_size`-byte payload.
+///
+/// Returns an empty list if the writer published no readable table.
+fn published_layout_diff(
+    entries: &[layout::LayoutEntry],
+    expected_payload: usize,
+    map: *const u8,
+    map_len: usize,
+    data_fd: &OwnedFd,
+    payload_size: usize,
+) -> Vec<String> {
+    let file_len = nix::sys::stat::fstat(data_fd).map_or(0, |st| st.st_size as usize);
+    let start = core::mem::size_of::<P2pSegmentHeader>() + payload_size;
+    let end = file_len.min(map_len);
+    if start >= end {
+        return Vec::new();
+    }
+    // SAFETY: [start, end) lies within both the mapping and the file.
+    let bytes = unsafe { core::slice::from_raw_parts(map.add(start), end - start) };
+    let Some(published) = layout::decode_table(bytes) else {
+        return Vec::new();
+    };
+    let mut diff = Vec::new();
+    if payload_size != expected_payload {
+        diff.push(format!("payload size {expected_payload} → {payload_size}"));
+    }
+    diff.extend(layout::layout_diff(entries, &published));
+    diff
 }
 
 // ─── TypedP2pWriter ─────────────────────────────────────────────────
@@ -330,21 +823,152 @@ fn lock_path(name: &str) -> String {
 ///
 /// # SHM Layout
 ///
-/// The mapped region is `[P2pSegmentHeader (64 B)][T payload (size_of::<T>() B)]`,
-/// rounded up to the nearest page boundary. `T` is the **payload-only** type
-/// — it does NOT need to embed `P2pSegmentHeader` as its first field.
+/// The mapped region is `[P2pSegmentHeader (64 B)][T payload (size_of::<T>() B)]
+/// [layout table]`, rounded up to the nearest page boundary. `T` is the
+/// **payload-only** type — it does NOT need to embed `P2pSegmentHeader` as
+/// its first field. The layout table (flattened `T` fields, see
+/// [`layout`](crate::shm::layout)) is written once at create.
 ///
 /// # Safety Requirements
 ///
-/// `T` must be `#[repr(C)]` with all-zeroes being a valid bit pattern.
+/// `T` must be `#[repr(C)]` with all-zeroes being a valid bit pattern, and
+/// implement [`ShmLayout`] (see [`impl_shm_layout!`](crate::impl_shm_layout)).
 ///
 /// # Lifecycle
 ///
 /// - **Create**: `shm_open(O_CREAT | O_RDWR)` + `ftruncate` + `mmap`
-///   + `flock(LOCK_EX)` on separate `.lock` shm segment
+///   + `flock(LOCK_EX)` on separate `.lock` shm segment + layout table
 /// - **Write**: Copy payload to pre-allocated buffer, apply header, increment heartbeat
 /// - **Drop**: `munmap` + `shm_unlink` + lock file auto-released
-pub struct TypedP2pWriter<T: Copy> {
+pub struct TypedP2pWriter<T: ShmLayout> {
+    /// Untyped segment writer (lock, mapping, write protocol).
+    raw: RawP2pWriter,
+    _marker: PhantomData<T>,
+}
+
+impl<T: ShmLayout> TypedP2pWriter<T> {
+    /// Create a new P2P shared memory segment and acquire exclusive writer lock.
+    ///
+    /// # Arguments
+    /// - `name`: Segment name (e.g., `"hal_cu"`). Will be prefixed with `evo_`.
+    /// - `source`: Source module identifier.
+    /// - `dest`: Destination module identifier.
+    ///
+    /// # Errors
+    /// - `ShmError::WriterAlreadyExists` if another writer holds the segment.
+    /// - `ShmError::Os` for system-level errors.
+    pub fn create(
+        name: &str,
+        source: ModuleAbbrev,
+        dest: ModuleAbbrev,
+    ) -> Result<Self, ShmError> {
+        Self::create_in(&ShmNamespace::default(), name, source, dest)
+    }
+
+    /// Same as [`create`](Self::create), in namespace `ns`.
+    pub fn create_in(
+        ns: &ShmNamespace,
+        name: &str,
+        source: ModuleAbbrev,
+        dest: ModuleAbbrev,
+    ) -> Result<Self, ShmError> {
+        let table = layout::encode_table(&layout::flatten::<T>());
+        let header = P2pSegmentHeader::new(
+            source,
+            dest,
+            T::LAYOUT_HASH,
+            core::mem::size_of::<T>() as u32,
+        );
+        Ok(Self {
+            raw: RawP2pWriter::create_in(ns, name, header, &table)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Publish the payload schema as `/dev/shm/evo_<name>.schema.json`.
+    ///
+    /// Optional: lets tools outside the workspace decode the segment
+    /// without the Rust structs (see `shm::schema`). The file is removed
+    /// together with the segment when the writer is dropped.
+    ///
+    /// # Errors
+    /// - `ShmError::Os` if the schema file cannot be created or written.
+    pub fn publish_schema(&mut self) -> Result<(), ShmError> {
+        let mut schema = schema::SegmentSchema::of::<T>(&self.raw.name);
+        schema.shm_name = self.raw.ns.shm_name(&self.raw.name);
+        self.raw.publish_schema(&schema.to_json())
+    }
+
+    /// Write a complete segment payload to shared memory.
+    ///
+    /// See [`RawP2pWriter::commit`] for the write protocol.
+    ///
+    /// # RT Safety
+    /// No heap allocation occurs in this method. The write buffer is
+    /// pre-allocated at `create()` time. The only syscall is `FUTEX_WAKE`,
+    /// issued only while a reader's wait lease is running.
+    pub fn commit(&mut self, payload: &T) -> Result<(), ShmError> {
+        // SAFETY: T is a repr(C) plain-data payload; its bytes are readable.
+        let bytes: &[u8] = unsafe {
+            core::slice::from_raw_parts(payload as *const T as *const u8, core::mem::size_of::<T>())
+        };
+        self.raw.commit(bytes)
+    }
+
+    /// Update the payload in place in shared memory.
+    ///
+    /// Zero-copy alternative to [`commit`](Self::commit) for large
+    /// segments: `f` receives the mapped payload, still holding the last
+    /// committed value (all-zero before the first commit), and only
+    /// writes what changed. See [`RawP2pWriter::commit_with`].
+    ///
+    /// # RT Safety
+    /// No heap allocation, no copy beyond the fields `f` writes. The only
+    /// syscall is `FUTEX_WAKE`, issued only while a reader's wait lease is
+    /// running.
+    pub fn commit_with(&mut self, f: impl FnOnce(&mut T)) {
+        let () = TypedP2pReader::<T>::PAYLOAD_ALIGNED;
+        self.raw.commit_with(|bytes| {
+            // SAFETY: `bytes` is the mapped payload — size_of::<T>() bytes
+            // at offset 64 of a page-aligned mapping, so aligned for T
+            // (PAYLOAD_ALIGNED) — and any bytes are a valid T (ShmLayout).
+            f(unsafe { &mut *(bytes.as_mut_ptr() as *mut T) })
+        });
+    }
+
+    /// Get the current heartbeat counter value.
+    #[inline]
+    pub fn heartbeat(&self) -> u64 {
+        self.raw.heartbeat
+    }
+
+    /// Get the segment name (without prefix).
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.raw.name
+    }
+
+    /// Get the SHM namespace the segment lives in.
+    #[inline]
+    pub fn namespace(&self) -> &ShmNamespace {
+        &self.raw.ns
+    }
+
+    /// Get the mapped data size.
+    #[inline]
+    pub fn data_size(&self) -> usize {
+        self.raw.map_len
+    }
+}
+
+// ─── RawP2pWriter ───────────────────────────────────────────────────
+
+/// Untyped segment writer: the P2P write protocol over payload bytes.
+///
+/// [`TypedP2pWriter`] wraps one. Used directly where the payload type is
+/// only known at runtime, e.g. the recorder's replayer re-creating
+/// recorded segments from their header fields and layout table.
+pub struct RawP2pWriter {
     /// Exclusive flock on the `.lock` SHM segment — prevents duplicate writers.
     /// Held for the lifetime of the writer; readers don't touch this file.
     _lock: Flock<OwnedFd>,
@@ -355,111 +979,76 @@ pub struct TypedP2pWriter<T: Copy> {
     map_ptr: NonNull<libc::c_void>,
     /// Total mapped size (page-aligned).
     map_len: usize,
-    /// Segment name (without `/evo_` prefix).
+    /// Segment name (without namespace prefix).
     name: String,
+    /// SHM namespace the segment lives in.
+    ns: ShmNamespace,
+    /// Payload size in bytes.
+    payload_size: usize,
     /// Pre-allocated byte buffer (page-aligned size). Reused every cycle.
     write_buf: Vec<u8>,
     /// Cached P2P header template (magic, version_hash, source, dest, payload_size).
     header_template: [u8; 64],
     /// Monotonic heartbeat counter, incremented on every `commit()`.
     heartbeat: u64,
-    _marker: PhantomData<T>,
+    /// Whether `publish_schema()` wrote a schema file (unlinked on drop).
+    schema_published: bool,
 }
 
 // SAFETY: The mmap pointer is only accessed by the single owning writer.
 // The segment is protected by the lock-free write_seq protocol plus the
 // exclusive .lock file that prevents duplicate writers.
-unsafe impl<T: Copy> Send for TypedP2pWriter<T> {}
+unsafe impl Send for RawP2pWriter {}
 
-impl<T: Copy> TypedP2pWriter<T> {
-    /// Create a new P2P shared memory segment and acquire exclusive writer lock.
+impl RawP2pWriter {
+    /// Create a segment from an explicit header and layout table.
     ///
-    /// # Arguments
-    /// - `name`: Segment name (e.g., `"hal_cu"`). Will be prefixed with `evo_`.
-    /// - `source`: Source module identifier.
-    /// - `dest`: Destination module identifier.
+    /// `header` supplies `source_module`, `dest_module`, `version_hash` and
+    /// `payload_size`; magic, heartbeat and `write_seq` are reset.
+    /// `layout_table` is published after the payload as-is (may be empty).
     ///
     /// # Errors
     /// - `ShmError::WriterAlreadyExists` if another writer holds the segment.
     /// - `ShmError::Os` for system-level errors.
     pub fn create(
         name: &str,
-        source: ModuleAbbrev,
-        dest: ModuleAbbrev,
+        header: P2pSegmentHeader,
+        layout_table: &[u8],
     ) -> Result<Self, ShmError> {
-        let data_size = segment_mmap_size::<T>();
+        Self::create_in(&ShmNamespace::default(), name, header, layout_table)
+    }
 
-        // === Writer-exclusivity: flock on a separate .lock segment ===
-        let lock_name = lock_path(name);
-        let lock_fd = mman::shm_open(
-            lock_name.as_str(),
-            OFlag::O_CREAT | OFlag::O_RDWR,
-            Mode::S_IRUSR | Mode::S_IWUSR,
-        )
-        .map_err(|e| ShmError::Os {
-            segment: name.to_string(),
-            source: e,
-        })?;
+    /// Same as [`create`](Self::create), in namespace `ns`.
+    pub fn create_in(
+        ns: &ShmNamespace,
+        name: &str,
+        header: P2pSegmentHeader,
+        layout_table: &[u8],
+    ) -> Result<Self, ShmError> {
+        let payload_size = header.payload_size as usize;
+        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        let data_size = mmap_size_for(hdr_size + payload_size + layout_table.len());
+        let lock = acquire_writer_lock(ns, name)?;
+        let (data_fd, map_ptr) = create_data_segment(ns, name, data_size)?;
 
-        let lock = Flock::lock(lock_fd, FlockArg::LockExclusiveNonblock).map_err(
-            |(_, errno)| {
-                if errno == nix::errno::Errno::EWOULDBLOCK {
-                    ShmError::WriterAlreadyExists {
-                        segment: name.to_string(),
-                    }
-                } else {
-                    ShmError::Os {
-                        segment: name.to_string(),
-                        source: errno,
-                    }
-                }
-            },
-        )?;
-
-        // === Open/create the data segment (no flock) ===
-        let shm_name = shm_path(name);
-        let data_fd = mman::shm_open(
-            shm_name.as_str(),
-            OFlag::O_CREAT | OFlag::O_RDWR,
-            Mode::S_IRUSR | Mode::S_IWUSR, // 0o600
-        )
-        .map_err(|e| ShmError::Os {
-            segment: name.to_string(),
-            source: e,
-        })?;
-
-        // Set the segment size.
-        unistd::ftruncate(&data_fd, data_size as libc::off_t).map_err(|e| ShmError::Os {
-            segment: name.to_string(),
-            source: e,
-        })?;
-
-        // Memory-map the segment.
-        let map_ptr = unsafe {
-            mman::mmap(
-                None,
-                std::num::NonZeroUsize::new(data_size).unwrap(),
-                ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
-                MapFlags::MAP_SHARED,
-                &data_fd,
-                0,
-            )
-            .map_err(|e| ShmError::Os {
-                segment: name.to_string(),
-                source: e,
-            })?
-        };
-
-        // Advise sequential access for RT performance.
-        let _ = unsafe { mman::madvise(map_ptr, data_size, MmapAdvise::MADV_SEQUENTIAL) };
+        // Advise sequential access for RT performance.
+        let _ = unsafe { mman::madvise(map_ptr, data_size, MmapAdvise::MADV_SEQUENTIAL) };
 
         // Build initial P2P header.
-        let payload_bytes = core::mem::size_of::<T>() as u32;
-        let header = P2pSegmentHeader::new(source, dest, struct_version_hash::<T>(), payload_bytes);
+        let header = P2pSegmentHeader {
+            magic: EVO_P2P_MAGIC,
+            heartbeat: 0,
+            write_seq: 0,
+            wait_until_ms: 0,
+            commit_ns: 0,
+            _padding: [0u8; 16],
+            ..header
+        };
 
-        // Serialize header into the write buffer.
+        // Serialize header and layout table into the write buffer.
         let mut write_buf = vec![0u8; data_size];
-        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        let table_start = hdr_size + payload_size;
+        write_buf[table_start..table_start + layout_table.len()].copy_from_slice(layout_table);
         let hdr_bytes: &[u8] = unsafe {
             core::slice::from_raw_parts(
                 &header as *const P2pSegmentHeader as *const u8,
@@ -490,28 +1079,66 @@ impl<T: Copy> TypedP2pWriter<T> {
             map_ptr,
             map_len: data_size,
             name: name.to_string(),
+            ns: ns.clone(),
+            payload_size,
             write_buf,
             header_template,
             heartbeat: 0,
-            _marker: PhantomData,
+            schema_published: false,
         })
     }
 
+    /// Publish `json` as `/dev/shm/evo_<name>.schema.json` (see
+    /// [`TypedP2pWriter::publish_schema`]).
+    ///
+    /// # Errors
+    /// - `ShmError::Os` if the schema file cannot be created or written.
+    pub fn publish_schema(&mut self, json: &str) -> Result<(), ShmError> {
+        let os_err = |source| ShmError::Os {
+            segment: self.name.clone(),
+            source,
+        };
+        let fd = mman::shm_open(
+            self.ns.schema_name(&self.name).as_str(),
+            OFlag::O_CREAT | OFlag::O_TRUNC | OFlag::O_RDWR,
+            Mode::S_IRUSR | Mode::S_IWUSR,
+        )
+        .map_err(os_err)?;
+        self.schema_published = true;
+        std::fs::File::from(fd)
+            .write_all(json.as_bytes())
+            .map_err(|e| os_err(nix::errno::Errno::from_raw(e.raw_os_error().unwrap_or(libc::EIO))))
+    }
+
     /// Write a complete segment payload to shared memory.
     ///
     /// This method:
     /// 1. Sets `write_seq` to odd (write in progress) in mapped memory.
-    /// 2. Copies the payload `T` into the pre-allocated buffer.
+    /// 2. Copies the payload into the pre-allocated buffer.
     /// 3. Re-applies the cached P2P header template.
     /// 4. Increments the heartbeat counter.
-    /// 5. Copies buffer to mapped memory with committed `write_seq`.
+    /// 5. Copies buffer to mapped memory with committed `write_seq`
+    ///    (the reader-owned `wait_until_ms` word is left untouched).
     /// 6. Issues a release fence.
+    /// 7. Wakes readers blocked in `read_wait()` while a wait lease runs.
     ///
     /// # RT Safety
     /// No heap allocation occurs in this method. The write buffer is
-    /// pre-allocated at `create()` time.
-    pub fn commit(&mut self, payload: &T) -> Result<(), ShmError> {
-        let type_size = core::mem::size_of::<T>();
+    /// pre-allocated at `create()` time. The only syscall is `FUTEX_WAKE`
+    /// in step 7, issued only while a reader's wait lease is running.
+    ///
+    /// # Errors
+    /// - `ShmError::PayloadSizeMismatch` if `payload` is not exactly the
+    ///   segment's payload size.
+    pub fn commit(&mut self, payload: &[u8]) -> Result<(), ShmError> {
+        let type_size = self.payload_size;
+        if payload.len() != type_size {
+            return Err(ShmError::PayloadSizeMismatch {
+                segment: self.name.clone(),
+                expected: type_size,
+                actual: payload.len(),
+            });
+        }
         let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
         let map = self.map_ptr.as_ptr() as *mut u8;
 
@@ -532,39 +1159,92 @@ impl<T: Copy> TypedP2pWriter<T> {
         // === STEP 2: Build payload in pre-allocated buffer ===
 
         // Copy payload bytes to pre-allocated buffer at offset after header.
-        let src: &[u8] =
-            unsafe { core::slice::from_raw_parts(payload as *const T as *const u8, type_size) };
-        self.write_buf[hdr_size..hdr_size + type_size].copy_from_slice(src);
+        self.write_buf[hdr_size..hdr_size + type_size].copy_from_slice(payload);
 
         // Re-apply cached P2P header template (magic, version_hash, source,
         // dest, payload_size). Ensures correctness even if the caller passes
         // a zeroed or partially-filled struct.
         self.write_buf[..hdr_size].copy_from_slice(&self.header_template);
 
-        // Write heartbeat into buffer.
+        // Write heartbeat and commit timestamp into buffer.
         self.write_buf[HEARTBEAT_OFFSET..HEARTBEAT_OFFSET + 8]
             .copy_from_slice(&self.heartbeat.to_ne_bytes());
+        let now = monotonic_ns();
+        self.write_buf[COMMIT_NS_OFFSET..COMMIT_NS_OFFSET + 8]
+            .copy_from_slice(&now.to_ne_bytes());
 
         // Write committed write_seq into buffer.
         self.write_buf[WRITE_SEQ_OFFSET..WRITE_SEQ_OFFSET + 4]
             .copy_from_slice(&seq_even.to_ne_bytes());
 
-        // === STEP 3: Copy buffer to mapped memory ===
+        // === STEP 3: Copy header + payload to mapped memory, skipping `wait_until_ms` ===
+        // (the layout table behind the payload is static since create()).
         std::sync::atomic::fence(std::sync::atomic::Ordering::Release);
+        let len = hdr_size + type_size;
+        let tail = WAIT_UNTIL_OFFSET + 4;
         unsafe {
+            core::ptr::copy_nonoverlapping(self.write_buf.as_ptr(), map, WAIT_UNTIL_OFFSET);
             core::ptr::copy_nonoverlapping(
-                self.write_buf.as_ptr(),
-                map,
-                self.map_len.min(self.write_buf.len()),
+                self.write_buf.as_ptr().add(tail),
+                map.add(tail),
+                len - tail,
             );
         }
 
         // === STEP 4: Final barrier — committed write_seq is now visible ===
-        std::sync::atomic::fence(std::sync::atomic::Ordering::Release);
+        // SeqCst pairs with the reader's registration fence in `read_wait()`:
+        // either the reader sees the new write_seq or we see its registration.
+        std::sync::atomic::fence(std::sync::atomic::Ordering::SeqCst);
+
+        // === STEP 5: Wake blocked readers (no syscall without waiters) ===
+        wake_waiters(map, now);
 
         Ok(())
     }
 
+    /// Update the payload in place in the mapping.
+    ///
+    /// While `write_seq` is odd, `f` receives the mapped payload bytes,
+    /// still holding the last committed payload — no staging buffer, no
+    /// copy. The heartbeat and the committed `write_seq` are then published
+    /// and blocked readers woken, as in [`commit`](Self::commit).
+    ///
+    /// If `f` panics, `write_seq` stays odd and readers report
+    /// `ShmError::ReadContention` until the next commit.
+    ///
+    /// # RT Safety
+    /// Same as `commit()`; the copy cost is whatever `f` writes.
+    pub fn commit_with(&mut self, f: impl FnOnce(&mut [u8])) {
+        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        let map = self.map_ptr.as_ptr() as *mut u8;
+
+        self.heartbeat += 1;
+        let seq_odd = self.heartbeat.wrapping_mul(2).wrapping_sub(1) as u32;
+        let seq_even = self.heartbeat.wrapping_mul(2) as u32;
+        let write_seq = unsafe { header_word(map, WRITE_SEQ_OFFSET) };
+
+        // Signal write-in-progress before touching the payload.
+        write_seq.store(seq_odd, Ordering::Relaxed);
+        std::sync::atomic::fence(Ordering::Release);
+
+        // SAFETY: the mapping holds header + payload (create()) and only
+        // this writer mutates it; readers retry on the odd write_seq.
+        let payload =
+            unsafe { core::slice::from_raw_parts_mut(map.add(hdr_size), self.payload_size) };
+        f(payload);
+
+        let now = monotonic_ns();
+        unsafe {
+            core::ptr::write_volatile(map.add(HEARTBEAT_OFFSET) as *mut u64, self.heartbeat);
+            core::ptr::write_volatile(map.add(COMMIT_NS_OFFSET) as *mut u64, now);
+        }
+        write_seq.store(seq_even, Ordering::Release);
+        // Pairs with the reader's registration fence in `read_wait()`.
+        std::sync::atomic::fence(Ordering::SeqCst);
+
+        wake_waiters(map, now);
+    }
+
     /// Get the current heartbeat counter value.
     #[inline]
     pub fn heartbeat(&self) -> u64 {
@@ -577,25 +1257,40 @@ impl<T: Copy> TypedP2pWriter<T> {
         &self.name
     }
 
+    /// Get the SHM namespace the segment lives in.
+    #[inline]
+    pub fn namespace(&self) -> &ShmNamespace {
+        &self.ns
+    }
+
     /// Get the mapped data size.
     #[inline]
     pub fn data_size(&self) -> usize {
         self.map_len
     }
+
+    /// Get the payload size in bytes.
+    #[inline]
+    pub fn payload_size(&self) -> usize {
+        self.payload_size
+    }
 }
 
-impl<T: Copy> Drop for TypedP2pWriter<T> {
+impl Drop for RawP2pWriter {
     fn drop(&mut self) {
         // Unmap the data segment.
         unsafe {
             let _ = mman::munmap(self.map_ptr, self.map_len);
         }
         // Unlink the SHM data segment (removes from /dev/shm).
-        let shm_name = shm_path(&self.name);
+        let shm_name = self.ns.shm_name(&self.name);
         let _ = mman::shm_unlink(shm_name.as_str());
         // Unlink the lock file.
-        let lk = lock_path(&self.name);
+        let lk = self.ns.lock_name(&self.name);
         let _ = mman::shm_unlink(lk.as_str());
+        if self.schema_published {
+            let _ = mman::shm_unlink(self.ns.schema_name(&self.name).as_str());
+        }
         // _lock (Flock<OwnedFd>) and _data_fd (OwnedFd) are dropped automatically,
         // releasing the flock and closing file descriptors.
     }
@@ -623,15 +1318,17 @@ impl<T: Copy> Drop for TypedP2pWriter<T> {
 /// - **Attach**: `shm_open(O_RDONLY)` + `mmap(PROT_READ)`
 /// - **Read**: Copy from mmap to aligned buffer, validate header, check heartbeat
 /// - **Drop**: `munmap` (no shm_unlink — writer owns segment lifetime)
-pub struct TypedP2pReader<T: Copy> {
+pub struct TypedP2pReader<T: ShmLayout> {
     /// POSIX SHM file descriptor for the data segment (read-only, no flock).
     _data_fd: OwnedFd,
     /// Memory-mapped pointer to the data segment (PROT_READ).
     map_ptr: NonNull<libc::c_void>,
     /// Total mapped size.
     map_len: usize,
-    /// Segment name (without `/evo_` prefix).
+    /// Segment name (without namespace prefix).
     name: String,
+    /// SHM namespace the segment lives in.
+    ns: ShmNamespace,
     /// Pre-allocated aligned buffer for payload deserialization.
     payload: T,
     /// Pre-allocated buffer for reading the P2P header separately from payload.
@@ -646,14 +1343,21 @@ pub struct TypedP2pReader<T: Copy> {
     stale_count: u32,
     /// Staleness threshold (number of unchanged reads before error).
     stale_threshold: u32,
+    /// Time-based staleness limit; replaces `stale_threshold` when set.
+    max_age: Option<Duration>,
+    /// Writer→reader age of the last successful read.
+    last_age: Option<Duration>,
+    /// Read-write mapping of the header page, created by the first
+    /// `read_wait()` to take leases in the `wait_until_ms` word.
+    waiter_map: Option<NonNull<libc::c_void>>,
     _marker: PhantomData<T>,
 }
 
 // SAFETY: The mmap pointer is read-only. The lock-free write_seq protocol
 // ensures consistent reads without any kernel-level locking.
-unsafe impl<T: Copy> Send for TypedP2pReader<T> {}
+unsafe impl<T: ShmLayout> Send for TypedP2pReader<T> {}
 
-impl<T: Copy> TypedP2pReader<T> {
+impl<T: ShmLayout> TypedP2pReader<T> {
     /// Attach to an existing P2P segment.
     ///
     /// # Arguments
@@ -665,40 +1369,930 @@ impl<T: Copy> TypedP2pReader<T> {
     /// - `ShmError::PayloadTooSmall` if the segment is smaller than `T`.
     /// - `ShmError::Os` for system-level errors.
     pub fn attach(name: &str, stale_threshold: u32) -> Result<Self, ShmError> {
-        let shm_name = shm_path(name);
-
-        // Open existing SHM segment (read-only — no flock needed).
-        let data_fd = mman::shm_open(shm_name.as_str(), OFlag::O_RDONLY, Mode::empty()).map_err(
-            |e| {
-                if e == nix::errno::Errno::ENOENT {
-                    ShmError::SegmentNotFound {
-                        segment: name.to_string(),
-                    }
-                } else if e == nix::errno::Errno::EACCES {
-                    ShmError::PermissionDenied {
-                        segment: name.to_string(),
-                        reason: "insufficient permissions to open SHM segment".to_string(),
-                    }
-                } else {
-                    ShmError::Os {
-                        segment: name.to_string(),
-                        source: e,
-                    }
-                }
-            },
-        )?;
+        Self::attach_in(&ShmNamespace::default(), name, stale_threshold)
+    }
 
-        // Get segment size from fd.
-        let stat = nix::sys::stat::fstat(&data_fd).map_err(|e| ShmError::Os {
-            segment: name.to_string(),
-            source: e,
-        })?;
-        let file_size = stat.st_size as usize;
+    /// Same as [`attach`](Self::attach), in namespace `ns`.
+    pub fn attach_in(
+        ns: &ShmNamespace,
+        name: &str,
+        stale_threshold: u32,
+    ) -> Result<Self, ShmError> {
+        let (data_fd, file_size) = open_data_segment(ns, name)?;
+
+        // Validate size — segment must hold at least header + payload.
+        let type_size = core::mem::size_of::<T>();
+        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        let min_size = hdr_size + type_size;
+        if file_size < min_size {
+            return Err(ShmError::PayloadTooSmall {
+                segment: name.to_string(),
+                expected: min_size,
+                actual: file_size,
+            });
+        }
+
+        // Compute map length (at least one page).
+        let map_len = segment_mmap_size::<T>(0).max(file_size);
+        let map_ptr = map_read_only(name, &data_fd, map_len)?;
+
+        // Zero-initialize the payload buffer.
+        // SAFETY: any bit pattern, all-zeros included, is a valid T
+        // (ShmLayout contract).
+        let payload: T = unsafe { core::mem::zeroed() };
+        let header_buf: P2pSegmentHeader = unsafe { core::mem::zeroed() };
+
+        Ok(Self {
+            _data_fd: data_fd,
+            map_ptr,
+            map_len,
+            name: name.to_string(),
+            ns: ns.clone(),
+            payload,
+            header_buf,
+            verified: false,
+            expected_hash: T::LAYOUT_HASH,
+            last_heartbeat: 0,
+            stale_count: 0,
+            stale_threshold,
+            max_age: None,
+            last_age: None,
+            waiter_map: None,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Attach to an existing P2P segment with destination module validation.
+    ///
+    /// Same as `attach()`, but additionally validates that the segment's
+    /// destination module matches `expected_dest`.
+    pub fn attach_validated(
+        name: &str,
+        stale_threshold: u32,
+        expected_dest: ModuleAbbrev,
+    ) -> Result<Self, ShmError> {
+        Self::attach_validated_in(&ShmNamespace::default(), name, stale_threshold, expected_dest)
+    }
+
+    /// Same as [`attach_validated`](Self::attach_validated), in namespace `ns`.
+    pub fn attach_validated_in(
+        ns: &ShmNamespace,
+        name: &str,
+        stale_threshold: u32,
+        expected_dest: ModuleAbbrev,
+    ) -> Result<Self, ShmError> {
+        let reader = Self::attach_in(ns, name, stale_threshold)?;
+
+        // Read the dest_module byte directly from mapped memory.
+        // P2pSegmentHeader layout: dest_module is at byte offset 25.
+        let map = reader.map_ptr.as_ptr() as *const u8;
+        let dest_byte = unsafe { core::ptr::read(map.add(25)) };
+
+        if let Some(actual_dest) = ModuleAbbrev::from_u8(dest_byte) {
+            if actual_dest != expected_dest {
+                return Err(ShmError::DestinationMismatch {
+                    segment: name.to_string(),
+                    expected: expected_dest,
+                    actual: actual_dest,
+                });
+            }
+        }
+
+        Ok(reader)
+    }
+
+    /// Read the current segment payload.
+    ///
+    /// On the first successful read, validates P2P magic and version hash.
+    /// On every read, checks the heartbeat for staleness.
+    ///
+    /// Returns a reference to the internal aligned buffer containing the
+    /// latest payload. The reference is valid until the next call to `read()`.
+    ///
+    /// # Errors
+    /// - `ShmError::InvalidMagic` on first read if magic is wrong.
+    /// - `ShmError::VersionMismatch` on first read if hash differs, with
+    ///   the differing fields when the writer published a layout table.
+    /// - `ShmError::HeartbeatStale` if heartbeat unchanged for `stale_threshold` reads.
+    /// - `ShmError::CommitTooOld` if the last commit is older than the
+    ///   [`max_age`](Self::with_max_age), when set.
+    /// - `ShmError::ReadContention` if too many read retries.
+    pub fn read(&mut self) -> Result<&T, ShmError> {
+        let type_size = core::mem::size_of::<T>();
+        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        let max_retries = 10u32;
+        let map = self.map_ptr.as_ptr() as *const u8;
+
+        for _attempt in 0..max_retries {
+            // Read write_seq before data.
+            let seq_before = unsafe {
+                let ws_ptr = map.add(WRITE_SEQ_OFFSET) as *const u32;
+                core::ptr::read_volatile(ws_ptr)
+            };
+
+            // Skip if write is in progress (odd write_seq).
+            if seq_before & 1 != 0 {
+                std::thread::yield_now();
+                continue;
+            }
+
+            // Acquire barrier before reading data.
+            std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
+
+            // Copy header from mmap[0..hdr_size] to header_buf.
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    map,
+                    &mut self.header_buf as *mut P2pSegmentHeader as *mut u8,
+                    hdr_size,
+                );
+            }
+
+            // Copy payload from mmap[hdr_size..hdr_size+type_size] to payload buf.
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    map.add(hdr_size),
+                    &mut self.payload as *mut T as *mut u8,
+                    type_size,
+                );
+            }
+
+            // Acquire barrier after reading data.
+            std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
+
+            // Read write_seq after data.
+            let seq_after = unsafe {
+                let ws_ptr = map.add(WRITE_SEQ_OFFSET) as *const u32;
+                core::ptr::read_volatile(ws_ptr)
+            };
+
+            // If write_seq changed during read, retry.
+            if seq_before != seq_after {
+                std::thread::yield_now();
+                continue;
+            }
+
+            // === Successful consistent read ===
+            let header = self.header_buf;
+            self.verify_header(&header)?;
+            self.check_stale(header.heartbeat, header.commit_ns)?;
+
+            return Ok(&self.payload);
+        }
+
+        // Too many retries.
+        Err(ShmError::ReadContention {
+            segment: self.name.clone(),
+        })
+    }
+
+    /// Read the current payload in place, without copying it.
+    ///
+    /// Zero-copy alternative to [`read`](Self::read) for large segments:
+    /// `f` runs on the mapped payload inside the seqlock window and its
+    /// result is returned only if no commit overlapped. Otherwise the
+    /// result is discarded and `f` runs again, so `f` may see a torn
+    /// payload and must only compute its result (no side effects).
+    ///
+    /// Header validation and staleness detection are the same as `read()`;
+    /// on the first call the header is validated before `f` runs.
+    ///
+    /// # Errors
+    /// Same as [`read`](Self::read).
+    pub fn read_with<R>(&mut self, mut f: impl FnMut(&T) -> R) -> Result<R, ShmError> {
+        let () = Self::PAYLOAD_ALIGNED;
+        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        let max_retries = 10u32;
+        let map = self.map_ptr.as_ptr() as *const u8;
+
+        if !self.verified {
+            // magic and version_hash never change after create().
+            let header = unsafe { core::ptr::read_volatile(map as *const P2pSegmentHeader) };
+            self.verify_header(&header)?;
+        }
+
+        for _attempt in 0..max_retries {
+            let seq_before =
+                unsafe { core::ptr::read_volatile(map.add(WRITE_SEQ_OFFSET) as *const u32) };
+            if seq_before & 1 != 0 {
+                std::thread::yield_now();
+                continue;
+            }
+            std::sync::atomic::fence(Ordering::Acquire);
+
+            let heartbeat =
+                unsafe { core::ptr::read_volatile(map.add(HEARTBEAT_OFFSET) as *const u64) };
+            let commit_ns =
+                unsafe { core::ptr::read_volatile(map.add(COMMIT_NS_OFFSET) as *const u64) };
+            // SAFETY: attach() checked the mapping holds header + T; the
+            // payload sits at offset 64 of a page-aligned mapping, which is
+            // aligned for T (PAYLOAD_ALIGNED), and whatever bytes the writer
+            // left there are a valid T (ShmLayout contract).
+            let result = f(unsafe { &*(map.add(hdr_size) as *const T) });
+
+            std::sync::atomic::fence(Ordering::Acquire);
+            let seq_after =
+                unsafe { core::ptr::read_volatile(map.add(WRITE_SEQ_OFFSET) as *const u32) };
+            if seq_before != seq_after {
+                std::thread::yield_now();
+                continue;
+            }
+
+            self.check_stale(heartbeat, commit_ns)?;
+            return Ok(result);
+        }
+
+        Err(ShmError::ReadContention {
+            segment: self.name.clone(),
+        })
+    }
+
+    /// Compile-time check that the mapped payload (offset 64) is aligned for `T`.
+    const PAYLOAD_ALIGNED: () = assert!(
+        core::mem::align_of::<T>() <= core::mem::align_of::<P2pSegmentHeader>(),
+        "payload alignment exceeds the 64-byte header alignment"
+    );
+
+    /// One-time P2P header validation (magic + version hash).
+    fn verify_header(&mut self, header: &P2pSegmentHeader) -> Result<(), ShmError> {
+        if self.verified {
+            return Ok(());
+        }
+        if !header.is_magic_valid() {
+            return Err(ShmError::InvalidMagic {
+                segment: self.name.clone(),
+            });
+        }
+        if header.version_hash != self.expected_hash {
+            return Err(ShmError::VersionMismatch {
+                segment: self.name.clone(),
+                expected: self.expected_hash,
+                actual: header.version_hash,
+                diff: published_layout_diff(
+                    &layout::flatten::<T>(),
+                    core::mem::size_of::<T>(),
+                    self.map_ptr.as_ptr() as *const u8,
+                    self.map_len,
+                    &self._data_fd,
+                    header.payload_size as usize,
+                ),
+            });
+        }
+        self.verified = true;
+        Ok(())
+    }
+
+    /// Heartbeat staleness check after a consistent read.
+    ///
+    /// Records the writer→reader age of `commit_ns`, then applies either
+    /// the time-based limit (`max_age`) or the read-count threshold.
+    fn check_stale(&mut self, heartbeat: u64, commit_ns: u64) -> Result<(), ShmError> {
+        self.last_age = (commit_ns != 0)
+            .then(|| Duration::from_nanos(monotonic_ns().saturating_sub(commit_ns)));
+        if let (Some(max_age), Some(age)) = (self.max_age, self.last_age)
+            && age > max_age
+        {
+            return Err(ShmError::CommitTooOld {
+                segment: self.name.clone(),
+                age,
+                max_age,
+            });
+        }
+
+        if heartbeat == self.last_heartbeat && self.last_heartbeat != 0 {
+            self.stale_count += 1;
+            if self.max_age.is_none() && self.stale_count >= self.stale_threshold {
+                return Err(ShmError::HeartbeatStale {
+                    segment: self.name.clone(),
+                    missed_beats: self.stale_count,
+                });
+            }
+        } else {
+            self.last_heartbeat = heartbeat;
+            self.stale_count = 0;
+        }
+        Ok(())
+    }
+
+    /// Check if the segment has new data since the last read.
+    ///
+    /// This is a cheap check using the P2P heartbeat field (no data copy).
+    pub fn has_changed(&self) -> bool {
+        let map = self.map_ptr.as_ptr() as *const u8;
+        let heartbeat = unsafe {
+            let hb_ptr = map.add(HEARTBEAT_OFFSET) as *const u64;
+            core::ptr::read_volatile(hb_ptr)
+        };
+        heartbeat != self.last_heartbeat
+    }
+
+    /// Block until the writer commits new data, then read it.
+    ///
+    /// Returns at once if the segment changed since the last read (see
+    /// [`has_changed`](Self::has_changed)). Otherwise registers in the
+    /// header `wait_until_ms` lease and sleeps on a futex on `write_seq`, in
+    /// slices of at most [`WAIT_LEASE`] that each renew the lease, until the
+    /// next `commit()` or until `timeout` expires.
+    ///
+    /// Returns `Ok(None)` on timeout; the stale counter is not touched, the
+    /// caller decides what a silent writer means.
+    ///
+    /// # RT Safety
+    /// Not RT-safe (syscalls). Intended for non-RT consumers. The first
+    /// call maps the header page read-write, which requires write
+    /// permission on the segment.
+    ///
+    /// # Errors
+    /// Same as [`read`](Self::read), plus `ShmError::PermissionDenied` /
+    /// `ShmError::Os` if the header cannot be mapped for waiting.
+    pub fn read_wait(&mut self, timeout: Duration) -> Result<Option<&T>, ShmError> {
+        if !self.has_changed() && !self.wait_for_commit(timeout)? {
+            return Ok(None);
+        }
+        self.read().map(Some)
+    }
+
+    /// Sleep on `write_seq` until the heartbeat moves or `timeout` expires.
+    ///
+    /// Returns `true` if new data was committed.
+    fn wait_for_commit(&mut self, timeout: Duration) -> Result<bool, ShmError> {
+        let deadline = Instant::now() + timeout;
+        let hdr = match self.waiter_map {
+            Some(map) => map,
+            None => *self.waiter_map.insert(map_header_rw(&self.ns, &self.name)?),
+        }
+        .as_ptr() as *const u8;
+        let write_seq = unsafe { header_word(hdr, WRITE_SEQ_OFFSET) };
+
+        // The lease is not released on return: another reader may share
+        // it, and an unused lease only costs the writer wake-ups until it
+        // expires.
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break Ok(self.has_changed());
+            }
+            let slice = (deadline - now).min(WAIT_LEASE);
+            // One extra millisecond covers the truncation in `lease_ms`.
+            let until = lease_ms(monotonic_ns() + slice.as_nanos() as u64 + 1_000_000);
+            renew_lease(hdr, until);
+            // Pairs with the writer's SeqCst fence after commit (see `commit()`).
+            std::sync::atomic::fence(Ordering::SeqCst);
+
+            // Load write_seq before re-checking: a commit after this load
+            // changes the word, so the futex wait cannot miss it.
+            let seq = write_seq.load(Ordering::Acquire);
+            if self.has_changed() {
+                break Ok(true);
+            }
+            futex_wait(write_seq, seq, slice);
+        }
+    }
+
+    /// Get the segment name (without prefix).
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the SHM namespace the segment lives in.
+    #[inline]
+    pub fn namespace(&self) -> &ShmNamespace {
+        &self.ns
+    }
+
+    /// Get the current stale count (consecutive reads without heartbeat change).
+    #[inline]
+    pub fn stale_count(&self) -> u32 {
+        self.stale_count
+    }
+
+    /// Get the last observed heartbeat value.
+    #[inline]
+    pub fn last_heartbeat(&self) -> u64 {
+        self.last_heartbeat
+    }
+
+    /// Reset staleness counter (e.g., after a recovery action).
+    pub fn reset_stale(&mut self) {
+        self.stale_count = 0;
+    }
+
+    /// Switch to time-based staleness: reads fail with
+    /// `ShmError::CommitTooOld` once the writer's last commit is older
+    /// than `max_age`, independent of how often the reader polls.
+    ///
+    /// Replaces the read-count `stale_threshold` given at attach.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Get the time-based staleness limit, if set.
+    #[inline]
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// Writer→reader age of the last successful read: time from the
+    /// writer's commit to the read, on `CLOCK_MONOTONIC`.
+    ///
+    /// `None` before the first read of a committed segment.
+    #[inline]
+    pub fn age(&self) -> Option<Duration> {
+        self.last_age
+    }
+}
+
+impl<T: ShmLayout> Drop for TypedP2pReader<T> {
+    fn drop(&mut self) {
+        // Unmap the data segment.
+        unsafe {
+            let _ = mman::munmap(self.map_ptr, self.map_len);
+            if let Some(map) = self.waiter_map {
+                let _ = mman::munmap(map, PAGE_SIZE);
+            }
+        }
+        // _data_fd (OwnedFd) is dropped automatically, closing the fd.
+        // Note: Reader does NOT shm_unlink — only the writer owns the segment lifetime.
+    }
+}
+
+// ─── RawP2pReader ───────────────────────────────────────────────────
+
+/// Untyped segment reader: copies the payload bytes of any P2P segment.
+///
+/// The payload size and version hash are taken from the segment header
+/// instead of a compiled-in type, so tools such as the recorder can follow
+/// segments they have no Rust type for. No version or staleness checks;
+/// [`read_new`](Self::read_new) only reports payloads with a new heartbeat.
+pub struct RawP2pReader {
+    /// POSIX SHM file descriptor for the data segment (read-only, no flock).
+    _data_fd: OwnedFd,
+    /// Memory-mapped pointer to the data segment (PROT_READ).
+    map_ptr: NonNull<libc::c_void>,
+    /// Total mapped size.
+    map_len: usize,
+    /// Segment name (without namespace prefix).
+    name: String,
+    /// SHM namespace the segment lives in.
+    ns: ShmNamespace,
+    /// Header copied by the last consistent read.
+    header: P2pSegmentHeader,
+    /// Payload copied by the last consistent read.
+    payload: Vec<u8>,
+    /// Layout table published after the payload (empty if none).
+    layout_table: Vec<u8>,
+    /// Heartbeat of the last payload returned (0 = none yet).
+    last_heartbeat: u64,
+}
+
+// SAFETY: The mmap pointer is read-only. The lock-free write_seq protocol
+// ensures consistent reads without any kernel-level locking.
+unsafe impl Send for RawP2pReader {}
+
+impl RawP2pReader {
+    /// Attach to an existing P2P segment of any payload type.
+    ///
+    /// # Errors
+    /// - `ShmError::SegmentNotFound` / `ShmError::PermissionDenied` /
+    ///   `ShmError::Os` if the segment cannot be opened.
+    /// - `ShmError::InvalidMagic` if the header is not a P2P header.
+    /// - `ShmError::PayloadTooSmall` if the segment is shorter than the
+    ///   payload size announced in its header.
+    pub fn attach(name: &str) -> Result<Self, ShmError> {
+        Self::attach_in(&ShmNamespace::default(), name)
+    }
+
+    /// Same as [`attach`](Self::attach), in namespace `ns`.
+    pub fn attach_in(ns: &ShmNamespace, name: &str) -> Result<Self, ShmError> {
+        let (data_fd, file_size) = open_data_segment(ns, name)?;
+        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        if file_size < hdr_size {
+            return Err(ShmError::PayloadTooSmall {
+                segment: name.to_string(),
+                expected: hdr_size,
+                actual: file_size,
+            });
+        }
+        let map_ptr = map_read_only(name, &data_fd, file_size)?;
+        let map = map_ptr.as_ptr() as *const u8;
+        // SAFETY: the mapping holds at least one header.
+        let header = unsafe { core::ptr::read_volatile(map as *const P2pSegmentHeader) };
+        let payload_size = header.payload_size as usize;
+        let fail = |err| {
+            let _ = unsafe { mman::munmap(map_ptr, file_size) };
+            Err(err)
+        };
+        if !header.is_magic_valid() {
+            return fail(ShmError::InvalidMagic {
+                segment: name.to_string(),
+            });
+        }
+        if file_size < hdr_size + payload_size {
+            return fail(ShmError::PayloadTooSmall {
+                segment: name.to_string(),
+                expected: hdr_size + payload_size,
+                actual: file_size,
+            });
+        }
+
+        // SAFETY: [hdr + payload, file_size) lies within the mapping; the
+        // table is written once at create and never changes.
+        let tail = unsafe {
+            core::slice::from_raw_parts(
+                map.add(hdr_size + payload_size),
+                file_size - hdr_size - payload_size,
+            )
+        };
+        let layout_table = match layout::decode_table(tail) {
+            Some(entries) => tail[..layout::table_size(entries.len())].to_vec(),
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            _data_fd: data_fd,
+            map_ptr,
+            map_len: file_size,
+            name: name.to_string(),
+            ns: ns.clone(),
+            header,
+            payload: vec![0u8; payload_size],
+            layout_table,
+            last_heartbeat: 0,
+        })
+    }
+
+    /// Read the payload if the writer committed since the last read.
+    ///
+    /// The first call returns the current payload once the writer has
+    /// committed at all. Returns `Ok(None)` if the heartbeat is unchanged.
+    ///
+    /// # Errors
+    /// - `ShmError::ReadContention` if too many read retries.
+    pub fn read_new(&mut self) -> Result<Option<&[u8]>, ShmError> {
+        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
+        let map = self.map_ptr.as_ptr() as *const u8;
+
+        for _attempt in 0..10 {
+            let seq_before = unsafe { core::ptr::read_volatile(map.add(WRITE_SEQ_OFFSET) as *const u32) };
+            if seq_before & 1 != 0 {
+                std::thread::yield_now();
+                continue;
+            }
+            std::sync::atomic::fence(Ordering::Acquire);
+
+            let heartbeat = unsafe { core::ptr::read_volatile(map.add(HEARTBEAT_OFFSET) as *const u64) };
+            if heartbeat == self.last_heartbeat {
+                return Ok(None);
+            }
+            // SAFETY: attach() checked the mapping holds header + payload.
+            let header = unsafe { core::ptr::read_volatile(map as *const P2pSegmentHeader) };
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    map.add(hdr_size),
+                    self.payload.as_mut_ptr(),
+                    self.payload.len(),
+                );
+            }
+
+            std::sync::atomic::fence(Ordering::Acquire);
+            let seq_after = unsafe { core::ptr::read_volatile(map.add(WRITE_SEQ_OFFSET) as *const u32) };
+            if seq_before != seq_after {
+                std::thread::yield_now();
+                continue;
+            }
+            self.header = header;
+            self.last_heartbeat = header.heartbeat;
+            return Ok(Some(&self.payload));
+        }
+
+        Err(ShmError::ReadContention {
+            segment: self.name.clone(),
+        })
+    }
+
+    /// Header as of attach or the last successful [`read_new`](Self::read_new).
+    #[inline]
+    pub fn header(&self) -> &P2pSegmentHeader {
+        &self.header
+    }
+
+    /// `true` once the writer has unlinked the mapped segment (writer
+    /// dropped or restarted under the same name).
+    pub fn is_unlinked(&self) -> bool {
+        nix::sys::stat::fstat(&self._data_fd).map_or(true, |st| st.st_nlink == 0)
+    }
+
+    /// Payload copied by the last successful [`read_new`](Self::read_new).
+    #[inline]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Encoded layout table published by the writer (empty if none).
+    #[inline]
+    pub fn layout_table(&self) -> &[u8] {
+        &self.layout_table
+    }
+
+    /// Get the segment name (without prefix).
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the SHM namespace the segment lives in.
+    #[inline]
+    pub fn namespace(&self) -> &ShmNamespace {
+        &self.ns
+    }
+}
+
+impl Drop for RawP2pReader {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = mman::munmap(self.map_ptr, self.map_len);
+        }
+    }
+}
+
+// ─── P2pRing ────────────────────────────────────────────────────────
+
+/// Ring segment payload layout: write cursor + N slots of `T`.
+///
+/// The mapped region is `[P2pSegmentHeader (64 B)][P2pRing<T, N>]`; the
+/// header carries the usual magic, version hash ([`Self::LAYOUT_HASH`], so
+/// N is part of it) and heartbeat; the layout table behind the ring
+/// describes `T`. Never constructed in process memory —
+/// accessed only through [`P2pRingWriter`] / [`P2pRingReader`].
+///
+/// ## Protocol
+///
+/// Entry `i` (0-based, monotonic) lives in slot `i % N`. Each slot has a
+/// seqlock stamp: `2i + 1` while entry `i` is being written, `2i + 2` once
+/// committed. `head` (entries pushed so far) is published after the stamp.
+/// A reader at cursor `c` accepts slot `c % N` only if its stamp is
+/// `2c + 2` before and after the copy; anything else means the writer
+/// lapped the reader.
+#[repr(C, align(64))]
+pub struct P2pRing<T: ShmLayout, const N: usize> {
+    /// Number of entries pushed since creation.
+    head: AtomicU64,
+    /// Ring slots.
+    slots: [RingSlot<T>; N],
+}
+
+/// One ring slot: seqlock stamp + payload.
+#[repr(C)]
+struct RingSlot<T: ShmLayout> {
+    stamp: AtomicU64,
+    payload: T,
+}
+
+impl<T: ShmLayout, const N: usize> P2pRing<T, N> {
+    /// Reject zero-capacity rings at compile time.
+    const NON_EMPTY: () = assert!(N > 0, "P2pRing capacity N must be > 0");
+
+    /// Header `version_hash` of the ring: layout of `[T; N]` mixed with the
+    /// ring's own size/alignment, so it never matches a plain `T` segment.
+    pub const LAYOUT_HASH: u32 = <[T; N]>::LAYOUT_HASH ^ struct_version_hash::<Self>();
+
+    /// Write cursor in the mapped segment.
+    ///
+    /// # Safety
+    /// `map` must point to a mapped `[P2pSegmentHeader][P2pRing<T, N>]` region.
+    unsafe fn head<'a>(map: *const u8) -> &'a AtomicU64 {
+        unsafe { &(*Self::ring(map)).head }
+    }
+
+    /// Slot for entry `index` in the mapped segment.
+    ///
+    /// # Safety
+    /// As [`Self::head`].
+    unsafe fn slot(map: *const u8, index: u64) -> *mut RingSlot<T> {
+        let slots = unsafe { core::ptr::addr_of!((*Self::ring(map)).slots) } as *mut RingSlot<T>;
+        slots.wrapping_add((index % N as u64) as usize)
+    }
+
+    fn ring(map: *const u8) -> *const Self {
+        map.wrapping_add(core::mem::size_of::<P2pSegmentHeader>()) as *const Self
+    }
+}
+
+/// Single writer of a [`P2pRing`] segment.
+///
+/// Same lifecycle as [`TypedP2pWriter`]: exclusive `.lock` flock, segment
+/// unlinked on drop. [`push`](Self::push) never blocks on readers and
+/// never allocates — the oldest entry is overwritten when the ring is full.
+pub struct P2pRingWriter<T: ShmLayout, const N: usize> {
+    /// Exclusive flock on the `.lock` SHM segment — prevents duplicate writers.
+    _lock: Flock<OwnedFd>,
+    /// POSIX SHM file descriptor for the data segment.
+    _data_fd: OwnedFd,
+    /// Memory-mapped pointer to the data segment.
+    map_ptr: NonNull<libc::c_void>,
+    /// Total mapped size (page-aligned).
+    map_len: usize,
+    /// Segment name (without namespace prefix).
+    name: String,
+    /// SHM namespace the segment lives in.
+    ns: ShmNamespace,
+    /// Entries pushed so far (next entry index).
+    pushed: u64,
+    /// Monotonic heartbeat counter, incremented on every push / beat.
+    heartbeat: u64,
+    _marker: PhantomData<T>,
+}
+
+// SAFETY: The mmap pointer is only written by the single owning writer,
+// guarded by the exclusive .lock file; readers use the slot seqlocks.
+unsafe impl<T: ShmLayout, const N: usize> Send for P2pRingWriter<T, N> {}
+
+impl<T: ShmLayout, const N: usize> P2pRingWriter<T, N> {
+    /// Create a ring segment and acquire the exclusive writer lock.
+    ///
+    /// # Errors
+    /// - `ShmError::WriterAlreadyExists` if another writer holds the segment.
+    /// - `ShmError::Os` for system-level errors.
+    pub fn create(name: &str, source: ModuleAbbrev, dest: ModuleAbbrev) -> Result<Self, ShmError> {
+        Self::create_in(&ShmNamespace::default(), name, source, dest)
+    }
+
+    /// Same as [`create`](Self::create), in namespace `ns`.
+    pub fn create_in(
+        ns: &ShmNamespace,
+        name: &str,
+        source: ModuleAbbrev,
+        dest: ModuleAbbrev,
+    ) -> Result<Self, ShmError> {
+        let () = P2pRing::<T, N>::NON_EMPTY;
+        let table = layout::encode_table(&layout::flatten::<T>());
+        let data_size = segment_mmap_size::<P2pRing<T, N>>(table.len());
+        let lock = acquire_writer_lock(ns, name)?;
+        let (data_fd, map_ptr) = create_data_segment(ns, name, data_size)?;
+
+        // ftruncate zero-fills: head = 0, all stamps = 0 (empty).
+        let ring_size = core::mem::size_of::<P2pRing<T, N>>();
+        let header = P2pSegmentHeader::new(
+            source,
+            dest,
+            P2pRing::<T, N>::LAYOUT_HASH,
+            ring_size as u32,
+        );
+        unsafe {
+            let map = map_ptr.as_ptr() as *mut u8;
+            core::ptr::write_volatile(map as *mut P2pSegmentHeader, header);
+            let table_start = core::mem::size_of::<P2pSegmentHeader>() + ring_size;
+            core::ptr::copy_nonoverlapping(table.as_ptr(), map.add(table_start), table.len());
+        }
+        std::sync::atomic::fence(Ordering::Release);
+
+        Ok(Self {
+            _lock: lock,
+            _data_fd: data_fd,
+            map_ptr,
+            map_len: data_size,
+            name: name.to_string(),
+            ns: ns.clone(),
+            pushed: 0,
+            heartbeat: 0,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Append an entry, overwriting the oldest one if the ring is full.
+    ///
+    /// # RT Safety
+    /// No heap allocation, no syscalls, never waits for readers.
+    pub fn push(&mut self, entry: &T) {
+        let map = self.map_ptr.as_ptr() as *const u8;
+        let index = self.pushed;
+        // SAFETY: the mapping holds a full P2pRing<T, N> (create()).
+        let slot = unsafe { P2pRing::<T, N>::slot(map, index) };
+        let stamp = unsafe { &(*slot).stamp };
+
+        stamp.store(2 * index + 1, Ordering::Relaxed);
+        std::sync::atomic::fence(Ordering::Release);
+        unsafe {
+            core::ptr::write_volatile(core::ptr::addr_of_mut!((*slot).payload), *entry);
+        }
+        stamp.store(2 * index + 2, Ordering::Release);
+
+        self.pushed += 1;
+        unsafe { P2pRing::<T, N>::head(map) }.store(self.pushed, Ordering::Release);
+        self.beat();
+    }
+
+    /// Increment the heartbeat without pushing (call once per idle cycle).
+    pub fn beat(&mut self) {
+        self.heartbeat += 1;
+        unsafe {
+            let hb_ptr = (self.map_ptr.as_ptr() as *mut u8).add(HEARTBEAT_OFFSET) as *mut u64;
+            core::ptr::write_volatile(hb_ptr, self.heartbeat);
+        }
+    }
+
+    /// Entries pushed since creation.
+    #[inline]
+    pub fn pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    /// Ring capacity N.
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Get the current heartbeat counter value.
+    #[inline]
+    pub fn heartbeat(&self) -> u64 {
+        self.heartbeat
+    }
+
+    /// Get the segment name (without prefix).
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the SHM namespace the segment lives in.
+    #[inline]
+    pub fn namespace(&self) -> &ShmNamespace {
+        &self.ns
+    }
+}
+
+impl<T: ShmLayout, const N: usize> Drop for P2pRingWriter<T, N> {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = mman::munmap(self.map_ptr, self.map_len);
+        }
+        let _ = mman::shm_unlink(self.ns.shm_name(&self.name).as_str());
+        let _ = mman::shm_unlink(self.ns.lock_name(&self.name).as_str());
+    }
+}
+
+/// Reader of a [`P2pRing`] segment with its own cursor.
+///
+/// Any number of readers can attach; each sees every entry pushed after
+/// its cursor, in order, as long as it keeps up within N entries. A
+/// reader that was lapped gets one `ShmError::RingOverflow` with the
+/// number of lost entries and resumes at the oldest retained entry.
+pub struct P2pRingReader<T: ShmLayout, const N: usize> {
+    /// POSIX SHM file descriptor for the data segment (read-only, no flock).
+    _data_fd: OwnedFd,
+    /// Memory-mapped pointer to the data segment (PROT_READ).
+    map_ptr: NonNull<libc::c_void>,
+    /// Total mapped size.
+    map_len: usize,
+    /// Segment name (without namespace prefix).
+    name: String,
+    /// SHM namespace the segment lives in.
+    ns: ShmNamespace,
+    /// Index of the next entry to read.
+    cursor: u64,
+    /// Total entries lost to overflow.
+    overflow_count: u64,
+    /// Whether the one-time P2P header validation has been done.
+    verified: bool,
+    /// Last observed heartbeat value.
+    last_heartbeat: u64,
+    /// Consecutive heartbeat checks without change.
+    stale_count: u32,
+    /// Staleness threshold (number of unchanged checks before error).
+    stale_threshold: u32,
+    _marker: PhantomData<T>,
+}
+
+// SAFETY: The mmap pointer is read-only; consistency via slot seqlocks.
+unsafe impl<T: ShmLayout, const N: usize> Send for P2pRingReader<T, N> {}
+
+impl<T: ShmLayout, const N: usize> P2pRingReader<T, N> {
+    /// Attach to an existing ring segment.
+    ///
+    /// The cursor starts at the oldest entry still retained, so a reader
+    /// attaching late still receives up to N queued entries; call
+    /// [`skip_to_latest`](Self::skip_to_latest) to only see new ones.
+    ///
+    /// # Errors
+    /// - `ShmError::SegmentNotFound` if the segment does not exist.
+    /// - `ShmError::PayloadTooSmall` if the segment is smaller than the ring.
+    /// - `ShmError::Os` for system-level errors.
+    pub fn attach(name: &str, stale_threshold: u32) -> Result<Self, ShmError> {
+        Self::attach_in(&ShmNamespace::default(), name, stale_threshold)
+    }
 
-        // Validate size — segment must hold at least header + payload.
-        let type_size = core::mem::size_of::<T>();
-        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
-        let min_size = hdr_size + type_size;
+    /// Same as [`attach`](Self::attach), in namespace `ns`.
+    pub fn attach_in(
+        ns: &ShmNamespace,
+        name: &str,
+        stale_threshold: u32,
+    ) -> Result<Self, ShmError> {
+        let () = P2pRing::<T, N>::NON_EMPTY;
+        let (data_fd, file_size) = open_data_segment(ns, name)?;
+        let min_size = core::mem::size_of::<P2pSegmentHeader>() + core::mem::size_of::<P2pRing<T, N>>();
         if file_size < min_size {
             return Err(ShmError::PayloadTooSmall {
                 segment: name.to_string(),
@@ -706,201 +2300,105 @@ impl<T: Copy> TypedP2pReader<T> {
                 actual: file_size,
             });
         }
+        let map_len = segment_mmap_size::<P2pRing<T, N>>(0).max(file_size);
+        let map_ptr = map_read_only(name, &data_fd, map_len)?;
 
-        // Compute map length (at least one page).
-        let map_len = segment_mmap_size::<T>().max(file_size);
-
-        // Memory-map the segment (read-only).
-        let map_ptr = unsafe {
-            mman::mmap(
-                None,
-                std::num::NonZeroUsize::new(map_len).unwrap(),
-                ProtFlags::PROT_READ,
-                MapFlags::MAP_SHARED,
-                &data_fd,
-                0,
-            )
-            .map_err(|e| ShmError::Os {
-                segment: name.to_string(),
-                source: e,
-            })?
-        };
-
-        // Zero-initialize the payload buffer.
-        // SAFETY: All P2P segment types are repr(C) with only numeric fields;
-        // all-zeros is a valid bit pattern for every field.
-        let payload: T = unsafe { core::mem::zeroed() };
-        let header_buf: P2pSegmentHeader = unsafe { core::mem::zeroed() };
-
-        Ok(Self {
+        let mut reader = Self {
             _data_fd: data_fd,
             map_ptr,
             map_len,
             name: name.to_string(),
-            payload,
-            header_buf,
+            ns: ns.clone(),
+            cursor: 0,
+            overflow_count: 0,
             verified: false,
-            expected_hash: struct_version_hash::<T>(),
             last_heartbeat: 0,
             stale_count: 0,
             stale_threshold,
             _marker: PhantomData,
-        })
-    }
-
-    /// Attach to an existing P2P segment with destination module validation.
-    ///
-    /// Same as `attach()`, but additionally validates that the segment's
-    /// destination module matches `expected_dest`.
-    pub fn attach_validated(
-        name: &str,
-        stale_threshold: u32,
-        expected_dest: ModuleAbbrev,
-    ) -> Result<Self, ShmError> {
-        let reader = Self::attach(name, stale_threshold)?;
-
-        // Read the dest_module byte directly from mapped memory.
-        // P2pSegmentHeader layout: dest_module is at byte offset 25.
-        let map = reader.map_ptr.as_ptr() as *const u8;
-        let dest_byte = unsafe { core::ptr::read(map.add(25)) };
-
-        if let Some(actual_dest) = ModuleAbbrev::from_u8(dest_byte) {
-            if actual_dest != expected_dest {
-                return Err(ShmError::DestinationMismatch {
-                    segment: name.to_string(),
-                    expected: expected_dest,
-                    actual: actual_dest,
-                });
-            }
-        }
-
+        };
+        reader.cursor = reader.head().saturating_sub(N as u64);
         Ok(reader)
     }
 
-    /// Read the current segment payload.
-    ///
-    /// On the first successful read, validates P2P magic and version hash.
-    /// On every read, checks the heartbeat for staleness.
+    /// Take the next entry, `Ok(None)` when caught up with the writer.
     ///
-    /// Returns a reference to the internal aligned buffer containing the
-    /// latest payload. The reference is valid until the next call to `read()`.
+    /// Validates magic and version hash on first use.
     ///
     /// # Errors
-    /// - `ShmError::InvalidMagic` on first read if magic is wrong.
-    /// - `ShmError::VersionMismatch` on first read if hash differs.
-    /// - `ShmError::HeartbeatStale` if heartbeat unchanged for `stale_threshold` reads.
-    /// - `ShmError::ReadContention` if too many read retries.
-    pub fn read(&mut self) -> Result<&T, ShmError> {
-        let type_size = core::mem::size_of::<T>();
-        let hdr_size = core::mem::size_of::<P2pSegmentHeader>();
-        let max_retries = 10u32;
+    /// - `ShmError::InvalidMagic` / `ShmError::VersionMismatch` on first use.
+    /// - `ShmError::RingOverflow` once per lap; the next call continues at
+    ///   the oldest retained entry.
+    pub fn pop(&mut self) -> Result<Option<T>, ShmError> {
+        self.verify()?;
         let map = self.map_ptr.as_ptr() as *const u8;
+        let head = self.head();
+        if self.cursor >= head {
+            // Never ahead of the writer (e.g. after skip_to_latest).
+            self.cursor = head;
+            return Ok(None);
+        }
+        if head - self.cursor > N as u64 {
+            return Err(self.overflow(head - N as u64));
+        }
 
-        for _attempt in 0..max_retries {
-            // Read write_seq before data.
-            let seq_before = unsafe {
-                let ws_ptr = map.add(WRITE_SEQ_OFFSET) as *const u32;
-                core::ptr::read_volatile(ws_ptr)
-            };
-
-            // Skip if write is in progress (odd write_seq).
-            if seq_before & 1 != 0 {
-                std::thread::yield_now();
-                continue;
-            }
-
-            // Acquire barrier before reading data.
-            std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
-
-            // Copy header from mmap[0..hdr_size] to header_buf.
-            unsafe {
-                core::ptr::copy_nonoverlapping(
-                    map,
-                    &mut self.header_buf as *mut P2pSegmentHeader as *mut u8,
-                    hdr_size,
-                );
-            }
-
-            // Copy payload from mmap[hdr_size..hdr_size+type_size] to payload buf.
-            unsafe {
-                core::ptr::copy_nonoverlapping(
-                    map.add(hdr_size),
-                    &mut self.payload as *mut T as *mut u8,
-                    type_size,
-                );
-            }
-
-            // Acquire barrier after reading data.
-            std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
-
-            // Read write_seq after data.
-            let seq_after = unsafe {
-                let ws_ptr = map.add(WRITE_SEQ_OFFSET) as *const u32;
-                core::ptr::read_volatile(ws_ptr)
-            };
-
-            // If write_seq changed during read, retry.
-            if seq_before != seq_after {
-                std::thread::yield_now();
-                continue;
-            }
-
-            // === Successful consistent read ===
-
-            // One-time P2P header validation.
-            if !self.verified {
-                let header = &self.header_buf;
-
-                if !header.is_magic_valid() {
-                    return Err(ShmError::InvalidMagic {
-                        segment: self.name.clone(),
-                    });
-                }
-                if header.version_hash != self.expected_hash {
-                    return Err(ShmError::VersionMismatch {
-                        segment: self.name.clone(),
-                        expected: self.expected_hash,
-                        actual: header.version_hash,
-                    });
-                }
-                self.verified = true;
+        let expected = 2 * self.cursor + 2;
+        // SAFETY: the mapping holds a full P2pRing<T, N> (attach()).
+        let slot = unsafe { P2pRing::<T, N>::slot(map, self.cursor) };
+        let stamp = unsafe { &(*slot).stamp };
+        if stamp.load(Ordering::Acquire) == expected {
+            let entry = unsafe { core::ptr::read_volatile(core::ptr::addr_of!((*slot).payload)) };
+            std::sync::atomic::fence(Ordering::Acquire);
+            if stamp.load(Ordering::Relaxed) == expected {
+                self.cursor += 1;
+                return Ok(Some(entry));
             }
+        }
 
-            // Heartbeat staleness check.
-            let heartbeat = self.header_buf.heartbeat;
-
-            if heartbeat == self.last_heartbeat && self.last_heartbeat != 0 {
-                self.stale_count += 1;
-                if self.stale_count >= self.stale_threshold {
-                    return Err(ShmError::HeartbeatStale {
-                        segment: self.name.clone(),
-                        missed_beats: self.stale_count,
-                    });
-                }
-            } else {
-                self.last_heartbeat = heartbeat;
-                self.stale_count = 0;
-            }
+        // Slot reused by entry cursor + N or later while we were reading.
+        let oldest = self.head().saturating_sub(N as u64 - 1).max(self.cursor + 1);
+        Err(self.overflow(oldest))
+    }
 
-            return Ok(&self.payload);
-        }
+    /// Entries waiting to be read (capped at N).
+    pub fn pending(&self) -> u64 {
+        self.head().saturating_sub(self.cursor).min(N as u64)
+    }
 
-        // Too many retries.
-        Err(ShmError::ReadContention {
-            segment: self.name.clone(),
-        })
+    /// Drop all pending entries; only entries pushed from now on are read.
+    pub fn skip_to_latest(&mut self) {
+        self.cursor = self.head();
     }
 
-    /// Check if the segment has new data since the last read.
+    /// Check the writer heartbeat for staleness (call once per cycle).
     ///
-    /// This is a cheap check using the P2P heartbeat field (no data copy).
-    pub fn has_changed(&self) -> bool {
-        let map = self.map_ptr.as_ptr() as *const u8;
+    /// # Errors
+    /// - `ShmError::HeartbeatStale` if the heartbeat is unchanged for
+    ///   `stale_threshold` consecutive checks.
+    pub fn check_heartbeat(&mut self) -> Result<(), ShmError> {
         let heartbeat = unsafe {
-            let hb_ptr = map.add(HEARTBEAT_OFFSET) as *const u64;
+            let hb_ptr = (self.map_ptr.as_ptr() as *const u8).add(HEARTBEAT_OFFSET) as *const u64;
             core::ptr::read_volatile(hb_ptr)
         };
-        heartbeat != self.last_heartbeat
+        if heartbeat == self.last_heartbeat && self.last_heartbeat != 0 {
+            self.stale_count += 1;
+            if self.stale_count >= self.stale_threshold {
+                return Err(ShmError::HeartbeatStale {
+                    segment: self.name.clone(),
+                    missed_beats: self.stale_count,
+                });
+            }
+        } else {
+            self.last_heartbeat = heartbeat;
+            self.stale_count = 0;
+        }
+        Ok(())
+    }
+
+    /// Total entries lost to overflow since attach.
+    #[inline]
+    pub fn overflow_count(&self) -> u64 {
+        self.overflow_count
     }
 
     /// Get the segment name (without prefix).
@@ -909,32 +2407,65 @@ impl<T: Copy> TypedP2pReader<T> {
         &self.name
     }
 
-    /// Get the current stale count (consecutive reads without heartbeat change).
+    /// Get the SHM namespace the segment lives in.
     #[inline]
-    pub fn stale_count(&self) -> u32 {
-        self.stale_count
+    pub fn namespace(&self) -> &ShmNamespace {
+        &self.ns
     }
 
-    /// Get the last observed heartbeat value.
-    #[inline]
-    pub fn last_heartbeat(&self) -> u64 {
-        self.last_heartbeat
+    fn head(&self) -> u64 {
+        // SAFETY: the mapping holds a full P2pRing<T, N> (attach()).
+        unsafe { P2pRing::<T, N>::head(self.map_ptr.as_ptr() as *const u8) }.load(Ordering::Acquire)
     }
 
-    /// Reset staleness counter (e.g., after a recovery action).
-    pub fn reset_stale(&mut self) {
-        self.stale_count = 0;
+    /// Skip to `oldest` and report the entries lost on the way.
+    fn overflow(&mut self, oldest: u64) -> ShmError {
+        let lost = oldest - self.cursor;
+        self.cursor = oldest;
+        self.overflow_count += lost;
+        ShmError::RingOverflow {
+            segment: self.name.clone(),
+            lost,
+        }
+    }
+
+    fn verify(&mut self) -> Result<(), ShmError> {
+        if self.verified {
+            return Ok(());
+        }
+        let header =
+            unsafe { core::ptr::read_volatile(self.map_ptr.as_ptr() as *const P2pSegmentHeader) };
+        if !header.is_magic_valid() {
+            return Err(ShmError::InvalidMagic {
+                segment: self.name.clone(),
+            });
+        }
+        let expected = P2pRing::<T, N>::LAYOUT_HASH;
+        if header.version_hash != expected {
+            return Err(ShmError::VersionMismatch {
+                segment: self.name.clone(),
+                expected,
+                actual: header.version_hash,
+                diff: published_layout_diff(
+                    &layout::flatten::<T>(),
+                    core::mem::size_of::<P2pRing<T, N>>(),
+                    self.map_ptr.as_ptr() as *const u8,
+                    self.map_len,
+                    &self._data_fd,
+                    header.payload_size as usize,
+                ),
+            });
+        }
+        self.verified = true;
+        Ok(())
     }
 }
 
-impl<T: Copy> Drop for TypedP2pReader<T> {
+impl<T: ShmLayout, const N: usize> Drop for P2pRingReader<T, N> {
     fn drop(&mut self) {
-        // Unmap the data segment.
         unsafe {
             let _ = mman::munmap(self.map_ptr, self.map_len);
         }
-        // _data_fd (OwnedFd) is dropped automatically, closing the fd.
-        // Note: Reader does NOT shm_unlink — only the writer owns the segment lifetime.
     }
 }
 
@@ -943,7 +2474,7 @@ impl<T: Copy> Drop for TypedP2pReader<T> {
 /// Information about a discovered SHM segment.
 #[derive(Debug, Clone)]
 pub struct SegmentInfo {
-    /// Segment name (without `/evo_` prefix), e.g. `"hal_cu"`.
+    /// Segment name (without namespace prefix), e.g. `"hal_cu"`.
     pub name: String,
     /// Full filesystem path, e.g. `"/dev/shm/evo_hal_cu"`.
     pub path: std::path::PathBuf,
@@ -973,20 +2504,28 @@ pub struct SegmentInfo {
 ///     println!("{}: alive={}, hb={:?}", seg.name, seg.writer_alive, seg.heartbeat);
 /// }
 /// ```
+///
+/// The plain functions cover the default namespace; the `_in` variants
+/// only see segments of the given instance.
 pub struct SegmentDiscovery;
 
 impl SegmentDiscovery {
     /// SHM directory path.
     const SHM_DIR: &'static str = "/dev/shm";
-    /// Filename prefix for EVO segments (matches SHM_PREFIX without leading `/`).
-    const FILE_PREFIX: &'static str = "evo_";
 
-    /// List all EVO P2P segments found in `/dev/shm/`.
+    /// List all EVO P2P segments of the default namespace in `/dev/shm/`.
     ///
     /// Returns a sorted `Vec<SegmentInfo>` for every file matching `evo_*`
-    /// (excluding `.lock` files). Each entry probes the P2P header for magic
+    /// (excluding `.lock` and `.schema.json` files). Each entry probes the P2P header for magic
     /// validation and the `.lock` SHM file for writer liveness.
     pub fn list_segments() -> Vec<SegmentInfo> {
+        Self::list_segments_in(&ShmNamespace::default())
+    }
+
+    /// Same as [`list_segments`](Self::list_segments), for the files of
+    /// namespace `ns` (`evo-<id>_*` for an instance).
+    pub fn list_segments_in(ns: &ShmNamespace) -> Vec<SegmentInfo> {
+        let prefix = ns.file_prefix();
         let dir = match std::fs::read_dir(Self::SHM_DIR) {
             Ok(d) => d,
             Err(_) => return Vec::new(),
@@ -997,12 +2536,15 @@ impl SegmentDiscovery {
             let fname = entry.file_name();
             let fname_str = fname.to_string_lossy();
 
-            // Skip non-evo files and .lock files.
-            if !fname_str.starts_with(Self::FILE_PREFIX) || fname_str.ends_with(".lock") {
+            // Skip non-evo files, .lock files and published schemas.
+            if !fname_str.starts_with(&prefix)
+                || fname_str.ends_with(".lock")
+                || fname_str.ends_with(schema::SCHEMA_SUFFIX)
+            {
                 continue;
             }
 
-            let name = fname_str[Self::FILE_PREFIX.len()..].to_string();
+            let name = fname_str[prefix.len()..].to_string();
             let path = entry.path();
 
             let meta = match entry.metadata() {
@@ -1031,7 +2573,7 @@ impl SegmentDiscovery {
             }
 
             // Probe writer liveness via flock on the `.lock` SHM file.
-            info.writer_alive = Self::probe_writer(&name);
+            info.writer_alive = Self::probe_writer(ns, &name);
 
             segments.push(info);
         }
@@ -1042,7 +2584,12 @@ impl SegmentDiscovery {
 
     /// List segments where the source or destination matches `module`.
     pub fn list_for(module: ModuleAbbrev) -> Vec<SegmentInfo> {
-        Self::list_segments()
+        Self::list_for_in(&ShmNamespace::default(), module)
+    }
+
+    /// Same as [`list_for`](Self::list_for), in namespace `ns`.
+    pub fn list_for_in(ns: &ShmNamespace, module: ModuleAbbrev) -> Vec<SegmentInfo> {
+        Self::list_segments_in(ns)
             .into_iter()
             .filter(|s| s.source == Some(module) || s.dest == Some(module))
             .collect()
@@ -1053,14 +2600,19 @@ impl SegmentDiscovery {
     /// A segment is considered dead if no writer holds the `.lock` file.
     /// Returns the number of cleaned-up segments.
     pub fn cleanup_dead() -> usize {
-        let segments = Self::list_segments();
+        Self::cleanup_dead_in(&ShmNamespace::default())
+    }
+
+    /// Same as [`cleanup_dead`](Self::cleanup_dead), in namespace `ns`.
+    /// Segments of other instances are never touched.
+    pub fn cleanup_dead_in(ns: &ShmNamespace) -> usize {
+        let segments = Self::list_segments_in(ns);
         let mut cleaned = 0;
         for seg in &segments {
             if !seg.writer_alive {
-                let data_name = format!("{}{}", SHM_PREFIX, seg.name);
-                let lock_name = format!("{}{}.lock", SHM_PREFIX, seg.name);
-                let _ = mman::shm_unlink(data_name.as_str());
-                let _ = mman::shm_unlink(lock_name.as_str());
+                let _ = mman::shm_unlink(ns.shm_name(&seg.name).as_str());
+                let _ = mman::shm_unlink(ns.lock_name(&seg.name).as_str());
+                let _ = mman::shm_unlink(ns.schema_name(&seg.name).as_str());
                 cleaned += 1;
             }
         }
@@ -1090,8 +2642,8 @@ impl SegmentDiscovery {
     /// - If `flock(LOCK_EX | LOCK_NB)` **succeeds**, no writer holds it → writer is dead.
     /// - If it fails with `EWOULDBLOCK`, a writer is alive.
     /// - If the `.lock` file doesn't exist, the writer is dead.
-    fn probe_writer(segment_name: &str) -> bool {
-        let lock_shm_name = format!("{}{}.lock", SHM_PREFIX, segment_name);
+    fn probe_writer(ns: &ShmNamespace, segment_name: &str) -> bool {
+        let lock_shm_name = ns.lock_name(segment_name);
 
         let fd = match mman::shm_open(
             lock_shm_name.as_str(),
@@ -1200,6 +2752,29 @@ mod tests {
             u32::from_ne_bytes(bytes[WRITE_SEQ_OFFSET..WRITE_SEQ_OFFSET + 4].try_into().unwrap());
         assert_eq!(ws, 0);
 
+        let header = P2pSegmentHeader { wait_until_ms: 0xA5A5_A5A5, ..header };
+        let bytes: &[u8] = unsafe {
+            core::slice::from_raw_parts(
+                &header as *const P2pSegmentHeader as *const u8,
+                core::mem::size_of::<P2pSegmentHeader>(),
+            )
+        };
+        let wt = u32::from_ne_bytes(
+            bytes[WAIT_UNTIL_OFFSET..WAIT_UNTIL_OFFSET + 4].try_into().unwrap(),
+        );
+        assert_eq!(wt, 0xA5A5_A5A5);
+
+        let header = P2pSegmentHeader { commit_ns: 0x0123_4567_89AB_CDEF, ..header };
+        let bytes: &[u8] = unsafe {
+            core::slice::from_raw_parts(
+                &header as *const P2pSegmentHeader as *const u8,
+                core::mem::size_of::<P2pSegmentHeader>(),
+            )
+        };
+        let ts =
+            u64::from_ne_bytes(bytes[COMMIT_NS_OFFSET..COMMIT_NS_OFFSET + 8].try_into().unwrap());
+        assert_eq!(ts, 0x0123_4567_89AB_CDEF);
+
         let vh = u32::from_ne_bytes(bytes[8..12].try_into().unwrap());
         assert_eq!(vh, 0xDEAD_BEEF);
 
@@ -1219,6 +2794,7 @@ mod tests {
             value: u64,
             _pad: [u8; 56],
         }
+        crate::impl_shm_layout!(TestSegment { header, value, _pad });
 
         let mut writer =
             TypedP2pWriter::<TestSegment>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
@@ -1253,6 +2829,7 @@ mod tests {
             header: P2pSegmentHeader,
             _pad: [u8; 64],
         }
+        crate::impl_shm_layout!(Seg { header, _pad });
 
         let _writer =
             TypedP2pWriter::<Seg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
@@ -1271,6 +2848,7 @@ mod tests {
             header: P2pSegmentHeader,
             _pad: [u8; 64],
         }
+        crate::impl_shm_layout!(Seg { header, _pad });
 
         let result = TypedP2pReader::<Seg>::attach("nonexistent_seg_12345", 10);
         assert!(matches!(result, Err(ShmError::SegmentNotFound { .. })));
@@ -1287,6 +2865,7 @@ mod tests {
             header: P2pSegmentHeader,
             _pad: [u8; 64],
         }
+        crate::impl_shm_layout!(Seg { header, _pad });
 
         let mut writer =
             TypedP2pWriter::<Seg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
@@ -1321,6 +2900,7 @@ mod tests {
             value: u64,
             _pad: [u8; 56],
         }
+        crate::impl_shm_layout!(SegA { header, value, _pad });
 
         // SegB is 256 bytes (different size → different version hash).
         #[derive(Debug, Clone, Copy)]
@@ -1329,6 +2909,7 @@ mod tests {
             header: P2pSegmentHeader,
             values: [u64; 24],
         }
+        crate::impl_shm_layout!(SegB { header, values });
 
         // Sanity: hashes must differ.
         assert_ne!(
@@ -1348,6 +2929,57 @@ mod tests {
         assert!(matches!(result, Err(ShmError::VersionMismatch { .. })));
     }
 
+    /// Test: equal-size field swap is rejected and the error names the
+    /// moved fields.
+    #[test]
+    fn version_mismatch_reports_field_diff() {
+        let name = format!("test_vmm_diff_{}", std::process::id());
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Writer {
+            position: f64,
+            velocity: f64,
+            flags: u32,
+        }
+        crate::impl_shm_layout!(Writer { position, velocity, flags });
+
+        #[derive(Debug, Clone, Copy)]
+        #[repr(C)]
+        struct Reader {
+            velocity: f64,
+            position: f64,
+            flags: i32,
+        }
+        crate::impl_shm_layout!(Reader { velocity, position, flags });
+
+        // The size/align hash cannot tell them apart.
+        assert_eq!(struct_version_hash::<Writer>(), struct_version_hash::<Reader>());
+
+        let mut writer = TypedP2pWriter::<Writer>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+            .expect("create writer");
+        writer
+            .commit(&Writer { position: 1.0, velocity: 2.0, flags: 0 })
+            .expect("commit");
+
+        let mut reader = TypedP2pReader::<Reader>::attach(&name, 10).expect("attach");
+        let Err(err) = reader.read() else {
+            panic!("field swap must be rejected");
+        };
+        let ShmError::VersionMismatch { diff, .. } = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(
+            diff,
+            &[
+                "velocity: f64@0+8 → f64@8+8",
+                "position: f64@8+8 → f64@0+8",
+                "flags: i32@16+4 → u32@16+4",
+            ]
+        );
+        assert!(err.to_string().contains("fields (reader → writer): velocity: f64@0+8"));
+    }
+
     /// Test: writer drop cleans up SHM segment.
     #[test]
     fn writer_drop_cleanup() {
@@ -1359,6 +2991,7 @@ mod tests {
             header: P2pSegmentHeader,
             _pad: [u8; 64],
         }
+        crate::impl_shm_layout!(Seg { header, _pad });
 
         {
             let _writer =
@@ -1384,6 +3017,7 @@ mod tests {
             header: P2pSegmentHeader,
             _pad: [u8; 64],
         }
+        crate::impl_shm_layout!(Seg { header, _pad });
 
         let mut writer =
             TypedP2pWriter::<Seg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
@@ -1411,6 +3045,7 @@ mod tests {
             value: u64,
             _pad: [u8; 56],
         }
+        crate::impl_shm_layout!(Seg { header, value, _pad });
 
         let mut writer =
             TypedP2pWriter::<Seg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
@@ -1443,6 +3078,7 @@ mod tests {
             header: P2pSegmentHeader,
             values: [u64; 8],
         }
+        crate::impl_shm_layout!(Seg { header, values });
 
         let mut writer =
             TypedP2pWriter::<Seg>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
@@ -1475,6 +3111,7 @@ mod tests {
             val: u64,
             _pad: [u8; 56],
         }
+        crate::impl_shm_layout!(Seg { header, val, _pad });
 
         // Create and immediately drop the writer.
         {
@@ -1504,6 +3141,7 @@ mod tests {
             header: P2pSegmentHeader,
             _data: [u8; 64],
         }
+        crate::impl_shm_layout!(Seg { header, _data });
 
         let mut wa =
             TypedP2pWriter::<Seg>::create(&name_a, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
@@ -1544,6 +3182,7 @@ mod tests {
             header: P2pSegmentHeader,
             _data: [u8; 64],
         }
+        crate::impl_shm_layout!(Seg { header, _data });
 
         // Create a segment, commit data, then manually unlink the lock file
         // to simulate a crashed writer (data remains but lock file is gone).
@@ -1603,4 +3242,182 @@ mod tests {
         // Live writer should still be present.
         assert!(after.iter().any(|s| s.name == name), "live segment survives cleanup");
     }
+
+    // ── P2pRing ─────────────────────────────────────────────────────
+
+    fn ring_pair<const N: usize>(tag: &str) -> (P2pRingWriter<u64, N>, P2pRingReader<u64, N>) {
+        let name = format!("test_ring_{tag}_{}", std::process::id());
+        let writer = P2pRingWriter::<u64, N>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Mqt)
+            .expect("create ring");
+        let reader = P2pRingReader::<u64, N>::attach(&name, 10).expect("attach ring");
+        (writer, reader)
+    }
+
+    fn drain<T: ShmLayout, const N: usize>(reader: &mut P2pRingReader<T, N>) -> Vec<T> {
+        let mut out = Vec::new();
+        while let Some(entry) = reader.pop().expect("pop") {
+            out.push(entry);
+        }
+        out
+    }
+
+    #[test]
+    fn ring_delivers_every_entry_in_order() {
+        let (mut writer, mut reader) = ring_pair::<8>("order");
+        assert_eq!(reader.pop().unwrap(), None);
+
+        for v in 1..=5 {
+            writer.push(&v);
+        }
+        assert_eq!(reader.pending(), 5);
+        assert_eq!(drain(&mut reader), vec![1, 2, 3, 4, 5]);
+        assert_eq!(reader.pop().unwrap(), None);
+
+        // Wraps around the ring.
+        for v in 6..=15 {
+            writer.push(&v);
+            assert_eq!(reader.pop().unwrap(), Some(v));
+        }
+        assert_eq!(writer.pushed(), 15);
+        assert_eq!(writer.heartbeat(), 15);
+        assert_eq!(reader.overflow_count(), 0);
+    }
+
+    #[test]
+    fn ring_overflow_detected_and_counted() {
+        let (mut writer, mut reader) = ring_pair::<8>("overflow");
+        for v in 0..20 {
+            writer.push(&v);
+        }
+        let err = reader.pop().unwrap_err();
+        assert!(matches!(err, ShmError::RingOverflow { lost: 12, .. }), "{err}");
+        assert!(err.to_string().contains("12 entries lost"));
+        assert_eq!(reader.overflow_count(), 12);
+
+        // Resumes at the oldest retained entry.
+        assert_eq!(drain(&mut reader), (12..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ring_readers_have_independent_cursors() {
+        let name = format!("test_ring_cursors_{}", std::process::id());
+        let mut writer = P2pRingWriter::<u64, 4>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Re)
+            .expect("create ring");
+        let mut fast = P2pRingReader::<u64, 4>::attach(&name, 10).expect("attach");
+
+        for v in 0..6 {
+            writer.push(&v);
+        }
+        // Late reader starts at the oldest retained entry.
+        let mut late = P2pRingReader::<u64, 4>::attach(&name, 10).expect("attach late");
+        assert_eq!(drain(&mut late), vec![2, 3, 4, 5]);
+
+        // A third reader skips the backlog.
+        let mut live = P2pRingReader::<u64, 4>::attach(&name, 10).expect("attach live");
+        live.skip_to_latest();
+        writer.push(&6);
+        assert_eq!(drain(&mut live), vec![6]);
+
+        // The first reader was lapped; the others are unaffected.
+        assert!(matches!(fast.pop(), Err(ShmError::RingOverflow { lost: 3, .. })));
+        assert_eq!(drain(&mut fast), vec![3, 4, 5, 6]);
+        assert_eq!(late.overflow_count(), 0);
+    }
+
+    #[test]
+    fn ring_capacity_mismatch_rejected() {
+        let name = format!("test_ring_vmm_{}", std::process::id());
+        let _writer = P2pRingWriter::<u64, 8>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Mqt)
+            .expect("create ring");
+        let mut reader = P2pRingReader::<u64, 16>::attach(&name, 10).expect("attach");
+        match reader.pop() {
+            Err(ShmError::VersionMismatch { diff, .. }) => {
+                assert_eq!(diff.len(), 1, "{diff:?}");
+                assert!(diff[0].starts_with("payload size"), "{diff:?}");
+            }
+            other => panic!("expected VersionMismatch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ring_duplicate_writer_rejected() {
+        let name = format!("test_ring_dup_{}", std::process::id());
+        let _writer = P2pRingWriter::<u64, 8>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Mqt)
+            .expect("first writer");
+        let result = P2pRingWriter::<u64, 8>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Mqt);
+        assert!(matches!(result, Err(ShmError::WriterAlreadyExists { .. })));
+    }
+
+    #[test]
+    fn ring_heartbeat_staleness() {
+        let (mut writer, mut reader) = ring_pair::<8>("stale");
+        writer.beat();
+        reader.check_heartbeat().expect("first check");
+        reader.check_heartbeat().expect("stale 1");
+        writer.push(&1);
+        reader.check_heartbeat().expect("push beats");
+        for _ in 0..9 {
+            reader.check_heartbeat().expect("below threshold");
+        }
+        assert!(matches!(reader.check_heartbeat(), Err(ShmError::HeartbeatStale { .. })));
+    }
+
+    /// Concurrent writer and reader: every entry is either delivered
+    /// intact and in order, or counted as lost.
+    #[test]
+    fn ring_concurrent_no_torn_entries() {
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Event {
+            seq: u64,
+            check: u64,
+            _data: [u64; 6],
+        }
+        crate::impl_shm_layout!(Event { seq, check, _data });
+
+        const TOTAL: u64 = 200_000;
+        let name = format!("test_ring_conc_{}", std::process::id());
+        let mut writer = P2pRingWriter::<Event, 64>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Mqt)
+            .expect("create ring");
+        let mut reader = P2pRingReader::<Event, 64>::attach(&name, 10).expect("attach");
+
+        let producer = std::thread::spawn(move || {
+            for seq in 0..TOTAL {
+                writer.push(&Event { seq, check: !seq, _data: [seq; 6] });
+            }
+            writer
+        });
+
+        let mut received = 0u64;
+        let mut next = 0u64;
+        while next < TOTAL {
+            match reader.pop() {
+                Ok(Some(event)) => {
+                    assert_eq!(event.check, !event.seq, "torn entry");
+                    assert_eq!(event._data, [event.seq; 6], "torn entry");
+                    assert_eq!(event.seq, next, "out of order");
+                    next += 1;
+                    received += 1;
+                }
+                Ok(None) => std::thread::yield_now(),
+                Err(ShmError::RingOverflow { lost, .. }) => next += lost,
+                Err(e) => panic!("{e}"),
+            }
+        }
+        let _writer = producer.join().unwrap();
+        assert_eq!(received + reader.overflow_count(), TOTAL);
+    }
+
+    #[test]
+    fn wait_lease_compare_wraps() {
+        assert!(!lease_running(0, 5), "0 = no lease");
+        assert!(lease_running(10, 5));
+        assert!(lease_running(5, 5));
+        assert!(!lease_running(4, 5));
+        // Across the u32 millisecond wrap.
+        assert!(lease_running(20, u32::MAX - 10));
+        assert!(!lease_running(u32::MAX - 10, 20));
+        assert_eq!(lease_ms(0), 1);
+        assert_eq!(lease_ms(7_000_000), 7);
+    }
 }
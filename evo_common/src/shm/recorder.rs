@@ -0,0 +1,866 @@
+//! SHM recorder and replayer for post-mortem analysis.
+//!
+//! [`Recorder`] attaches to a configurable set of P2P segments (found via
+//! [`SegmentDiscovery`]) with untyped [`RawP2pReader`]s and appends every
+//! new heartbeat, stamped with `CLOCK_MONOTONIC`, to a compact binary log.
+//! Files rotate by size; with a [`TriggerWindow`] the recorder keeps only
+//! the last `pre` seconds in memory and writes them, plus `post` seconds
+//! after the trigger, when [`Recorder::trigger`] fires (e.g. on SafetyStop).
+//!
+//! [`Recording`] loads a log; [`Replayer`] becomes the writer of recorded
+//! segments (same header fields and layout table, so typed readers attach
+//! unchanged) and feeds the samples back, e.g. a HAL trace into a live CU.
+//!
+//! ## File Format (little-endian)
+//!
+//! ```text
+//! [0..8]   magic   b"EVO_REC\0"
+//! [8..12]  version u32 (1)
+//! [12..16] reserved
+//! records: kind u8, segment u16, len u32, body[len]
+//!   1 SEGMENT  source u8, dest u8, version_hash u32, payload_size u32,
+//!              name_len u8, name, layout table (rest)
+//!   2 SAMPLE   timestamp_ns u64, heartbeat u64, payload
+//!   3 TRIGGER  timestamp_ns u64
+//! ```
+//!
+//! Every file starts with the SEGMENT records of all attached segments, so
+//! rotated files load on their own. A truncated last record (crash while
+//! writing) is ignored on load.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use thiserror::Error;
+
+use super::layout::ShmLayout;
+use super::p2p::{
+    EVO_P2P_MAGIC, P2pSegmentHeader, RawP2pReader, RawP2pWriter, SegmentDiscovery, ShmError,
+    ShmNamespace, monotonic_ns,
+};
+
+// ─── Constants ──────────────────────────────────────────────────────
+
+/// Log file magic bytes.
+pub const RECORD_MAGIC: [u8; 8] = *b"EVO_REC\0";
+
+/// Log format version.
+pub const RECORD_VERSION: u32 = 1;
+
+/// Log file extension.
+pub const RECORD_EXTENSION: &str = "evorec";
+
+const FILE_HEADER_LEN: usize = 16;
+const RECORD_HEADER_LEN: usize = 7;
+const KIND_SEGMENT: u8 = 1;
+const KIND_SAMPLE: u8 = 2;
+const KIND_TRIGGER: u8 = 3;
+
+// ─── Error Type ─────────────────────────────────────────────────────
+
+/// Recorder / replayer errors.
+#[derive(Debug, Error)]
+pub enum RecorderError {
+    /// Log file I/O failed.
+    #[error("recording I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Segment attach / write failed.
+    #[error(transparent)]
+    Shm(#[from] ShmError),
+
+    /// Log file is not a valid recording.
+    #[error("invalid recording '{path}': {reason}")]
+    Format {
+        /// File path.
+        path: PathBuf,
+        /// What is wrong.
+        reason: String,
+    },
+
+    /// Recorded payload does not match the requested type.
+    #[error("segment '{segment}' recorded with hash 0x{recorded:08X}, expected 0x{expected:08X}")]
+    TypeMismatch {
+        /// Segment name.
+        segment: String,
+        /// Hash expected for the requested type.
+        expected: u32,
+        /// Hash stored in the recording.
+        recorded: u32,
+    },
+
+    /// Replay speed is not a positive finite factor.
+    #[error("invalid replay speed {0}: must be positive and finite")]
+    InvalidSpeed(f64),
+}
+
+// ─── Configuration ──────────────────────────────────────────────────
+
+/// Pre/post trigger capture window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerWindow {
+    /// History kept in memory and written when the trigger fires.
+    pub pre: Duration,
+    /// Recording time after the (last) trigger.
+    pub post: Duration,
+}
+
+/// Recorder configuration.
+#[derive(Debug, Clone)]
+pub struct RecorderConfig {
+    /// Segment names to record (e.g. `"hal_cu"`); empty = every segment
+    /// with a live writer.
+    pub segments: Vec<String>,
+    /// SHM namespace of the recorded EVO instance.
+    pub namespace: ShmNamespace,
+    /// Output directory (created if missing).
+    pub dir: PathBuf,
+    /// File name prefix: `<prefix>_<unix_ms>_<n>.evorec`.
+    pub prefix: String,
+    /// Start a new file once the current one reaches this size.
+    pub max_file_bytes: u64,
+    /// Files kept; the oldest are removed beyond this (0 = keep all).
+    pub max_files: usize,
+    /// Trigger window; `None` records continuously.
+    pub trigger_window: Option<TriggerWindow>,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            segments: Vec::new(),
+            namespace: ShmNamespace::default(),
+            dir: std::env::temp_dir().join("evo_rec"),
+            prefix: "evo".to_string(),
+            max_file_bytes: 64 * 1024 * 1024,
+            max_files: 8,
+            trigger_window: None,
+        }
+    }
+}
+
+/// Recorder counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecorderStats {
+    /// Samples captured (buffered or written).
+    pub samples: u64,
+    /// Reads skipped due to write contention.
+    pub contended: u64,
+    /// Triggers fired.
+    pub triggers: u64,
+    /// Files opened.
+    pub files: u64,
+}
+
+/// Trigger predicate, called with `(segment, payload)` for every sample.
+pub type TriggerFn = Box<dyn FnMut(&str, &[u8]) -> bool + Send>;
+
+// ─── Recorder ───────────────────────────────────────────────────────
+
+/// An attached segment.
+struct Source {
+    id: u16,
+    reader: RawP2pReader,
+    /// Encoded SEGMENT record, repeated at the start of every file.
+    declaration: Vec<u8>,
+}
+
+/// Captures new heartbeats of P2P segments into rotating log files.
+///
+/// Call [`poll`](Self::poll) faster than the fastest recorded writer
+/// (samples between two polls are lost; gaps show as heartbeat jumps) and
+/// [`refresh`](Self::refresh) now and then to follow restarted writers.
+pub struct Recorder {
+    config: RecorderConfig,
+    sources: Vec<Source>,
+    next_id: u16,
+    out: Option<BufWriter<File>>,
+    out_bytes: u64,
+    files: VecDeque<PathBuf>,
+    file_index: u32,
+    session: u128,
+    /// Pending records `(timestamp_ns, record)` in trigger mode.
+    pre_buffer: VecDeque<(u64, Vec<u8>)>,
+    /// End of the post-trigger window while capturing.
+    capture_until: Option<u64>,
+    trigger: Option<TriggerFn>,
+    stats: RecorderStats,
+}
+
+impl Recorder {
+    /// Create the output directory and attach to the configured segments.
+    ///
+    /// # Errors
+    /// - `RecorderError::Io` if the directory cannot be created.
+    pub fn new(config: RecorderConfig) -> Result<Self, RecorderError> {
+        std::fs::create_dir_all(&config.dir)?;
+        let session = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut recorder = Self {
+            config,
+            sources: Vec::new(),
+            next_id: 0,
+            out: None,
+            out_bytes: 0,
+            files: VecDeque::new(),
+            file_index: 0,
+            session,
+            pre_buffer: VecDeque::new(),
+            capture_until: None,
+            trigger: None,
+            stats: RecorderStats::default(),
+        };
+        recorder.refresh()?;
+        Ok(recorder)
+    }
+
+    /// Fire [`trigger`](Self::trigger) automatically when `predicate`
+    /// returns `true` for a captured sample.
+    pub fn set_trigger(&mut self, predicate: impl FnMut(&str, &[u8]) -> bool + Send + 'static) {
+        self.trigger = Some(Box::new(predicate));
+    }
+
+    /// Re-scan `/dev/shm`: detach segments whose writer is gone or was
+    /// replaced and attach configured segments with a live writer. Returns
+    /// the number attached.
+    ///
+    /// # Errors
+    /// - `RecorderError::Io` if the declaration cannot be written.
+    pub fn refresh(&mut self) -> Result<usize, RecorderError> {
+        let live: Vec<String> = SegmentDiscovery::list_segments_in(&self.config.namespace)
+            .into_iter()
+            .filter(|s| s.valid_magic && s.writer_alive)
+            .map(|s| s.name)
+            .filter(|name| self.config.segments.is_empty() || self.config.segments.contains(name))
+            .collect();
+
+        self.sources
+            .retain(|s| !s.reader.is_unlinked() && live.iter().any(|name| name == s.reader.name()));
+
+        let mut attached = 0;
+        for name in live {
+            if self.sources.iter().any(|s| s.reader.name() == name) {
+                continue;
+            }
+            // The writer may vanish between discovery and attach.
+            let Ok(reader) = RawP2pReader::attach_in(&self.config.namespace, &name) else {
+                continue;
+            };
+            let id = self.next_id;
+            self.next_id = self.next_id.wrapping_add(1);
+            let declaration = encode_declaration(id, &reader);
+            if let Some(out) = self.out.as_mut() {
+                out.write_all(&declaration)?;
+                self.out_bytes += declaration.len() as u64;
+            }
+            self.sources.push(Source {
+                id,
+                reader,
+                declaration,
+            });
+            attached += 1;
+        }
+        Ok(attached)
+    }
+
+    /// Capture the new payloads of all attached segments.
+    ///
+    /// Returns the number of samples captured.
+    ///
+    /// # Errors
+    /// - `RecorderError::Io` if the log cannot be written.
+    pub fn poll(&mut self) -> Result<usize, RecorderError> {
+        let mut captured = 0;
+        for i in 0..self.sources.len() {
+            let source = &mut self.sources[i];
+            match source.reader.read_new() {
+                Ok(Some(_)) => {}
+                Ok(None) => continue,
+                Err(_) => {
+                    self.stats.contended += 1;
+                    continue;
+                }
+            }
+            let (id, reader) = (source.id, &source.reader);
+            let now = monotonic_ns();
+            let record = encode_sample(id, now, reader.header().heartbeat, reader.payload());
+            let fire = match self.trigger.as_mut() {
+                Some(predicate) => predicate(reader.name(), reader.payload()),
+                None => false,
+            };
+            self.stats.samples += 1;
+            captured += 1;
+            self.push(now, record)?;
+            if fire {
+                self.trigger()?;
+            }
+        }
+        self.finish_capture(monotonic_ns())?;
+        Ok(captured)
+    }
+
+    /// Mark a trigger in the log.
+    ///
+    /// In trigger mode, writes the buffered pre-trigger history and keeps
+    /// recording for the post window; a trigger during a capture extends
+    /// the window. In continuous mode only the marker is written.
+    ///
+    /// # Errors
+    /// - `RecorderError::Io` if the log cannot be written.
+    pub fn trigger(&mut self) -> Result<(), RecorderError> {
+        let now = monotonic_ns();
+        self.stats.triggers += 1;
+        if let Some(window) = self.config.trigger_window {
+            self.capture_until = Some(now + window.post.as_nanos() as u64);
+            let buffered: Vec<_> = self.pre_buffer.drain(..).map(|(_, r)| r).collect();
+            for record in buffered {
+                self.write_record(&record)?;
+            }
+        }
+        self.write_record(&encode_trigger(now))
+    }
+
+    /// `true` while records go to a file: always in continuous mode,
+    /// inside the post-trigger window in trigger mode.
+    pub fn is_capturing(&self) -> bool {
+        self.config.trigger_window.is_none() || self.capture_until.is_some()
+    }
+
+    /// Names of the attached segments.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.sources.iter().map(|s| s.reader.name())
+    }
+
+    /// Files written by this recorder that are still on disk, oldest first.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(PathBuf::as_path)
+    }
+
+    /// Recorder counters.
+    pub fn stats(&self) -> RecorderStats {
+        self.stats
+    }
+
+    /// Flush the current file.
+    ///
+    /// # Errors
+    /// - `RecorderError::Io` if the flush fails.
+    pub fn flush(&mut self) -> Result<(), RecorderError> {
+        if let Some(out) = self.out.as_mut() {
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Route a captured record to the file or the pre-trigger buffer.
+    fn push(&mut self, now: u64, record: Vec<u8>) -> Result<(), RecorderError> {
+        match self.config.trigger_window {
+            Some(window) if self.capture_until.is_none() => {
+                let horizon = now.saturating_sub(window.pre.as_nanos() as u64);
+                while self.pre_buffer.front().is_some_and(|(t, _)| *t < horizon) {
+                    self.pre_buffer.pop_front();
+                }
+                self.pre_buffer.push_back((now, record));
+                Ok(())
+            }
+            _ => self.write_record(&record),
+        }
+    }
+
+    /// Close the capture file once the post-trigger window has passed.
+    fn finish_capture(&mut self, now: u64) -> Result<(), RecorderError> {
+        if self.capture_until.is_some_and(|end| now >= end) {
+            self.capture_until = None;
+            self.close_file()?;
+        }
+        Ok(())
+    }
+
+    fn write_record(&mut self, record: &[u8]) -> Result<(), RecorderError> {
+        if self.out.is_none() {
+            self.open_file()?;
+        }
+        if let Some(out) = self.out.as_mut() {
+            out.write_all(record)?;
+        }
+        self.out_bytes += record.len() as u64;
+        if self.out_bytes >= self.config.max_file_bytes {
+            self.close_file()?;
+        }
+        Ok(())
+    }
+
+    fn open_file(&mut self) -> Result<(), RecorderError> {
+        let path = self.config.dir.join(format!(
+            "{}_{}_{:04}.{RECORD_EXTENSION}",
+            self.config.prefix, self.session, self.file_index
+        ));
+        self.file_index += 1;
+        let mut out = BufWriter::new(File::create(&path)?);
+        out.write_all(&file_header())?;
+        self.out_bytes = FILE_HEADER_LEN as u64;
+        for source in &self.sources {
+            out.write_all(&source.declaration)?;
+            self.out_bytes += source.declaration.len() as u64;
+        }
+        self.out = Some(out);
+        self.stats.files += 1;
+        self.files.push_back(path);
+        if self.config.max_files > 0 {
+            while self.files.len() > self.config.max_files {
+                if let Some(old) = self.files.pop_front() {
+                    let _ = std::fs::remove_file(old);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn close_file(&mut self) -> Result<(), RecorderError> {
+        if let Some(mut out) = self.out.take() {
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.close_file();
+    }
+}
+
+// ─── Recording ──────────────────────────────────────────────────────
+
+/// A segment declared in a recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedSegment {
+    /// Id used by the samples of this segment.
+    pub id: u16,
+    /// Segment name (without `evo_` prefix).
+    pub name: String,
+    /// Header `source_module`.
+    pub source: u8,
+    /// Header `dest_module`.
+    pub dest: u8,
+    /// Header `version_hash`.
+    pub version_hash: u32,
+    /// Payload size in bytes.
+    pub payload_size: u32,
+    /// Encoded layout table published by the writer.
+    pub layout_table: Vec<u8>,
+}
+
+/// One captured payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedSample {
+    /// Id of the [`RecordedSegment`].
+    pub segment: u16,
+    /// `CLOCK_MONOTONIC` capture time in nanoseconds.
+    pub timestamp_ns: u64,
+    /// Writer heartbeat of the payload.
+    pub heartbeat: u64,
+    /// Raw payload bytes.
+    pub payload: Vec<u8>,
+}
+
+/// A loaded log file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    /// Declared segments, in file order.
+    pub segments: Vec<RecordedSegment>,
+    /// Samples, in capture order.
+    pub samples: Vec<RecordedSample>,
+    /// Trigger timestamps (`CLOCK_MONOTONIC` ns).
+    pub triggers: Vec<u64>,
+}
+
+impl Recording {
+    /// Load a log file.
+    ///
+    /// # Errors
+    /// - `RecorderError::Io` if the file cannot be read.
+    /// - `RecorderError::Format` if it is not a recording.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecorderError> {
+        let path = path.as_ref();
+        Self::parse(&std::fs::read(path)?).map_err(|reason| RecorderError::Format {
+            path: path.to_path_buf(),
+            reason,
+        })
+    }
+
+    /// Parse log file bytes.
+    fn parse(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < FILE_HEADER_LEN || bytes[..8] != RECORD_MAGIC {
+            return Err("bad magic".to_string());
+        }
+        let version = read_u32(bytes, 8);
+        if version != RECORD_VERSION {
+            return Err(format!("unsupported version {version}"));
+        }
+
+        let mut rec = Self::default();
+        let mut pos = FILE_HEADER_LEN;
+        while pos + RECORD_HEADER_LEN <= bytes.len() {
+            let kind = bytes[pos];
+            let id = u16::from_le_bytes([bytes[pos + 1], bytes[pos + 2]]);
+            let len = read_u32(bytes, pos + 3) as usize;
+            let Some(body) = bytes.get(pos + RECORD_HEADER_LEN..pos + RECORD_HEADER_LEN + len)
+            else {
+                break; // truncated last record
+            };
+            pos += RECORD_HEADER_LEN + len;
+            match kind {
+                KIND_SEGMENT => rec.segments.push(decode_declaration(id, body)?),
+                KIND_SAMPLE if body.len() >= 16 => rec.samples.push(RecordedSample {
+                    segment: id,
+                    timestamp_ns: read_u64(body, 0),
+                    heartbeat: read_u64(body, 8),
+                    payload: body[16..].to_vec(),
+                }),
+                KIND_TRIGGER if body.len() >= 8 => rec.triggers.push(read_u64(body, 0)),
+                _ => return Err(format!("bad record kind {kind} at offset {pos}")),
+            }
+        }
+        Ok(rec)
+    }
+
+    /// The last declaration of segment `name`.
+    pub fn segment(&self, name: &str) -> Option<&RecordedSegment> {
+        self.segments.iter().rev().find(|s| s.name == name)
+    }
+
+    /// Samples of segment `name` (all its declarations), in capture order.
+    pub fn samples_for<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a RecordedSample> {
+        let ids: Vec<u16> = self
+            .segments
+            .iter()
+            .filter(|s| s.name == name)
+            .map(|s| s.id)
+            .collect();
+        self.samples
+            .iter()
+            .filter(move |s| ids.contains(&s.segment))
+    }
+
+    /// Decode the samples of segment `name` as `T`, with their timestamps.
+    ///
+    /// # Errors
+    /// - `RecorderError::TypeMismatch` if the segment was recorded with a
+    ///   different layout hash than `T::LAYOUT_HASH`.
+    pub fn payloads<T: ShmLayout>(&self, name: &str) -> Result<Vec<(u64, T)>, RecorderError> {
+        for seg in self.segments.iter().filter(|s| s.name == name) {
+            if seg.version_hash != T::LAYOUT_HASH {
+                return Err(RecorderError::TypeMismatch {
+                    segment: name.to_string(),
+                    expected: T::LAYOUT_HASH,
+                    recorded: seg.version_hash,
+                });
+            }
+        }
+        Ok(self
+            .samples_for(name)
+            .filter(|s| s.payload.len() == size_of::<T>())
+            // SAFETY: size and layout hash match; payloads are repr(C)
+            // plain data for which any bit pattern written by T is valid.
+            .map(|s| {
+                (s.timestamp_ns, unsafe {
+                    core::ptr::read_unaligned(s.payload.as_ptr() as *const T)
+                })
+            })
+            .collect())
+    }
+}
+
+// ─── Replayer ───────────────────────────────────────────────────────
+
+/// Feeds a [`Recording`] back into SHM as the writer of its segments.
+///
+/// The segments must not have a live writer (stop the recorded module
+/// first); readers attach as usual since the recorded header fields and
+/// layout table are reproduced.
+pub struct Replayer {
+    recording: Recording,
+    /// Recorded segment id → index into `writers`.
+    routes: HashMap<u16, usize>,
+    writers: Vec<RawP2pWriter>,
+    cursor: usize,
+}
+
+impl Replayer {
+    /// Create writers for `segments` (empty = every recorded segment).
+    ///
+    /// # Errors
+    /// - `RecorderError::Shm` (`WriterAlreadyExists`) if a segment still
+    ///   has a live writer.
+    pub fn new(recording: Recording, segments: &[&str]) -> Result<Self, RecorderError> {
+        Self::new_in(&ShmNamespace::default(), recording, segments)
+    }
+
+    /// Same as [`new`](Self::new), replaying into namespace `ns`.
+    pub fn new_in(
+        ns: &ShmNamespace,
+        recording: Recording,
+        segments: &[&str],
+    ) -> Result<Self, RecorderError> {
+        let mut routes = HashMap::new();
+        let mut writers: Vec<RawP2pWriter> = Vec::new();
+        for seg in &recording.segments {
+            if !segments.is_empty() && !segments.contains(&seg.name.as_str()) {
+                continue;
+            }
+            let index = match writers.iter().position(|w| w.name() == seg.name) {
+                Some(index) => index,
+                None => {
+                    let header = P2pSegmentHeader {
+                        magic: EVO_P2P_MAGIC,
+                        version_hash: seg.version_hash,
+                        heartbeat: 0,
+                        source_module: seg.source,
+                        dest_module: seg.dest,
+                        payload_size: seg.payload_size,
+                        write_seq: 0,
+                        wait_until_ms: 0,
+                        commit_ns: 0,
+                        _padding: [0; 16],
+                    };
+                    writers.push(RawP2pWriter::create_in(ns, &seg.name, header, &seg.layout_table)?);
+                    writers.len() - 1
+                }
+            };
+            routes.insert(seg.id, index);
+        }
+        Ok(Self {
+            recording,
+            routes,
+            writers,
+            cursor: 0,
+        })
+    }
+
+    /// Commit the next sample of a replayed segment; `Ok(None)` at the end.
+    ///
+    /// # Errors
+    /// - `RecorderError::Shm` if the commit fails.
+    pub fn step(&mut self) -> Result<Option<&RecordedSample>, RecorderError> {
+        while let Some(sample) = self.recording.samples.get(self.cursor) {
+            self.cursor += 1;
+            if let Some(&index) = self.routes.get(&sample.segment) {
+                self.writers[index].commit(&sample.payload)?;
+                return Ok(Some(sample));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replay the remaining samples with their recorded spacing divided
+    /// by `speed` (1.0 = real time). Returns the number committed.
+    ///
+    /// # Errors
+    /// - `RecorderError::InvalidSpeed` if `speed` is not positive and
+    ///   finite; nothing is replayed.
+    /// - `RecorderError::Shm` if a commit fails.
+    pub fn run(&mut self, speed: f64) -> Result<usize, RecorderError> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(RecorderError::InvalidSpeed(speed));
+        }
+        let start = std::time::Instant::now();
+        let mut first_ts = None;
+        let mut count = 0;
+        loop {
+            let next_ts = self.recording.samples[self.cursor..]
+                .iter()
+                .find(|s| self.routes.contains_key(&s.segment))
+                .map(|s| s.timestamp_ns);
+            let Some(ts) = next_ts else {
+                return Ok(count);
+            };
+            let offset = ts - *first_ts.get_or_insert(ts);
+            let due = Duration::from_nanos((offset as f64 / speed) as u64);
+            if let Some(wait) = due.checked_sub(start.elapsed()) {
+                std::thread::sleep(wait);
+            }
+            self.step()?;
+            count += 1;
+        }
+    }
+
+    /// Restart from the first sample.
+    pub fn rewind(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Samples left to replay (all segments).
+    pub fn remaining(&self) -> usize {
+        self.recording.samples.len() - self.cursor
+    }
+
+    /// The replayed recording.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+}
+
+// ─── Encoding ───────────────────────────────────────────────────────
+
+fn file_header() -> [u8; FILE_HEADER_LEN] {
+    let mut header = [0u8; FILE_HEADER_LEN];
+    header[..8].copy_from_slice(&RECORD_MAGIC);
+    header[8..12].copy_from_slice(&RECORD_VERSION.to_le_bytes());
+    header
+}
+
+fn encode_record(kind: u8, id: u16, parts: &[&[u8]]) -> Vec<u8> {
+    let len: usize = parts.iter().map(|p| p.len()).sum();
+    let mut rec = Vec::with_capacity(RECORD_HEADER_LEN + len);
+    rec.push(kind);
+    rec.extend_from_slice(&id.to_le_bytes());
+    rec.extend_from_slice(&(len as u32).to_le_bytes());
+    for part in parts {
+        rec.extend_from_slice(part);
+    }
+    rec
+}
+
+fn encode_declaration(id: u16, reader: &RawP2pReader) -> Vec<u8> {
+    let header = reader.header();
+    let name = reader.name().as_bytes();
+    let name = &name[..name.len().min(u8::MAX as usize)];
+    encode_record(
+        KIND_SEGMENT,
+        id,
+        &[
+            &[header.source_module, header.dest_module],
+            &header.version_hash.to_le_bytes(),
+            &header.payload_size.to_le_bytes(),
+            &[name.len() as u8],
+            name,
+            reader.layout_table(),
+        ],
+    )
+}
+
+fn decode_declaration(id: u16, body: &[u8]) -> Result<RecordedSegment, String> {
+    let name_len = *body.get(10).ok_or("short segment record")? as usize;
+    let name = body.get(11..11 + name_len).ok_or("short segment name")?;
+    Ok(RecordedSegment {
+        id,
+        name: String::from_utf8_lossy(name).into_owned(),
+        source: body[0],
+        dest: body[1],
+        version_hash: read_u32(body, 2),
+        payload_size: read_u32(body, 6),
+        layout_table: body[11 + name_len..].to_vec(),
+    })
+}
+
+fn encode_sample(id: u16, timestamp_ns: u64, heartbeat: u64, payload: &[u8]) -> Vec<u8> {
+    encode_record(
+        KIND_SAMPLE,
+        id,
+        &[
+            &timestamp_ns.to_le_bytes(),
+            &heartbeat.to_le_bytes(),
+            payload,
+        ],
+    )
+}
+
+fn encode_trigger(timestamp_ns: u64) -> Vec<u8> {
+    encode_record(KIND_TRIGGER, 0, &[&timestamp_ns.to_le_bytes()])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(records: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = file_header().to_vec();
+        for rec in records {
+            bytes.extend_from_slice(rec);
+        }
+        bytes
+    }
+
+    fn declaration(id: u16, name: &str) -> Vec<u8> {
+        encode_record(
+            KIND_SEGMENT,
+            id,
+            &[
+                &[1, 2],
+                &7u32.to_le_bytes(),
+                &4u32.to_le_bytes(),
+                &[name.len() as u8],
+                name.as_bytes(),
+                b"EVOL",
+            ],
+        )
+    }
+
+    #[test]
+    fn parse_roundtrip() {
+        let bytes = log(&[
+            declaration(3, "hal_cu"),
+            encode_sample(3, 100, 1, &[1, 2, 3, 4]),
+            encode_trigger(150),
+            encode_sample(3, 200, 2, &[5, 6, 7, 8]),
+        ]);
+        let rec = Recording::parse(&bytes).unwrap();
+
+        let seg = rec.segment("hal_cu").unwrap();
+        assert_eq!((seg.id, seg.source, seg.dest), (3, 1, 2));
+        assert_eq!((seg.version_hash, seg.payload_size), (7, 4));
+        assert_eq!(seg.layout_table, b"EVOL");
+
+        assert_eq!(rec.triggers, [150]);
+        let samples: Vec<_> = rec.samples_for("hal_cu").map(|s| s.heartbeat).collect();
+        assert_eq!(samples, [1, 2]);
+        assert_eq!(rec.samples[1].payload, [5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn truncated_tail_ignored() {
+        let mut bytes = log(&[declaration(0, "cu_hal"), encode_sample(0, 1, 1, &[0; 4])]);
+        bytes.extend_from_slice(&encode_sample(0, 2, 2, &[0; 4])[..10]);
+        let rec = Recording::parse(&bytes).unwrap();
+        assert_eq!(rec.samples.len(), 1);
+    }
+
+    #[test]
+    fn rejects_foreign_files() {
+        assert!(Recording::parse(b"not a recording").is_err());
+        let mut bytes = file_header().to_vec();
+        bytes[8] = 9;
+        assert!(Recording::parse(&bytes).unwrap_err().contains("version"));
+        let bytes = log(&[encode_record(42, 0, &[])]);
+        assert!(Recording::parse(&bytes).is_err());
+    }
+
+    #[test]
+    fn payloads_check_layout_hash() {
+        let bytes = log(&[
+            declaration(0, "x"),
+            encode_sample(0, 5, 1, &9u32.to_ne_bytes()),
+        ]);
+        let rec = Recording::parse(&bytes).unwrap();
+        assert!(matches!(
+            rec.payloads::<u32>("x"),
+            Err(RecorderError::TypeMismatch { recorded: 7, .. })
+        ));
+    }
+}
@@ -0,0 +1,403 @@
+//! Request/response channels over paired P2P segments.
+//!
+//! Command segments carry a correlation ID in one segment and its answer
+//! in the segment flowing back (`ReToHalSegment::request_id`,
+//! `RpcToHalSegment::request_id` / `HalToRpcSegment::request_id`,
+//! `RpcCommand::sequence_id` / `CuToReSegment::last_ack_seq_id`). This
+//! module gives all of them one set of rules:
+//!
+//! - ID `0` means "no request"; valid IDs start at 1 and increase
+//!   monotonically, wrapping back to 1 after the field's maximum.
+//! - Each segment holds one request; a newer request overwrites an older
+//!   one. The response segment holds the answer to the last request the
+//!   server accepted.
+//! - The server processes each ID exactly once ([`RequestEdge`]). A
+//!   server that (re)attaches to a request segment treats the request
+//!   already there as answered: a restarted server must not execute it
+//!   again.
+//! - A client continues after the ID in the response segment, so a
+//!   restarted client does not reuse the last accepted ID.
+//!
+//! ## Types
+//!
+//! - [`RequestId`]: access to the correlation field of a payload.
+//! - [`P2pRequestChannel`]: client side (non-RT) — allocates IDs, writes
+//!   requests, tracks in-flight requests with a timeout, and matches
+//!   responses.
+//! - [`P2pRequestServer`]: server side (RT) — new-request edge detection
+//!   plus an in-place ack writer.
+//! - [`RequestEdge`]: the bare edge detector, for servers that build the
+//!   response segment themselves (e.g. the CU folds acks into `evo_cu_re`).
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::layout::ShmLayout;
+use super::p2p::{ShmError, TypedP2pReader, TypedP2pWriter};
+use super::segments::{
+    CuToReSegment, HalToRpcSegment, ReToCuSegment, ReToHalSegment, ReToRpcSegment, RpcToCuSegment,
+    RpcToHalSegment,
+};
+
+// ─── Correlation Field ──────────────────────────────────────────────
+
+/// A payload carrying a request correlation ID (0 = none).
+pub trait RequestId: ShmLayout {
+    /// Largest ID the wire field can hold.
+    const MAX_ID: u64 = u64::MAX;
+
+    /// Current correlation ID.
+    fn request_id(&self) -> u64;
+
+    /// Set the correlation ID (`id <= MAX_ID`).
+    fn set_request_id(&mut self, id: u64);
+}
+
+macro_rules! impl_request_id {
+    ($ty:ty, $($field:ident).+) => {
+        impl RequestId for $ty {
+            #[inline]
+            fn request_id(&self) -> u64 {
+                self.$($field).+
+            }
+
+            #[inline]
+            fn set_request_id(&mut self, id: u64) {
+                self.$($field).+ = id;
+            }
+        }
+    };
+    ($ty:ty, $($field:ident).+ as u32) => {
+        impl RequestId for $ty {
+            const MAX_ID: u64 = u32::MAX as u64;
+
+            #[inline]
+            fn request_id(&self) -> u64 {
+                u64::from(self.$($field).+)
+            }
+
+            #[inline]
+            fn set_request_id(&mut self, id: u64) {
+                self.$($field).+ = id as u32;
+            }
+        }
+    };
+}
+
+impl_request_id!(ReToHalSegment, request_id);
+impl_request_id!(RpcToHalSegment, request_id);
+impl_request_id!(HalToRpcSegment, request_id);
+impl_request_id!(ReToRpcSegment, request_id);
+impl_request_id!(ReToCuSegment, command.sequence_id as u32);
+impl_request_id!(RpcToCuSegment, command.sequence_id as u32);
+impl_request_id!(CuToReSegment, last_ack_seq_id as u32);
+
+// ─── Edge Detector ──────────────────────────────────────────────────
+
+/// Exactly-once detector for new request IDs.
+///
+/// Holds only the last accepted ID; RT-safe.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RequestEdge {
+    last: u64,
+}
+
+impl RequestEdge {
+    /// Detector that has accepted nothing yet.
+    pub const fn new() -> Self {
+        Self { last: 0 }
+    }
+
+    /// Detector that treats `id` as already accepted.
+    pub const fn primed(id: u64) -> Self {
+        Self { last: id }
+    }
+
+    /// Detector primed with the request `reader` holds on attach.
+    ///
+    /// The request left in a surviving client segment was issued to the
+    /// previous server instance; it is not delivered again. A segment
+    /// that cannot be read yet primes with 0.
+    pub fn attached<T: RequestId>(reader: &mut TypedP2pReader<T>) -> Self {
+        Self::primed(reader.read().map_or(0, RequestId::request_id))
+    }
+
+    /// `true` exactly once for each new non-zero ID.
+    ///
+    /// A repeated read of the same request returns `false`.
+    #[inline]
+    pub fn accept(&mut self, id: u64) -> bool {
+        if id == 0 || id == self.last {
+            return false;
+        }
+        self.last = id;
+        true
+    }
+
+    /// Last accepted ID (0 = none).
+    #[inline]
+    pub fn last(&self) -> u64 {
+        self.last
+    }
+}
+
+// ─── Client ─────────────────────────────────────────────────────────
+
+/// Outcome of an in-flight request, returned by [`P2pRequestChannel::poll`].
+#[derive(Debug, Clone, Copy)]
+pub enum RequestEvent<Resp> {
+    /// The server answered request `id`.
+    Completed {
+        id: u64,
+        response: Resp,
+        /// Time from [`send`](P2pRequestChannel::send) to the response.
+        latency: Duration,
+    },
+    /// The server answered a later request first; `id` was overwritten
+    /// in the request segment before the server saw it, or its answer was
+    /// overwritten before the client read it.
+    Superseded { id: u64 },
+    /// No answer to `id` within the channel timeout.
+    TimedOut { id: u64 },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InFlight {
+    id: u64,
+    sent: Instant,
+}
+
+/// Client side of a request/response segment pair.
+///
+/// Owns the request writer and the response reader. Not RT-safe:
+/// [`send`](Self::send) copies the request and the in-flight list may
+/// grow.
+pub struct P2pRequestChannel<Req: RequestId, Resp: RequestId> {
+    writer: TypedP2pWriter<Req>,
+    reader: TypedP2pReader<Resp>,
+    timeout: Duration,
+    next_id: u64,
+    in_flight: VecDeque<InFlight>,
+    /// In-flight entries at the front older than the last completion.
+    superseded: usize,
+}
+
+impl<Req: RequestId, Resp: RequestId> P2pRequestChannel<Req, Resp> {
+    /// Pair a request writer with the reader of its response segment.
+    ///
+    /// IDs continue after the one the response segment holds (1 if it
+    /// cannot be read), so a restarted client is not mistaken for a
+    /// repeat of the last accepted request.
+    pub fn new(
+        writer: TypedP2pWriter<Req>,
+        mut reader: TypedP2pReader<Resp>,
+        timeout: Duration,
+    ) -> Self {
+        let last = reader.read().map_or(0, RequestId::request_id);
+        Self {
+            writer,
+            reader,
+            timeout,
+            next_id: Self::after(last),
+            in_flight: VecDeque::new(),
+            superseded: 0,
+        }
+    }
+
+    /// Largest ID both wire fields can hold.
+    const MAX_ID: u64 = if Req::MAX_ID < Resp::MAX_ID {
+        Req::MAX_ID
+    } else {
+        Resp::MAX_ID
+    };
+
+    /// ID following `id`, wrapping back to 1 after `MAX_ID`.
+    const fn after(id: u64) -> u64 {
+        if id >= Self::MAX_ID { 1 } else { id + 1 }
+    }
+
+    /// Send a request under a newly allocated ID and return the ID.
+    ///
+    /// The ID field of `request` is overwritten.
+    ///
+    /// # Errors
+    /// Propagates [`TypedP2pWriter::commit`] errors; the ID is then not
+    /// tracked.
+    pub fn send(&mut self, mut request: Req) -> Result<u64, ShmError> {
+        let id = self.next_id;
+        self.next_id = Self::after(id);
+        request.set_request_id(id);
+        self.writer.commit(&request)?;
+        self.in_flight.push_back(InFlight {
+            id,
+            sent: Instant::now(),
+        });
+        Ok(id)
+    }
+
+    /// Next event for the in-flight requests, if any.
+    ///
+    /// Call in a loop (`while let Some(ev) = ch.poll()?`) until it
+    /// returns `None`; each call reports at most one request.
+    ///
+    /// # Errors
+    /// Propagates [`TypedP2pReader::read`] errors of the response segment.
+    pub fn poll(&mut self) -> Result<Option<RequestEvent<Resp>>, ShmError> {
+        if self.superseded > 0 {
+            self.superseded -= 1;
+            if let Some(entry) = self.in_flight.pop_front() {
+                return Ok(Some(RequestEvent::Superseded { id: entry.id }));
+            }
+        }
+
+        if self.reader.has_changed() {
+            let response = *self.reader.read()?;
+            let id = response.request_id();
+            if let Some(pos) = self.in_flight.iter().position(|e| e.id == id)
+                && let Some(entry) = self.in_flight.remove(pos)
+            {
+                self.superseded = pos;
+                return Ok(Some(RequestEvent::Completed {
+                    id,
+                    response,
+                    latency: entry.sent.elapsed(),
+                }));
+            }
+        }
+
+        // Constant timeout: the oldest request expires first.
+        if let Some(front) = self.in_flight.front()
+            && front.sent.elapsed() > self.timeout
+        {
+            let id = front.id;
+            self.in_flight.pop_front();
+            return Ok(Some(RequestEvent::TimedOut { id }));
+        }
+        Ok(None)
+    }
+
+    /// Number of requests awaiting an event.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Whether `id` awaits an event.
+    pub fn is_in_flight(&self, id: u64) -> bool {
+        self.in_flight.iter().any(|e| e.id == id)
+    }
+
+    /// Response timeout.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+// ─── Server ─────────────────────────────────────────────────────────
+
+/// Server side of a request/response segment pair.
+///
+/// Owns the request reader and the response writer.
+///
+/// # RT Safety
+/// [`poll`](Self::poll) and [`respond`](Self::respond) do not allocate.
+pub struct P2pRequestServer<Req: RequestId, Resp: RequestId> {
+    reader: TypedP2pReader<Req>,
+    writer: TypedP2pWriter<Resp>,
+    edge: RequestEdge,
+}
+
+impl<Req: RequestId, Resp: RequestId> P2pRequestServer<Req, Resp> {
+    /// Pair a request reader with the writer of its response segment.
+    ///
+    /// The request the segment holds at this point is not delivered (see
+    /// [`RequestEdge::attached`]).
+    pub fn new(mut reader: TypedP2pReader<Req>, writer: TypedP2pWriter<Resp>) -> Self {
+        let edge = RequestEdge::attached(&mut reader);
+        Self {
+            reader,
+            writer,
+            edge,
+        }
+    }
+
+    /// The request, exactly once per new ID.
+    ///
+    /// Returns `None` while the request segment is unchanged or still
+    /// holds an already accepted request.
+    ///
+    /// # Errors
+    /// Propagates [`TypedP2pReader::read`] errors of the request segment.
+    pub fn poll(&mut self) -> Result<Option<&Req>, ShmError> {
+        if !self.reader.has_changed() {
+            return Ok(None);
+        }
+        let request = self.reader.read()?;
+        Ok(self.edge.accept(request.request_id()).then_some(request))
+    }
+
+    /// Answer request `id` by updating the response segment in place.
+    ///
+    /// `f` fills the response; the ID field is set afterwards.
+    pub fn respond(&mut self, id: u64, f: impl FnOnce(&mut Resp)) {
+        self.writer.commit_with(|response| {
+            f(response);
+            response.set_request_id(id);
+        });
+    }
+
+    /// Replace the request reader, e.g. after the client recreated its
+    /// segment. Accepted IDs carry over: the restarted client continues
+    /// after them.
+    pub fn reattach(&mut self, reader: TypedP2pReader<Req>) {
+        self.reader = reader;
+    }
+
+    /// Last accepted request ID (0 = none).
+    pub fn last_id(&self) -> u64 {
+        self.edge.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_accepts_each_id_once() {
+        let mut edge = RequestEdge::new();
+        assert!(!edge.accept(0));
+        assert!(edge.accept(1));
+        assert!(!edge.accept(1));
+        assert!(edge.accept(2));
+        assert!(!edge.accept(0));
+        assert_eq!(edge.last(), 2);
+        // Wrap back to 1 is a new request.
+        assert!(edge.accept(1));
+    }
+
+    #[test]
+    fn primed_edge_skips_seed() {
+        let mut edge = RequestEdge::primed(5);
+        assert_eq!(edge.last(), 5);
+        assert!(!edge.accept(5));
+        assert!(edge.accept(6));
+    }
+
+    #[test]
+    fn request_id_fields() {
+        let mut seg = RpcToCuSegment::default();
+        seg.set_request_id(42);
+        assert_eq!(seg.command.sequence_id, 42);
+        assert_eq!(seg.request_id(), 42);
+        assert_eq!(RpcToCuSegment::MAX_ID, u32::MAX as u64);
+
+        let mut seg = CuToReSegment::default();
+        seg.set_request_id(7);
+        assert_eq!(seg.last_ack_seq_id, 7);
+
+        let mut seg = RpcToHalSegment::default();
+        seg.set_request_id(u64::MAX);
+        assert_eq!(seg.request_id, u64::MAX);
+        assert_eq!(RpcToHalSegment::MAX_ID, u64::MAX);
+    }
+}
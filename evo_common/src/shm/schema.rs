@@ -0,0 +1,260 @@
+//! Machine-readable segment schemas for language-agnostic decoding.
+//!
+//! A [`SegmentSchema`] describes one P2P segment as seen in `/dev/shm/evo_*`:
+//! the 64-byte [`P2pSegmentHeader`], the payload offset, and every payload
+//! field with its offset, size, type, element count and enum discriminant
+//! names. Schemas are built from the compile-time [`ShmLayout`] descriptors,
+//! so they always match the `#[repr(C)]` structs in `shm::segments`.
+//!
+//! [`segment_schemas_json`] exports all 15 segments; a writer may also
+//! publish its own schema next to the segment as
+//! `/dev/shm/evo_<name>.schema.json` (see `TypedP2pWriter::publish_schema`).
+//!
+//! ## Decoding
+//!
+//! - Field offsets are relative to the parent: the payload for top-level
+//!   fields, the array element for nested fields.
+//! - Arrays have `count > 1`; the element stride is `size / count`.
+//! - Nested struct fields are listed in `fields`; leaf types are `u8`–`u64`,
+//!   `i8`–`i64`, `f32`, `f64` in native (little-endian) byte order.
+//! - A consistent snapshot follows the seqlock protocol: read header
+//!   `write_seq`, copy the payload, re-read `write_seq`; retry if it is odd
+//!   or changed.
+
+use serde::{Deserialize, Serialize};
+
+use super::layout::{FieldLayout, ShmLayout};
+use super::p2p::{P2pSegmentHeader, SHM_PREFIX};
+use super::segments::{
+    CuToHalSegment, CuToMqtSegment, CuToReSegment, CuToRpcSegment, HalToCuSegment, HalToMqtSegment,
+    HalToReSegment, HalToRpcSegment, ReToCuSegment, ReToHalSegment, ReToMqtSegment, ReToRpcSegment,
+    RpcToCuSegment, RpcToHalSegment, RpcToReSegment, SEG_CU_HAL, SEG_CU_MQT, SEG_CU_RE, SEG_CU_RPC,
+    SEG_HAL_CU, SEG_HAL_MQT, SEG_HAL_RE, SEG_HAL_RPC, SEG_RE_CU, SEG_RE_HAL, SEG_RE_MQT,
+    SEG_RE_RPC, SEG_RPC_CU, SEG_RPC_HAL, SEG_RPC_RE,
+};
+
+/// Suffix of the schema file published next to a segment.
+pub const SCHEMA_SUFFIX: &str = ".schema.json";
+
+// ─── Schema Types ───────────────────────────────────────────────────
+
+/// Schema of one P2P segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentSchema {
+    /// Segment name (e.g. `"hal_cu"`).
+    pub segment: String,
+    /// POSIX SHM name (e.g. `"/evo_hal_cu"`).
+    pub shm_name: String,
+    /// Payload struct name.
+    pub type_name: String,
+    /// `version_hash` stored in the header by the writer.
+    pub layout_hash: u32,
+    /// Payload offset from the start of the segment.
+    pub payload_offset: usize,
+    /// Payload size in bytes.
+    pub payload_size: usize,
+    /// Payload alignment in bytes.
+    pub payload_align: usize,
+    /// P2P header fields.
+    pub header: Vec<FieldSchema>,
+    /// Payload fields.
+    pub fields: Vec<FieldSchema>,
+}
+
+/// Schema of one field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    /// Field name.
+    pub name: String,
+    /// Byte offset within the parent.
+    pub offset: usize,
+    /// Size in bytes (whole array for arrays).
+    pub size: usize,
+    /// Element type name (primitive or struct).
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Element count (1 for scalars).
+    pub count: usize,
+    /// Enum carried by the integer elements.
+    #[serde(rename = "enum", default, skip_serializing_if = "Option::is_none")]
+    pub enumeration: Option<EnumSchema>,
+    /// Element fields for struct types.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldSchema>,
+}
+
+/// Schema of an enum carried by an integer field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumSchema {
+    /// Enum type name.
+    pub name: String,
+    /// Variants in declaration order.
+    pub variants: Vec<VariantSchema>,
+}
+
+/// One enum variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantSchema {
+    /// Variant name.
+    pub name: String,
+    /// Discriminant value.
+    pub value: u64,
+}
+
+impl SegmentSchema {
+    /// Build the schema of segment `name` carrying payload `T`.
+    pub fn of<T: ShmLayout>(name: &str) -> Self {
+        Self {
+            segment: name.to_string(),
+            shm_name: format!("{SHM_PREFIX}{name}"),
+            type_name: T::TYPE_NAME.to_string(),
+            layout_hash: T::LAYOUT_HASH,
+            payload_offset: size_of::<P2pSegmentHeader>(),
+            payload_size: size_of::<T>(),
+            payload_align: align_of::<T>(),
+            header: fields_schema(P2pSegmentHeader::FIELDS),
+            fields: fields_schema(T::FIELDS),
+        }
+    }
+
+    /// Pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("schema serialization is infallible")
+    }
+
+    /// Parse a schema from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Find a field by dotted path (e.g. `"axes.position"`).
+    pub fn field(&self, path: &str) -> Option<&FieldSchema> {
+        let mut fields = &self.fields;
+        let mut found = None;
+        for part in path.split('.') {
+            let field = fields.iter().find(|f| f.name == part)?;
+            fields = &field.fields;
+            found = Some(field);
+        }
+        found
+    }
+}
+
+fn fields_schema(fields: &[FieldLayout]) -> Vec<FieldSchema> {
+    fields
+        .iter()
+        .map(|f| FieldSchema {
+            name: f.name.to_string(),
+            offset: f.offset,
+            size: f.size,
+            type_name: f.type_name.to_string(),
+            count: f.count,
+            enumeration: f.enumeration.map(|e| EnumSchema {
+                name: e.name.to_string(),
+                variants: e
+                    .variants
+                    .iter()
+                    .map(|v| VariantSchema {
+                        name: v.name.to_string(),
+                        value: v.value,
+                    })
+                    .collect(),
+            }),
+            fields: fields_schema(f.fields),
+        })
+        .collect()
+}
+
+// ─── Segment Catalogue ──────────────────────────────────────────────
+
+/// Schemas of all 15 P2P segments, in segment-table order.
+pub fn segment_schemas() -> Vec<SegmentSchema> {
+    vec![
+        SegmentSchema::of::<HalToCuSegment>(SEG_HAL_CU),
+        SegmentSchema::of::<CuToHalSegment>(SEG_CU_HAL),
+        SegmentSchema::of::<CuToMqtSegment>(SEG_CU_MQT),
+        SegmentSchema::of::<HalToMqtSegment>(SEG_HAL_MQT),
+        SegmentSchema::of::<ReToCuSegment>(SEG_RE_CU),
+        SegmentSchema::of::<ReToHalSegment>(SEG_RE_HAL),
+        SegmentSchema::of::<ReToMqtSegment>(SEG_RE_MQT),
+        SegmentSchema::of::<ReToRpcSegment>(SEG_RE_RPC),
+        SegmentSchema::of::<RpcToCuSegment>(SEG_RPC_CU),
+        SegmentSchema::of::<RpcToHalSegment>(SEG_RPC_HAL),
+        SegmentSchema::of::<RpcToReSegment>(SEG_RPC_RE),
+        SegmentSchema::of::<CuToReSegment>(SEG_CU_RE),
+        SegmentSchema::of::<CuToRpcSegment>(SEG_CU_RPC),
+        SegmentSchema::of::<HalToRpcSegment>(SEG_HAL_RPC),
+        SegmentSchema::of::<HalToReSegment>(SEG_HAL_RE),
+    ]
+}
+
+/// Schema of a known segment by name.
+pub fn segment_schema(name: &str) -> Option<SegmentSchema> {
+    segment_schemas().into_iter().find(|s| s.segment == name)
+}
+
+/// All 15 segment schemas as a pretty-printed JSON array.
+pub fn segment_schemas_json() -> String {
+    serde_json::to_string_pretty(&segment_schemas()).expect("schema serialization is infallible")
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogue_covers_all_segments() {
+        let schemas = segment_schemas();
+        assert_eq!(schemas.len(), 15);
+        for s in &schemas {
+            assert_eq!(s.payload_offset, 64);
+            assert_eq!(s.shm_name, format!("/evo_{}", s.segment));
+            let last = s.fields.last().unwrap();
+            assert!(last.offset + last.size <= s.payload_size, "{}", s.segment);
+        }
+        assert_eq!(
+            segment_schema(SEG_HAL_RPC).unwrap().layout_hash,
+            HalToRpcSegment::LAYOUT_HASH
+        );
+        assert!(segment_schema("nope").is_none());
+    }
+
+    #[test]
+    fn arrays_nested_fields_and_enums() {
+        let s = segment_schema(SEG_CU_MQT).unwrap();
+
+        let state = s.field("machine_state").unwrap();
+        let variants = &state.enumeration.as_ref().unwrap().variants;
+        let names: Vec<_> = variants.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names[..3], ["Stopped", "Starting", "Idle"]);
+
+        let status = s.field("axis_status").unwrap();
+        assert_eq!(status.type_name, "CuAxisStatus");
+        assert_eq!(status.count, crate::consts::MAX_AXES as usize);
+        assert_eq!(status.size / status.count, 16);
+
+        let motion = s.field("axis_status.motion_state").unwrap();
+        assert_eq!(motion.offset, 1);
+        assert_eq!(motion.enumeration.as_ref().unwrap().name, "MotionState");
+
+        let re = segment_schema(SEG_RE_CU).unwrap();
+        let cmd = re.field("command.command_type").unwrap();
+        assert_eq!(cmd.enumeration.as_ref().unwrap().variants.len(), 17);
+
+        let seq = s.header.iter().find(|f| f.name == "write_seq").unwrap();
+        assert_eq!(seq.offset, 32);
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let s = segment_schema(SEG_HAL_CU).unwrap();
+        let json = s.to_json();
+        assert!(json.contains("\"type\": \"HalAxisFeedback\""));
+        assert_eq!(SegmentSchema::from_json(&json).unwrap(), s);
+
+        let all: Vec<SegmentSchema> = serde_json::from_str(&segment_schemas_json()).unwrap();
+        assert_eq!(all, segment_schemas());
+    }
+}
@@ -15,19 +15,29 @@
 //! | 2 | `evo_cu_hal`  | CU → HAL      | `CuToHalSegment`   | Active      |
 //! | 3 | `evo_cu_mqt`  | CU → MQTT     | `CuToMqtSegment`   | Skeleton    |
 //! | 4 | `evo_hal_mqt` | HAL → MQTT    | `HalToMqtSegment`  | Skeleton    |
-//! | 5 | `evo_re_cu`   | RE → CU       | `ReToCuSegment`    | Skeleton    |
+//! | 5 | `evo_re_cu`   | RE → CU       | `ReToCuSegment`    | Active      |
 //! | 6 | `evo_re_hal`  | RE → HAL      | `ReToHalSegment`   | Skeleton    |
 //! | 7 | `evo_re_mqt`  | RE → MQTT     | `ReToMqtSegment`   | Skeleton    |
 //! | 8 | `evo_re_rpc`  | RE → gRPC     | `ReToRpcSegment`   | Skeleton    |
-//! | 9 | `evo_rpc_cu`  | gRPC → CU     | `RpcToCuSegment`   | Skeleton    |
-//! |10 | `evo_rpc_hal` | gRPC → HAL    | `RpcToHalSegment`  | Skeleton    |
+//! | 9 | `evo_rpc_cu`  | gRPC → CU     | `RpcToCuSegment`   | Active      |
+//! |10 | `evo_rpc_hal` | gRPC → HAL    | `RpcToHalSegment`  | Active      |
 //! |11 | `evo_rpc_re`  | gRPC → RE     | `RpcToReSegment`   | Skeleton    |
-//! |12 | `evo_cu_re`   | CU → RE       | `CuToReSegment`    | Placeholder |
+//! |12 | `evo_cu_re`   | CU → RE       | `CuToReSegment`    | Active      |
 //! |13 | `evo_cu_rpc`  | CU → gRPC     | `CuToRpcSegment`   | Placeholder |
-//! |14 | `evo_hal_rpc` | HAL → gRPC    | `HalToRpcSegment`  | Placeholder |
+//! |14 | `evo_hal_rpc` | HAL → gRPC    | `HalToRpcSegment`  | Active      |
 //! |15 | `evo_hal_re`  | HAL → RE      | `HalToReSegment`   | Placeholder |
+//!
+//! ## Command Payloads
+//!
+//! `ReCommand` (RE → CU) and `RpcCommand` (gRPC → CU) are the wire format
+//! of the command segments; the CU acknowledges their `sequence_id` in
+//! `CuToReSegment`, each source in its own ack field pair.
 
 use crate::consts::{MAX_AXES, MAX_AI, MAX_AO};
+use crate::control_unit::state::{
+    MachineState, MotionState, OperationalMode, PowerState, SafetyState,
+};
+use crate::hal::types::CommandMode;
 use crate::shm::io_helpers::BANK_WORDS;
 
 // ─── Segment Name Constants ─────────────────────────────────────────
@@ -69,7 +79,7 @@ pub const SEG_HAL_RE: &str = "hal_re";
 ///
 /// Used in `HalToCuSegment`, `HalToMqtSegment`, `HalToReSegment`.
 ///
-/// Size: 32 bytes (3×f64 + 4×u8 + 4 trailing pad).
+/// Size: 40 bytes (4×f64 + 5×u8 + 3 trailing pad).
 #[derive(Debug, Clone, Copy, Default)]
 #[repr(C)]
 pub struct HalAxisFeedback {
@@ -77,8 +87,10 @@ pub struct HalAxisFeedback {
     pub position: f64,
     /// Current axis velocity (mm/s or deg/s).
     pub velocity: f64,
-    /// Estimated torque (Nm or %).
+    /// Actual torque reported by the drive (% of rated).
     pub torque_estimate: f64,
+    /// Actual motor current reported by the drive (A).
+    pub current: f64,
     /// Drive ready flag (0=not ready, 1=ready).
     pub drive_ready: u8,
     /// Drive fault flag (0=ok, 1=fault).
@@ -87,14 +99,21 @@ pub struct HalAxisFeedback {
     pub referenced: u8,
     /// Axis active flag (0=inactive, 1=active).
     pub active: u8,
-    // Implicit trailing padding: 4 bytes → total 32 = 4×align(8)
+    /// Drive mode in effect (`CommandMode` as u8).
+    pub command_mode: u8,
+    // Implicit trailing padding: 3 bytes → total 40 = 5×align(8)
 }
 
 /// Per-axis command from CU (control outputs + enable flags).
 ///
 /// Used in `CuToHalSegment`.
 ///
-/// Size: 40 bytes (4×f64 + 2×u8 + 6 trailing pad).
+/// The drive closes its loop on the setpoint selected by `command_mode`:
+/// `target_position` (CSP, with `target_velocity` as velocity feedforward),
+/// `target_velocity` (CSV) or `calculated_torque` (CST). `torque_offset`
+/// is added in every mode.
+///
+/// Size: 40 bytes (4×f64 + 3×u8 + 5 trailing pad).
 #[derive(Debug, Clone, Copy, Default)]
 #[repr(C)]
 pub struct CuAxisCommand {
@@ -110,7 +129,9 @@ pub struct CuAxisCommand {
     pub enable: u8,
     /// Brake release command (0=engage, 1=release).
     pub brake_release: u8,
-    // Implicit trailing padding: 6 bytes → total 40 = 5×align(8)
+    /// Drive mode (`CommandMode` as u8: 0=CSP, 1=CSV, 2=CST).
+    pub command_mode: u8,
+    // Implicit trailing padding: 5 bytes → total 40 = 5×align(8)
 }
 
 /// Per-axis status for CU → MQTT / gRPC segments.
@@ -155,6 +176,237 @@ pub struct AxisPidState {
     pub output: f64,
 }
 
+// ─── Command Payloads ───────────────────────────────────────────────
+
+/// Recipe command carried by [`ReToCuSegment`].
+///
+/// `sequence_id` changes with every new command; the CU processes each
+/// value once and acknowledges it in [`CuToReSegment::last_ack_seq_id`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ReCommand {
+    /// Command type.
+    pub command_type: u8,
+    /// Padding for alignment.
+    pub _pad0: [u8; 7],
+    /// Bit mask: bit N = axis (N+1) is targeted.
+    pub axis_mask: u64,
+    /// Per-axis motion targets.
+    pub targets: [ReAxisTarget; MAX_AXES as usize],
+    /// Arc geometry for `PathArcCw` / `PathArcCcw` (ignored otherwise).
+    pub arc: RePathArc,
+    /// Monotonic sequence ID for ack tracking.
+    pub sequence_id: u32,
+    /// Padding.
+    pub _pad1: [u8; 4],
+}
+
+impl Default for ReCommand {
+    fn default() -> Self {
+        Self {
+            command_type: ReCommandType::Nop as u8,
+            _pad0: [0u8; 7],
+            axis_mask: 0,
+            targets: [ReAxisTarget::default(); MAX_AXES as usize],
+            arc: RePathArc::default(),
+            sequence_id: 0,
+            _pad1: [0u8; 4],
+        }
+    }
+}
+
+/// RE command types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ReCommandType {
+    /// No active command.
+    Nop = 0,
+    MoveAbsolute = 1,
+    MoveRelative = 2,
+    MoveVelocity = 3,
+    Home = 4,
+    Stop = 5,
+    EmergencyStop = 6,
+    EnableAxis = 7,
+    DisableAxis = 8,
+    SetMode = 9,
+    Couple = 10,
+    Decouple = 11,
+    GearChange = 12,
+    /// Authorize manual mode for axes in axis_mask (FR-004).
+    AllowManualMode = 13,
+    /// Coordinated linear path segment over an axis group.
+    ///
+    /// Masked axes give the end point in `target_position`; the path feed
+    /// rate is the largest `target_velocity` (0 = axis limits only).
+    PathLinear = 14,
+    /// Coordinated clockwise arc (G2) over an axis group.
+    ///
+    /// As [`PathLinear`](Self::PathLinear); the arc plane and centre come
+    /// from [`ReCommand::arc`].
+    PathArcCw = 15,
+    /// Coordinated counter-clockwise arc (G3), see [`PathArcCw`](Self::PathArcCw).
+    PathArcCcw = 16,
+}
+
+impl ReCommandType {
+    #[inline]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Nop),
+            1 => Some(Self::MoveAbsolute),
+            2 => Some(Self::MoveRelative),
+            3 => Some(Self::MoveVelocity),
+            4 => Some(Self::Home),
+            5 => Some(Self::Stop),
+            6 => Some(Self::EmergencyStop),
+            7 => Some(Self::EnableAxis),
+            8 => Some(Self::DisableAxis),
+            9 => Some(Self::SetMode),
+            10 => Some(Self::Couple),
+            11 => Some(Self::Decouple),
+            12 => Some(Self::GearChange),
+            13 => Some(Self::AllowManualMode),
+            14 => Some(Self::PathLinear),
+            15 => Some(Self::PathArcCw),
+            16 => Some(Self::PathArcCcw),
+            _ => None,
+        }
+    }
+}
+
+/// Per-axis motion target from RE (40 bytes).
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ReAxisTarget {
+    /// Target position [mm].
+    pub target_position: f64,
+    /// Maximum velocity [mm/s].
+    pub target_velocity: f64,
+    /// Acceleration [mm/s²].
+    pub acceleration: f64,
+    /// Deceleration [mm/s²].
+    pub deceleration: f64,
+    /// Operational mode.
+    pub mode: u8,
+    /// Padding.
+    pub _pad: [u8; 7],
+}
+
+impl Default for ReAxisTarget {
+    fn default() -> Self {
+        Self {
+            target_position: 0.0,
+            target_velocity: 0.0,
+            acceleration: 0.0,
+            deceleration: 0.0,
+            mode: OperationalMode::Position as u8,
+            _pad: [0u8; 7],
+        }
+    }
+}
+
+/// Arc geometry of a path arc command (24 bytes).
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct RePathArc {
+    /// Axis indices (bit N of `axis_mask` = index N) of the first and
+    /// second plane axis; both must be masked.
+    pub plane: [u8; 2],
+    /// Padding.
+    pub _pad: [u8; 6],
+    /// Arc centre on the two plane axes [mm].
+    pub center: [f64; 2],
+}
+
+/// RPC command carried by [`RpcToCuSegment`].
+///
+/// Deduplicated on `sequence_id` like [`ReCommand`], acknowledged in
+/// [`CuToReSegment::rpc_ack_seq_id`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RpcCommand {
+    /// Command type.
+    pub command_type: u8,
+    /// Target axis ID (0 = global command).
+    pub axis_id: u8,
+    /// Padding.
+    pub _pad: [u8; 6],
+    /// Command-specific float parameter.
+    pub param_f64: f64,
+    /// Command-specific integer parameter.
+    pub param_u32: u32,
+    /// Monotonic sequence ID for ack tracking.
+    pub sequence_id: u32,
+}
+
+impl Default for RpcCommand {
+    fn default() -> Self {
+        Self {
+            command_type: RpcCommandType::Nop as u8,
+            axis_id: 0,
+            _pad: [0u8; 6],
+            param_f64: 0.0,
+            param_u32: 0,
+            sequence_id: 0,
+        }
+    }
+}
+
+/// RPC command types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RpcCommandType {
+    Nop = 0,
+    JogPositive = 1,
+    JogNegative = 2,
+    JogStop = 3,
+    MoveAbsolute = 4,
+    EnableAxis = 5,
+    DisableAxis = 6,
+    HomeAxis = 7,
+    ResetError = 8,
+    /// param_u32 = target MachineState.
+    SetMachineState = 9,
+    SetMode = 10,
+    GearChange = 11,
+    /// Request source lock.
+    AcquireLock = 12,
+    ReleaseLock = 13,
+    /// Authorize manual mode for axis_id (FR-004).
+    AllowManualMode = 14,
+    /// Hot-reload config during SAFETY_STOP (FR-145).
+    ReloadConfig = 15,
+    /// Operator authorization of the SAFETY_STOP recovery sequence (FR-122).
+    AuthorizeRecovery = 16,
+}
+
+impl RpcCommandType {
+    #[inline]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Nop),
+            1 => Some(Self::JogPositive),
+            2 => Some(Self::JogNegative),
+            3 => Some(Self::JogStop),
+            4 => Some(Self::MoveAbsolute),
+            5 => Some(Self::EnableAxis),
+            6 => Some(Self::DisableAxis),
+            7 => Some(Self::HomeAxis),
+            8 => Some(Self::ResetError),
+            9 => Some(Self::SetMachineState),
+            10 => Some(Self::SetMode),
+            11 => Some(Self::GearChange),
+            12 => Some(Self::AcquireLock),
+            13 => Some(Self::ReleaseLock),
+            14 => Some(Self::AllowManualMode),
+            15 => Some(Self::ReloadConfig),
+            16 => Some(Self::AuthorizeRecovery),
+            _ => None,
+        }
+    }
+}
+
 // ─── Default via zeroed() ───────────────────────────────────────────
 //
 // Large segment structs use mem::zeroed() for Default to avoid deep stack
@@ -274,14 +526,15 @@ pub struct HalToMqtSegment {
 /// **#5** RE → CU command segment (`evo_re_cu`).
 ///
 /// Motion requests, program commands, `AllowManualMode`.
-/// Content deferred to RE specification.
+/// One `ReCommand` per commit; the CU processes each new `sequence_id`
+/// once and acknowledges it in `evo_cu_re`.
 ///
-/// FR-014, FR-040, FR-090.
+/// FR-014, FR-040, FR-090, FR-133.
 #[derive(Clone, Copy)]
 #[repr(C, align(64))]
 pub struct ReToCuSegment {
-    /// Reserved — content defined in RE spec.
-    pub _reserved: [u8; 256],
+    /// Current recipe command.
+    pub command: ReCommand,
 }
 
 /// **#6** RE → HAL I/O command segment (`evo_re_hal`).
@@ -360,14 +613,16 @@ pub struct ReToRpcSegment {
 /// **#9** gRPC → CU command segment (`evo_rpc_cu`).
 ///
 /// External commands: jog, mode change, config reload, service bypass.
-/// Content resolved in gRPC specification.
+/// One `RpcCommand` per commit, deduplicated on `sequence_id` by the CU.
 ///
 /// FR-014, FR-040, FR-090.
 #[derive(Clone, Copy)]
 #[repr(C, align(64))]
 pub struct RpcToCuSegment {
-    /// Reserved — content defined in gRPC spec.
-    pub _reserved: [u8; 256],
+    /// Current RPC command.
+    pub command: RpcCommand,
+    /// Reserved for future expansion.
+    pub _reserved: [u8; 232],
 }
 
 /// **#10** gRPC → HAL command segment (`evo_rpc_hal`).
@@ -380,8 +635,10 @@ pub struct RpcToCuSegment {
 pub struct RpcToHalSegment {
     /// Target pin or axis index.
     pub target: u16,
+    /// Command type (see [`RpcHalCommandType`]).
+    pub command_type: u8,
     /// Padding.
-    pub _pad1: [u8; 6],
+    pub _pad1: [u8; 5],
     /// Command value.
     pub value: f64,
     /// Request ID for ack correlation.
@@ -390,6 +647,34 @@ pub struct RpcToHalSegment {
     pub _reserved: [u8; 232],
 }
 
+/// Direct HAL command in [`RpcToHalSegment::command_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum RpcHalCommandType {
+    /// No active command.
+    Nop = 0,
+    /// Set digital output `target` (`value` ≠ 0 = ON).
+    SetDo = 1,
+    /// Set analog output `target` to `value` (normalized).
+    SetAo = 2,
+    /// Pass `target` (u16 LE) and `value` (f64 LE) to the driver's
+    /// custom command handler.
+    DriverCommand = 3,
+}
+
+impl RpcHalCommandType {
+    #[inline]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Nop),
+            1 => Some(Self::SetDo),
+            2 => Some(Self::SetAo),
+            3 => Some(Self::DriverCommand),
+            _ => None,
+        }
+    }
+}
+
 /// **#11** gRPC → RE segment (`evo_rpc_re`).
 ///
 /// Placeholder — content defined in separate spec.
@@ -410,14 +695,54 @@ pub struct RpcToReSegment {
 /// **#12** CU → RE segment (`evo_cu_re`).
 ///
 /// Ack, execution status, axis availability, error feedback.
-/// Reserved placeholder — heartbeat only.
+/// Written every cycle by the CU.
 ///
 /// FR-014, FR-040.
 #[derive(Clone, Copy)]
 #[repr(C, align(64))]
 pub struct CuToReSegment {
-    /// Reserved — content defined in RE spec.
-    pub _reserved: [u8; 256],
+    /// Last processed RE `sequence_id`.
+    pub last_ack_seq_id: u32,
+    /// Ack status of `last_ack_seq_id` (see [`AckStatus`]).
+    pub ack_status: u8,
+    /// Padding.
+    pub _pad: [u8; 3],
+    /// Bit per axis: axis is in position (bit 0 = axis 1).
+    pub axes_in_position: u64,
+    /// Bit per axis: axis has error (bit 0 = axis 1).
+    pub axes_in_error: u64,
+    /// Last processed RPC `sequence_id` (RE and RPC number independently).
+    pub rpc_ack_seq_id: u32,
+    /// Ack status of `rpc_ack_seq_id` (see [`AckStatus`]).
+    pub rpc_ack_status: u8,
+    /// Padding.
+    pub _pad2: [u8; 3],
+    /// Reserved for future expansion.
+    pub _reserved: [u8; 224],
+}
+
+/// Command acknowledgement status in [`CuToReSegment::ack_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AckStatus {
+    /// Command accepted and routed to the axis state machines.
+    Ok = 0,
+    /// Command rejected (source lock, invalid state, unknown axis).
+    Rejected = 1,
+    /// Command could not be decoded.
+    Error = 2,
+}
+
+impl AckStatus {
+    #[inline]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Ok),
+            1 => Some(Self::Rejected),
+            2 => Some(Self::Error),
+            _ => None,
+        }
+    }
 }
 
 /// **#13** CU → gRPC diagnostic segment (`evo_cu_rpc`).
@@ -471,6 +796,74 @@ pub struct HalToRpcSegment {
     pub _reserved: [u8; 112],
 }
 
+impl HalToRpcSegment {
+    /// Build a response; `message` is truncated to fit the fixed buffer.
+    pub fn response(request_id: u64, result: HalRpcResult, message: &str) -> Self {
+        let mut seg = Self {
+            request_id,
+            result_code: result as u32,
+            ..Self::default()
+        };
+        let mut len = message.len().min(seg.error_message.len() - 1);
+        while !message.is_char_boundary(len) {
+            len -= 1;
+        }
+        seg.error_message[..len].copy_from_slice(&message.as_bytes()[..len]);
+        seg
+    }
+
+    /// Error message up to the first NUL (empty if not valid UTF-8).
+    pub fn error_text(&self) -> &str {
+        let len = self
+            .error_message
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(self.error_message.len());
+        core::str::from_utf8(&self.error_message[..len]).unwrap_or("")
+    }
+}
+
+/// Result of a direct HAL command in [`HalToRpcSegment::result_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum HalRpcResult {
+    /// Command applied.
+    Ok = 0,
+    /// Target pin is assigned to an `IoRole` (FR-036).
+    IoRoleOwned = 1,
+    /// Target pin outside the I/O bank.
+    PinOutOfRange = 2,
+    /// Driver does not support or refused the command.
+    DriverRejected = 3,
+    /// Unknown command type.
+    InvalidCommand = 4,
+}
+
+impl HalRpcResult {
+    #[inline]
+    pub const fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Ok),
+            1 => Some(Self::IoRoleOwned),
+            2 => Some(Self::PinOutOfRange),
+            3 => Some(Self::DriverRejected),
+            4 => Some(Self::InvalidCommand),
+            _ => None,
+        }
+    }
+
+    /// Error code name used in logs and error messages.
+    pub const fn code_name(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::IoRoleOwned => "ERR_IO_ROLE_OWNED",
+            Self::PinOutOfRange => "ERR_PIN_OUT_OF_RANGE",
+            Self::DriverRejected => "ERR_DRIVER_REJECTED",
+            Self::InvalidCommand => "ERR_INVALID_COMMAND",
+        }
+    }
+}
+
 /// **#15** HAL → RE feedback segment (`evo_hal_re`).
 ///
 /// Full I/O states and per-axis feedback for RE decision logic.
@@ -514,15 +907,109 @@ impl_default_zeroed!(
     HalToReSegment,
 );
 
+// ─── Layout descriptors ─────────────────────────────────────────────
+//
+// Field-level layout hashes checked by `TypedP2pReader` on attach
+// (see `shm::layout`). Every payload type and nested struct is listed;
+// integer fields carrying an enum discriminant are tagged `as Enum` for
+// the published schemas (see `shm::schema`).
+
+crate::impl_shm_enum!(
+    MachineState { Stopped, Starting, Idle, Manual, Active, Service, SystemError },
+    SafetyState { Safe, SafeReducedSpeed, SafetyStop },
+    PowerState { PowerOff, PoweringOn, Standby, Motion, PoweringOff, NoBrake, PowerError },
+    MotionState {
+        Standstill, Accelerating, ConstantVelocity, Decelerating, Stopping, EmergencyStop, Homing,
+        GearAssistMotion, MotionError,
+    },
+    OperationalMode { Position, Velocity, Torque, Manual, Test },
+    CommandMode { CyclicSyncPosition, CyclicSyncVelocity, CyclicSyncTorque },
+    RpcHalCommandType { Nop, SetDo, SetAo, DriverCommand },
+    AckStatus { Ok, Rejected, Error },
+    ReCommandType {
+        Nop, MoveAbsolute, MoveRelative, MoveVelocity, Home, Stop, EmergencyStop, EnableAxis,
+        DisableAxis, SetMode, Couple, Decouple, GearChange, AllowManualMode, PathLinear, PathArcCw,
+        PathArcCcw,
+    },
+    RpcCommandType {
+        Nop, JogPositive, JogNegative, JogStop, MoveAbsolute, EnableAxis, DisableAxis, HomeAxis,
+        ResetError, SetMachineState, SetMode, GearChange, AcquireLock, ReleaseLock,
+        AllowManualMode, ReloadConfig, AuthorizeRecovery,
+    },
+    HalRpcResult { Ok, IoRoleOwned, PinOutOfRange, DriverRejected, InvalidCommand },
+);
+
+crate::impl_shm_layout!(
+    HalAxisFeedback {
+        position, velocity, torque_estimate, current, drive_ready, drive_fault, referenced, active,
+        command_mode as CommandMode,
+    },
+    CuAxisCommand {
+        target_position, target_velocity, calculated_torque, torque_offset, enable, brake_release,
+        command_mode as CommandMode,
+    },
+    CuAxisStatus {
+        axis_state as PowerState, motion_state as MotionState, homing_state, safety_state,
+        error_state, enable_state as OperationalMode, error_code, safety_flags, _reserved,
+    },
+    AxisPidState { error, integral, output },
+    ReAxisTarget {
+        target_position, target_velocity, acceleration, deceleration, mode, _pad,
+    },
+    RePathArc { plane, _pad, center },
+    ReCommand {
+        command_type as ReCommandType, _pad0, axis_mask, targets, arc, sequence_id, _pad1,
+    },
+    RpcCommand {
+        command_type as RpcCommandType, axis_id, _pad, param_f64, param_u32, sequence_id,
+    },
+    HalToCuSegment { axes, di_bank, ai_values, axis_count },
+    CuToHalSegment { axes, do_bank, ao_values, axis_count },
+    CuToMqtSegment {
+        machine_state as MachineState, safety_state as SafetyState, axis_count, _pad1, error_flags,
+        axis_status,
+    },
+    HalToMqtSegment {
+        axes, di_bank, ai_values, do_bank, ao_values, cycle_time_ns, driver_state, axis_count,
+    },
+    ReToCuSegment { command },
+    ReToHalSegment {
+        set_do_pin, set_do_value, _pad1, set_ao_pin, _pad2, set_ao_value, request_id, _reserved,
+    },
+    ReToMqtSegment {
+        current_step, re_state, _pad1, error_code, cycle_count, program_name, _reserved,
+    },
+    ReToRpcSegment { execution_progress, step_result, _pad1, request_id, error_message, _reserved },
+    RpcToCuSegment { command, _reserved },
+    RpcToHalSegment {
+        target, command_type as RpcHalCommandType, _pad1, value, request_id, _reserved,
+    },
+    RpcToReSegment { _reserved },
+    CuToReSegment {
+        last_ack_seq_id, ack_status as AckStatus, _pad, axes_in_position, axes_in_error,
+        rpc_ack_seq_id, rpc_ack_status as AckStatus, _pad2, _reserved,
+    },
+    CuToRpcSegment {
+        machine_state as MachineState, safety_state as SafetyState, axis_count, _pad1, error_flags,
+        axis_status, pid_states, last_cycle_ns, max_cycle_ns, jitter_histogram_us,
+    },
+    HalToRpcSegment { request_id, result_code as HalRpcResult, _pad1, error_message, _reserved },
+    HalToReSegment { axes, di_bank, do_bank, ai_values, ao_values, axis_count },
+);
+
 // ═══════════════════════════════════════════════════════════════════
 //  Static Assertions (T027)
 // ═══════════════════════════════════════════════════════════════════
 
 // Sub-struct sizes (repr(C) with implicit trailing padding).
-const _: () = assert!(core::mem::size_of::<HalAxisFeedback>() == 32);
+const _: () = assert!(core::mem::size_of::<HalAxisFeedback>() == 40);
 const _: () = assert!(core::mem::size_of::<CuAxisCommand>() == 40);
 const _: () = assert!(core::mem::size_of::<CuAxisStatus>() == 16);
 const _: () = assert!(core::mem::size_of::<AxisPidState>() == 24);
+const _: () = assert!(core::mem::size_of::<ReAxisTarget>() == 40);
+const _: () = assert!(core::mem::size_of::<RePathArc>() == 24);
+const _: () = assert!(core::mem::size_of::<ReCommand>() == 2608);
+const _: () = assert!(core::mem::size_of::<RpcCommand>() == 24);
 
 // All 15 segment structs: alignment == 64 (cache-line aligned).
 const _: () = assert!(core::mem::align_of::<HalToCuSegment>() == 64);
@@ -559,8 +1046,10 @@ const _: () = assert!(core::mem::size_of::<HalToRpcSegment>() % 64 == 0);
 const _: () = assert!(core::mem::size_of::<HalToReSegment>() % 64 == 0);
 
 // Active segments: verify exact sizes.
-const _: () = assert!(core::mem::size_of::<HalToCuSegment>() == 10432);
+const _: () = assert!(core::mem::size_of::<HalToCuSegment>() == 10944);
 const _: () = assert!(core::mem::size_of::<CuToHalSegment>() == 10944);
+const _: () = assert!(core::mem::size_of::<RpcToCuSegment>() == 256);
+const _: () = assert!(core::mem::size_of::<CuToReSegment>() == 256);
 
 // ═══════════════════════════════════════════════════════════════════
 //  Tests
@@ -569,20 +1058,93 @@ const _: () = assert!(core::mem::size_of::<CuToHalSegment>() == 10944);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shm::layout::ShmLayout;
     use crate::shm::p2p::struct_version_hash;
 
     #[test]
     fn sub_struct_sizes() {
-        assert_eq!(core::mem::size_of::<HalAxisFeedback>(), 32);
+        assert_eq!(core::mem::size_of::<HalAxisFeedback>(), 40);
         assert_eq!(core::mem::size_of::<CuAxisCommand>(), 40);
         assert_eq!(core::mem::size_of::<CuAxisStatus>(), 16);
         assert_eq!(core::mem::size_of::<AxisPidState>(), 24);
+        assert_eq!(core::mem::size_of::<ReAxisTarget>(), 40);
+        assert_eq!(core::mem::size_of::<RePathArc>(), 24);
+        assert_eq!(core::mem::size_of::<ReCommand>(), 2608);
+        assert_eq!(core::mem::size_of::<RpcCommand>(), 24);
     }
 
     #[test]
     fn active_segment_sizes() {
-        assert_eq!(core::mem::size_of::<HalToCuSegment>(), 10432);
+        assert_eq!(core::mem::size_of::<HalToCuSegment>(), 10944);
         assert_eq!(core::mem::size_of::<CuToHalSegment>(), 10944);
+        assert_eq!(core::mem::size_of::<RpcToCuSegment>(), 256);
+        assert_eq!(core::mem::size_of::<CuToReSegment>(), 256);
+    }
+
+    #[test]
+    fn re_command_type_roundtrip() {
+        for v in 0..=16u8 {
+            let cmd = ReCommandType::from_u8(v).unwrap();
+            assert_eq!(cmd as u8, v);
+        }
+        assert!(ReCommandType::from_u8(17).is_none());
+    }
+
+    #[test]
+    fn rpc_command_type_roundtrip() {
+        for v in 0..=16u8 {
+            let cmd = RpcCommandType::from_u8(v).unwrap();
+            assert_eq!(cmd as u8, v);
+        }
+        assert!(RpcCommandType::from_u8(17).is_none());
+    }
+
+    #[test]
+    fn command_defaults() {
+        let cmd = ReCommand::default();
+        assert_eq!(cmd.command_type, ReCommandType::Nop as u8);
+        assert_eq!(cmd.targets[0].mode, OperationalMode::Position as u8);
+        assert_eq!(RpcCommand::default().command_type, RpcCommandType::Nop as u8);
+
+        // Zeroed segments decode as `Nop` with sequence 0.
+        let seg = ReToCuSegment::default();
+        assert_eq!(ReCommandType::from_u8(seg.command.command_type), Some(ReCommandType::Nop));
+        assert_eq!(RpcToCuSegment::default().command.sequence_id, 0);
+    }
+
+    #[test]
+    fn ack_status_roundtrip() {
+        for v in 0..=2u8 {
+            assert_eq!(AckStatus::from_u8(v).unwrap() as u8, v);
+        }
+        assert!(AckStatus::from_u8(3).is_none());
+    }
+
+    #[test]
+    fn hal_rpc_codes_roundtrip() {
+        for v in 0..=3 {
+            assert_eq!(RpcHalCommandType::from_u8(v).unwrap() as u8, v);
+        }
+        assert!(RpcHalCommandType::from_u8(4).is_none());
+        for v in 0..=4 {
+            assert_eq!(HalRpcResult::from_u32(v).unwrap() as u32, v);
+        }
+        assert!(HalRpcResult::from_u32(5).is_none());
+        assert_eq!(HalRpcResult::IoRoleOwned.code_name(), "ERR_IO_ROLE_OWNED");
+    }
+
+    #[test]
+    fn hal_rpc_response_message_truncated() {
+        let seg = HalToRpcSegment::response(7, HalRpcResult::PinOutOfRange, "pin 2000");
+        assert_eq!(seg.request_id, 7);
+        assert_eq!(seg.result_code, HalRpcResult::PinOutOfRange as u32);
+        assert_eq!(seg.error_text(), "pin 2000");
+
+        // Never splits a UTF-8 character and always keeps a NUL terminator.
+        let long = "µ".repeat(100);
+        let seg = HalToRpcSegment::response(8, HalRpcResult::Ok, &long);
+        assert_eq!(seg.error_text(), "µ".repeat(63));
+        assert_eq!(seg.error_message[127], 0);
     }
 
     #[test]
@@ -597,32 +1159,49 @@ mod tests {
     }
 
     #[test]
-    fn version_hashes_differ_for_different_sizes() {
-        // Segments with different sizes must have different version hashes.
-        let h1 = struct_version_hash::<HalToCuSegment>();
-        let h2 = struct_version_hash::<CuToHalSegment>();
-        let h3 = struct_version_hash::<CuToMqtSegment>();
-        let h4 = struct_version_hash::<HalToMqtSegment>();
-        let h5 = struct_version_hash::<CuToRpcSegment>();
-        let h6 = struct_version_hash::<HalToReSegment>();
-
-        // All different sizes → all different hashes.
+    fn layout_hashes_differ_between_segments() {
+        let h1 = HalToCuSegment::LAYOUT_HASH;
+        let h2 = CuToHalSegment::LAYOUT_HASH;
+        let h3 = CuToMqtSegment::LAYOUT_HASH;
+        let h4 = HalToMqtSegment::LAYOUT_HASH;
+        let h5 = CuToRpcSegment::LAYOUT_HASH;
+        let h6 = HalToReSegment::LAYOUT_HASH;
+
+        // HalToCu and CuToHal share size and alignment, so their size-only
+        // hashes collide; the field-level hashes must not.
+        assert_eq!(core::mem::size_of::<HalToCuSegment>(), core::mem::size_of::<CuToHalSegment>());
+        assert_eq!(struct_version_hash::<HalToCuSegment>(), struct_version_hash::<CuToHalSegment>());
         assert_ne!(h1, h2, "HalToCu vs CuToHal");
+
         assert_ne!(h1, h3, "HalToCu vs CuToMqt");
         assert_ne!(h1, h4, "HalToCu vs HalToMqt");
         assert_ne!(h2, h3, "CuToHal vs CuToMqt");
         assert_ne!(h4, h5, "HalToMqt vs CuToRpc");
         assert_ne!(h4, h6, "HalToMqt vs HalToRe");
+
+        // Same size too: RpcToCu vs CuToRe, RpcToHal vs ReToHal.
+        assert_ne!(RpcToCuSegment::LAYOUT_HASH, CuToReSegment::LAYOUT_HASH);
+        assert_ne!(RpcToHalSegment::LAYOUT_HASH, ReToHalSegment::LAYOUT_HASH);
+    }
+
+    #[test]
+    fn layout_hash_tracks_nested_fields() {
+        // A change inside HalAxisFeedback must change every segment using it.
+        let fields = HalToCuSegment::FIELDS;
+        assert_eq!(fields[0].name, "axes");
+        assert_eq!(fields[0].hash, <[HalAxisFeedback; MAX_AXES as usize]>::LAYOUT_HASH);
+        assert_eq!(fields[0].fields[0].name, "position");
+        assert_ne!(HalAxisFeedback::LAYOUT_HASH, CuAxisCommand::LAYOUT_HASH);
     }
 
     #[test]
     fn version_hash_stable() {
         // Version hashes should not change between compilations.
         // Record known-good values; update if struct layout intentionally changes.
-        let h = struct_version_hash::<HalToCuSegment>();
+        let h = HalToCuSegment::LAYOUT_HASH;
         assert_ne!(h, 0, "hash should not be zero");
         // Same call must produce same result.
-        assert_eq!(h, struct_version_hash::<HalToCuSegment>());
+        assert_eq!(h, HalToCuSegment::LAYOUT_HASH);
     }
 
     #[test]
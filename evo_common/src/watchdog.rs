@@ -9,8 +9,33 @@
 //! The trait is deliberately thin — it captures the four core operations
 //! that any watchdog implementation must provide, without mandating a
 //! specific process management strategy (fork, systemd, container, etc.).
+//!
+//! # Hang detection
+//!
+//! [`HeartbeatProbe`] tracks the heartbeats of the segments a module
+//! writes (from [`SegmentDiscovery`](crate::shm::p2p::SegmentDiscovery)).
+//! A module whose outbound heartbeats all stay frozen is hung even though
+//! its process is alive ([`HealthStatus::Stale`]).
+//!
+//! # Control socket
+//!
+//! [`control`] defines the request/response protocol between the
+//! supervisor and `evo ctl`.
+//!
+//! # Post-mortem bundles
+//!
+//! [`postmortem`] snapshots the SHM segments, exit status, recent output
+//! and config of a failed module before the supervisor cleans up.
+
+pub mod control;
+pub mod postmortem;
 
 use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::shm::p2p::{ModuleAbbrev, SegmentInfo};
 
 /// Identifies a managed child module.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -27,8 +52,47 @@ pub enum ManagedModule {
     Mqtt,
 }
 
+impl ManagedModule {
+    /// All managed modules.
+    pub const ALL: [Self; 5] = [
+        Self::Hal,
+        Self::Cu,
+        Self::RecipeExecutor,
+        Self::Grpc,
+        Self::Mqtt,
+    ];
+
+    /// Executable name.
+    pub const fn binary(self) -> &'static str {
+        match self {
+            Self::Hal => "evo_hal",
+            Self::Cu => "evo_control_unit",
+            Self::RecipeExecutor => "evo_recipe_executor",
+            Self::Grpc => "evo_grpc",
+            Self::Mqtt => "evo_mqtt",
+        }
+    }
+
+    /// Module running executable `binary`, if it is a managed one.
+    pub fn from_binary(binary: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.binary() == binary)
+    }
+
+    /// Source identifier in the headers of the segments it writes.
+    pub const fn abbrev(self) -> ModuleAbbrev {
+        match self {
+            Self::Hal => ModuleAbbrev::Hal,
+            Self::Cu => ModuleAbbrev::Cu,
+            Self::RecipeExecutor => ModuleAbbrev::Re,
+            Self::Grpc => ModuleAbbrev::Rpc,
+            Self::Mqtt => ModuleAbbrev::Mqt,
+        }
+    }
+}
+
 /// Health status returned by [`Watchdog::health_check`].
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
 pub enum HealthStatus {
     /// Module is running and its heartbeat is current.
     Healthy,
@@ -99,7 +163,8 @@ pub trait Watchdog {
     ///
     /// Returns the OS PID of the spawned process on success.
     /// The implementation should forward `config_dir` to the child
-    /// via `--config-dir` CLI argument.
+    /// via `--config-dir` CLI argument, and its instance id (if any) via
+    /// `--instance` so the child uses the supervisor's SHM namespace.
     fn spawn_module(
         &mut self,
         module: ManagedModule,
@@ -116,7 +181,7 @@ pub trait Watchdog {
     ///
     /// The implementation should:
     /// 1. Terminate the existing process (if still alive).
-    /// 2. Clean up associated SHM segments.
+    /// 2. Clean up associated SHM segments (own namespace only).
     /// 3. Re-spawn with the same config.
     /// 4. Return the new PID.
     fn restart_module(
@@ -130,6 +195,74 @@ pub trait Watchdog {
     /// 1. Send SIGTERM to each child (CU first, then HAL, etc.).
     /// 2. Wait up to a timeout for graceful exit.
     /// 3. Escalate to SIGKILL for unresponsive processes.
-    /// 4. Clean up all `evo_*` SHM segments.
+    /// 4. Clean up all SHM segments of the instance (`evo_*` by default).
     fn shutdown_all(&mut self) -> Result<(), WatchdogError>;
 }
+
+// ─── Heartbeat Probe ────────────────────────────────────────────────
+
+/// Outbound heartbeat tracker for hang detection.
+///
+/// Feed it [`SegmentDiscovery::list_segments_in`](crate::shm::p2p::SegmentDiscovery::list_segments_in)
+/// snapshots; [`age`](Self::age) is the time since any segment written by
+/// `source` (with a live writer) last advanced its heartbeat. While the
+/// module has no such segment the age stays zero — a module that never
+/// created its segments is a readiness problem, not a hang.
+#[derive(Debug, Clone)]
+pub struct HeartbeatProbe {
+    source: ModuleAbbrev,
+    /// `(segment, heartbeat)` of the last update.
+    beats: Vec<(String, u64)>,
+    last_change: Instant,
+}
+
+impl HeartbeatProbe {
+    /// Probe for the segments written by `source`, starting at age zero.
+    pub fn new(source: ModuleAbbrev, now: Instant) -> Self {
+        Self {
+            source,
+            beats: Vec::new(),
+            last_change: now,
+        }
+    }
+
+    /// Compare the heartbeats in `segments` with the last update.
+    pub fn update(&mut self, segments: &[SegmentInfo], now: Instant) {
+        let mut count = 0;
+        let mut changed = false;
+        for seg in segments {
+            if seg.source != Some(self.source) || !seg.writer_alive {
+                continue;
+            }
+            let Some(heartbeat) = seg.heartbeat else { continue };
+            count += 1;
+            match self.beats.iter_mut().find(|(name, _)| *name == seg.name) {
+                Some((_, last)) if *last == heartbeat => {}
+                Some((_, last)) => {
+                    *last = heartbeat;
+                    changed = true;
+                }
+                None => {
+                    self.beats.push((seg.name.clone(), heartbeat));
+                    changed = true;
+                }
+            }
+        }
+        if count == 0 {
+            self.beats.clear();
+        }
+        if changed || count == 0 {
+            self.last_change = now;
+        }
+    }
+
+    /// Time since the last heartbeat change.
+    pub fn age(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_change)
+    }
+
+    /// Source identifier of the probed segments.
+    pub fn source(&self) -> ModuleAbbrev {
+        self.source
+    }
+}
@@ -0,0 +1,267 @@
+//! # Supervisor Control Socket
+//!
+//! Unix-domain socket served by the `evo` supervisor and used by
+//! `evo ctl`. One request per connection: the client writes a
+//! [`CtlRequest`] as one JSON line, the supervisor answers with one
+//! [`CtlResponse`] JSON line.
+//!
+//! ```text
+//! → {"cmd":"restart","module":"cu"}
+//! ← {"result":"ok","message":"restarted cu"}
+//! ```
+//!
+//! The socket is named after the SHM namespace prefix and lives in the
+//! temp directory (not `/dev/shm`, so segment cleanup never sees it):
+//! `/tmp/evo_ctl.sock` by default, `/tmp/evo-<id>_ctl.sock` for an
+//! instance (see [`socket_path`]).
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::HealthStatus;
+use crate::config::RestartPolicy;
+use crate::shm::p2p::ShmNamespace;
+
+/// Control socket path of namespace `ns`.
+pub fn socket_path(ns: &ShmNamespace) -> PathBuf {
+    std::env::temp_dir().join(format!("{}ctl.sock", ns.file_prefix()))
+}
+
+// ─── Protocol ───────────────────────────────────────────────────────
+
+/// Command sent to the supervisor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum CtlRequest {
+    /// Per-module PID, uptime, restart count and health.
+    Status,
+    /// Start a stopped module, after its stopped dependencies.
+    Start { module: String },
+    /// Stop a module and every module depending on it; they stay
+    /// stopped (also across automatic restarts) until started again.
+    Stop { module: String },
+    /// Restart a module with the running modules its restart policy
+    /// restarts along (`restart_with`, `restart-dependents`).
+    Restart { module: String },
+    /// Re-read the config directory and restart all modules with it.
+    Reload,
+    /// Stop all modules and exit.
+    Shutdown,
+}
+
+/// Supervisor answer to a [`CtlRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum CtlResponse {
+    /// Command accepted / done.
+    Ok { message: String },
+    /// Answer to [`CtlRequest::Status`].
+    Status(SupervisorStatus),
+    /// Command rejected or failed.
+    Error { message: String },
+}
+
+/// Supervisor-wide status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SupervisorStatus {
+    /// SHM namespace (`"default"` or the instance id).
+    pub namespace: String,
+    /// Supervisor PID.
+    pub pid: u32,
+    /// Seconds since the supervisor started.
+    pub uptime_s: f64,
+    /// Reason of the fatal state: all modules stopped, none restarted
+    /// until `reload`.
+    pub fatal: Option<String>,
+    /// Enabled modules in start order.
+    pub modules: Vec<ModuleStatus>,
+}
+
+/// Runtime state of a supervised module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleState {
+    /// Process spawned.
+    Running,
+    /// Waiting for its restart delay or its dependencies.
+    Pending,
+    /// Stopped by `evo ctl stop`, or exited cleanly under `on-failure`.
+    Stopped,
+    /// Left down by its restart policy (`never`) or the fatal state.
+    Failed,
+}
+
+/// Status of one module.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleStatus {
+    /// Module name from `[[modules]]`.
+    pub name: String,
+    /// Executable name.
+    pub binary: String,
+    /// Runtime state.
+    pub state: ModuleState,
+    /// OS PID while spawned.
+    pub pid: Option<u32>,
+    /// Seconds since the current process was spawned.
+    pub uptime_s: Option<f64>,
+    /// Times the module was spawned again after its first start.
+    pub restarts: u32,
+    /// Configured restart policy.
+    pub restart: RestartPolicy,
+    /// Liveness and heartbeat health.
+    pub health: HealthStatus,
+    /// Most recent exit status, hang or readiness timeout.
+    pub last_failure: Option<String>,
+}
+
+impl CtlResponse {
+    /// Single-line JSON encoding.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("response serialization is infallible")
+    }
+
+    /// Indented JSON encoding, for `evo ctl --json`.
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("response serialization is infallible")
+    }
+}
+
+/// Error type for control socket operations.
+#[derive(Debug, Error)]
+pub enum CtlError {
+    /// No supervisor listening on the socket.
+    #[error("cannot connect to {path}: {source}")]
+    Connect { path: PathBuf, source: io::Error },
+
+    /// Another supervisor already serves the socket.
+    #[error("control socket {0} already in use")]
+    InUse(PathBuf),
+
+    /// Socket I/O failed.
+    #[error("control socket I/O: {0}")]
+    Io(#[from] io::Error),
+
+    /// Malformed request or response line.
+    #[error("control protocol: {0}")]
+    Protocol(#[from] serde_json::Error),
+}
+
+// ─── Client ─────────────────────────────────────────────────────────
+
+/// Send `request` to the supervisor at `path` and wait for its answer.
+///
+/// `timeout` bounds each read and write; commands that start modules
+/// wait for their readiness, so allow for `ready_timeout_s`.
+///
+/// # Errors
+/// - `CtlError::Connect` if no supervisor listens on `path`.
+/// - `CtlError::Io` / `CtlError::Protocol` on transport failures.
+pub fn send(path: &Path, request: &CtlRequest, timeout: Duration) -> Result<CtlResponse, CtlError> {
+    let mut stream = UnixStream::connect(path).map_err(|source| CtlError::Connect {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    Ok(serde_json::from_str(&reply)?)
+}
+
+// ─── Server ─────────────────────────────────────────────────────────
+
+/// Non-blocking control socket listener.
+///
+/// The socket file is removed on drop.
+pub struct ControlSocket {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+/// Accepted request connection; answer with [`reply`](Self::reply).
+pub struct CtlConnection {
+    stream: UnixStream,
+}
+
+impl ControlSocket {
+    /// Bind the socket at `path`, replacing a stale file left by a
+    /// crashed supervisor.
+    ///
+    /// # Errors
+    /// - `CtlError::InUse` if a live supervisor answers on `path`.
+    /// - `CtlError::Io` if the socket cannot be created.
+    pub fn bind(path: &Path) -> Result<Self, CtlError> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                return Err(CtlError::InUse(path.to_path_buf()));
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Socket path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Next pending request, if any. Never blocks on an idle socket.
+    ///
+    /// Reading the request waits at most `timeout`. A malformed request
+    /// is answered with [`CtlResponse::Error`] here and skipped.
+    pub fn accept(&self, timeout: Duration) -> Option<(CtlRequest, CtlConnection)> {
+        loop {
+            let (stream, _) = self.listener.accept().ok()?;
+            if stream.set_nonblocking(false).is_err()
+                || stream.set_read_timeout(Some(timeout)).is_err()
+                || stream.set_write_timeout(Some(timeout)).is_err()
+            {
+                continue;
+            }
+            let mut line = String::new();
+            let Ok(reader_stream) = stream.try_clone() else {
+                continue;
+            };
+            if BufReader::new(reader_stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            let conn = CtlConnection { stream };
+            match serde_json::from_str(&line) {
+                Ok(request) => return Some((request, conn)),
+                Err(e) => conn.reply(&CtlResponse::Error {
+                    message: format!("bad request: {e}"),
+                }),
+            }
+        }
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl CtlConnection {
+    /// Send the answer and close the connection.
+    pub fn reply(mut self, response: &CtlResponse) {
+        let mut line = response.to_json();
+        line.push('\n');
+        // The client may have given up; nothing to do then.
+        let _ = self.stream.write_all(line.as_bytes());
+    }
+}
@@ -0,0 +1,526 @@
+//! # Crash Post-Mortem Bundles
+//!
+//! When a supervised module fails, `evo` records the evidence before it
+//! stops the restart set and unlinks the dead segments: one bundle
+//! directory per failure under `watchdog.postmortem_dir`, of which the
+//! newest `watchdog.postmortem_keep` are kept.
+//!
+//! ```text
+//! <postmortem_dir>/20261016T031840.123456Z_default_cu/
+//!   report.json   failure, exit status / signal, decoded segment headers
+//!   output.log    last lines the module wrote to stdout / stderr
+//!   shm/          raw copy of every file of the namespace in /dev/shm
+//!   config/       copy of the active config directory
+//! ```
+//!
+//! Bundle names start with the UTC time, so they sort chronologically.
+//! [`OutputTail`] keeps the recent output of a child whose stdout and
+//! stderr are piped, while still echoing it to the supervisor's own.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::shm::p2p::{ModuleAbbrev, SegmentDiscovery, SegmentInfo, ShmNamespace};
+
+/// Lines of child output kept for a bundle.
+pub const OUTPUT_TAIL_LINES: usize = 500;
+
+/// Name of the report file; marks a directory as a bundle.
+pub const REPORT_FILE: &str = "report.json";
+
+const SHM_DIR: &str = "/dev/shm";
+
+// ─── Report ─────────────────────────────────────────────────────────
+
+/// Contents of `report.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    /// Module name from `[[modules]]`.
+    pub module: String,
+    /// Executable name.
+    pub binary: String,
+    /// SHM namespace (`"default"` or the instance id).
+    pub namespace: String,
+    /// OS PID of the failed process.
+    pub pid: Option<u32>,
+    /// Failure as logged by the supervisor.
+    pub failure: String,
+    /// Exit code, if the process exited.
+    pub exit_code: Option<i32>,
+    /// Terminating signal (e.g. `"SIGSEGV"`), if killed by one.
+    pub signal: Option<String>,
+    /// Whether the kernel dumped core.
+    pub core_dumped: bool,
+    /// Seconds the process ran.
+    pub uptime_s: Option<f64>,
+    /// Bundle time, RFC 3339 UTC. Set by [`PostMortem::write`].
+    pub time: String,
+    /// Files of the namespace at failure time. Set by [`PostMortem::write`].
+    pub segments: Vec<SegmentSnapshot>,
+}
+
+/// One `/dev/shm` file of the namespace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentSnapshot {
+    /// File name, e.g. `"evo_hal_cu"`; the raw copy is `shm/<file>`.
+    pub file: String,
+    /// File size in bytes.
+    pub size: u64,
+    /// Decoded P2P header, for P2P segments.
+    pub header: Option<SegmentHeader>,
+    /// Why the file could not be copied.
+    pub error: Option<String>,
+}
+
+/// P2P header fields as reported by [`SegmentDiscovery`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentHeader {
+    /// Segment name without namespace prefix, e.g. `"hal_cu"`.
+    pub name: String,
+    /// Whether the header carries the P2P magic.
+    pub valid_magic: bool,
+    /// Whether a writer still held the `.lock` file.
+    pub writer_alive: bool,
+    /// Source module abbreviation.
+    pub source: Option<String>,
+    /// Destination module abbreviation.
+    pub dest: Option<String>,
+    /// Writer heartbeat counter.
+    pub heartbeat: Option<u64>,
+}
+
+impl From<&SegmentInfo> for SegmentHeader {
+    fn from(info: &SegmentInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            valid_magic: info.valid_magic,
+            writer_alive: info.writer_alive,
+            source: info.source.map(|m| abbrev_name(m).to_string()),
+            dest: info.dest.map(|m| abbrev_name(m).to_string()),
+            heartbeat: info.heartbeat,
+        }
+    }
+}
+
+/// Abbreviation as used in segment names (`hal_cu`).
+fn abbrev_name(module: ModuleAbbrev) -> &'static str {
+    match module {
+        ModuleAbbrev::Cu => "cu",
+        ModuleAbbrev::Hal => "hal",
+        ModuleAbbrev::Re => "re",
+        ModuleAbbrev::Mqt => "mqt",
+        ModuleAbbrev::Rpc => "rpc",
+    }
+}
+
+// ─── Bundle Writer ──────────────────────────────────────────────────
+
+/// Writes bundles into one directory and enforces its retention limit.
+#[derive(Debug, Clone)]
+pub struct PostMortem {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl PostMortem {
+    /// Bundles go to `dir`; only the newest `keep` are kept.
+    pub fn new(dir: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            keep,
+        }
+    }
+
+    /// Directory holding the bundles.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Snapshot the files of namespace `ns`, `output` and `config_dir`
+    /// with `report` into a new bundle, then prune old bundles.
+    /// Returns the bundle path.
+    ///
+    /// Files that vanish or cannot be read are listed in the report
+    /// with their error; the bundle is written anyway.
+    ///
+    /// # Errors
+    /// I/O errors creating the bundle or writing the report.
+    pub fn write(
+        &self,
+        ns: &ShmNamespace,
+        config_dir: &Path,
+        mut report: CrashReport,
+        output: &[String],
+    ) -> io::Result<PathBuf> {
+        let now = SystemTime::now();
+        let bundle = self.create_bundle_dir(&format!(
+            "{}_{ns}_{}",
+            utc_timestamp(now, true),
+            report.module
+        ))?;
+
+        let shm = bundle.join("shm");
+        std::fs::create_dir(&shm)?;
+        report.segments = snapshot_segments(ns, &shm);
+
+        let mut log = String::new();
+        for line in output {
+            log.push_str(line);
+            log.push('\n');
+        }
+        std::fs::write(bundle.join("output.log"), log)?;
+
+        // A config copy is best effort: the report matters more.
+        if let Err(e) = copy_dir(config_dir, &bundle.join("config"), &self.dir) {
+            std::fs::write(bundle.join("config.error"), format!("{}: {e}\n", config_dir.display()))?;
+        }
+
+        report.time = utc_timestamp(now, false);
+        let json = serde_json::to_string_pretty(&report).map_err(io::Error::other)?;
+        std::fs::write(bundle.join(REPORT_FILE), json)?;
+
+        self.prune()?;
+        Ok(bundle)
+    }
+
+    /// Bundles in `dir`, oldest first.
+    ///
+    /// # Errors
+    /// I/O errors listing `dir` (a missing `dir` has no bundles).
+    pub fn bundles(&self) -> io::Result<Vec<PathBuf>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut bundles: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.join(REPORT_FILE).is_file())
+            .collect();
+        bundles.sort();
+        Ok(bundles)
+    }
+
+    /// Remove the oldest bundles beyond `keep`. Returns how many were
+    /// removed. Other entries of `dir` are never touched.
+    ///
+    /// # Errors
+    /// I/O errors listing or removing bundles.
+    pub fn prune(&self) -> io::Result<usize> {
+        let bundles = self.bundles()?;
+        let excess = bundles.len().saturating_sub(self.keep);
+        for bundle in &bundles[..excess] {
+            std::fs::remove_dir_all(bundle)?;
+        }
+        Ok(excess)
+    }
+
+    /// Create `dir/<name>`, suffixed `-2`, `-3`, ... if it exists.
+    fn create_bundle_dir(&self, name: &str) -> io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        for n in 1.. {
+            let path = match n {
+                1 => self.dir.join(name),
+                _ => self.dir.join(format!("{name}-{n}")),
+            };
+            match std::fs::create_dir(&path) {
+                Ok(()) => return Ok(path),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("bundle name suffixes exhausted")
+    }
+}
+
+/// Copy every file of namespace `ns` except `.lock` files into `dest`.
+fn snapshot_segments(ns: &ShmNamespace, dest: &Path) -> Vec<SegmentSnapshot> {
+    let infos = SegmentDiscovery::list_segments_in(ns);
+    let prefix = ns.file_prefix();
+    let mut files: Vec<String> = std::fs::read_dir(SHM_DIR)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .filter(|f| f.starts_with(&prefix) && !f.ends_with(".lock"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|file| {
+            let path = Path::new(SHM_DIR).join(&file);
+            let header = infos
+                .iter()
+                .find(|info| info.path == path)
+                .map(SegmentHeader::from);
+            // Read first: the copy must be the bytes at failure time even
+            // if the segment is unlinked meanwhile.
+            let (size, error) = match std::fs::read(&path) {
+                Ok(bytes) => match std::fs::write(dest.join(&file), &bytes) {
+                    Ok(()) => (bytes.len() as u64, None),
+                    Err(e) => (bytes.len() as u64, Some(e.to_string())),
+                },
+                Err(e) => (0, Some(e.to_string())),
+            };
+            SegmentSnapshot {
+                file,
+                size,
+                header,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Recursively copy the files of `src` into `dest`, skipping `exclude`
+/// (the bundle directory, should it live inside the config directory).
+fn copy_dir(src: &Path, dest: &Path, exclude: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dest)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path == exclude {
+            continue;
+        }
+        let target = dest.join(entry.file_name());
+        // Symlinked directories are not followed.
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &target, exclude)?;
+        } else if path.is_file() {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// UTC time as `2026-10-16T03:18:40.123456Z`, or
+/// `20261016T031840.123456Z` when `compact` (for file names).
+pub fn utc_timestamp(time: SystemTime, compact: bool) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs();
+    let (h, m, s) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let us = since.subsec_micros();
+    if compact {
+        format!("{year:04}{month:02}{day:02}T{h:02}{m:02}{s:02}.{us:06}Z")
+    } else {
+        format!("{year:04}-{month:02}-{day:02}T{h:02}:{m:02}:{s:02}.{us:06}Z")
+    }
+}
+
+// ─── Child Output ───────────────────────────────────────────────────
+
+/// Lines queued for the echo of one stream before further lines are
+/// dropped from the echo (they are still buffered).
+const ECHO_BACKLOG: usize = 1024;
+
+/// Recent stdout/stderr lines of a child process.
+///
+/// Lines are tagged `[out]` / `[err]`, with terminal escape sequences
+/// removed. Cloning shares the buffer.
+#[derive(Debug, Clone, Default)]
+pub struct OutputTail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    readers: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl OutputTail {
+    /// Keep the last `capacity` lines of `child`'s piped stdout and
+    /// stderr, echoing them unchanged to this process's stdout and
+    /// stderr. Streams that are not piped are ignored.
+    ///
+    /// The echo runs on its own thread behind a queue of
+    /// [`ECHO_BACKLOG`] lines. While the terminal does not accept output
+    /// (e.g. a stopped pager), further lines are left out of the echo and
+    /// a count of them is echoed once it drains, so the child never
+    /// blocks on its pipe.
+    pub fn capture(child: &mut Child, capacity: usize) -> Self {
+        let tail = Self::default();
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(tail.forward(stdout, "[out] ", io::stdout, capacity));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(tail.forward(stderr, "[err] ", io::stderr, capacity));
+        }
+        *tail.readers.lock().unwrap_or_else(|e| e.into_inner()) = readers;
+        tail
+    }
+
+    /// Buffered lines, oldest first.
+    pub fn lines(&self) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().cloned().collect()
+    }
+
+    /// Wait up to `timeout` for the child's streams to close, so the last
+    /// lines of an exited child are buffered. Returns `false` on timeout
+    /// (child still running, or a descendant holds the streams open).
+    pub fn drain(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let readers = self.readers.lock().unwrap_or_else(|e| e.into_inner());
+            if readers.iter().all(JoinHandle::is_finished) {
+                return true;
+            }
+            drop(readers);
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Spawn a thread buffering the lines of `stream` and handing them to
+    /// an echo thread writing to `echo`.
+    fn forward<R, W>(
+        &self,
+        stream: R,
+        tag: &'static str,
+        echo: fn() -> W,
+        capacity: usize,
+    ) -> JoinHandle<()>
+    where
+        R: Read + Send + 'static,
+        W: Write + 'static,
+    {
+        let lines = Arc::clone(&self.lines);
+        let (tx, rx) = mpsc::sync_channel::<Vec<u8>>(ECHO_BACKLOG);
+        std::thread::spawn(move || {
+            let mut out = echo();
+            for line in rx {
+                let _ = out.write_all(&line).and_then(|()| out.flush());
+            }
+        });
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stream);
+            let mut line = Vec::new();
+            let mut dropped = 0usize;
+            loop {
+                line.clear();
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(_) => return,
+                    Ok(_) => {}
+                }
+                dropped = echo_line(&tx, &line, dropped);
+
+                let text = strip_ansi(&String::from_utf8_lossy(&line));
+                let mut lines = lines.lock().unwrap_or_else(|e| e.into_inner());
+                if lines.len() == capacity {
+                    lines.pop_front();
+                }
+                if capacity > 0 {
+                    lines.push_back(format!("{tag}{}", text.trim_end_matches(['\n', '\r'])));
+                }
+            }
+        })
+    }
+}
+
+/// Queue `line` for the echo without waiting, after a notice of the
+/// `dropped` lines before it. Returns the updated drop count.
+fn echo_line(tx: &SyncSender<Vec<u8>>, line: &[u8], dropped: usize) -> usize {
+    if dropped > 0 {
+        let notice = format!("[evo] {dropped} output lines not echoed (terminal blocked)\n");
+        match tx.try_send(notice.into_bytes()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => return dropped + 1,
+            Err(TrySendError::Disconnected(_)) => return 0,
+        }
+    }
+    match tx.try_send(line.to_vec()) {
+        Err(TrySendError::Full(_)) => 1,
+        Ok(()) | Err(TrySendError::Disconnected(_)) => 0,
+    }
+}
+
+/// `text` without ANSI escape sequences (tracing colours).
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        // CSI: ESC '[' parameters, terminated by a byte in '@'..='~'.
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Terminal that never accepts output.
+    struct Stuck;
+
+    impl Write for Stuck {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            loop {
+                std::thread::park();
+            }
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn blocked_echo_does_not_stall_capture() {
+        let count = ECHO_BACKLOG * 3;
+        let output: String = (0..count).map(|i| format!("line {i}\n")).collect();
+        let tail = OutputTail::default();
+        let reader = tail.forward(io::Cursor::new(output.into_bytes()), "[out] ", || Stuck, 2);
+        *tail.readers.lock().unwrap() = vec![reader];
+
+        assert!(tail.drain(Duration::from_secs(5)), "reader blocked on the echo");
+        let last = count - 1;
+        let expected = [format!("[out] line {}", last - 1), format!("[out] line {last}")];
+        assert_eq!(tail.lines(), expected);
+    }
+
+    #[test]
+    fn dropped_lines_are_announced() {
+        let (tx, rx) = mpsc::sync_channel(1);
+        assert_eq!(echo_line(&tx, b"a\n", 0), 0);
+        assert_eq!(echo_line(&tx, b"b\n", 0), 1);
+        assert_eq!(echo_line(&tx, b"c\n", 1), 2);
+        assert_eq!(rx.recv().unwrap(), b"a\n");
+        assert_eq!(echo_line(&tx, b"d\n", 2), 1, "notice queued, line dropped");
+        let notice = String::from_utf8(rx.recv().unwrap()).unwrap();
+        assert!(notice.contains("2 output lines not echoed"), "{notice}");
+        assert_eq!(echo_line(&tx, b"e\n", 1), 1);
+    }
+}
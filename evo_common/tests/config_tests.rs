@@ -2,9 +2,15 @@
 //!
 //! Tests for `load_config_dir()`: axis file discovery, NN↔id validation,
 //! duplicate detection, missing axes error, unknown fields rejection,
-//! legacy `[[axes]]` rejection, numeric bounds validation (FR-054).
-
-use evo_common::config::{load_config_dir, ConfigError};
+//! legacy `[[axes]]` rejection, numeric bounds validation (FR-054),
+//! `[dynamics]` plant model section, `standstill_velocity`, `[[modules]]`
+//! start order.
+
+use evo_common::config::{
+    load_config_dir, ConfigError, ModuleConfig, RestartPolicy, DEFAULT_POSTMORTEM_DIR,
+};
+use evo_common::control_unit::config::CuAxisConfig;
+use evo_common::hal::config::{AxisConfig, AxisType, DriveLoop};
 use std::fs;
 use std::path::Path;
 use tempfile::TempDir;
@@ -408,6 +414,46 @@ max_restarts = 0
         matches!(result, Err(ConfigError::ValidationError(_))),
         "expected ValidationError for watchdog bounds"
     );
+
+    // hang_timeout_s: 0 disables, otherwise 0.1..=60.0.
+    for (hang, ok) in [(0.0, true), (0.5, true), (0.05, false), (61.0, false)] {
+        fs::write(
+            dir.join("config.toml"),
+            format!("[watchdog]\nhang_timeout_s = {hang:?}\n"),
+        )
+        .unwrap();
+        assert_eq!(load_config_dir(dir).is_ok(), ok, "hang_timeout_s = {hang}");
+    }
+
+    // restart_window_s: 1..=86400.
+    for (window, ok) in [(1, true), (86_400, true), (0, false), (86_401, false)] {
+        fs::write(
+            dir.join("config.toml"),
+            format!("[watchdog]\nrestart_window_s = {window}\n"),
+        )
+        .unwrap();
+        assert_eq!(load_config_dir(dir).is_ok(), ok, "restart_window_s = {window}");
+    }
+
+    // postmortem_keep: 0 disables, at most 1000; a directory is needed.
+    for (pm, ok) in [
+        ("postmortem_keep = 0", true),
+        ("postmortem_keep = 1000", true),
+        ("postmortem_keep = 1001", false),
+        ("postmortem_dir = \"\"", false),
+        ("postmortem_dir = \"\"\npostmortem_keep = 0", true),
+    ] {
+        fs::write(dir.join("config.toml"), format!("[watchdog]\n{pm}\n")).unwrap();
+        assert_eq!(load_config_dir(dir).is_ok(), ok, "{pm}");
+    }
+
+    // Unknown restart policy.
+    fs::write(
+        dir.join("config.toml"),
+        "[[modules]]\nname = \"hal\"\nbinary = \"evo_hal\"\nrestart = \"sometimes\"\n",
+    )
+    .unwrap();
+    assert!(load_config_dir(dir).is_err());
 }
 
 /// Test: load actual config/ directory with 8 axes.
@@ -428,6 +474,13 @@ fn load_actual_config_directory() {
     assert_eq!(full.axes.len(), 8, "should have 8 axes");
     assert_eq!(full.axes[0].axis.name, "X-Axis");
     assert_eq!(full.axes[7].axis.name, "Tailstock");
+    let order = full.system.start_order().expect("valid module list");
+    let names: Vec<&str> = order.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["hal", "cu"], "liaisons disabled by default");
+    assert_eq!(order[1].restart_with, ["hal"], "RT pair restarts together");
+    assert_eq!(order[1].ready_segment.as_deref(), Some("cu_hal"));
+    let mqtt = full.system.modules.iter().find(|m| m.name == "mqtt").unwrap();
+    assert_eq!(mqtt.restart, RestartPolicy::OnFailure);
     assert_eq!(full.machine.machine.name, "Test 8-Axis CNC");
 }
 
@@ -477,3 +530,263 @@ timeout = 30.0
         "expected ValidationError for min_pos >= max_pos"
     );
 }
+
+/// Test: `standstill_velocity` is optional, mapped to the CU and bounded.
+#[test]
+fn standstill_velocity_default_and_bounds() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+
+    write_config_toml(dir);
+    write_machine_toml(dir);
+    write_axis_toml(dir, 1, "x");
+    write_axis_toml(dir, 2, "y");
+    let path = dir.join("axis_02_y.toml");
+    let content = fs::read_to_string(&path).unwrap().replace(
+        "in_position_window = 0.05\n",
+        "in_position_window = 0.05\nstandstill_velocity = 0.5\n",
+    );
+    fs::write(&path, &content).unwrap();
+
+    let full = load_config_dir(dir).expect("should load");
+    assert_eq!(full.axes[0].kinematics.standstill_velocity, None);
+    assert_eq!(CuAxisConfig::from_new_axis_config(&full.axes[0]).standstill_velocity, 0.1);
+    assert_eq!(CuAxisConfig::from_new_axis_config(&full.axes[1]).standstill_velocity, 0.5);
+
+    fs::write(&path, content.replace("standstill_velocity = 0.5", "standstill_velocity = 0.0"))
+        .unwrap();
+    assert!(
+        matches!(load_config_dir(dir), Err(ConfigError::ValidationError(_))),
+        "expected ValidationError for standstill_velocity = 0"
+    );
+}
+
+/// Test: `[dynamics]` section parsed and mapped to a dynamic HAL axis.
+#[test]
+fn dynamics_section_maps_to_dynamic_axis() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+
+    write_config_toml(dir);
+    write_machine_toml(dir);
+    write_axis_toml(dir, 1, "x");
+    write_axis_toml(dir, 2, "y");
+    let path = dir.join("axis_02_y.toml");
+    let mut content = fs::read_to_string(&path).unwrap();
+    content.push_str(
+        "\n[dynamics]\ninertia = 0.02\ncoulomb_friction = 0.5\nencoder_resolution = 1000.0\n\
+         drive_loop = \"drive\"\n",
+    );
+    fs::write(&path, content).unwrap();
+
+    let full = load_config_dir(dir).expect("should load");
+    assert!(full.axes[0].dynamics.is_none());
+
+    let x = AxisConfig::from_new_axis_config(&full.axes[0]);
+    assert_eq!(x.axis_type, AxisType::Positioning);
+    assert!(x.dynamics.is_none());
+    assert_eq!(x.soft_limit_negative, Some(-100.0));
+    assert_eq!(x.lag_error_limit, Some(0.5));
+    assert!(x.validate(0, &[]).is_ok());
+
+    let y = AxisConfig::from_new_axis_config(&full.axes[1]);
+    let dynamics = y.dynamics.as_ref().expect("dynamic axis");
+    assert_eq!(dynamics.inertia, 0.02);
+    assert_eq!(dynamics.coulomb_friction, 0.5);
+    assert_eq!(dynamics.drive_loop, DriveLoop::Drive);
+    assert_eq!(y.encoder_resolution, Some(1000.0));
+    assert!(y.validate(1, &[x]).is_ok());
+}
+
+/// Test: unknown keys in `[dynamics]` are rejected.
+#[test]
+fn dynamics_unknown_field_rejected() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+
+    write_config_toml(dir);
+    write_machine_toml(dir);
+    write_axis_toml(dir, 1, "x");
+    let path = dir.join("axis_01_x.toml");
+    let mut content = fs::read_to_string(&path).unwrap();
+    content.push_str("\n[dynamics]\ninertia = 0.02\nmass = 3.0\n");
+    fs::write(&path, content).unwrap();
+
+    assert!(load_config_dir(dir).is_err());
+}
+
+/// Test: top-level `instance` selects the SHM namespace; invalid ids are rejected.
+#[test]
+fn instance_selects_shm_namespace() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+
+    write_config_toml(dir);
+    write_machine_toml(dir);
+    write_axis_toml(dir, 1, "x");
+    let full = load_config_dir(dir).expect("should load");
+    assert_eq!(full.system.instance, None);
+    assert_eq!(full.system.shm_namespace().unwrap().file_prefix(), "evo_");
+
+    let path = dir.join("config.toml");
+    let content = fs::read_to_string(&path).unwrap();
+    fs::write(&path, format!("instance = \"line-2\"\n{content}")).unwrap();
+    let full = load_config_dir(dir).expect("should load");
+    assert_eq!(full.system.shm_namespace().unwrap().file_prefix(), "evo-line-2_");
+
+    fs::write(&path, format!("instance = \"line_2\"\n{content}")).unwrap();
+    assert!(matches!(load_config_dir(dir), Err(ConfigError::ValidationError(_))));
+}
+
+/// Test: without `[[modules]]` the supervisor starts HAL, then CU.
+#[test]
+fn default_modules_hal_then_cu() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write_config_toml(dir);
+    write_machine_toml(dir);
+    write_axis_toml(dir, 1, "x");
+
+    let full = load_config_dir(dir).expect("should load");
+    assert_eq!(full.system.watchdog.hang_timeout_s, 2.0);
+    let order = full.system.start_order().unwrap();
+    let names: Vec<&str> = order.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["hal", "cu"]);
+    assert_eq!(order[0].ready_segment.as_deref(), Some("hal_cu"));
+    assert_eq!(order[1].ready_segment.as_deref(), Some("cu_hal"));
+    assert_eq!(
+        order[1].resolved_args(Path::new("/etc/evo")),
+        ["--config-dir", "/etc/evo"]
+    );
+    // RT pair restarts together.
+    assert_eq!(order[0].restart, RestartPolicy::RestartDependents);
+    assert_eq!(order[1].restart_with, ["hal"]);
+    assert_eq!(full.system.watchdog.restart_window_s, 60);
+    assert_eq!(
+        full.system.watchdog.postmortem_dir,
+        Path::new(DEFAULT_POSTMORTEM_DIR)
+    );
+    assert_eq!(full.system.watchdog.postmortem_keep, 20);
+}
+
+/// Test: `[[modules]]` start order follows dependencies, skips disabled modules.
+#[test]
+fn modules_start_in_dependency_order() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write_config_toml(dir);
+    write_machine_toml(dir);
+    write_axis_toml(dir, 1, "x");
+    let path = dir.join("config.toml");
+    let base = fs::read_to_string(&path).unwrap();
+    fs::write(
+        &path,
+        format!(
+            r#"{base}
+[[modules]]
+name = "grpc"
+binary = "evo_grpc"
+depends_on = ["cu", "re"]
+forward_instance = false
+
+[[modules]]
+name = "re"
+binary = "evo_recipe_executor"
+depends_on = ["cu"]
+ready_segment = "re_cu"
+ready_timeout_s = 2.0
+
+[[modules]]
+name = "cu"
+binary = "evo_control_unit"
+args = ["--config-dir", "{{config_dir}}"]
+depends_on = ["hal"]
+
+[[modules]]
+name = "hal"
+binary = "evo_hal"
+ready_segment = "hal_cu"
+
+[[modules]]
+name = "mqtt"
+binary = "evo_mqtt"
+enabled = false
+restart = "on-failure"
+max_restarts = 0
+restart_window_s = 600
+fatal_on_exhaustion = true
+"#
+        ),
+    )
+    .unwrap();
+
+    let full = load_config_dir(dir).expect("should load");
+    let order = full.system.start_order().unwrap();
+    let names: Vec<&str> = order.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["hal", "cu", "re", "grpc"]);
+    assert!(!order[3].forward_instance);
+    assert_eq!(order[2].ready_timeout_s, Some(2.0));
+    assert_eq!(order[2].restart, RestartPolicy::RestartDependents);
+
+    let mqtt = &full.system.modules[4];
+    assert_eq!(mqtt.restart, RestartPolicy::OnFailure);
+    assert_eq!(mqtt.max_restarts, Some(0));
+    assert_eq!(mqtt.restart_window_s, Some(600));
+    assert!(mqtt.exhaustion_is_fatal());
+    assert!(order[0].exhaustion_is_fatal(), "hal defaults to fatal");
+    assert!(!order[2].exhaustion_is_fatal(), "liaisons default to non-fatal");
+}
+
+/// Test: invalid module lists are rejected at load time.
+#[test]
+fn invalid_modules_rejected() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path();
+    write_config_toml(dir);
+    write_machine_toml(dir);
+    write_axis_toml(dir, 1, "x");
+    let full = load_config_dir(dir).expect("should load");
+
+    type Edit = fn(&mut Vec<ModuleConfig>);
+    let cases: [(&str, Edit); 10] = [
+        ("duplicate", |m| m[1].name = "hal".into()),
+        ("unknown dependency", |m| m[1].depends_on = vec!["plc".into()]),
+        ("disabled dependency", |m| m[0].enabled = false),
+        ("cycle", |m| m[0].depends_on = vec!["cu".into()]),
+        ("empty binary", |m| m[1].binary.clear()),
+        ("hang timeout", |m| m[1].hang_timeout_s = Some(0.05)),
+        ("unknown restart_with", |m| m[1].restart_with = vec!["plc".into()]),
+        ("disabled restart_with", |m| {
+            m.push(ModuleConfig::new("mqtt", "evo_mqtt"));
+            m[2].enabled = false;
+            m[1].restart_with = vec!["mqtt".into()];
+        }),
+        ("max restarts", |m| m[1].max_restarts = Some(101)),
+        ("restart window", |m| m[1].restart_window_s = Some(0)),
+    ];
+    for (what, edit) in cases {
+        let mut system = full.system.clone();
+        edit(&mut system.modules);
+        assert!(
+            matches!(system.start_order(), Err(ConfigError::ValidationError(_))),
+            "{what} should be rejected"
+        );
+    }
+
+    // A disabled module may depend on anything known.
+    let mut system = full.system.clone();
+    system.modules[0].enabled = false;
+    system.modules[1].enabled = false;
+    system.modules.push(ModuleConfig::new("re", "evo_recipe_executor"));
+    assert_eq!(system.start_order().unwrap().len(), 1);
+
+    // Unknown keys in [[modules]] are rejected.
+    let path = dir.join("config.toml");
+    let base = fs::read_to_string(&path).unwrap();
+    fs::write(
+        &path,
+        format!("{base}\n[[modules]]\nname = \"hal\"\nbinary = \"evo_hal\"\nbogus = 1\n"),
+    )
+    .unwrap();
+    assert!(matches!(load_config_dir(dir), Err(ConfigError::UnknownField(_))));
+}
@@ -0,0 +1,171 @@
+//! Supervisor control socket tests — `watchdog::control`: wire format,
+//! request/reply round trip, malformed requests and socket ownership.
+
+use evo_common::config::RestartPolicy;
+use evo_common::shm::p2p::ShmNamespace;
+use evo_common::watchdog::HealthStatus;
+use evo_common::watchdog::control::{
+    self, ControlSocket, CtlError, CtlRequest, CtlResponse, ModuleState, ModuleStatus,
+    SupervisorStatus,
+};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn test_socket(tag: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("evo_ctl_test_{tag}_{}.sock", std::process::id()))
+}
+
+/// Serve exactly one request on `socket` from a background thread.
+fn serve_one(
+    socket: ControlSocket,
+    answer: impl FnOnce(CtlRequest) -> CtlResponse + Send + 'static,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for _ in 0..500 {
+            if let Some((request, conn)) = socket.accept(TIMEOUT) {
+                conn.reply(&answer(request));
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("no request received");
+    })
+}
+
+/// Test: socket path follows the SHM namespace prefix.
+#[test]
+fn socket_path_per_namespace() {
+    let default = control::socket_path(&ShmNamespace::default());
+    let b = control::socket_path(&ShmNamespace::new("b").expect("ns"));
+    assert!(default.ends_with("evo_ctl.sock"));
+    assert!(b.ends_with("evo-b_ctl.sock"));
+    assert!(!default.starts_with("/dev/shm"));
+}
+
+/// Test: JSON wire format of requests and responses.
+#[test]
+fn wire_format() {
+    let req = CtlRequest::Restart {
+        module: "cu".to_string(),
+    };
+    assert_eq!(
+        serde_json::to_string(&req).expect("encode"),
+        r#"{"cmd":"restart","module":"cu"}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<CtlRequest>(r#"{"cmd":"status"}"#).expect("decode"),
+        CtlRequest::Status
+    );
+
+    let status = CtlResponse::Status(SupervisorStatus {
+        namespace: "default".to_string(),
+        pid: 1,
+        uptime_s: 2.0,
+        fatal: None,
+        modules: vec![ModuleStatus {
+            name: "hal".to_string(),
+            binary: "evo_hal".to_string(),
+            state: ModuleState::Running,
+            pid: Some(42),
+            uptime_s: Some(1.5),
+            restarts: 3,
+            restart: RestartPolicy::OnFailure,
+            health: HealthStatus::Stale { age_secs: 4 },
+            last_failure: Some("exited with status 1".to_string()),
+        }],
+    });
+    let value: serde_json::Value = serde_json::from_str(&status.to_json()).expect("json");
+    assert_eq!(value["result"], "status");
+    assert_eq!(value["modules"][0]["state"], "running");
+    assert_eq!(value["modules"][0]["restarts"], 3);
+    assert_eq!(value["modules"][0]["restart"], "on-failure");
+    assert_eq!(value["modules"][0]["health"]["status"], "stale");
+    assert_eq!(value["modules"][0]["health"]["age_secs"], 4);
+    assert_eq!(
+        serde_json::from_str::<CtlResponse>(&status.to_json_pretty()).expect("decode"),
+        status
+    );
+}
+
+/// Test: client request reaches the server and its reply comes back.
+#[test]
+fn request_reply_round_trip() {
+    let path = test_socket("rt");
+    let socket = ControlSocket::bind(&path).expect("bind");
+    assert!(
+        socket.accept(TIMEOUT).is_none(),
+        "idle socket must not block"
+    );
+
+    let server = serve_one(socket, |request| match request {
+        CtlRequest::Stop { module } => CtlResponse::Ok {
+            message: format!("stopped {module}"),
+        },
+        other => CtlResponse::Error {
+            message: format!("unexpected {other:?}"),
+        },
+    });
+    let response = control::send(
+        &path,
+        &CtlRequest::Stop {
+            module: "cu".to_string(),
+        },
+        TIMEOUT,
+    )
+    .expect("send");
+    assert_eq!(
+        response,
+        CtlResponse::Ok {
+            message: "stopped cu".to_string()
+        }
+    );
+    server.join().expect("server");
+    assert!(!path.exists(), "socket file removed on drop");
+}
+
+/// Test: malformed request answered with an error, not delivered.
+#[test]
+fn malformed_request_rejected() {
+    let path = test_socket("bad");
+    let socket = ControlSocket::bind(&path).expect("bind");
+
+    let mut stream = UnixStream::connect(&path).expect("connect");
+    stream.write_all(b"{\"cmd\":\"explode\"}\n").expect("write");
+    assert!(socket.accept(TIMEOUT).is_none());
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).expect("read");
+    match serde_json::from_str::<CtlResponse>(&reply).expect("decode") {
+        CtlResponse::Error { message } => assert!(message.contains("bad request")),
+        other => panic!("expected Error, got {other:?}"),
+    }
+}
+
+/// Test: live socket cannot be taken over; stale socket file is replaced.
+#[test]
+fn bind_ownership() {
+    let path = test_socket("own");
+    let socket = ControlSocket::bind(&path).expect("bind");
+    assert!(matches!(
+        ControlSocket::bind(&path),
+        Err(CtlError::InUse(_))
+    ));
+    drop(socket);
+
+    // Stale file from a crashed supervisor: listener gone, file left.
+    let stale = std::os::unix::net::UnixListener::bind(&path).expect("stale bind");
+    drop(stale);
+    assert!(path.exists());
+    let socket = ControlSocket::bind(&path).expect("rebind over stale file");
+    assert_eq!(socket.path(), path);
+
+    drop(socket);
+    assert!(matches!(
+        control::send(&path, &CtlRequest::Status, TIMEOUT),
+        Err(CtlError::Connect { .. })
+    ));
+}
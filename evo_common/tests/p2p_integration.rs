@@ -5,9 +5,10 @@
 //! - Reader attaches from the parent process
 //! - Verifies data consistency across process boundary
 //! - Verifies cleanup after writer exit
+//! - Verifies a reader killed inside `read_wait()` stops costing wake-ups
 
 use evo_common::shm::p2p::{
-    ModuleAbbrev, P2pSegmentHeader, SegmentDiscovery, TypedP2pReader, TypedP2pWriter,
+    ModuleAbbrev, P2pSegmentHeader, SegmentDiscovery, TypedP2pReader, TypedP2pWriter, WAIT_LEASE,
 };
 use std::time::{Duration, Instant};
 
@@ -20,6 +21,7 @@ struct IntegSeg {
     cycle: u64,
     _pad: [u8; 48],
 }
+evo_common::impl_shm_layout!(IntegSeg { header, value, cycle, _pad });
 
 /// Wait until a file appears in /dev/shm or timeout.
 fn wait_for_shm(name: &str, timeout: Duration) -> bool {
@@ -207,6 +209,7 @@ fn cross_process_version_mismatch() {
             header: P2pSegmentHeader,
             data: [u64; 32], // 256 bytes, vs IntegSeg's 128
         }
+        evo_common::impl_shm_layout!(WrongSeg { header, data });
 
         match TypedP2pReader::<WrongSeg>::attach(&name, 10) {
             Ok(mut reader) => {
@@ -237,3 +240,58 @@ fn cross_process_version_mismatch() {
         "child should have exited successfully (version mismatch detected)"
     );
 }
+
+/// Read the `wait_until_ms` header word (bytes 36..40) straight from `/dev/shm`.
+fn wait_lease(name: &str) -> u32 {
+    let bytes = std::fs::read(format!("/dev/shm/evo_{name}")).expect("read segment file");
+    u32::from_ne_bytes(bytes[36..40].try_into().unwrap())
+}
+
+/// Test: a reader killed while blocked in `read_wait()` leaves only a
+/// short wait lease behind.
+///
+/// 1. Child blocks in `read_wait()` and is killed with SIGKILL.
+/// 2. Its lease runs out within `WAIT_LEASE`; the next commit clears it,
+///    so later commits skip `FUTEX_WAKE` again.
+#[test]
+fn cross_process_dead_waiter_expires() {
+    let name = format!("integ_dead_{}", std::process::id());
+
+    let mut writer =
+        TypedP2pWriter::<IntegSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("parent: create writer");
+    let payload: IntegSeg = unsafe { core::mem::zeroed() };
+    writer.commit(&payload).expect("parent: commit");
+
+    let pid = unsafe { libc::fork() };
+
+    if pid == 0 {
+        // ── CHILD PROCESS (blocked reader) ──
+        let mut reader = TypedP2pReader::<IntegSeg>::attach(&name, 10).expect("child: attach");
+        reader.read().expect("child: read");
+        let _ = reader.read_wait(Duration::from_secs(60));
+        std::process::exit(1);
+    }
+
+    // ── PARENT PROCESS ──
+    assert!(pid > 0, "fork failed");
+
+    let start = Instant::now();
+    while wait_lease(&name) == 0 {
+        assert!(start.elapsed() < Duration::from_secs(5), "child never blocked");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let mut status: libc::c_int = 0;
+    unsafe {
+        libc::kill(pid, libc::SIGKILL);
+        libc::waitpid(pid, &mut status, 0);
+    }
+    assert!(libc::WIFSIGNALED(status), "child killed inside read_wait()");
+    assert_ne!(wait_lease(&name), 0, "dead reader's lease still set");
+
+    std::thread::sleep(WAIT_LEASE + Duration::from_millis(5));
+    writer.commit(&payload).expect("parent: commit");
+    assert_eq!(wait_lease(&name), 0, "expired lease cleared");
+    writer.commit(&payload).expect("parent: commit");
+    assert_eq!(wait_lease(&name), 0);
+}
@@ -1,10 +1,16 @@
 //! Extended P2P unit tests — covers `has_changed`, `reset_stale`, heartbeat
-//! increment on commit, and edge cases that complement the inline `mod tests`
+//! increment on commit, blocking `read_wait`, in-place `commit_with` /
+//! `read_with`, commit timestamps and time-based staleness, published
+//! schemas, per-instance SHM namespaces, and edge cases that complement the inline `mod tests`
 //! block in `evo_common::shm::p2p`.
 
 use evo_common::shm::p2p::{
-    ModuleAbbrev, P2pSegmentHeader, ShmError, TypedP2pReader, TypedP2pWriter,
+    ModuleAbbrev, P2pSegmentHeader, SegmentDiscovery, ShmError, ShmNamespace, TypedP2pReader,
+    TypedP2pWriter, WAIT_LEASE, monotonic_ns,
 };
+use evo_common::shm::schema::SegmentSchema;
+use evo_common::shm::segments::{HalToCuSegment, SEG_HAL_CU};
+use std::time::{Duration, Instant};
 
 /// Helper segment for testing — 128 bytes, cache-line aligned.
 #[derive(Debug, Clone, Copy)]
@@ -14,6 +20,7 @@ struct TestSeg {
     value: u64,
     _pad: [u8; 56],
 }
+evo_common::impl_shm_layout!(TestSeg { header, value, _pad });
 
 /// Test: `has_changed()` returns true after new write, false when unchanged.
 #[test]
@@ -141,6 +148,7 @@ fn large_payload_roundtrip() {
         header: P2pSegmentHeader,
         data: [u64; 512], // 4096 bytes of data
     }
+    evo_common::impl_shm_layout!(BigSeg { header, data });
 
     let mut writer =
         TypedP2pWriter::<BigSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
@@ -190,3 +198,399 @@ fn rapid_overwrite() {
     let data = reader.read().expect("read");
     assert_eq!(data.value, 99, "reader should see the last committed value");
 }
+
+/// Read the `wait_until_ms` header word (bytes 36..40) straight from `/dev/shm`.
+fn wait_lease(name: &str) -> u32 {
+    let bytes = std::fs::read(format!("/dev/shm/evo_{name}")).expect("read segment file");
+    u32::from_ne_bytes(bytes[36..40].try_into().unwrap())
+}
+
+/// Test: `read_wait()` returns at once when data is already pending and
+/// times out with `None` when the writer is silent.
+#[test]
+fn read_wait_pending_and_timeout() {
+    let name = format!("test_rw_to_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("create");
+    let mut reader = TypedP2pReader::<TestSeg>::attach(&name, 10).expect("attach");
+
+    let mut payload: TestSeg = unsafe { core::mem::zeroed() };
+    payload.value = 7;
+    writer.commit(&payload).expect("commit");
+
+    let data = reader.read_wait(Duration::from_secs(5)).expect("read_wait");
+    assert_eq!(data.map(|d| d.value), Some(7), "pending commit returned at once");
+
+    let start = Instant::now();
+    let data = reader.read_wait(Duration::from_millis(50)).expect("read_wait");
+    assert!(data.is_none(), "no commit → timeout");
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(reader.stale_count(), 0, "timeout does not count as stale read");
+    assert_ne!(wait_lease(&name), 0, "lease taken while waiting");
+
+    // The writer drops the lease once it has expired.
+    std::thread::sleep(WAIT_LEASE + Duration::from_millis(5));
+    writer.commit(&payload).expect("commit");
+    assert_eq!(wait_lease(&name), 0, "expired lease cleared by commit");
+}
+
+/// Test: a blocked `read_wait()` is woken by the writer's next `commit()`,
+/// also after waits longer than one lease slice.
+#[test]
+fn read_wait_woken_by_commit() {
+    let name = format!("test_rw_wake_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("create");
+    let mut reader = TypedP2pReader::<TestSeg>::attach(&name, 10).expect("attach");
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let waiter = std::thread::spawn(move || {
+        for _ in 0..3 {
+            let data = reader.read_wait(Duration::from_secs(10)).expect("read_wait");
+            tx.send(data.map(|d| d.value)).unwrap();
+        }
+    });
+
+    let start = Instant::now();
+    for i in 1..=3u64 {
+        // Wait until the reader is blocked, then commit.
+        // Let the reader renew its lease at least once before the commit.
+        while wait_lease(&name) == 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        std::thread::sleep(WAIT_LEASE * 2);
+        let mut payload: TestSeg = unsafe { core::mem::zeroed() };
+        payload.value = i;
+        writer.commit(&payload).expect("commit");
+        assert_eq!(rx.recv().unwrap(), Some(i), "woken with the committed value");
+    }
+
+    waiter.join().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(5), "woken by futex, not timeout");
+}
+
+/// Test: `commit_with()` edits the mapped payload in place — untouched
+/// fields keep their last committed value — and bumps the heartbeat.
+#[test]
+fn commit_with_updates_in_place() {
+    let name = format!("test_cw_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<HalToCuSegment>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+            .expect("create");
+    let mut reader = TypedP2pReader::<HalToCuSegment>::attach(&name, 10).expect("attach");
+
+    writer.commit_with(|seg| {
+        assert_eq!(seg.axis_count, 0, "fresh segment is zeroed");
+        seg.axis_count = 2;
+        seg.axes[0].position = 1.5;
+    });
+    assert_eq!(writer.heartbeat(), 1);
+
+    writer.commit_with(|seg| seg.axes[1].position = -3.0);
+    assert_eq!(writer.heartbeat(), 2);
+
+    let data = reader.read().expect("read");
+    assert_eq!(data.axis_count, 2, "previous value kept");
+    assert_eq!(data.axes[0].position, 1.5, "previous value kept");
+    assert_eq!(data.axes[1].position, -3.0);
+
+    // Mixing with copying commits stays consistent.
+    let mut payload = *data;
+    payload.axes[0].position = 10.0;
+    writer.commit(&payload).expect("commit");
+    writer.commit_with(|seg| seg.axes[0].position += 1.0);
+    assert_eq!(writer.heartbeat(), 4);
+    assert_eq!(reader.read().expect("read").axes[0].position, 11.0);
+}
+
+/// Test: `read_with()` sees the same data as `read()` and shares its
+/// staleness tracking.
+#[test]
+fn read_with_matches_read_and_tracks_stale() {
+    let name = format!("test_rdw_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("create");
+    let mut reader = TypedP2pReader::<TestSeg>::attach(&name, 3).expect("attach");
+
+    writer.commit_with(|seg| seg.value = 42);
+    assert_eq!(reader.read_with(|seg| seg.value).expect("read_with"), 42);
+    assert!(!reader.has_changed());
+
+    // No new commits: read() and read_with() count towards the same threshold.
+    assert_eq!(reader.read().expect("read").value, 42);
+    assert_eq!(reader.stale_count(), 1);
+    assert!(reader.read_with(|seg| seg.value).is_ok());
+    assert_eq!(reader.stale_count(), 2);
+    assert!(matches!(
+        reader.read_with(|seg| seg.value),
+        Err(ShmError::HeartbeatStale { missed_beats: 3, .. })
+    ));
+
+    writer.commit_with(|seg| seg.value += 1);
+    assert_eq!(reader.read_with(|seg| seg.value).expect("fresh"), 43);
+    assert_eq!(reader.stale_count(), 0);
+}
+
+/// Test: `read_with()` rejects a layout mismatch before running the closure.
+#[test]
+fn read_with_version_mismatch() {
+    #[derive(Debug, Clone, Copy)]
+    #[repr(C, align(64))]
+    struct OtherSeg {
+        header: P2pSegmentHeader,
+        other: u64,
+        _pad: [u8; 56],
+    }
+    evo_common::impl_shm_layout!(OtherSeg { header, other, _pad });
+
+    let name = format!("test_rdw_ver_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("create");
+    writer.commit_with(|seg| seg.value = 1);
+
+    let mut reader = TypedP2pReader::<OtherSeg>::attach(&name, 10).expect("attach");
+    let result = reader.read_with(|_| panic!("closure must not run on mismatch"));
+    assert!(matches!(result, Err(ShmError::VersionMismatch { .. })));
+}
+
+/// Test: a blocked `read_wait()` is woken by `commit_with()`.
+#[test]
+fn read_wait_woken_by_commit_with() {
+    let name = format!("test_rw_cw_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("create");
+    let mut reader = TypedP2pReader::<TestSeg>::attach(&name, 10).expect("attach");
+
+    let waiter = std::thread::spawn(move || {
+        let data = reader.read_wait(Duration::from_secs(10)).expect("read_wait");
+        data.map(|d| d.value)
+    });
+
+    while wait_lease(&name) == 0 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let start = Instant::now();
+    writer.commit_with(|seg| seg.value = 5);
+    assert_eq!(waiter.join().unwrap(), Some(5));
+    assert!(start.elapsed() < Duration::from_secs(5), "woken by futex, not timeout");
+}
+
+/// Test: concurrent `read_with()` never returns a torn payload while the
+/// writer updates in place.
+#[test]
+fn read_with_consistent_under_concurrent_commit_with() {
+    let name = format!("test_rdw_conc_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<HalToCuSegment>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+            .expect("create");
+    let mut reader = TypedP2pReader::<HalToCuSegment>::attach(&name, u32::MAX).expect("attach");
+
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let stop_w = stop.clone();
+    let writer_thread = std::thread::spawn(move || {
+        let mut i = 0.0;
+        while !stop_w.load(std::sync::atomic::Ordering::Relaxed) {
+            i += 1.0;
+            // Every axis carries the same value within one commit.
+            writer.commit_with(|seg| seg.axes.iter_mut().for_each(|a| a.position = i));
+        }
+    });
+
+    for _ in 0..2000 {
+        match reader.read_with(|seg| (seg.axes[0].position, seg.axes[seg.axes.len() - 1].position)) {
+            Ok((first, last)) => assert_eq!(first, last, "torn snapshot returned"),
+            Err(ShmError::ReadContention { .. }) => {}
+            Err(e) => panic!("unexpected error: {e}"),
+        }
+    }
+
+    stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    writer_thread.join().unwrap();
+}
+
+/// Read the `commit_ns` header word (bytes 40..48) straight from `/dev/shm`.
+fn commit_ns(name: &str) -> u64 {
+    let bytes = std::fs::read(format!("/dev/shm/evo_{name}")).expect("read segment file");
+    u64::from_ne_bytes(bytes[40..48].try_into().unwrap())
+}
+
+/// Test: `commit()` and `commit_with()` stamp the header with the
+/// monotonic commit time; readers report the writer→reader age.
+#[test]
+fn commit_timestamp_and_read_age() {
+    let name = format!("test_ts_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("create");
+    let mut reader = TypedP2pReader::<TestSeg>::attach(&name, 10).expect("attach");
+    assert_eq!(commit_ns(&name), 0, "never committed");
+    assert_eq!(reader.age(), None);
+
+    let before = monotonic_ns();
+    writer.commit(&unsafe { core::mem::zeroed() }).expect("commit");
+    let stamped = commit_ns(&name);
+    assert!(stamped >= before && stamped <= monotonic_ns());
+
+    std::thread::sleep(Duration::from_millis(20));
+    reader.read().expect("read");
+    let age = reader.age().expect("age after read");
+    assert!(age >= Duration::from_millis(20), "age {age:?}");
+    assert!(age < Duration::from_secs(5), "age {age:?}");
+
+    writer.commit_with(|seg| seg.value = 1);
+    assert!(commit_ns(&name) > stamped, "commit_with stamps too");
+    reader.read_with(|seg| seg.value).expect("read_with");
+    assert!(reader.age().unwrap() < age, "fresh commit is younger");
+}
+
+/// Test: with `with_max_age()`, staleness depends on the commit age, not
+/// on how often the reader polls.
+#[test]
+fn max_age_staleness() {
+    let name = format!("test_max_age_{}", std::process::id());
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Cu, ModuleAbbrev::Hal)
+            .expect("create");
+    let mut reader = TypedP2pReader::<TestSeg>::attach(&name, 1)
+        .expect("attach")
+        .with_max_age(Duration::from_millis(200));
+    assert_eq!(reader.max_age(), Some(Duration::from_millis(200)));
+
+    writer.commit_with(|seg| seg.value = 1);
+    // Fast polling within max_age is not stale, despite stale_threshold = 1.
+    for _ in 0..100 {
+        assert!(reader.read().is_ok());
+    }
+    assert!(reader.stale_count() >= 99, "reads are still counted");
+
+    std::thread::sleep(Duration::from_millis(250));
+    match reader.read_with(|seg| seg.value) {
+        Err(ShmError::CommitTooOld { age, max_age, .. }) => {
+            assert!(age > max_age);
+            assert_eq!(max_age, Duration::from_millis(200));
+        }
+        other => panic!("expected CommitTooOld, got {other:?}"),
+    }
+
+    writer.commit_with(|seg| seg.value = 2);
+    assert_eq!(reader.read().expect("fresh commit").value, 2);
+}
+
+/// Test: `publish_schema()` writes the schema next to the segment, hidden
+/// from discovery and removed with the writer.
+#[test]
+fn publish_schema_alongside_segment() {
+    let name = format!("test_schema_{}", std::process::id());
+    let path = format!("/dev/shm/evo_{name}.schema.json");
+    let mut writer =
+        TypedP2pWriter::<HalToCuSegment>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+            .expect("create");
+    writer.publish_schema().expect("publish");
+
+    let schema = SegmentSchema::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let mut expected = SegmentSchema::of::<HalToCuSegment>(SEG_HAL_CU);
+    expected.segment = name.clone();
+    expected.shm_name = format!("/evo_{name}");
+    assert_eq!(schema, expected);
+
+    let listed: Vec<_> = SegmentDiscovery::list_segments()
+        .into_iter()
+        .filter(|s| s.name.starts_with(&name))
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(listed, std::slice::from_ref(&name));
+
+    drop(writer);
+    assert!(!std::path::Path::new(&path).exists());
+}
+
+/// Test: two instances create the same segment name without colliding;
+/// each reader sees its own instance's data.
+#[test]
+fn instances_coexist() {
+    let name = format!("test_ns_{}", std::process::id());
+    let ns_a = ShmNamespace::new(&format!("a{}", std::process::id())).unwrap();
+    let ns_b = ShmNamespace::new(&format!("b{}", std::process::id())).unwrap();
+
+    let mut wa = TypedP2pWriter::<TestSeg>::create_in(&ns_a, &name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+        .expect("create a");
+    let mut wb = TypedP2pWriter::<TestSeg>::create_in(&ns_b, &name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+        .expect("create b");
+    assert!(matches!(
+        TypedP2pWriter::<TestSeg>::create_in(&ns_a, &name, ModuleAbbrev::Hal, ModuleAbbrev::Cu),
+        Err(ShmError::WriterAlreadyExists { .. })
+    ));
+    assert!(matches!(
+        TypedP2pReader::<TestSeg>::attach(&name, 10),
+        Err(ShmError::SegmentNotFound { .. })
+    ));
+
+    let mut payload: TestSeg = unsafe { core::mem::zeroed() };
+    payload.value = 1;
+    wa.commit(&payload).unwrap();
+    payload.value = 2;
+    wb.commit(&payload).unwrap();
+
+    let mut ra = TypedP2pReader::<TestSeg>::attach_in(&ns_a, &name, 10).expect("attach a");
+    let mut rb = TypedP2pReader::<TestSeg>::attach_in(&ns_b, &name, 10).expect("attach b");
+    assert_eq!(ra.read().unwrap().value, 1);
+    assert_eq!(rb.read().unwrap().value, 2);
+    assert_eq!(ra.namespace(), &ns_a);
+
+    let path = format!("/dev/shm/{}{name}", ns_a.file_prefix());
+    assert!(std::path::Path::new(&path).exists(), "{path}");
+    drop(wa);
+    assert!(!std::path::Path::new(&path).exists());
+}
+
+/// Test: discovery and dead-segment cleanup only see their own namespace.
+#[test]
+fn discovery_and_cleanup_scoped_per_instance() {
+    let name = format!("test_ns_scope_{}", std::process::id());
+    let ns = ShmNamespace::new(&format!("s{}", std::process::id())).unwrap();
+    let other = ShmNamespace::new(&format!("s{}-x", std::process::id())).unwrap();
+
+    let _live = TypedP2pWriter::<TestSeg>::create_in(&ns, &name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+        .unwrap();
+    let _default = TypedP2pWriter::<TestSeg>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+        .unwrap();
+    // Orphan in the other instance: data file without a live writer.
+    let orphan = format!("/dev/shm/{}{name}", other.file_prefix());
+    std::fs::write(&orphan, [0u8; 64]).unwrap();
+
+    let listed: Vec<_> = SegmentDiscovery::list_segments_in(&ns).into_iter().map(|s| s.name).collect();
+    assert_eq!(listed, std::slice::from_ref(&name));
+    assert_eq!(SegmentDiscovery::list_for_in(&ns, ModuleAbbrev::Cu).len(), 1);
+    assert!(
+        SegmentDiscovery::list_segments()
+            .iter()
+            .any(|s| s.name == name && s.path.ends_with(format!("evo_{name}")))
+    );
+
+    // Cleaning `ns` leaves the other instance's orphan alone.
+    assert_eq!(SegmentDiscovery::cleanup_dead_in(&ns), 0);
+    assert!(std::path::Path::new(&orphan).exists());
+    assert!(SegmentDiscovery::cleanup_dead_in(&other) >= 1);
+    assert!(!std::path::Path::new(&orphan).exists());
+}
+
+/// Test: instance ids are validated; empty selects the default namespace.
+#[test]
+fn instance_id_validation() {
+    assert_eq!(ShmNamespace::new("").unwrap(), ShmNamespace::default());
+    assert_eq!(ShmNamespace::from_instance(None).unwrap().instance(), None);
+    let ns = ShmNamespace::new("line-2").unwrap();
+    assert_eq!(ns.shm_name("hal_cu"), "/evo-line-2_hal_cu");
+    assert_eq!(ShmNamespace::default().shm_name("hal_cu"), "/evo_hal_cu");
+    for bad in ["a_b", "a/b", "a.b", &"x".repeat(33)] {
+        assert!(
+            matches!(ShmNamespace::new(bad), Err(ShmError::InvalidInstance { .. })),
+            "{bad}"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Crash post-mortem tests — `watchdog::postmortem`: bundle contents,
+//! retention, timestamps and child output capture.
+
+use evo_common::shm::p2p::{ModuleAbbrev, P2pSegmentHeader, ShmNamespace, TypedP2pWriter};
+use evo_common::watchdog::postmortem::{self, CrashReport, OutputTail, PostMortem, REPORT_FILE};
+use std::fs;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::TempDir;
+
+/// Helper segment — 128 bytes, cache-line aligned.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, align(64))]
+struct TestSeg {
+    header: P2pSegmentHeader,
+    value: u64,
+    _pad: [u8; 56],
+}
+evo_common::impl_shm_layout!(TestSeg { header, value, _pad });
+
+fn report(module: &str) -> CrashReport {
+    CrashReport {
+        module: module.to_string(),
+        binary: "evo_control_unit".to_string(),
+        namespace: "default".to_string(),
+        pid: Some(4242),
+        failure: "killed by SIGSEGV (core dumped)".to_string(),
+        exit_code: None,
+        signal: Some("SIGSEGV".to_string()),
+        core_dumped: true,
+        uptime_s: Some(1.5),
+        time: String::new(),
+        segments: Vec::new(),
+    }
+}
+
+/// Test: bundle holds report, raw segments with decoded headers, output
+/// and the config directory.
+#[test]
+fn bundle_contents() {
+    let ns = ShmNamespace::new(&format!("pm{}", std::process::id())).unwrap();
+    let mut writer =
+        TypedP2pWriter::<TestSeg>::create_in(&ns, "hal_cu", ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+            .expect("create");
+    let mut payload: TestSeg = unsafe { core::mem::zeroed() };
+    payload.value = 7;
+    writer.commit(&payload).unwrap();
+
+    let config = TempDir::new().unwrap();
+    fs::write(config.path().join("config.toml"), "[watchdog]\n").unwrap();
+    fs::create_dir(config.path().join("axes")).unwrap();
+    fs::write(config.path().join("axes/x.toml"), "id = 1\n").unwrap();
+    let out = TempDir::new().unwrap();
+    let pm = PostMortem::new(out.path().join("bundles"), 5);
+
+    let output = vec!["[out] cycle 1".to_string(), "[err] boom".to_string()];
+    let bundle = pm.write(&ns, config.path(), report("cu"), &output).expect("write");
+
+    let name = bundle.file_name().unwrap().to_str().unwrap();
+    assert!(name.ends_with(&format!("_{ns}_cu")), "{name}");
+    assert_eq!(pm.bundles().unwrap(), std::slice::from_ref(&bundle));
+
+    let report: CrashReport =
+        serde_json::from_str(&fs::read_to_string(bundle.join(REPORT_FILE)).unwrap()).unwrap();
+    assert_eq!(report.signal.as_deref(), Some("SIGSEGV"));
+    assert!(report.time.ends_with('Z'));
+    let file = format!("{}hal_cu", ns.file_prefix());
+    assert_eq!(report.segments.len(), 1, "lock file skipped: {:?}", report.segments);
+    let seg = &report.segments[0];
+    assert_eq!(seg.file, file);
+    assert_eq!(seg.error, None);
+    let header = seg.header.as_ref().expect("decoded header");
+    assert_eq!(header.name, "hal_cu");
+    assert!(header.valid_magic && header.writer_alive);
+    assert_eq!(header.source.as_deref(), Some("hal"));
+    assert_eq!(header.dest.as_deref(), Some("cu"));
+    assert_eq!(header.heartbeat, Some(1));
+
+    let raw = fs::read(bundle.join("shm").join(&file)).unwrap();
+    assert_eq!(raw, fs::read(format!("/dev/shm/{file}")).unwrap());
+    assert_eq!(seg.size, raw.len() as u64);
+
+    assert_eq!(
+        fs::read_to_string(bundle.join("output.log")).unwrap(),
+        "[out] cycle 1\n[err] boom\n"
+    );
+    assert_eq!(
+        fs::read_to_string(bundle.join("config/axes/x.toml")).unwrap(),
+        "id = 1\n"
+    );
+    assert!(bundle.join("config/config.toml").is_file());
+}
+
+/// Test: only the newest `keep` bundles survive; other entries stay.
+#[test]
+fn retention_keeps_newest() {
+    let ns = ShmNamespace::new(&format!("pmr{}", std::process::id())).unwrap();
+    let config = TempDir::new().unwrap();
+    let out = TempDir::new().unwrap();
+    fs::create_dir(out.path().join("notes")).unwrap();
+    let pm = PostMortem::new(out.path(), 2);
+
+    let written: Vec<_> = ["hal", "cu", "re"]
+        .into_iter()
+        .map(|m| pm.write(&ns, config.path(), report(m), &[]).expect("write"))
+        .collect();
+    assert_eq!(pm.bundles().unwrap(), written[1..]);
+    assert!(out.path().join("notes").is_dir());
+
+    // Back-to-back bundles of one module stay distinct and ordered.
+    let a = pm.write(&ns, config.path(), report("cu"), &[]).unwrap();
+    let b = pm.write(&ns, config.path(), report("cu"), &[]).unwrap();
+    assert_ne!(a, b);
+    assert_eq!(pm.bundles().unwrap(), [a, b]);
+}
+
+/// Test: UTC timestamps of bundle names and reports.
+#[test]
+fn utc_timestamps() {
+    let at = |secs: u64, us: u64| UNIX_EPOCH + Duration::from_micros(secs * 1_000_000 + us);
+    assert_eq!(
+        postmortem::utc_timestamp(UNIX_EPOCH, false),
+        "1970-01-01T00:00:00.000000Z"
+    );
+    assert_eq!(
+        postmortem::utc_timestamp(at(1_709_164_800, 5), false),
+        "2024-02-29T00:00:00.000005Z"
+    );
+    assert_eq!(
+        postmortem::utc_timestamp(at(1_792_120_720, 123_456), true),
+        "20261016T031840.123456Z"
+    );
+    let now = postmortem::utc_timestamp(SystemTime::now(), true);
+    assert_eq!(now.len(), "20261016T031840.123456Z".len());
+}
+
+/// Test: output tail keeps the last lines, tagged, without colours.
+#[test]
+fn output_tail_capture() {
+    let mut child = Command::new("sh")
+        .args(["-c", "for i in 1 2 3 4; do echo line $i; done"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn sh");
+    let tail = OutputTail::capture(&mut child, 2);
+    child.wait().unwrap();
+    assert!(tail.drain(Duration::from_secs(5)));
+    assert_eq!(tail.lines(), ["[out] line 3", "[out] line 4"]);
+
+    let mut child = Command::new("sh")
+        .args(["-c", r"printf 'ok\n'; printf '\033[31mERROR\033[0m fault\n' >&2"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn sh");
+    let tail = OutputTail::capture(&mut child, 10);
+    child.wait().unwrap();
+    assert!(tail.drain(Duration::from_secs(5)));
+    let mut lines = tail.lines();
+    lines.sort();
+    assert_eq!(lines, ["[err] ERROR fault", "[out] ok"]);
+}
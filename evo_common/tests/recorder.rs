@@ -0,0 +1,179 @@
+//! SHM recorder / replayer tests — continuous capture, pre/post trigger
+//! window, file rotation, and replay into a typed reader.
+
+use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
+use evo_common::shm::recorder::{
+    Recorder, RecorderConfig, RecorderError, Recording, Replayer, TriggerWindow,
+};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C, align(64))]
+struct Feedback {
+    position: f64,
+    fault: u8,
+    _pad: [u8; 55],
+}
+evo_common::impl_shm_layout!(Feedback { position, fault, _pad });
+
+fn sample(position: f64, fault: u8) -> Feedback {
+    Feedback {
+        position,
+        fault,
+        _pad: [0; 55],
+    }
+}
+
+fn config(dir: &tempfile::TempDir, segment: &str) -> RecorderConfig {
+    RecorderConfig {
+        segments: vec![segment.to_string()],
+        dir: dir.path().to_path_buf(),
+        ..RecorderConfig::default()
+    }
+}
+
+fn writer(name: &str) -> TypedP2pWriter<Feedback> {
+    TypedP2pWriter::create(name, ModuleAbbrev::Hal, ModuleAbbrev::Cu).expect("create")
+}
+
+fn positions(path: &std::path::Path, name: &str) -> Vec<f64> {
+    let rec = Recording::load(path).expect("load");
+    rec.payloads::<Feedback>(name)
+        .expect("payloads")
+        .into_iter()
+        .map(|(_, p)| p.position)
+        .collect()
+}
+
+/// Test: every committed heartbeat is captured in order with the header.
+#[test]
+fn continuous_recording() {
+    let name = format!("test_rec_cont_{}", std::process::id());
+    let dir = tempfile::tempdir().unwrap();
+    let mut w = writer(&name);
+    let mut recorder = Recorder::new(config(&dir, &name)).expect("recorder");
+    assert_eq!(recorder.segments().collect::<Vec<_>>(), [name.as_str()]);
+
+    for i in 1..=5 {
+        w.commit(&sample(i as f64, 0)).unwrap();
+        assert_eq!(recorder.poll().unwrap(), 1);
+    }
+    assert_eq!(recorder.poll().unwrap(), 0, "no new heartbeat");
+    recorder.flush().unwrap();
+
+    let path = recorder.files().next().unwrap().to_path_buf();
+    let rec = Recording::load(&path).unwrap();
+    let seg = rec.segment(&name).unwrap();
+    assert_eq!(seg.source, ModuleAbbrev::Hal as u8);
+    assert_eq!(seg.payload_size as usize, size_of::<Feedback>());
+    let beats: Vec<_> = rec.samples_for(&name).map(|s| s.heartbeat).collect();
+    assert_eq!(beats, [1, 2, 3, 4, 5]);
+    assert!(
+        rec.samples
+            .windows(2)
+            .all(|w| w[0].timestamp_ns <= w[1].timestamp_ns)
+    );
+    assert_eq!(positions(&path, &name), [1.0, 2.0, 3.0, 4.0, 5.0]);
+}
+
+/// Test: only the pre-window history and the post window reach the file.
+#[test]
+fn trigger_window_capture() {
+    let name = format!("test_rec_trig_{}", std::process::id());
+    let dir = tempfile::tempdir().unwrap();
+    let mut w = writer(&name);
+    let mut cfg = config(&dir, &name);
+    cfg.trigger_window = Some(TriggerWindow {
+        pre: Duration::from_millis(30),
+        post: Duration::ZERO,
+    });
+    let mut recorder = Recorder::new(cfg).unwrap();
+    recorder.set_trigger(|_, payload| payload[8] != 0);
+
+    w.commit(&sample(1.0, 0)).unwrap();
+    recorder.poll().unwrap();
+    std::thread::sleep(Duration::from_millis(60));
+    w.commit(&sample(2.0, 0)).unwrap();
+    recorder.poll().unwrap();
+    assert!(!recorder.is_capturing());
+    assert_eq!(
+        recorder.files().count(),
+        0,
+        "nothing written before trigger"
+    );
+
+    // Fault → trigger; post window of zero closes the file on this poll.
+    w.commit(&sample(3.0, 1)).unwrap();
+    recorder.poll().unwrap();
+    assert!(!recorder.is_capturing());
+    w.commit(&sample(4.0, 0)).unwrap();
+    recorder.poll().unwrap();
+
+    let files: Vec<_> = recorder.files().map(|p| p.to_path_buf()).collect();
+    assert_eq!(files.len(), 1);
+    assert_eq!(positions(&files[0], &name), [2.0, 3.0]);
+    assert_eq!(Recording::load(&files[0]).unwrap().triggers.len(), 1);
+    assert_eq!(recorder.stats().triggers, 1);
+}
+
+/// Test: size rotation keeps `max_files` self-contained files.
+#[test]
+fn rotation_keeps_newest_files() {
+    let name = format!("test_rec_rot_{}", std::process::id());
+    let dir = tempfile::tempdir().unwrap();
+    let mut w = writer(&name);
+    let mut cfg = config(&dir, &name);
+    cfg.max_file_bytes = 1;
+    cfg.max_files = 2;
+    let mut recorder = Recorder::new(cfg).unwrap();
+
+    for i in 1..=4 {
+        w.commit(&sample(i as f64, 0)).unwrap();
+        recorder.poll().unwrap();
+    }
+    assert_eq!(recorder.stats().files, 4);
+    let files: Vec<_> = recorder.files().map(|p| p.to_path_buf()).collect();
+    assert_eq!(files.len(), 2);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    assert_eq!(positions(&files[0], &name), [3.0]);
+    assert_eq!(positions(&files[1], &name), [4.0]);
+}
+
+/// Test: the replayer becomes the segment writer and a typed reader
+/// receives the recorded payloads.
+#[test]
+fn replay_into_typed_reader() {
+    let name = format!("test_rec_replay_{}", std::process::id());
+    let dir = tempfile::tempdir().unwrap();
+    let path = {
+        let mut w = writer(&name);
+        let mut recorder = Recorder::new(config(&dir, &name)).unwrap();
+        for i in 1..=3 {
+            w.commit(&sample(i as f64 * 0.5, 0)).unwrap();
+            recorder.poll().unwrap();
+        }
+        recorder.flush().unwrap();
+        recorder.files().next().unwrap().to_path_buf()
+    };
+
+    let mut replayer = Replayer::new(Recording::load(&path).unwrap(), &[]).unwrap();
+    assert!(
+        TypedP2pWriter::<Feedback>::create(&name, ModuleAbbrev::Hal, ModuleAbbrev::Cu).is_err()
+    );
+
+    let mut reader = TypedP2pReader::<Feedback>::attach_validated(&name, 10, ModuleAbbrev::Cu)
+        .expect("attach replayed segment");
+    let mut seen = Vec::new();
+    while replayer.step().unwrap().is_some() {
+        seen.push(reader.read().expect("read").position);
+    }
+    assert_eq!(seen, [0.5, 1.0, 1.5]);
+
+    replayer.rewind();
+    for speed in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(matches!(replayer.run(speed), Err(RecorderError::InvalidSpeed(_))));
+    }
+    assert_eq!(replayer.remaining(), 3, "nothing replayed");
+    assert_eq!(replayer.run(1000.0).unwrap(), 3);
+    assert_eq!(reader.read().unwrap().position, 1.5);
+}
@@ -0,0 +1,214 @@
+//! Request/response channel tests — `P2pRequestChannel` / `P2pRequestServer`
+//! over real segment pairs: ID allocation, exactly-once delivery, ack
+//! matching, superseded requests, timeouts and restarts of either side.
+
+use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
+use evo_common::shm::request::{P2pRequestChannel, P2pRequestServer, RequestEvent};
+use evo_common::shm::segments::{
+    CuToReSegment, HalRpcResult, HalToRpcSegment, RpcCommandType, RpcToCuSegment, RpcToHalSegment,
+};
+use std::time::Duration;
+
+type HalChannel = (
+    P2pRequestChannel<RpcToHalSegment, HalToRpcSegment>,
+    P2pRequestServer<RpcToHalSegment, HalToRpcSegment>,
+);
+
+/// gRPC → HAL pair: request segment `req_<tag>`, response segment `resp_<tag>`.
+fn hal_pair(tag: &str, timeout: Duration) -> HalChannel {
+    let req = format!("req_{tag}_{}", std::process::id());
+    let resp = format!("resp_{tag}_{}", std::process::id());
+    let req_writer =
+        TypedP2pWriter::<RpcToHalSegment>::create(&req, ModuleAbbrev::Rpc, ModuleAbbrev::Hal)
+            .expect("create request");
+    let resp_writer =
+        TypedP2pWriter::<HalToRpcSegment>::create(&resp, ModuleAbbrev::Hal, ModuleAbbrev::Rpc)
+            .expect("create response");
+    let req_reader = TypedP2pReader::<RpcToHalSegment>::attach(&req, 1000).expect("attach request");
+    let resp_reader =
+        TypedP2pReader::<HalToRpcSegment>::attach(&resp, 1000).expect("attach response");
+    (
+        P2pRequestChannel::new(req_writer, resp_reader, timeout),
+        P2pRequestServer::new(req_reader, resp_writer),
+    )
+}
+
+/// Test: request delivered once, response matched to its ID.
+#[test]
+fn request_delivered_once_and_completed() {
+    let (mut client, mut server) = hal_pair("once", Duration::from_secs(5));
+
+    let id = client
+        .send(RpcToHalSegment {
+            target: 3,
+            value: 1.0,
+            ..Default::default()
+        })
+        .expect("send");
+    assert_eq!(id, 1);
+    assert!(client.is_in_flight(id));
+    assert!(client.poll().expect("poll").is_none(), "no response yet");
+
+    let req = server.poll().expect("server poll").expect("new request");
+    assert_eq!(req.target, 3);
+    assert_eq!(server.last_id(), 1);
+    // Same request is not delivered again.
+    assert!(server.poll().expect("server poll").is_none());
+
+    server.respond(id, |r| r.result_code = HalRpcResult::Ok as u32);
+    match client.poll().expect("poll") {
+        Some(RequestEvent::Completed {
+            id: done, response, ..
+        }) => {
+            assert_eq!(done, id);
+            assert_eq!(response.request_id, id);
+            assert_eq!(response.result_code, HalRpcResult::Ok as u32);
+        }
+        _ => panic!("expected Completed"),
+    }
+    assert_eq!(client.in_flight(), 0);
+    assert!(client.poll().expect("poll").is_none());
+
+    // Next request gets the next ID and is delivered again.
+    let id2 = client.send(RpcToHalSegment::default()).expect("send 2");
+    assert_eq!(id2, 2);
+    assert!(server.poll().expect("server poll").is_some());
+}
+
+/// Test: overwritten request reported as superseded before the completion
+/// of the later one is consumed.
+#[test]
+fn overwritten_request_superseded() {
+    let (mut client, mut server) = hal_pair("sup", Duration::from_secs(5));
+
+    let first = client.send(RpcToHalSegment::default()).expect("send 1");
+    let second = client.send(RpcToHalSegment::default()).expect("send 2");
+
+    // Server only sees the latest request.
+    let seen = server
+        .poll()
+        .expect("server poll")
+        .expect("request")
+        .request_id;
+    assert_eq!(seen, second);
+    server.respond(second, |_| {});
+
+    let mut events = Vec::new();
+    while let Some(ev) = client.poll().expect("poll") {
+        events.push(ev);
+    }
+    assert!(matches!(events[0], RequestEvent::Completed { id, .. } if id == second));
+    assert!(matches!(events[1], RequestEvent::Superseded { id } if id == first));
+    assert_eq!(events.len(), 2);
+    assert_eq!(client.in_flight(), 0);
+}
+
+/// Test: a restarted server does not execute the request the surviving
+/// client segment still holds, but serves the next one.
+#[test]
+fn restarted_server_skips_old_request() {
+    let (mut client, mut server) = hal_pair("rst", Duration::from_secs(5));
+
+    let old = client.send(RpcToHalSegment::default()).expect("send");
+    assert!(server.poll().expect("server poll").is_some());
+    server.respond(old, |_| {});
+    drop(server);
+
+    // New server instance attaches to the same request segment.
+    let req = format!("req_rst_{}", std::process::id());
+    let resp = format!("resp_rst2_{}", std::process::id());
+    let mut server = P2pRequestServer::new(
+        TypedP2pReader::<RpcToHalSegment>::attach(&req, 1000).expect("reattach request"),
+        TypedP2pWriter::<HalToRpcSegment>::create(&resp, ModuleAbbrev::Hal, ModuleAbbrev::Rpc)
+            .expect("create response"),
+    );
+    assert_eq!(server.last_id(), old);
+    assert!(server.poll().expect("server poll").is_none(), "old request re-executed");
+
+    let next = client.send(RpcToHalSegment::default()).expect("send next");
+    let got = server.poll().expect("server poll").expect("next request");
+    assert_eq!(got.request_id, next);
+}
+
+/// Test: a restarted client continues after the answered ID instead of
+/// reusing it, so its first request is served.
+#[test]
+fn restarted_client_continues_after_answered_id() {
+    let (mut client, mut server) = hal_pair("rcl", Duration::from_secs(5));
+
+    let old = client.send(RpcToHalSegment::default()).expect("send");
+    assert_eq!(old, 1);
+    assert!(server.poll().expect("server poll").is_some());
+    server.respond(old, |_| {});
+    drop(client);
+
+    // New client instance recreates the request segment.
+    let req = format!("req_rcl_{}", std::process::id());
+    let resp = format!("resp_rcl_{}", std::process::id());
+    let mut client = P2pRequestChannel::new(
+        TypedP2pWriter::<RpcToHalSegment>::create(&req, ModuleAbbrev::Rpc, ModuleAbbrev::Hal)
+            .expect("recreate request"),
+        TypedP2pReader::<HalToRpcSegment>::attach(&resp, 1000).expect("reattach response"),
+        Duration::from_secs(5),
+    );
+    server.reattach(TypedP2pReader::attach(&req, 1000).expect("reattach request"));
+
+    let id = client.send(RpcToHalSegment::default()).expect("send after restart");
+    assert_eq!(id, old + 1);
+    let got = server.poll().expect("server poll").expect("request after restart");
+    assert_eq!(got.request_id, id);
+    server.respond(id, |r| r.result_code = HalRpcResult::Ok as u32);
+    assert!(matches!(
+        client.poll().expect("poll"),
+        Some(RequestEvent::Completed { id: c, .. }) if c == id
+    ));
+}
+
+/// Test: unanswered request times out.
+#[test]
+fn unanswered_request_times_out() {
+    let (mut client, _server) = hal_pair("tmo", Duration::from_millis(20));
+
+    let id = client.send(RpcToHalSegment::default()).expect("send");
+    assert!(client.poll().expect("poll").is_none());
+    std::thread::sleep(Duration::from_millis(40));
+    assert!(matches!(
+        client.poll().expect("poll"),
+        Some(RequestEvent::TimedOut { id: t }) if t == id
+    ));
+    assert!(!client.is_in_flight(id));
+}
+
+/// Test: gRPC → CU commands acked through `CuToReSegment::last_ack_seq_id`.
+#[test]
+fn cu_sequence_id_channel() {
+    let req = format!("req_cu_{}", std::process::id());
+    let resp = format!("resp_cu_{}", std::process::id());
+    let req_writer =
+        TypedP2pWriter::<RpcToCuSegment>::create(&req, ModuleAbbrev::Rpc, ModuleAbbrev::Cu)
+            .expect("create request");
+    let resp_writer =
+        TypedP2pWriter::<CuToReSegment>::create(&resp, ModuleAbbrev::Cu, ModuleAbbrev::Re)
+            .expect("create response");
+    let mut client = P2pRequestChannel::new(
+        req_writer,
+        TypedP2pReader::<CuToReSegment>::attach(&resp, 1000).expect("attach response"),
+        Duration::from_secs(5),
+    );
+    let mut server = P2pRequestServer::new(
+        TypedP2pReader::<RpcToCuSegment>::attach(&req, 1000).expect("attach request"),
+        resp_writer,
+    );
+
+    let mut cmd = RpcToCuSegment::default();
+    cmd.command.command_type = RpcCommandType::EnableAxis as u8;
+    let id = client.send(cmd).expect("send");
+
+    let got = server.poll().expect("server poll").expect("request");
+    assert_eq!(got.command.sequence_id, id as u32);
+    server.respond(id, |_| {});
+    assert!(matches!(
+        client.poll().expect("poll"),
+        Some(RequestEvent::Completed { id: done, .. }) if done == id
+    ));
+}
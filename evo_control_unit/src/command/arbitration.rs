@@ -13,10 +13,15 @@
 //! and returns a `ReloadOutcome` that the cycle writer maps to
 //! an updated `evo_cu_mqt` snapshot.
 
-use evo_common::control_unit::shm::{ReCommandType, RpcCommand, RpcCommandType};
-use evo_common::control_unit::state::SafetyState;
+use evo_common::control_unit::command::{CommandSource, LockReason};
+use evo_common::shm::segments::{
+    ReCommand, ReCommandType, RpcCommand, RpcCommandType,
+};
+use evo_common::control_unit::state::{MachineState, SafetyState};
 
 use crate::config::{atomic_config_swap, LoadedConfig, ReloadResult};
+use crate::control::path::{ArcDirection, AxisGroup, PathCommand, PathGeometry};
+use crate::state::machine::MachineEvent;
 
 /// Decoded command from either RE or RPC source.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -81,6 +86,8 @@ pub enum AxisCommand {
     ReleaseLock { axis_id: u8 },
     /// Reload config (during SAFETY_STOP only, FR-145).
     ReloadConfig,
+    /// Authorize the SAFETY_STOP recovery sequence (global, FR-122).
+    AuthorizeRecovery,
 }
 
 /// Source of a command.
@@ -90,6 +97,35 @@ pub enum CommandOrigin {
     RpcApi,
 }
 
+impl CommandOrigin {
+    /// Source-lock identity of this origin (FR-135).
+    #[inline]
+    pub const fn source(self) -> CommandSource {
+        match self {
+            Self::RecipeExecutor => CommandSource::RecipeExecutor,
+            Self::RpcApi => CommandSource::GrpcApi,
+        }
+    }
+
+    /// Lock reason recorded when this origin acquires an axis.
+    #[inline]
+    pub const fn lock_reason(self) -> LockReason {
+        match self {
+            Self::RecipeExecutor => LockReason::RecipeRunning,
+            Self::RpcApi => LockReason::ManualControl,
+        }
+    }
+
+    /// Machine event raised when this origin starts motion from `Idle`.
+    #[inline]
+    pub const fn motion_event(self) -> MachineEvent {
+        match self {
+            Self::RecipeExecutor => MachineEvent::RecipeStart,
+            Self::RpcApi => MachineEvent::ManualCommand,
+        }
+    }
+}
+
 /// Decode an RE command type into an AxisCommand.
 ///
 /// This only translates the command type; axis-specific parameters
@@ -103,6 +139,35 @@ pub fn decode_rpc_command(cmd_type: u8) -> Option<RpcCommandType> {
     RpcCommandType::from_u8(cmd_type)
 }
 
+/// Target axis (1-based) of a command, or `None` for global commands.
+pub const fn command_axis_id(cmd: &AxisCommand) -> Option<u8> {
+    match *cmd {
+        AxisCommand::Nop
+        | AxisCommand::SetMachineState { .. }
+        | AxisCommand::ReloadConfig
+        | AxisCommand::AuthorizeRecovery => None,
+        AxisCommand::EnableAxis { axis_id }
+        | AxisCommand::DisableAxis { axis_id }
+        | AxisCommand::MoveAbsolute { axis_id, .. }
+        | AxisCommand::MoveRelative { axis_id, .. }
+        | AxisCommand::MoveVelocity { axis_id, .. }
+        | AxisCommand::Stop { axis_id }
+        | AxisCommand::EmergencyStop { axis_id }
+        | AxisCommand::Home { axis_id }
+        | AxisCommand::SetMode { axis_id, .. }
+        | AxisCommand::Couple { axis_id }
+        | AxisCommand::Decouple { axis_id }
+        | AxisCommand::GearChange { axis_id, .. }
+        | AxisCommand::AllowManualMode { axis_id }
+        | AxisCommand::JogPositive { axis_id, .. }
+        | AxisCommand::JogNegative { axis_id, .. }
+        | AxisCommand::JogStop { axis_id }
+        | AxisCommand::ResetError { axis_id }
+        | AxisCommand::AcquireLock { axis_id }
+        | AxisCommand::ReleaseLock { axis_id } => Some(axis_id),
+    }
+}
+
 /// Check if a command requires source lock on the target axis.
 pub const fn requires_source_lock(cmd: &AxisCommand) -> bool {
     matches!(
@@ -135,6 +200,131 @@ pub const fn is_motion_command(cmd: &AxisCommand) -> bool {
     )
 }
 
+/// Dispatch one axis of an RE command into an AxisCommand (T043).
+///
+/// `axis_index` is 0-based (bit N of `axis_mask` = axis N+1); the motion
+/// parameters come from `targets[axis_index]`.
+///
+/// Returns `None` for Nop, unrecognized command types, or axes not
+/// selected by `axis_mask`.
+pub fn dispatch_re_command(re: &ReCommand, axis_index: usize) -> Option<AxisCommand> {
+    if axis_index >= re.targets.len() || re.axis_mask & (1u64 << axis_index) == 0 {
+        return None;
+    }
+    let cmd_type = ReCommandType::from_u8(re.command_type)?;
+    let axis_id = (axis_index + 1) as u8;
+    let t = &re.targets[axis_index];
+
+    match cmd_type {
+        ReCommandType::Nop => None,
+
+        ReCommandType::MoveAbsolute => Some(AxisCommand::MoveAbsolute {
+            axis_id,
+            position: t.target_position,
+            velocity: t.target_velocity,
+            acceleration: t.acceleration,
+            deceleration: t.deceleration,
+        }),
+
+        ReCommandType::MoveRelative => Some(AxisCommand::MoveRelative {
+            axis_id,
+            distance: t.target_position,
+            velocity: t.target_velocity,
+            acceleration: t.acceleration,
+            deceleration: t.deceleration,
+        }),
+
+        ReCommandType::MoveVelocity => Some(AxisCommand::MoveVelocity {
+            axis_id,
+            velocity: t.target_velocity,
+            acceleration: t.acceleration,
+        }),
+
+        ReCommandType::Home => Some(AxisCommand::Home { axis_id }),
+
+        ReCommandType::Stop => Some(AxisCommand::Stop { axis_id }),
+
+        ReCommandType::EmergencyStop => Some(AxisCommand::EmergencyStop { axis_id }),
+
+        ReCommandType::EnableAxis => Some(AxisCommand::EnableAxis { axis_id }),
+
+        ReCommandType::DisableAxis => Some(AxisCommand::DisableAxis { axis_id }),
+
+        ReCommandType::SetMode => Some(AxisCommand::SetMode {
+            axis_id,
+            mode: t.mode,
+        }),
+
+        ReCommandType::Couple => Some(AxisCommand::Couple { axis_id }),
+
+        ReCommandType::Decouple => Some(AxisCommand::Decouple { axis_id }),
+
+        // RE targets carry the requested gear step in `mode`.
+        ReCommandType::GearChange => Some(AxisCommand::GearChange {
+            axis_id,
+            gear: t.mode as u32,
+        }),
+
+        ReCommandType::AllowManualMode => Some(AxisCommand::AllowManualMode { axis_id }),
+
+        // Group-level, see `dispatch_path_command`.
+        ReCommandType::PathLinear | ReCommandType::PathArcCw | ReCommandType::PathArcCcw => None,
+    }
+}
+
+/// Check if an RE command type is a coordinated path segment.
+pub const fn is_path_command(cmd_type: ReCommandType) -> bool {
+    matches!(
+        cmd_type,
+        ReCommandType::PathLinear | ReCommandType::PathArcCw | ReCommandType::PathArcCcw
+    )
+}
+
+/// Dispatch an RE path command into a group-local [`PathCommand`].
+///
+/// End points come from `targets[i].target_position` of the masked axes;
+/// the feed rate is the largest masked `target_velocity`. Arcs take their
+/// plane and centre from [`ReCommand::arc`].
+///
+/// Returns `None` for non-path commands, masks that are empty or leave
+/// the group, and arcs whose plane axes are equal or not both masked.
+pub fn dispatch_path_command(re: &ReCommand, group: &AxisGroup) -> Option<PathCommand> {
+    let cmd_type = ReCommandType::from_u8(re.command_type)?;
+    if !is_path_command(cmd_type) || re.axis_mask == 0 || re.axis_mask & !group.mask() != 0 {
+        return None;
+    }
+
+    let mut cmd = PathCommand {
+        end: [0.0; _],
+        axis_mask: 0,
+        feed: 0.0,
+        geometry: PathGeometry::Linear,
+    };
+    for (k, &i) in group.members().iter().enumerate() {
+        if re.axis_mask & (1u64 << i) == 0 {
+            continue;
+        }
+        let t = &re.targets[i];
+        cmd.end[k] = t.target_position;
+        cmd.axis_mask |= 1 << k;
+        cmd.feed = cmd.feed.max(t.target_velocity);
+    }
+
+    let direction = match cmd_type {
+        ReCommandType::PathArcCw => ArcDirection::Clockwise,
+        ReCommandType::PathArcCcw => ArcDirection::CounterClockwise,
+        _ => return Some(cmd),
+    };
+    let [a, b] = re.arc.plane.map(usize::from);
+    let (u, v) = (group.local_index(a)?, group.local_index(b)?);
+    let plane = (1 << u) | (1 << v);
+    if u == v || cmd.axis_mask & plane != plane {
+        return None;
+    }
+    cmd.geometry = PathGeometry::Arc { u, v, center: re.arc.center, direction };
+    Some(cmd)
+}
+
 /// Dispatch an RPC command into an AxisCommand (T078).
 ///
 /// Translates the raw `RpcCommand` struct (from evo_rpc_cu SHM segment)
@@ -197,6 +387,32 @@ pub fn dispatch_rpc_command(rpc: &RpcCommand) -> Option<AxisCommand> {
         RpcCommandType::AllowManualMode => Some(AxisCommand::AllowManualMode { axis_id }),
 
         RpcCommandType::ReloadConfig => Some(AxisCommand::ReloadConfig),
+
+        RpcCommandType::AuthorizeRecovery => Some(AxisCommand::AuthorizeRecovery),
+    }
+}
+
+/// Map a `SetMachineState` request to the machine event that reaches
+/// `target` from `current` (contracts/state-machines.md §1).
+///
+/// Returns `None` when no single event leads there. `SystemError` is only
+/// left through the recovery sequence, never by command (I-MS-2).
+pub const fn machine_event_for_target(
+    current: MachineState,
+    target: MachineState,
+) -> Option<MachineEvent> {
+    use MachineState::*;
+
+    match (current, target) {
+        (SystemError, _) => None,
+        (_, Service) => Some(MachineEvent::ServiceAuthorize),
+        (Manual, Idle) => Some(MachineEvent::ManualStop),
+        (Active, Idle) => Some(MachineEvent::RecipeComplete),
+        (Service, Idle) => Some(MachineEvent::ServiceDeauthorize),
+        (Active, Manual) => Some(MachineEvent::RecipeCompleteManualPending),
+        (Idle, Manual) => Some(MachineEvent::ManualCommand),
+        (Idle | Manual, Active) => Some(MachineEvent::RecipeStart),
+        _ => None,
     }
 }
 
@@ -373,6 +589,17 @@ mod tests {
         assert!(matches!(cmd, AxisCommand::ReloadConfig));
     }
 
+    #[test]
+    fn dispatch_rpc_authorize_recovery() {
+        let rpc = RpcCommand {
+            command_type: RpcCommandType::AuthorizeRecovery as u8,
+            ..RpcCommand::default()
+        };
+        let cmd = dispatch_rpc_command(&rpc).unwrap();
+        assert!(matches!(cmd, AxisCommand::AuthorizeRecovery));
+        assert_eq!(command_axis_id(&cmd), None);
+    }
+
     #[test]
     fn dispatch_invalid_rpc_returns_none() {
         let rpc = RpcCommand {
@@ -386,6 +613,156 @@ mod tests {
         assert!(dispatch_rpc_command(&rpc).is_none());
     }
 
+    fn re_move(axis_mask: u64) -> ReCommand {
+        let mut re = ReCommand {
+            command_type: ReCommandType::MoveAbsolute as u8,
+            axis_mask,
+            sequence_id: 7,
+            ..ReCommand::default()
+        };
+        re.targets[0].target_position = 10.0;
+        re.targets[2].target_position = 30.0;
+        re.targets[2].target_velocity = 50.0;
+        re
+    }
+
+    #[test]
+    fn dispatch_re_uses_per_axis_target() {
+        let re = re_move(0b101);
+        let cmd = dispatch_re_command(&re, 2).unwrap();
+        assert!(matches!(
+            cmd,
+            AxisCommand::MoveAbsolute { axis_id: 3, position, velocity, .. }
+                if position == 30.0 && velocity == 50.0
+        ));
+        assert!(matches!(
+            dispatch_re_command(&re, 0),
+            Some(AxisCommand::MoveAbsolute { axis_id: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn dispatch_re_skips_unmasked_axes() {
+        let re = re_move(0b001);
+        assert!(dispatch_re_command(&re, 1).is_none());
+        assert!(dispatch_re_command(&re, 2).is_none());
+        assert!(dispatch_re_command(&re, 64).is_none());
+    }
+
+    #[test]
+    fn dispatch_re_nop_and_invalid_return_none() {
+        let mut re = ReCommand { axis_mask: 1, ..ReCommand::default() };
+        assert!(dispatch_re_command(&re, 0).is_none());
+        re.command_type = 99;
+        assert!(dispatch_re_command(&re, 0).is_none());
+    }
+
+    fn xyz_group() -> AxisGroup {
+        use evo_common::control_unit::config::{AxisGroupConfig, CuAxisConfig};
+        let axes: Vec<CuAxisConfig> = (1..=4)
+            .map(|id| {
+                toml::from_str(&format!("axis_id = {id}\nname = \"A{id}\"\nmax_velocity = 100.0"))
+                    .unwrap()
+            })
+            .collect();
+        let cfg = AxisGroupConfig {
+            name: "XYZ".into(),
+            axes: vec![1, 2, 3],
+            lookahead: 4,
+            corner_tolerance: 0.0,
+        };
+        AxisGroup::from_config(&cfg, &axes).unwrap()
+    }
+
+    #[test]
+    fn dispatch_path_linear_maps_group_axes() {
+        let group = xyz_group();
+        let mut re = ReCommand {
+            command_type: ReCommandType::PathLinear as u8,
+            axis_mask: 0b101,
+            ..ReCommand::default()
+        };
+        re.targets[0].target_position = 1.0;
+        re.targets[0].target_velocity = 20.0;
+        re.targets[2].target_position = 3.0;
+        re.targets[2].target_velocity = 30.0;
+
+        let cmd = dispatch_path_command(&re, &group).unwrap();
+        assert_eq!(cmd.axis_mask, 0b101);
+        assert_eq!(cmd.end[0], 1.0);
+        assert_eq!(cmd.end[2], 3.0);
+        assert_eq!(cmd.feed, 30.0);
+        assert_eq!(cmd.geometry, PathGeometry::Linear);
+        assert!(dispatch_re_command(&re, 0).is_none());
+
+        // Axis 4 is outside the group.
+        re.axis_mask = 0b1001;
+        assert!(dispatch_path_command(&re, &group).is_none());
+        re.axis_mask = 0;
+        assert!(dispatch_path_command(&re, &group).is_none());
+    }
+
+    #[test]
+    fn dispatch_path_arc_reads_plane_and_center() {
+        let group = xyz_group();
+        let mut re = ReCommand {
+            command_type: ReCommandType::PathArcCcw as u8,
+            axis_mask: 0b111,
+            ..ReCommand::default()
+        };
+        re.arc.plane = [1, 2];
+        re.arc.center = [5.0, -2.0];
+        re.targets[1].acceleration = 100.0;
+
+        let cmd = dispatch_path_command(&re, &group).unwrap();
+        assert_eq!(
+            cmd.geometry,
+            PathGeometry::Arc {
+                u: 1,
+                v: 2,
+                center: [5.0, -2.0],
+                direction: ArcDirection::CounterClockwise
+            }
+        );
+
+        // Duplicate, unmasked or out-of-range plane axes.
+        re.arc.plane = [1, 1];
+        assert!(dispatch_path_command(&re, &group).is_none());
+        re.arc.plane = [1, 2];
+        re.axis_mask = 0b011;
+        assert!(dispatch_path_command(&re, &group).is_none());
+        re.axis_mask = 0b111;
+        re.arc.plane = [1, 64];
+        assert!(dispatch_path_command(&re, &group).is_none());
+        assert!(is_path_command(ReCommandType::PathArcCw));
+        assert!(!is_path_command(ReCommandType::MoveAbsolute));
+    }
+
+    #[test]
+    fn command_axis_id_extraction() {
+        assert_eq!(command_axis_id(&AxisCommand::JogStop { axis_id: 4 }), Some(4));
+        assert_eq!(command_axis_id(&AxisCommand::AcquireLock { axis_id: 2 }), Some(2));
+        assert_eq!(command_axis_id(&AxisCommand::ReloadConfig), None);
+        assert_eq!(command_axis_id(&AxisCommand::SetMachineState { target_state: 3 }), None);
+    }
+
+    #[test]
+    fn origin_maps_to_lock_source() {
+        assert_eq!(CommandOrigin::RecipeExecutor.source(), CommandSource::RecipeExecutor);
+        assert_eq!(CommandOrigin::RpcApi.source(), CommandSource::GrpcApi);
+        assert_eq!(CommandOrigin::RpcApi.lock_reason(), LockReason::ManualControl);
+    }
+
+    #[test]
+    fn machine_event_for_target_mapping() {
+        use MachineState::*;
+        assert_eq!(machine_event_for_target(Idle, Service), Some(MachineEvent::ServiceAuthorize));
+        assert_eq!(machine_event_for_target(Active, Idle), Some(MachineEvent::RecipeComplete));
+        assert_eq!(machine_event_for_target(Service, Idle), Some(MachineEvent::ServiceDeauthorize));
+        assert_eq!(machine_event_for_target(SystemError, Idle), None);
+        assert_eq!(machine_event_for_target(Idle, Stopped), None);
+    }
+
     // ── T100: RELOAD_CONFIG handler tests ──
 
     use crate::config::load_config_from_strings;
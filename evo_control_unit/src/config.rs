@@ -2,14 +2,18 @@
 //!
 //! Loads `ControlUnitConfig`, `CuMachineConfig`, and `IoConfig` from TOML files.
 //! Validates: parameter bounds (FR-156), axis ID uniqueness, coupling graph
-//! acyclicity, required peripheral I/O roles, and global role completeness.
+//! acyclicity, axis group membership, required peripheral I/O roles, and
+//! global role completeness.
 
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+use evo_common::consts::MAX_AXIS_GROUPS;
 use evo_common::control_unit::config::{ControlUnitConfig, CuAxisConfig, CuMachineConfig};
 use evo_common::io::config::IoConfig;
 use evo_common::io::registry::{IoConfigError, IoRegistry};
+use evo_common::shm::p2p::ShmNamespace;
+use serde::Deserialize;
 
 // ─── Error Type ─────────────────────────────────────────────────────
 
@@ -69,6 +73,8 @@ pub struct LoadedConfig {
     pub cu_config: ControlUnitConfig,
     pub machine: CuMachineConfig,
     pub io_registry: IoRegistry,
+    /// SHM namespace of the EVO instance (default: `evo_*` segments).
+    pub shm_ns: ShmNamespace,
 }
 
 // ─── Loading Functions ──────────────────────────────────────────────
@@ -119,6 +125,7 @@ pub fn load_config(cu_config_path: &Path) -> Result<LoadedConfig, ConfigError> {
         cu_config,
         machine,
         io_registry,
+        shm_ns: ShmNamespace::default(),
     })
 }
 
@@ -149,14 +156,47 @@ pub fn load_config_from_strings(
         cu_config,
         machine,
         io_registry,
+        shm_ns: ShmNamespace::default(),
     })
 }
 
+// ─── Unified Config Section ─────────────────────────────────────────
+
+/// Keys of the `[cu]` section of the unified `config.toml` read by the CU.
+///
+/// Unknown keys are ignored; the section is shared with future settings.
+#[derive(Debug, Default, Deserialize)]
+pub struct CuSection {
+    /// HAL commit age limit [µs] (see `ControlUnitConfig::hal_max_age_us`).
+    #[serde(default)]
+    pub hal_max_age_us: Option<u32>,
+}
+
+impl CuSection {
+    /// Parse the `[cu]` table; an absent section yields the defaults.
+    pub fn from_value(cu: Option<&toml::Value>) -> Result<Self, ConfigError> {
+        let Some(value) = cu else {
+            return Ok(Self::default());
+        };
+        let section: Self = value
+            .clone()
+            .try_into()
+            .map_err(|e| ConfigError::ParseError(format!("[cu] section: {e}")))?;
+        if section.hal_max_age_us == Some(0) {
+            return Err(ConfigError::ValidationError(
+                "[cu] hal_max_age_us must be > 0".to_string(),
+            ));
+        }
+        Ok(section)
+    }
+}
+
 // ─── Machine Config Validation ──────────────────────────────────────
 
 pub fn validate_machine_config(machine: &CuMachineConfig) -> Result<(), ConfigError> {
     validate_axis_id_uniqueness(&machine.axes)?;
     validate_coupling_graph(&machine.axes)?;
+    validate_axis_groups(machine)?;
     Ok(())
 }
 
@@ -227,6 +267,36 @@ fn validate_coupling_graph(axes: &[CuAxisConfig]) -> Result<(), ConfigError> {
     Ok(())
 }
 
+/// Check axis groups: bounds, existing member axes, and each axis in at
+/// most one group.
+fn validate_axis_groups(machine: &CuMachineConfig) -> Result<(), ConfigError> {
+    if machine.axis_groups.len() > MAX_AXIS_GROUPS {
+        return Err(ConfigError::ValidationError(format!(
+            "{} axis groups configured, at most {MAX_AXIS_GROUPS} allowed",
+            machine.axis_groups.len()
+        )));
+    }
+    let mut grouped = HashSet::new();
+    for group in &machine.axis_groups {
+        group.validate().map_err(ConfigError::ValidationError)?;
+        for &id in &group.axes {
+            if !machine.axes.iter().any(|a| a.axis_id == id) {
+                return Err(ConfigError::ValidationError(format!(
+                    "axis group '{}' references non-existent axis {id}",
+                    group.name
+                )));
+            }
+            if !grouped.insert(id) {
+                return Err(ConfigError::ValidationError(format!(
+                    "axis {id} listed more than once in axis groups ('{}')",
+                    group.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 fn has_cycle(
     node: u8,
     graph: &HashMap<u8, Vec<u8>>,
@@ -496,6 +566,26 @@ io = [
         assert!(loaded.io_registry.has_role(&evo_common::io::role::IoRole::EStop));
     }
 
+    #[test]
+    fn cu_section_hal_max_age() {
+        assert_eq!(CuSection::from_value(None).unwrap().hal_max_age_us, None);
+        let value: toml::Value = toml::from_str("hal_max_age_us = 3000
+future = 1").unwrap();
+        let section = CuSection::from_value(Some(&value)).unwrap();
+        assert_eq!(section.hal_max_age_us, Some(3000));
+
+        let zero: toml::Value = toml::from_str("hal_max_age_us = 0").unwrap();
+        assert!(matches!(
+            CuSection::from_value(Some(&zero)),
+            Err(ConfigError::ValidationError(_))
+        ));
+        let negative: toml::Value = toml::from_str("hal_max_age_us = -1").unwrap();
+        assert!(matches!(
+            CuSection::from_value(Some(&negative)),
+            Err(ConfigError::ParseError(_))
+        ));
+    }
+
     #[test]
     fn reject_duplicate_axis_id() {
         let machine_toml = r#"
@@ -529,6 +619,24 @@ max_velocity = 500.0
         assert!(msg.contains("out of range"), "got: {msg}");
     }
 
+    #[test]
+    fn axis_group_members_must_exist_once() {
+        let group = |axes: &str| {
+            format!("{}\n[[axis_groups]]\nname = \"G\"\naxes = {axes}\n", minimal_machine_toml())
+        };
+        let loaded =
+            load_config_from_strings(minimal_cu_toml(), &group("[1]"), minimal_io_toml()).unwrap();
+        assert_eq!(loaded.machine.axis_groups[0].axes, [1]);
+
+        let err = load_config_from_strings(minimal_cu_toml(), &group("[1, 2]"), minimal_io_toml());
+        let msg = err.unwrap_err().to_string();
+        assert!(msg.contains("non-existent axis 2"), "got: {msg}");
+
+        let err = load_config_from_strings(minimal_cu_toml(), &group("[1, 1]"), minimal_io_toml());
+        let msg = err.unwrap_err().to_string();
+        assert!(msg.contains("more than once"), "got: {msg}");
+    }
+
     #[test]
     fn reject_cyclic_coupling() {
         let machine_toml = r#"
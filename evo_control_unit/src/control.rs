@@ -8,4 +8,6 @@ pub mod feedforward;
 pub mod filters;
 pub mod lag;
 pub mod output;
+pub mod path;
 pub mod pid;
+pub mod trajectory;
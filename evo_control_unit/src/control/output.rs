@@ -8,8 +8,9 @@
 //! mode change per invariants I-PW-4 / I-OM-4.
 
 use evo_common::control_unit::control::{ControlOutputVector, UniversalControlParameters};
-use evo_common::control_unit::shm::{CuAxisCommand, CuToHalSegment};
-use evo_common::control_unit::state::PowerState;
+use evo_common::control_unit::state::{OperationalMode, PowerState};
+use evo_common::hal::types::CommandMode;
+use evo_common::shm::segments::{CuAxisCommand, CuToHalSegment};
 
 use super::dob::{DobGains, DobState, dob_compute};
 use super::feedforward::{FeedforwardGains, feedforward_compute, torque_offset_compute};
@@ -175,7 +176,7 @@ pub fn compute_control_output(
 
 // ─── HAL Command Building ───────────────────────────────────────────
 
-/// Populate a `CuAxisCommand` for a single axis.
+/// Populate the `evo_cu_hal` command for a single axis.
 ///
 /// # Arguments
 /// - `power_state`: Current power state of the axis.
@@ -185,8 +186,9 @@ pub fn compute_control_output(
 /// # Returns
 /// A `CuAxisCommand` with:
 /// - `enable` = 1 if PowerState is Standby, Motion, PoweringOn; 0 otherwise.
-/// - `mode` = current operational mode.
-/// - `output` = the provided ControlOutputVector.
+/// - `brake_release` = 1 if PowerState is Standby, Motion, NoBrake; 0 otherwise.
+/// - `command_mode` = drive mode for the operational mode (see [`drive_command_mode`]).
+/// - setpoints = the provided ControlOutputVector.
 #[inline]
 pub fn build_axis_command(
     power_state: PowerState,
@@ -197,12 +199,34 @@ pub fn build_axis_command(
         PowerState::Standby | PowerState::Motion | PowerState::PoweringOn => 1u8,
         _ => 0u8,
     };
+    let brake_release = matches!(
+        power_state,
+        PowerState::Standby | PowerState::Motion | PowerState::NoBrake
+    ) as u8;
 
     CuAxisCommand {
-        output,
+        target_position: output.target_position,
+        target_velocity: output.target_velocity,
+        calculated_torque: output.calculated_torque,
+        torque_offset: output.torque_offset,
         enable,
-        mode,
-        _pad: [0u8; 6],
+        brake_release,
+        command_mode: drive_command_mode(OperationalMode::from_u8(mode).unwrap_or_default()) as u8,
+    }
+}
+
+/// Drive mode for an operational mode.
+///
+/// Velocity and torque control run the drive in CSV / CST; position,
+/// manual and test modes use CSP with velocity and torque feedforward.
+#[inline]
+pub const fn drive_command_mode(mode: OperationalMode) -> CommandMode {
+    match mode {
+        OperationalMode::Velocity => CommandMode::CyclicSyncVelocity,
+        OperationalMode::Torque => CommandMode::CyclicSyncTorque,
+        OperationalMode::Position | OperationalMode::Manual | OperationalMode::Test => {
+            CommandMode::CyclicSyncPosition
+        }
     }
 }
 
@@ -227,7 +251,7 @@ pub fn fill_cu_to_hal(
         }
     }
     // Zero out remaining axes.
-    for i in n..64 {
+    for i in n..segment.axes.len() {
         segment.axes[i] = CuAxisCommand::default();
     }
 }
@@ -297,6 +321,15 @@ mod tests {
         ControlOutputVector::default()
     }
 
+    #[test]
+    fn drive_mode_follows_operational_mode() {
+        assert_eq!(drive_command_mode(OperationalMode::Position), CommandMode::CyclicSyncPosition);
+        assert_eq!(drive_command_mode(OperationalMode::Velocity), CommandMode::CyclicSyncVelocity);
+        assert_eq!(drive_command_mode(OperationalMode::Torque), CommandMode::CyclicSyncTorque);
+        assert_eq!(drive_command_mode(OperationalMode::Manual), CommandMode::CyclicSyncPosition);
+        assert_eq!(drive_command_mode(OperationalMode::Test), CommandMode::CyclicSyncPosition);
+    }
+
     #[test]
     fn enable_when_standby() {
         let cmd = build_axis_command(PowerState::Standby, 0, zero_output());
@@ -307,13 +340,15 @@ mod tests {
     fn enable_when_motion() {
         let cmd = build_axis_command(PowerState::Motion, 1, zero_output());
         assert_eq!(cmd.enable, 1);
-        assert_eq!(cmd.mode, 1);
+        assert_eq!(cmd.brake_release, 1);
+        assert_eq!(cmd.command_mode, CommandMode::CyclicSyncVelocity as u8);
     }
 
     #[test]
     fn disable_when_power_off() {
         let cmd = build_axis_command(PowerState::PowerOff, 0, zero_output());
         assert_eq!(cmd.enable, 0);
+        assert_eq!(cmd.brake_release, 0);
     }
 
     #[test]
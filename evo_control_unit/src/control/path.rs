@@ -0,0 +1,1074 @@
+//! Coordinated multi-axis path interpolation (linear + circular).
+//!
+//! An [`AxisGroup`] (e.g. XYZABC) executes a queue of path segments and
+//! streams synchronized per-axis setpoints every cycle:
+//!
+//! - **Linear** segments move all group axes on a straight line.
+//! - **Arc** segments move two plane axes on a circle around a centre
+//!   (CW / CCW); remaining group axes move linearly (helix).
+//!
+//! ## Look-ahead
+//!
+//! Queued segments (up to the configured look-ahead depth) are planned
+//! backwards from a full stop at the end of the queue. Corner speeds are
+//! limited by the corner tolerance (junction deviation): the path may cut
+//! a corner by at most `corner_tolerance`, so `v² ≤ a·R` with
+//! `R = δ·sin(θ/2) / (1 − sin(θ/2))`. A tolerance of 0 stops at every corner.
+//!
+//! ## Limits
+//!
+//! Per-segment path velocity, acceleration and jerk are the tightest of
+//! the feed rate and each axis's `max_velocity` / `max_acceleration` /
+//! `max_jerk` scaled by the axis share of the motion. Arcs reserve half of
+//! the plane axes' acceleration for the centripetal part. Linear and
+//! rotary coordinates are combined in one Euclidean path length.
+//!
+//! The path acceleration changes by at most the jerk limit per second
+//! (S-curve along the path): each cycle takes the highest acceleration
+//! that neither overshoots the velocity limit once ramped out nor misses
+//! the segment exit speed with a jerk-limited brake. Jerk limits the
+//! tangential motion; the centripetal part of arcs is not jerk-limited.
+//!
+//! Segment storage is allocated once at construction; pushing, planning
+//! and stepping never allocate (RT-safe).
+
+use core::f64::consts::TAU;
+
+use evo_common::consts::MAX_GROUP_AXES;
+use evo_common::control_unit::config::{AxisGroupConfig, CuAxisConfig};
+
+use super::trajectory::{ProfilePhase, Setpoint};
+
+/// Allowed start/end radius mismatch of an arc, relative to the radius.
+pub const ARC_RADIUS_TOLERANCE: f64 = 1e-3;
+
+/// Segments shorter than this are dropped [user units].
+const MIN_SEGMENT_LENGTH: f64 = 1e-9;
+
+/// Share of the plane axes' acceleration reserved for centripetal motion.
+const ARC_CENTRIPETAL_SHARE: f64 = 0.5;
+
+/// Search steps for the braking acceleration and planned entry speeds.
+const BRAKE_SEARCH_STEPS: usize = 32;
+
+/// Coordinates of all group axes, in group order.
+pub type GroupVector = [f64; MAX_GROUP_AXES];
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+/// Kinematic limits of one group axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisLimits {
+    /// Maximum velocity [user units/s].
+    pub max_velocity: f64,
+    /// Maximum acceleration [user units/s²].
+    pub max_acceleration: f64,
+    /// Maximum jerk [user units/s³]; `f64::INFINITY` = not limited.
+    pub max_jerk: f64,
+}
+
+/// Arc direction in the selected plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArcDirection {
+    /// Clockwise (G2).
+    Clockwise,
+    /// Counter-clockwise (G3).
+    CounterClockwise,
+}
+
+/// Segment geometry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathGeometry {
+    /// Straight line to the end point.
+    Linear,
+    /// Circular arc in the plane spanned by group axes `u` and `v`.
+    ///
+    /// End point equal to the start point is a full circle.
+    Arc {
+        /// Group-local index of the first plane axis.
+        u: usize,
+        /// Group-local index of the second plane axis.
+        v: usize,
+        /// Arc centre `[u, v]` (absolute coordinates).
+        center: [f64; 2],
+        direction: ArcDirection,
+    },
+}
+
+/// One path segment request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathCommand {
+    /// End point (absolute) of the axes selected by `axis_mask`.
+    pub end: GroupVector,
+    /// Group-local bit mask of the coordinates given in `end`; the other
+    /// axes keep their position.
+    pub axis_mask: u8,
+    /// Path feed rate [user units/s]; 0 = limited by the axes only.
+    pub feed: f64,
+    pub geometry: PathGeometry,
+}
+
+/// Path segment rejection reasons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathError {
+    /// Look-ahead buffer is full.
+    BufferFull,
+    /// A controlled stop is in progress.
+    Stopping,
+    /// Negative or non-finite feed / coordinates.
+    InvalidParameter,
+    /// Arc plane axes are not distinct group axes.
+    InvalidPlane,
+    /// Start and end radius differ or the radius is zero.
+    InvalidArc,
+}
+
+/// Geometry resolved against the start point.
+#[derive(Debug, Clone, Copy, Default)]
+enum Shape {
+    #[default]
+    Linear,
+    Arc {
+        u: usize,
+        v: usize,
+        center: [f64; 2],
+        radius_start: f64,
+        radius_end: f64,
+        angle_start: f64,
+        /// Signed sweep angle (+ = CCW) [rad].
+        sweep: f64,
+    },
+}
+
+/// Queued segment with its planning data.
+#[derive(Debug, Clone, Copy, Default)]
+struct Segment {
+    start: GroupVector,
+    end: GroupVector,
+    shape: Shape,
+    length: f64,
+    /// Path velocity limit inside the segment.
+    v_max: f64,
+    /// Path acceleration limit inside the segment.
+    a_max: f64,
+    /// Path jerk limit inside the segment.
+    j_max: f64,
+    /// Corner limit at the segment entry.
+    v_junction: f64,
+    /// Planned entry velocity (backward pass).
+    v_entry: f64,
+}
+
+impl Segment {
+    /// Position `s` along the segment (0 ≤ s ≤ length).
+    fn point(&self, s: f64, n: usize) -> GroupVector {
+        let f = (s / self.length).clamp(0.0, 1.0);
+        let mut p = self.start;
+        for ((pk, e), s0) in p.iter_mut().zip(&self.end).zip(&self.start).take(n) {
+            *pk += (e - s0) * f;
+        }
+        if let Shape::Arc { u, v, center, radius_start, radius_end, angle_start, sweep } = self.shape
+        {
+            let angle = angle_start + sweep * f;
+            let radius = radius_start + (radius_end - radius_start) * f;
+            p[u] = center[0] + radius * angle.cos();
+            p[v] = center[1] + radius * angle.sin();
+        }
+        p
+    }
+
+    /// Unit tangent at `s` (direction of travel).
+    fn tangent(&self, s: f64, n: usize) -> GroupVector {
+        let mut t = [0.0; MAX_GROUP_AXES];
+        for ((tk, e), s0) in t.iter_mut().zip(&self.end).zip(&self.start).take(n) {
+            *tk = (e - s0) / self.length;
+        }
+        if let Shape::Arc { u, v, angle_start, sweep, radius_start, .. } = self.shape {
+            let f = (s / self.length).clamp(0.0, 1.0);
+            let angle = angle_start + sweep * f;
+            // Planar speed share of the path: r·|θ| / L.
+            let planar = radius_start * sweep / self.length;
+            t[u] = -planar * angle.sin();
+            t[v] = planar * angle.cos();
+        }
+        t
+    }
+}
+
+// ─── Path Planner ───────────────────────────────────────────────────
+
+/// Look-ahead path planner and interpolator for one axis group.
+#[derive(Debug, Clone)]
+pub struct PathPlanner {
+    axis_count: usize,
+    limits: [AxisLimits; MAX_GROUP_AXES],
+    corner_tolerance: f64,
+    /// Ring buffer, capacity = look-ahead depth.
+    segments: Box<[Segment]>,
+    head: usize,
+    len: usize,
+    /// Distance travelled in the head segment.
+    s: f64,
+    /// Path velocity.
+    v: f64,
+    /// Path acceleration.
+    a: f64,
+    /// Path velocity cap (SAFE_REDUCED_SPEED), `INFINITY` = none.
+    v_cap: f64,
+    stopping: bool,
+    phase: ProfilePhase,
+    setpoints: [Setpoint; MAX_GROUP_AXES],
+    /// End point of the last queued segment (start of the next one).
+    tail: GroupVector,
+}
+
+impl PathPlanner {
+    /// Create an idle planner at the origin.
+    ///
+    /// `limits` holds one entry per group axis (at most `MAX_GROUP_AXES`);
+    /// `lookahead` is the queue depth (≥ 1).
+    pub fn new(limits: &[AxisLimits], lookahead: usize, corner_tolerance: f64) -> Self {
+        let axis_count = limits.len().min(MAX_GROUP_AXES);
+        let mut lim = [AxisLimits { max_velocity: 0.0, max_acceleration: 0.0, max_jerk: 0.0 };
+            MAX_GROUP_AXES];
+        lim[..axis_count].copy_from_slice(&limits[..axis_count]);
+        Self {
+            axis_count,
+            limits: lim,
+            corner_tolerance: corner_tolerance.max(0.0),
+            segments: vec![Segment::default(); lookahead.max(1)].into_boxed_slice(),
+            head: 0,
+            len: 0,
+            s: 0.0,
+            v: 0.0,
+            a: 0.0,
+            v_cap: f64::INFINITY,
+            stopping: false,
+            phase: ProfilePhase::Idle,
+            setpoints: [Setpoint::default(); MAX_GROUP_AXES],
+            tail: [0.0; MAX_GROUP_AXES],
+        }
+    }
+
+    /// Number of group axes.
+    #[inline]
+    pub const fn axis_count(&self) -> usize {
+        self.axis_count
+    }
+
+    /// Whether segments are being executed.
+    #[inline]
+    pub const fn is_active(&self) -> bool {
+        self.len > 0
+    }
+
+    /// Queued segments (including the one in progress).
+    #[inline]
+    pub const fn queued(&self) -> usize {
+        self.len
+    }
+
+    /// Look-ahead depth.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Current path velocity [user units/s].
+    #[inline]
+    pub const fn path_velocity(&self) -> f64 {
+        self.v
+    }
+
+    /// Current path acceleration [user units/s²].
+    #[inline]
+    pub const fn path_acceleration(&self) -> f64 {
+        self.a
+    }
+
+    /// Phase of the last step (`Idle` when inactive).
+    #[inline]
+    pub const fn phase(&self) -> ProfilePhase {
+        self.phase
+    }
+
+    /// Setpoint of group axis `k` from the last step.
+    #[inline]
+    pub fn setpoint(&self, k: usize) -> Setpoint {
+        self.setpoints[k]
+    }
+
+    /// Drop all segments and rest at `position` (one entry per group axis).
+    pub fn hold(&mut self, position: &[f64]) {
+        let n = self.axis_count.min(position.len());
+        for (k, &p) in position.iter().enumerate().take(n) {
+            self.tail[k] = p;
+            self.setpoints[k] = Setpoint { position: p, velocity: 0.0, acceleration: 0.0 };
+        }
+        self.head = 0;
+        self.len = 0;
+        self.s = 0.0;
+        self.v = 0.0;
+        self.a = 0.0;
+        self.stopping = false;
+        self.phase = ProfilePhase::Idle;
+    }
+
+    /// Abort the path and hold the last commanded position.
+    pub fn cancel(&mut self) {
+        let mut p = [0.0; MAX_GROUP_AXES];
+        for (k, sp) in self.setpoints.iter().enumerate().take(self.axis_count) {
+            p[k] = sp.position;
+        }
+        self.hold(&p[..self.axis_count]);
+    }
+
+    /// Controlled stop along the path; queued segments are discarded once
+    /// the group is at rest.
+    pub fn stop(&mut self) {
+        if self.is_active() {
+            self.stopping = true;
+        }
+    }
+
+    /// Cap the path velocity (`f64::INFINITY` removes the cap).
+    #[inline]
+    pub fn set_velocity_limit(&mut self, limit: f64) {
+        self.v_cap = if limit > 0.0 { limit } else { f64::INFINITY };
+    }
+
+    /// Append a segment and re-plan the look-ahead queue.
+    ///
+    /// Zero-length segments are accepted and dropped.
+    pub fn push(&mut self, cmd: &PathCommand) -> Result<(), PathError> {
+        if self.stopping {
+            return Err(PathError::Stopping);
+        }
+        if self.len == self.segments.len() {
+            return Err(PathError::BufferFull);
+        }
+        if !cmd.feed.is_finite() || cmd.feed < 0.0 {
+            return Err(PathError::InvalidParameter);
+        }
+        let n = self.axis_count;
+        let start = self.tail;
+        let mut end = start;
+        for (k, e) in end.iter_mut().enumerate().take(n) {
+            if cmd.axis_mask & (1 << k) != 0 {
+                if !cmd.end[k].is_finite() {
+                    return Err(PathError::InvalidParameter);
+                }
+                *e = cmd.end[k];
+            }
+        }
+
+        let mut seg = Segment { start, end, ..Segment::default() };
+        self.resolve_shape(&mut seg, &cmd.geometry)?;
+        if seg.length < MIN_SEGMENT_LENGTH {
+            return Ok(());
+        }
+        self.apply_limits(&mut seg, cmd.feed);
+
+        // Corner limit against the previous queued segment.
+        seg.v_junction = if self.len == 0 {
+            0.0
+        } else {
+            let prev = &self.segments[self.index(self.len - 1)];
+            self.junction_velocity(prev, &seg)
+        };
+
+        if self.len == 0 {
+            self.head = 0;
+            self.s = 0.0;
+            self.v = 0.0;
+            self.a = 0.0;
+        }
+        let idx = self.index(self.len);
+        self.segments[idx] = seg;
+        self.len += 1;
+        self.tail = end;
+        self.plan();
+        Ok(())
+    }
+
+    /// Advance by `dt` seconds and update the per-axis setpoints.
+    pub fn step(&mut self, dt: f64) {
+        if !self.is_active() || dt <= 0.0 {
+            self.phase = ProfilePhase::Idle;
+            return;
+        }
+        let seg = self.segments[self.head];
+        let v_exit = if self.len > 1 { self.segments[self.index(1)].v_entry } else { 0.0 };
+        let a_max = seg.a_max;
+        // A jerk reaching full acceleration within one cycle is no limit.
+        let j = seg.j_max.min(a_max / dt);
+        let v_lim = if self.stopping { 0.0 } else { seg.v_max.min(self.v_cap) };
+
+        // Highest acceleration within one jerk step that does not overshoot
+        // the velocity limit, lowered until the exit speed stays reachable.
+        let lo = (self.a - j * dt).max(-a_max);
+        let hi = (self.a + j * dt).min(a_max);
+        let mut a_new = cap_acceleration(self.v, self.a, v_lim, j, dt).clamp(lo, hi);
+        // Braking distance beyond the segment exit after one cycle at `a1`.
+        let overrun = |a1: f64| {
+            let v1 = self.v + 0.5 * (self.a + a1) * dt;
+            let s1 = self.s + self.v * dt + (2.0 * self.a + a1) * dt * dt / 6.0;
+            if v1 <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            // Past the segment end only a speed at or below `v_exit` is fine.
+            braking_distance(v1, a1, v_exit, a_max, j) - (seg.length - s1).max(0.0)
+        };
+        if overrun(a_new) > 0.0 {
+            // The overrun is unimodal in `a1`: braking harder than the
+            // shortest brake only prolongs the ramp back. Find the shortest
+            // brake, then the gentlest one that still makes the exit.
+            let (mut left, mut right) = (lo, a_new);
+            for _ in 0..BRAKE_SEARCH_STEPS {
+                let m1 = left + (right - left) / 3.0;
+                let m2 = right - (right - left) / 3.0;
+                if overrun(m1) <= overrun(m2) {
+                    right = m2;
+                } else {
+                    left = m1;
+                }
+            }
+            let (mut ok, mut bad) = (0.5 * (left + right), a_new);
+            if overrun(ok) <= 0.0 {
+                for _ in 0..BRAKE_SEARCH_STEPS {
+                    let mid = 0.5 * (ok + bad);
+                    if overrun(mid) <= 0.0 {
+                        ok = mid;
+                    } else {
+                        bad = mid;
+                    }
+                }
+            }
+            a_new = ok;
+        }
+
+        let mut v_new = self.v + 0.5 * (self.a + a_new) * dt;
+        let mut ds = self.v * dt + (2.0 * self.a + a_new) * dt * dt / 6.0;
+        if v_new <= 0.0 {
+            // At rest: stopping, or at a stop point within one jerk step.
+            v_new = 0.0;
+            a_new = 0.0;
+            ds = if self.stopping { ds.max(0.0) } else { seg.length - self.s };
+        }
+
+        self.phase = if v_new > self.v + f64::EPSILON {
+            ProfilePhase::Accelerating
+        } else if v_new < self.v - f64::EPSILON {
+            ProfilePhase::Decelerating
+        } else {
+            ProfilePhase::ConstantVelocity
+        };
+
+        self.s += ds;
+        self.v = v_new;
+        self.a = a_new;
+
+        // Hand over to the next segments.
+        while self.len > 0 && self.s >= self.segments[self.head].length {
+            self.s -= self.segments[self.head].length;
+            if self.len == 1 {
+                let end = self.segments[self.head].end;
+                self.finish(&end, dt);
+                return;
+            }
+            self.head = self.index(1);
+            self.len -= 1;
+        }
+
+        if self.stopping && self.v <= 0.0 {
+            let seg = self.segments[self.head];
+            let here = seg.point(self.s, self.axis_count);
+            self.finish(&here, dt);
+            return;
+        }
+
+        let seg = self.segments[self.head];
+        let p = seg.point(self.s, self.axis_count);
+        let t = seg.tangent(self.s, self.axis_count);
+        for k in 0..self.axis_count {
+            let velocity = self.v * t[k];
+            let prev = self.setpoints[k];
+            self.setpoints[k] = Setpoint {
+                position: p[k],
+                velocity,
+                acceleration: (velocity - prev.velocity) / dt,
+            };
+        }
+    }
+
+    // ── Internals ──
+
+    #[inline]
+    fn index(&self, offset: usize) -> usize {
+        (self.head + offset) % self.segments.len()
+    }
+
+    /// Rest exactly at `end`; the queue is emptied.
+    fn finish(&mut self, end: &GroupVector, dt: f64) {
+        for (k, &e) in end.iter().enumerate().take(self.axis_count) {
+            let prev = self.setpoints[k];
+            self.setpoints[k] = Setpoint {
+                position: e,
+                velocity: 0.0,
+                acceleration: -prev.velocity / dt,
+            };
+        }
+        self.tail = *end;
+        self.head = 0;
+        self.len = 0;
+        self.s = 0.0;
+        self.v = 0.0;
+        self.a = 0.0;
+        self.stopping = false;
+        self.phase = ProfilePhase::Idle;
+    }
+
+    /// Resolve geometry and length against the segment start point.
+    fn resolve_shape(&self, seg: &mut Segment, geometry: &PathGeometry) -> Result<(), PathError> {
+        let n = self.axis_count;
+        match *geometry {
+            PathGeometry::Linear => {
+                seg.shape = Shape::Linear;
+                seg.length = (0..n)
+                    .map(|k| (seg.end[k] - seg.start[k]).powi(2))
+                    .sum::<f64>()
+                    .sqrt();
+            }
+            PathGeometry::Arc { u, v, center, direction } => {
+                if u >= n || v >= n || u == v {
+                    return Err(PathError::InvalidPlane);
+                }
+                if !center[0].is_finite() || !center[1].is_finite() {
+                    return Err(PathError::InvalidParameter);
+                }
+                let (su, sv) = (seg.start[u] - center[0], seg.start[v] - center[1]);
+                let (eu, ev) = (seg.end[u] - center[0], seg.end[v] - center[1]);
+                let radius_start = su.hypot(sv);
+                let radius_end = eu.hypot(ev);
+                if radius_start < MIN_SEGMENT_LENGTH
+                    || (radius_start - radius_end).abs() > ARC_RADIUS_TOLERANCE * radius_start
+                {
+                    return Err(PathError::InvalidArc);
+                }
+                let angle_start = sv.atan2(su);
+                let mut ccw = (ev.atan2(eu) - angle_start).rem_euclid(TAU);
+                if ccw < 1e-12 {
+                    ccw = TAU; // Full circle.
+                }
+                let sweep = match direction {
+                    ArcDirection::CounterClockwise => ccw,
+                    ArcDirection::Clockwise => ccw - TAU,
+                };
+                let sweep = if sweep == 0.0 { -TAU } else { sweep };
+                seg.shape = Shape::Arc { u, v, center, radius_start, radius_end, angle_start, sweep };
+
+                let helix: f64 = (0..n)
+                    .filter(|&k| k != u && k != v)
+                    .map(|k| (seg.end[k] - seg.start[k]).powi(2))
+                    .sum();
+                seg.length = ((radius_start * sweep).powi(2) + helix).sqrt();
+            }
+        }
+        Ok(())
+    }
+
+    /// Path velocity / acceleration limits from the feed and axis limits.
+    fn apply_limits(&self, seg: &mut Segment, feed: f64) {
+        let mut v_max = if feed > 0.0 { feed } else { f64::INFINITY };
+        let mut a_max = f64::INFINITY;
+        let mut j_max = f64::INFINITY;
+        let n = self.axis_count;
+
+        // Axis share of the path motion (max |tangent| component).
+        let mut share = [0.0f64; MAX_GROUP_AXES];
+        for (k, sh) in share.iter_mut().enumerate().take(n) {
+            *sh = ((seg.end[k] - seg.start[k]) / seg.length).abs();
+        }
+        if let Shape::Arc { u, v, radius_start, sweep, .. } = seg.shape {
+            let planar = (radius_start * sweep / seg.length).abs();
+            share[u] = planar;
+            share[v] = planar;
+
+            // Centripetal: (v·planar)² / r ≤ share · a_plane.
+            let a_plane = self.limits[u].max_acceleration.min(self.limits[v].max_acceleration);
+            let radius = radius_start;
+            v_max = v_max.min((ARC_CENTRIPETAL_SHARE * a_plane * radius).sqrt() / planar);
+            a_max = a_max.min((1.0 - ARC_CENTRIPETAL_SHARE) * a_plane / planar);
+        }
+        for (lim, &sk) in self.limits.iter().zip(&share).take(n) {
+            if sk > 0.0 {
+                v_max = v_max.min(lim.max_velocity / sk);
+                a_max = a_max.min(lim.max_acceleration / sk);
+                if lim.max_jerk > 0.0 {
+                    j_max = j_max.min(lim.max_jerk / sk);
+                }
+            }
+        }
+        seg.v_max = v_max;
+        seg.a_max = a_max;
+        seg.j_max = j_max;
+    }
+
+    /// Corner speed between `prev` (exit) and `next` (entry).
+    fn junction_velocity(&self, prev: &Segment, next: &Segment) -> f64 {
+        let n = self.axis_count;
+        let t1 = prev.tangent(prev.length, n);
+        let t2 = next.tangent(0.0, n);
+        let cos = (0..n).map(|k| t1[k] * t2[k]).sum::<f64>().clamp(-1.0, 1.0);
+        let v_limit = prev.v_max.min(next.v_max);
+
+        if cos > 1.0 - 1e-9 {
+            return v_limit; // Tangent continuation.
+        }
+        if cos < -1.0 + 1e-9 || self.corner_tolerance == 0.0 {
+            return 0.0; // Reversal or exact stop.
+        }
+        // Junction deviation: θ is the corner's interior angle.
+        let sin_half = (0.5 * (1.0 + cos)).sqrt();
+        let radius = self.corner_tolerance * sin_half / (1.0 - sin_half);
+        let a = prev.a_max.min(next.a_max);
+        (a * radius).sqrt().min(v_limit)
+    }
+
+    /// Backward pass: full stop at the end of the queue, each entry speed
+    /// reachable by a jerk-limited brake through the following segments.
+    fn plan(&mut self) {
+        let mut v_exit = 0.0;
+        for offset in (1..self.len).rev() {
+            let idx = self.index(offset);
+            let seg = &mut self.segments[idx];
+            let (a, j) = (seg.a_max, seg.j_max);
+            // Without jerk the brake reaches √(v² + 2aL); jerk only lowers it.
+            let (mut ok, mut bad) = (v_exit, (v_exit * v_exit + 2.0 * a * seg.length).sqrt());
+            if braking_distance(bad, 0.0, v_exit, a, j) <= seg.length {
+                ok = bad;
+            } else {
+                for _ in 0..BRAKE_SEARCH_STEPS {
+                    let mid = 0.5 * (ok + bad);
+                    if braking_distance(mid, 0.0, v_exit, a, j) <= seg.length {
+                        ok = mid;
+                    } else {
+                        bad = mid;
+                    }
+                }
+            }
+            seg.v_entry = seg.v_junction.min(ok);
+            v_exit = seg.v_entry;
+        }
+    }
+}
+
+/// Highest acceleration after one cycle of length `dt` whose ramp to zero
+/// at jerk `j` (one step per cycle) does not overshoot `v_lim`; negative
+/// while above it.
+fn cap_acceleration(v: f64, a: f64, v_lim: f64, j: f64, dt: f64) -> f64 {
+    // Velocity gain left after one cycle at constant acceleration.
+    let budget = v_lim - v - 0.5 * a * dt;
+    // Acceleration whose cycle plus ramp-out gains `gain`: k full jerk
+    // steps gain j·dt²·k(k+1)/2, the remainder r gains r·dt·(k+1).
+    let step = j * dt;
+    let inverse = |gain: f64| {
+        let k = ((1.0 + 8.0 * gain / (step * dt)).sqrt() - 1.0) * 0.5;
+        let k = k.floor().max(0.0);
+        k * step + (gain - step * dt * k * (k + 1.0) * 0.5) / (dt * (k + 1.0))
+    };
+    if budget >= 0.0 { inverse(budget) } else { -inverse(-budget) }
+}
+
+/// Distance to slow from velocity `v` and acceleration `a` to `v_exit`
+/// with jerk `j` and deceleration `a_max` (double-S brake).
+fn braking_distance(v: f64, a: f64, v_exit: f64, a_max: f64, j: f64) -> f64 {
+    let dv = v - v_exit;
+    if j.is_infinite() {
+        return (dv * (v + v_exit) / (2.0 * a_max)).max(0.0);
+    }
+    let peak_sq = 0.5 * a * a + j * dv;
+    if peak_sq <= a.min(0.0).powi(2) {
+        // Ramping the acceleration out already ends at or below `v_exit`.
+        return ramp(v, a, -j.copysign(a), a.abs() / j).0;
+    }
+    let peak = peak_sq.sqrt().min(a_max);
+    let hold = ((dv + (0.5 * a * a - peak * peak) / j) / peak).max(0.0);
+    let (d1, v1) = ramp(v, a, -j, (a + peak) / j);
+    let (d2, v2) = ramp(v1, -peak, 0.0, hold);
+    let (d3, _) = ramp(v2, -peak, j, peak / j);
+    d1 + d2 + d3
+}
+
+/// Distance and end velocity of `t` seconds at constant jerk.
+#[inline]
+fn ramp(v: f64, a: f64, jerk: f64, t: f64) -> (f64, f64) {
+    (
+        v * t + 0.5 * a * t * t + jerk * t * t * t / 6.0,
+        v + a * t + 0.5 * jerk * t * t,
+    )
+}
+
+// ─── Axis Group ─────────────────────────────────────────────────────
+
+/// Configured axis group bound to runtime axis indices.
+#[derive(Debug, Clone)]
+pub struct AxisGroup {
+    /// Group name from config.
+    pub name: String,
+    /// Runtime axis indices (0-based) in group order.
+    axes: [usize; MAX_GROUP_AXES],
+    axis_count: usize,
+    /// Path planner for the group.
+    pub planner: PathPlanner,
+}
+
+impl AxisGroup {
+    /// Resolve a group config against the configured axes.
+    ///
+    /// Returns `None` if a member axis is not configured.
+    pub fn from_config(group: &AxisGroupConfig, axes: &[CuAxisConfig]) -> Option<Self> {
+        let mut indices = [0usize; MAX_GROUP_AXES];
+        let mut limits =
+            [AxisLimits { max_velocity: 0.0, max_acceleration: 0.0, max_jerk: 0.0 }; MAX_GROUP_AXES];
+        let axis_count = group.axes.len().min(MAX_GROUP_AXES);
+        for (k, id) in group.axes.iter().take(axis_count).enumerate() {
+            let i = axes.iter().position(|a| a.axis_id == *id)?;
+            indices[k] = i;
+            limits[k] = AxisLimits {
+                max_velocity: axes[i].max_velocity,
+                max_acceleration: axes[i].max_acceleration,
+                max_jerk: axes[i].max_jerk,
+            };
+        }
+        Some(Self {
+            name: group.name.clone(),
+            axes: indices,
+            axis_count,
+            planner: PathPlanner::new(
+                &limits[..axis_count],
+                group.lookahead,
+                group.corner_tolerance,
+            ),
+        })
+    }
+
+    /// Runtime axis indices in group order.
+    #[inline]
+    pub fn members(&self) -> &[usize] {
+        &self.axes[..self.axis_count]
+    }
+
+    /// Runtime axis mask (bit N = axis index N).
+    #[inline]
+    pub fn mask(&self) -> u64 {
+        self.members().iter().fold(0, |m, &i| m | (1u64 << i))
+    }
+
+    /// Group-local index of runtime axis `axis`.
+    #[inline]
+    pub fn local_index(&self, axis: usize) -> Option<usize> {
+        self.members().iter().position(|&i| i == axis)
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 0.001;
+    const LIMITS: AxisLimits =
+        AxisLimits { max_velocity: 100.0, max_acceleration: 1000.0, max_jerk: 100_000.0 };
+
+    fn planner(axes: usize, tolerance: f64) -> PathPlanner {
+        let mut p = PathPlanner::new(&[LIMITS; MAX_GROUP_AXES][..axes], 8, tolerance);
+        p.hold(&[0.0; MAX_GROUP_AXES][..axes]);
+        p
+    }
+
+    fn linear(end: &[f64], feed: f64) -> PathCommand {
+        let mut e = [0.0; MAX_GROUP_AXES];
+        e[..end.len()].copy_from_slice(end);
+        PathCommand {
+            end: e,
+            axis_mask: (1u8 << end.len()) - 1,
+            feed,
+            geometry: PathGeometry::Linear,
+        }
+    }
+
+    fn arc(end: [f64; 2], center: [f64; 2], direction: ArcDirection, feed: f64) -> PathCommand {
+        PathCommand {
+            geometry: PathGeometry::Arc { u: 0, v: 1, center, direction },
+            ..linear(&end, feed)
+        }
+    }
+
+    /// Step until idle; returns (max path velocity, max axis velocity,
+    /// trace of positions).
+    fn run(p: &mut PathPlanner) -> (f64, f64, Vec<GroupVector>) {
+        let (mut v_path, mut v_axis) = (0.0f64, 0.0f64);
+        let mut trace = Vec::new();
+        for _ in 0..100_000 {
+            if !p.is_active() {
+                return (v_path, v_axis, trace);
+            }
+            p.step(DT);
+            v_path = v_path.max(p.path_velocity());
+            let mut pos = [0.0; MAX_GROUP_AXES];
+            for (k, pk) in pos.iter_mut().enumerate().take(p.axis_count()) {
+                *pk = p.setpoint(k).position;
+                v_axis = v_axis.max(p.setpoint(k).velocity.abs());
+            }
+            trace.push(pos);
+        }
+        panic!("path did not complete");
+    }
+
+    #[test]
+    fn linear_move_is_synchronized() {
+        let mut p = planner(3, 0.0);
+        p.push(&linear(&[30.0, 40.0, 0.0], 50.0)).unwrap();
+        let (v_path, _, trace) = run(&mut p);
+
+        assert!((v_path - 50.0).abs() < 1e-9);
+        // Every sample lies on the line (y = 4/3 x).
+        for pos in &trace {
+            assert!((pos[1] - pos[0] * 4.0 / 3.0).abs() < 1e-9);
+        }
+        assert_eq!(p.setpoint(0).position, 30.0);
+        assert_eq!(p.setpoint(1).position, 40.0);
+        assert_eq!(p.setpoint(1).velocity, 0.0);
+        assert_eq!(p.phase(), ProfilePhase::Idle);
+    }
+
+    #[test]
+    fn axis_limits_bound_path_velocity() {
+        let slow = AxisLimits { max_velocity: 10.0, ..LIMITS };
+        let mut p = PathPlanner::new(&[LIMITS, slow], 4, 0.0);
+        p.hold(&[0.0, 0.0]);
+        // Diagonal: the slow axis moves at v/√2 ≤ 10 → v ≤ 14.14.
+        p.push(&linear(&[50.0, 50.0], 0.0)).unwrap();
+        let (v_path, v_axis, _) = run(&mut p);
+        assert!((v_path - 10.0 * 2f64.sqrt()).abs() < 1e-6, "v_path {v_path}");
+        assert!(v_axis <= 10.0 + 1e-9);
+    }
+
+    #[test]
+    fn ccw_quarter_arc_stays_on_radius() {
+        let mut p = planner(2, 0.0);
+        p.hold(&[10.0, 0.0]);
+        p.push(&arc([0.0, 10.0], [0.0, 0.0], ArcDirection::CounterClockwise, 20.0)).unwrap();
+        let (_, _, trace) = run(&mut p);
+        for pos in &trace {
+            assert!((pos[0].hypot(pos[1]) - 10.0).abs() < 1e-9);
+            assert!(pos[0] >= -1e-9 && pos[1] >= -1e-9, "left first quadrant: {pos:?}");
+        }
+        assert_eq!(p.setpoint(0).position, 0.0);
+        assert_eq!(p.setpoint(1).position, 10.0);
+    }
+
+    #[test]
+    fn cw_arc_takes_the_long_way_and_full_circle() {
+        let mut p = planner(2, 0.0);
+        p.hold(&[10.0, 0.0]);
+        p.push(&arc([0.0, 10.0], [0.0, 0.0], ArcDirection::Clockwise, 50.0)).unwrap();
+        let (_, _, trace) = run(&mut p);
+        // CW from (10,0) to (0,10) passes through (0,-10) and (-10,0).
+        assert!(trace.iter().any(|p| p[1] < -9.9));
+        assert!(trace.iter().any(|p| p[0] < -9.9));
+
+        p.push(&arc([0.0, 10.0], [0.0, 0.0], ArcDirection::CounterClockwise, 50.0)).unwrap();
+        let (_, _, trace) = run(&mut p);
+        assert!(trace.iter().any(|p| p[1] < -9.9), "full circle expected");
+    }
+
+    #[test]
+    fn arc_respects_centripetal_limit() {
+        let mut p = planner(2, 0.0);
+        p.hold(&[1.0, 0.0]);
+        p.push(&arc([1.0, 0.0], [0.0, 0.0], ArcDirection::CounterClockwise, 100.0)).unwrap();
+        let (v_path, _, _) = run(&mut p);
+        // √(0.5 · 1000 · 1) ≈ 22.4 mm/s on a 1 mm radius.
+        assert!(v_path <= (0.5f64 * 1000.0).sqrt() + 1e-9, "v_path {v_path}");
+    }
+
+    #[test]
+    fn invalid_arcs_rejected() {
+        let mut p = planner(2, 0.0);
+        p.hold(&[10.0, 0.0]);
+        let bad_radius = arc([0.0, 12.0], [0.0, 0.0], ArcDirection::Clockwise, 10.0);
+        assert_eq!(p.push(&bad_radius), Err(PathError::InvalidArc));
+        let zero_radius = arc([10.0, 0.0], [10.0, 0.0], ArcDirection::Clockwise, 10.0);
+        assert_eq!(p.push(&zero_radius), Err(PathError::InvalidArc));
+        let mut same_axis = arc([0.0, 10.0], [0.0, 0.0], ArcDirection::Clockwise, 10.0);
+        same_axis.geometry = PathGeometry::Arc {
+            u: 1,
+            v: 1,
+            center: [0.0, 0.0],
+            direction: ArcDirection::Clockwise,
+        };
+        assert_eq!(p.push(&same_axis), Err(PathError::InvalidPlane));
+        assert_eq!(p.push(&linear(&[1.0, 1.0], -1.0)), Err(PathError::InvalidParameter));
+        assert!(!p.is_active());
+    }
+
+    #[test]
+    fn corner_tolerance_blends_corners() {
+        // Exact stop: velocity drops to zero at the corner.
+        let mut exact = planner(2, 0.0);
+        exact.push(&linear(&[20.0, 0.0], 50.0)).unwrap();
+        exact.push(&linear(&[20.0, 20.0], 50.0)).unwrap();
+        let mut min_mid = f64::MAX;
+        while exact.is_active() {
+            exact.step(DT);
+            if exact.setpoint(0).position > 19.0 && exact.setpoint(1).position < 1.0 {
+                min_mid = min_mid.min(exact.path_velocity());
+            }
+        }
+        assert!(min_mid < 1.0, "exact stop expected, min {min_mid}");
+
+        // With tolerance the corner is passed at speed.
+        let mut blended = planner(2, 0.5);
+        blended.push(&linear(&[20.0, 0.0], 50.0)).unwrap();
+        blended.push(&linear(&[20.0, 20.0], 50.0)).unwrap();
+        let mut min_mid = f64::MAX;
+        while blended.is_active() {
+            blended.step(DT);
+            if blended.setpoint(0).position > 19.0 && blended.setpoint(1).position < 1.0 {
+                min_mid = min_mid.min(blended.path_velocity());
+            }
+        }
+        assert!(min_mid > 10.0, "blended corner expected, min {min_mid}");
+        assert_eq!(blended.setpoint(1).position, 20.0);
+    }
+
+    #[test]
+    fn collinear_segments_keep_speed() {
+        let mut p = planner(2, 0.0);
+        p.push(&linear(&[10.0, 0.0], 50.0)).unwrap();
+        p.push(&linear(&[20.0, 0.0], 50.0)).unwrap();
+        let mut min_mid = f64::MAX;
+        while p.is_active() {
+            p.step(DT);
+            let x = p.setpoint(0).position;
+            if (9.0..11.0).contains(&x) {
+                min_mid = min_mid.min(p.path_velocity());
+            }
+        }
+        assert!((min_mid - 50.0).abs() < 1e-9, "{min_mid}");
+    }
+
+    #[test]
+    fn path_acceleration_is_jerk_limited() {
+        let lim = AxisLimits { max_jerk: 20_000.0, ..LIMITS };
+        let mut p = PathPlanner::new(&[lim, lim], 8, 0.0);
+        p.hold(&[0.0, 0.0]);
+        // Diagonal then straight on, with an exact stop in the corner.
+        p.push(&linear(&[30.0, 40.0], 50.0)).unwrap();
+        p.push(&linear(&[30.0, 100.0], 50.0)).unwrap();
+        let (mut a_prev, mut steps) = (0.0, 0);
+        let mut axis_prev = [0.0; 2];
+        let mut moving = false;
+        while p.is_active() {
+            p.step(DT);
+            steps += 1;
+            let a = p.path_acceleration();
+            // Path limits are the axis limits over the largest share (0.8).
+            // Coming to rest ends the last ramp within one cycle.
+            let j_step = if p.path_velocity() > 0.0 { 1.0 } else { 2.0 } * 25_000.0 * DT;
+            assert!((a - a_prev).abs() <= j_step + 1e-6, "jerk at step {steps}");
+            assert!(a.abs() <= 1250.0 + 1e-9);
+            a_prev = a;
+            let was_moving = std::mem::replace(&mut moving, p.path_velocity() > 0.0);
+            for (k, prev) in axis_prev.iter_mut().enumerate() {
+                let acc = p.setpoint(k).acceleration;
+                if moving && was_moving {
+                    assert!((acc - *prev).abs() <= 20_000.0 * DT + 1e-6, "axis {k} jerk");
+                }
+                *prev = acc;
+            }
+        }
+        // Per segment L/V + V/A + A/J: 1 + 0.04 + 0.05 s, then 1.2 + 0.1 s.
+        assert!((2380..2400).contains(&steps), "took {steps} cycles");
+        assert_eq!(p.setpoint(0).position, 30.0);
+        assert_eq!(p.setpoint(1).position, 100.0);
+    }
+
+    #[test]
+    fn buffer_limits_lookahead() {
+        let mut p = PathPlanner::new(&[LIMITS], 2, 0.0);
+        p.hold(&[0.0]);
+        p.push(&linear(&[1.0], 10.0)).unwrap();
+        p.push(&linear(&[2.0], 10.0)).unwrap();
+        assert_eq!(p.push(&linear(&[3.0], 10.0)), Err(PathError::BufferFull));
+        assert_eq!(p.queued(), 2);
+    }
+
+    #[test]
+    fn unmasked_axes_keep_position() {
+        let mut p = planner(3, 0.0);
+        p.hold(&[0.0, 0.0, 7.0]);
+        let mut cmd = linear(&[5.0, 5.0, 0.0], 10.0);
+        cmd.axis_mask = 0b011;
+        p.push(&cmd).unwrap();
+        run(&mut p);
+        assert_eq!(p.setpoint(2).position, 7.0);
+        assert_eq!(p.setpoint(0).position, 5.0);
+    }
+
+    #[test]
+    fn stop_decelerates_and_discards_queue() {
+        let mut p = planner(1, 0.0);
+        p.push(&linear(&[100.0], 50.0)).unwrap();
+        p.push(&linear(&[200.0], 50.0)).unwrap();
+        for _ in 0..500 {
+            p.step(DT);
+        }
+        p.stop();
+        assert_eq!(p.push(&linear(&[300.0], 50.0)), Err(PathError::Stopping));
+        let mut steps = 0;
+        while p.is_active() {
+            p.step(DT);
+            assert_ne!(p.phase(), ProfilePhase::Accelerating);
+            steps += 1;
+        }
+        // 50 mm/s at 1000 mm/s² → ~50 cycles.
+        assert!(steps <= 60, "stop took {steps} cycles");
+        assert!(p.setpoint(0).position < 100.0);
+        assert_eq!(p.setpoint(0).velocity, 0.0);
+    }
+
+    #[test]
+    fn velocity_limit_caps_path_speed() {
+        let mut p = planner(1, 0.0);
+        p.push(&linear(&[100.0], 80.0)).unwrap();
+        for _ in 0..200 {
+            p.step(DT);
+        }
+        p.set_velocity_limit(20.0);
+        for _ in 0..200 {
+            p.step(DT);
+        }
+        assert!((p.path_velocity() - 20.0).abs() < 1e-9);
+        p.set_velocity_limit(f64::INFINITY);
+        run(&mut p);
+        assert_eq!(p.setpoint(0).position, 100.0);
+    }
+
+    #[test]
+    fn cancel_holds_position() {
+        let mut p = planner(2, 0.0);
+        p.push(&linear(&[10.0, 10.0], 50.0)).unwrap();
+        for _ in 0..100 {
+            p.step(DT);
+        }
+        let x = p.setpoint(0).position;
+        p.cancel();
+        assert!(!p.is_active());
+        assert_eq!(p.setpoint(0).position, x);
+        assert_eq!(p.setpoint(0).velocity, 0.0);
+    }
+}
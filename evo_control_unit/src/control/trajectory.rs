@@ -0,0 +1,771 @@
+//! Jerk-limited trajectory generation (S-curve).
+//!
+//! Turns `MoveAbsolute` / `MoveRelative` / `MoveVelocity` / jog commands
+//! into per-cycle position, velocity and acceleration setpoints for
+//! [`ControlInput`](super::output::ControlInput).
+//!
+//! Point-to-point moves use the 7-segment double-S profile (jerk ±J,
+//! acceleration ≤ A, velocity ≤ V). Profiles are stored as constant-jerk
+//! segments and sampled in closed form, so setpoints do not drift.
+//!
+//! A new target, a new velocity or a controlled stop replans from the
+//! current commanded state:
+//! 1. Ramp acceleration to zero (one jerk segment).
+//! 2. Stop first if the target lies behind or inside the stopping distance.
+//! 3. Double-S to the target, or velocity ramp + open-ended cruise.
+//!
+//! Fixed-size storage — planning and sampling never allocate (RT-safe).
+
+/// Maximum number of constant-jerk segments in one profile.
+///
+/// Worst case: accel→0 (1) + stop (3) + ramp up (3) + cruise (1) + ramp down (3).
+pub const MAX_SEGMENTS: usize = 12;
+
+/// Bisection iterations for the peak velocity search.
+const PEAK_SEARCH_ITERATIONS: usize = 60;
+
+/// Distances below this are treated as "at target" [user units].
+const POSITION_EPSILON: f64 = 1e-9;
+
+// ─── Types ──────────────────────────────────────────────────────────
+
+/// Kinematic limits for one profile (all strictly positive).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrajectoryLimits {
+    /// Maximum velocity [user units/s].
+    pub max_velocity: f64,
+    /// Maximum acceleration (|v| increasing) [user units/s²].
+    pub max_acceleration: f64,
+    /// Maximum deceleration (|v| decreasing) [user units/s²].
+    pub max_deceleration: f64,
+    /// Maximum jerk [user units/s³].
+    pub max_jerk: f64,
+}
+
+impl TrajectoryLimits {
+    /// All limits finite and strictly positive.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        [
+            self.max_velocity,
+            self.max_acceleration,
+            self.max_deceleration,
+            self.max_jerk,
+        ]
+        .iter()
+        .all(|v| v.is_finite() && *v > 0.0)
+    }
+}
+
+/// Commanded kinematic state produced every cycle.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Setpoint {
+    /// Target position [user units].
+    pub position: f64,
+    /// Target velocity [user units/s].
+    pub velocity: f64,
+    /// Target acceleration [user units/s²].
+    pub acceleration: f64,
+}
+
+/// Profile phase of the active segment, mapped onto `MotionState`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProfilePhase {
+    /// No active profile.
+    #[default]
+    Idle,
+    /// |v| increasing.
+    Accelerating,
+    /// Cruising at constant velocity.
+    ConstantVelocity,
+    /// |v| decreasing.
+    Decelerating,
+}
+
+/// What the active profile is heading for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Goal {
+    /// Come to rest wherever the profile ends.
+    Hold,
+    /// Come to rest exactly at this position.
+    Position(f64),
+    /// Reach and keep this velocity.
+    Velocity(f64),
+}
+
+/// One constant-jerk segment with its start state.
+#[derive(Debug, Clone, Copy, Default)]
+struct Segment {
+    duration: f64,
+    jerk: f64,
+    phase: ProfilePhase,
+    start: Setpoint,
+}
+
+impl Segment {
+    /// Closed-form state `t` seconds into the segment.
+    #[inline]
+    fn sample(&self, t: f64) -> Setpoint {
+        let s = &self.start;
+        let j = self.jerk;
+        Setpoint {
+            position: s.position
+                + s.velocity * t
+                + s.acceleration * t * t / 2.0
+                + j * t * t * t / 6.0,
+            velocity: s.velocity + s.acceleration * t + j * t * t / 2.0,
+            acceleration: s.acceleration + j * t,
+        }
+    }
+}
+
+// ─── Profile Builder ────────────────────────────────────────────────
+
+/// Jerk-phase and constant-acceleration durations `(t_j, t_a)` of a
+/// velocity ramp by `dv` (≥ 0) starting and ending at zero acceleration.
+#[inline]
+fn ramp_times(dv: f64, accel: f64, jerk: f64) -> (f64, f64) {
+    if dv * jerk >= accel * accel {
+        (accel / jerk, dv / accel - accel / jerk)
+    } else {
+        ((dv / jerk).sqrt(), 0.0)
+    }
+}
+
+/// Distance covered by a ramp between two non-negative speeds.
+#[inline]
+fn ramp_distance(from: f64, to: f64, limits: &TrajectoryLimits) -> f64 {
+    let accel = if to > from {
+        limits.max_acceleration
+    } else {
+        limits.max_deceleration
+    };
+    let (tj, ta) = ramp_times((to - from).abs(), accel, limits.max_jerk);
+    (from + to) / 2.0 * (2.0 * tj + ta)
+}
+
+/// Appends constant-jerk segments while tracking the end state.
+struct Builder<'a> {
+    segments: &'a mut [Segment; MAX_SEGMENTS],
+    count: usize,
+    state: Setpoint,
+    limits: TrajectoryLimits,
+}
+
+impl<'a> Builder<'a> {
+    fn new(
+        segments: &'a mut [Segment; MAX_SEGMENTS],
+        start: Setpoint,
+        limits: TrajectoryLimits,
+    ) -> Self {
+        Self {
+            segments,
+            count: 0,
+            state: start,
+            limits,
+        }
+    }
+
+    fn push(&mut self, duration: f64, jerk: f64, phase: ProfilePhase) {
+        if duration <= 0.0 || self.count == MAX_SEGMENTS {
+            return;
+        }
+        let seg = Segment {
+            duration,
+            jerk,
+            phase,
+            start: self.state,
+        };
+        self.segments[self.count] = seg;
+        self.count += 1;
+        if duration.is_finite() {
+            self.state = seg.sample(duration);
+        }
+    }
+
+    /// Bring acceleration to zero with maximum jerk.
+    fn accel_to_zero(&mut self) {
+        let a = self.state.acceleration;
+        if a == 0.0 {
+            return;
+        }
+        let phase = if a * self.state.velocity >= 0.0 {
+            ProfilePhase::Accelerating
+        } else {
+            ProfilePhase::Decelerating
+        };
+        let j = self.limits.max_jerk;
+        self.push(a.abs() / j, -a.signum() * j, phase);
+        self.state.acceleration = 0.0;
+    }
+
+    /// Jerk-limited velocity ramp (zero acceleration at both ends).
+    ///
+    /// Ramps crossing zero velocity are split into a stop and a start.
+    fn ramp_to(&mut self, v1: f64) {
+        let v0 = self.state.velocity;
+        if v0 * v1 < 0.0 {
+            self.ramp_to(0.0);
+            self.ramp_to(v1);
+            return;
+        }
+        let dv = v1 - v0;
+        if dv == 0.0 {
+            return;
+        }
+        let (accel, phase) = if v1.abs() > v0.abs() {
+            (self.limits.max_acceleration, ProfilePhase::Accelerating)
+        } else {
+            (self.limits.max_deceleration, ProfilePhase::Decelerating)
+        };
+        let j = dv.signum() * self.limits.max_jerk;
+        let (tj, ta) = ramp_times(dv.abs(), accel, self.limits.max_jerk);
+        self.push(tj, j, phase);
+        self.push(ta, 0.0, phase);
+        self.push(tj, -j, phase);
+        self.state.velocity = v1;
+        self.state.acceleration = 0.0;
+    }
+
+    /// Constant-velocity segment (`f64::INFINITY` = open-ended).
+    fn cruise(&mut self, duration: f64) {
+        self.push(duration, 0.0, ProfilePhase::ConstantVelocity);
+    }
+
+    /// Double-S from the current speed to rest over `distance` (> 0) in
+    /// direction `dir`. Requires the stopping distance to fit.
+    fn double_s(&mut self, dir: f64, distance: f64) {
+        let lim = self.limits;
+        let v0 = (self.state.velocity * dir).max(0.0);
+        let total = |vp: f64| ramp_distance(v0, vp, &lim) + ramp_distance(vp, 0.0, &lim);
+
+        let vmax = lim.max_velocity;
+        let peak = if total(vmax) <= distance {
+            vmax
+        } else if v0 <= vmax {
+            // Largest peak in [v0, vmax] that still fits.
+            let (mut lo, mut hi) = (v0, vmax);
+            for _ in 0..PEAK_SEARCH_ITERATIONS {
+                let mid = 0.5 * (lo + hi);
+                if total(mid) <= distance { lo = mid } else { hi = mid }
+            }
+            lo
+        } else {
+            // Already above vmax with too little room: smallest peak in
+            // [vmax, v0] that still fits.
+            let (mut lo, mut hi) = (vmax, v0);
+            for _ in 0..PEAK_SEARCH_ITERATIONS {
+                let mid = 0.5 * (lo + hi);
+                if total(mid) <= distance { hi = mid } else { lo = mid }
+            }
+            hi
+        };
+
+        self.ramp_to(dir * peak);
+        let remaining = distance - total(peak);
+        if peak > 0.0 && remaining > 0.0 {
+            self.cruise(remaining / peak);
+        }
+        self.ramp_to(0.0);
+    }
+}
+
+// ─── Trajectory Generator ───────────────────────────────────────────
+
+/// Per-axis S-curve trajectory generator.
+///
+/// Holds the commanded state between cycles; [`step`](Self::step)
+/// advances it by one cycle. Inactive generators keep returning the last
+/// commanded state.
+#[derive(Debug, Clone)]
+pub struct TrajectoryGenerator {
+    segments: [Segment; MAX_SEGMENTS],
+    count: usize,
+    index: usize,
+    /// Time spent in the current segment [s].
+    elapsed: f64,
+    current: Setpoint,
+    goal: Goal,
+    limits: TrajectoryLimits,
+    active: bool,
+}
+
+impl Default for TrajectoryGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrajectoryGenerator {
+    /// Create an inactive generator at position 0.
+    pub const fn new() -> Self {
+        Self {
+            segments: [Segment {
+                duration: 0.0,
+                jerk: 0.0,
+                phase: ProfilePhase::Idle,
+                start: Setpoint {
+                    position: 0.0,
+                    velocity: 0.0,
+                    acceleration: 0.0,
+                },
+            }; MAX_SEGMENTS],
+            count: 0,
+            index: 0,
+            elapsed: 0.0,
+            current: Setpoint {
+                position: 0.0,
+                velocity: 0.0,
+                acceleration: 0.0,
+            },
+            goal: Goal::Hold,
+            limits: TrajectoryLimits {
+                max_velocity: 0.0,
+                max_acceleration: 0.0,
+                max_deceleration: 0.0,
+                max_jerk: 0.0,
+            },
+            active: false,
+        }
+    }
+
+    /// Whether a profile is being executed.
+    #[inline]
+    pub const fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Last commanded state.
+    #[inline]
+    pub const fn setpoint(&self) -> Setpoint {
+        self.current
+    }
+
+    /// Phase of the active segment (`Idle` when inactive).
+    #[inline]
+    pub fn phase(&self) -> ProfilePhase {
+        if self.active && self.index < self.count {
+            self.segments[self.index].phase
+        } else {
+            ProfilePhase::Idle
+        }
+    }
+
+    /// Final position of the active move (`None` for velocity moves,
+    /// stops and when inactive).
+    #[inline]
+    pub const fn target(&self) -> Option<f64> {
+        match self.goal {
+            Goal::Position(target) if self.active => Some(target),
+            _ => None,
+        }
+    }
+
+    /// Limits of the active profile.
+    #[inline]
+    pub const fn limits(&self) -> TrajectoryLimits {
+        self.limits
+    }
+
+    /// Deactivate and rest at `position` (bumpless start point for the
+    /// next profile).
+    pub fn hold(&mut self, position: f64) {
+        self.current = Setpoint {
+            position,
+            velocity: 0.0,
+            acceleration: 0.0,
+        };
+        self.goal = Goal::Hold;
+        self.active = false;
+    }
+
+    /// Abort the profile and hold the last commanded position.
+    pub fn cancel(&mut self) {
+        self.hold(self.current.position);
+    }
+
+    /// Start (or retarget) a point-to-point move to `target`.
+    ///
+    /// Returns `false` (profile unchanged) if `limits` are invalid.
+    pub fn move_to(&mut self, target: f64, limits: TrajectoryLimits) -> bool {
+        self.start(Goal::Position(target), limits)
+    }
+
+    /// Ramp to `velocity` and keep it until the next command.
+    ///
+    /// `velocity` is clamped to `±limits.max_velocity`.
+    pub fn move_velocity(&mut self, velocity: f64, limits: TrajectoryLimits) -> bool {
+        if !limits.is_valid() {
+            return false;
+        }
+        let v = velocity.clamp(-limits.max_velocity, limits.max_velocity);
+        self.start(Goal::Velocity(v), limits)
+    }
+
+    /// Controlled stop with the current limits (no-op when inactive).
+    pub fn stop(&mut self) {
+        if self.active {
+            self.goal = Goal::Hold;
+            self.replan();
+        }
+    }
+
+    /// Take over the moving state `from` (e.g. an aborted path) and stop
+    /// under control with `limits`.
+    ///
+    /// Returns `false` (profile unchanged) if `limits` are invalid.
+    pub fn stop_from(&mut self, from: Setpoint, limits: TrajectoryLimits) -> bool {
+        if !limits.is_valid() {
+            return false;
+        }
+        self.current = from;
+        self.limits = limits;
+        self.goal = Goal::Hold;
+        self.replan();
+        true
+    }
+
+    /// Lower the velocity limit of the active profile (SAFE_REDUCED_SPEED).
+    ///
+    /// Replans only if `max_velocity` is below the current limit.
+    pub fn limit_velocity(&mut self, max_velocity: f64) {
+        if !self.active
+            || max_velocity.is_nan()
+            || max_velocity <= 0.0
+            || max_velocity >= self.limits.max_velocity
+        {
+            return;
+        }
+        self.limits.max_velocity = max_velocity;
+        if let Goal::Velocity(v) = self.goal {
+            self.goal = Goal::Velocity(v.clamp(-max_velocity, max_velocity));
+        }
+        self.replan();
+    }
+
+    /// Advance by `dt` seconds and return the new setpoint.
+    pub fn step(&mut self, dt: f64) -> Setpoint {
+        if !self.active {
+            return self.current;
+        }
+        self.elapsed += dt;
+        while self.index < self.count && self.elapsed >= self.segments[self.index].duration {
+            self.elapsed -= self.segments[self.index].duration;
+            self.index += 1;
+        }
+        if self.index < self.count {
+            self.current = self.segments[self.index].sample(self.elapsed);
+        } else {
+            self.finish();
+        }
+        self.current
+    }
+
+    fn start(&mut self, goal: Goal, limits: TrajectoryLimits) -> bool {
+        if !limits.is_valid() {
+            return false;
+        }
+        if !self.active {
+            // Start from rest at the last commanded position.
+            self.current.velocity = 0.0;
+            self.current.acceleration = 0.0;
+        }
+        self.limits = limits;
+        self.goal = goal;
+        self.replan();
+        true
+    }
+
+    /// Rebuild the segment list from the current commanded state.
+    fn replan(&mut self) {
+        let mut b = Builder::new(&mut self.segments, self.current, self.limits);
+        b.accel_to_zero();
+
+        match self.goal {
+            Goal::Hold => b.ramp_to(0.0),
+            Goal::Velocity(v) => {
+                b.ramp_to(v);
+                if v != 0.0 {
+                    b.cruise(f64::INFINITY);
+                }
+            }
+            Goal::Position(target) => {
+                let v = b.state.velocity;
+                let h = target - b.state.position;
+                let stop = ramp_distance(v.abs(), 0.0, &b.limits) * v.signum();
+                // Target behind us or inside the stopping distance: stop first.
+                if v != 0.0 && (h * v < 0.0 || stop.abs() > h.abs()) {
+                    b.ramp_to(0.0);
+                }
+                let h = target - b.state.position;
+                if h.abs() > POSITION_EPSILON {
+                    b.double_s(h.signum(), h.abs());
+                }
+            }
+        }
+
+        self.count = b.count;
+        self.index = 0;
+        self.elapsed = 0.0;
+        self.active = true;
+        if self.count == 0 {
+            self.finish();
+        }
+    }
+
+    /// Profile complete: rest exactly at the goal.
+    fn finish(&mut self) {
+        let position = match self.goal {
+            Goal::Position(target) => target,
+            _ if self.count > 0 => {
+                let last = &self.segments[self.count - 1];
+                last.sample(last.duration).position
+            }
+            _ => self.current.position,
+        };
+        self.hold(position);
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 0.001;
+    const TOL: f64 = 1e-6;
+
+    fn limits() -> TrajectoryLimits {
+        TrajectoryLimits {
+            max_velocity: 100.0,
+            max_acceleration: 1000.0,
+            max_deceleration: 500.0,
+            max_jerk: 20_000.0,
+        }
+    }
+
+    /// Run until idle (bounded), checking limits and continuity.
+    fn run(tg: &mut TrajectoryGenerator, max_steps: usize) -> Vec<Setpoint> {
+        let lim = tg.limits();
+        let mut prev = tg.setpoint();
+        let mut out = Vec::new();
+        for _ in 0..max_steps {
+            if !tg.is_active() {
+                break;
+            }
+            let sp = tg.step(DT);
+            assert!(sp.velocity.abs() <= lim.max_velocity + TOL, "v {}", sp.velocity);
+            let a_max = lim.max_acceleration.max(lim.max_deceleration);
+            assert!(sp.acceleration.abs() <= a_max + TOL, "a {}", sp.acceleration);
+            assert!(
+                (sp.acceleration - prev.acceleration).abs() <= lim.max_jerk * DT + TOL,
+                "jerk limit violated"
+            );
+            assert!((sp.velocity - prev.velocity).abs() <= a_max * DT + TOL, "velocity jump");
+            prev = sp;
+            out.push(sp);
+        }
+        out
+    }
+
+    #[test]
+    fn rest_to_rest_move_reaches_target() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.hold(5.0);
+        assert!(tg.move_to(105.0, limits()));
+        assert_eq!(tg.target(), Some(105.0));
+        let trace = run(&mut tg, 10_000);
+
+        assert!(!tg.is_active());
+        assert_eq!(tg.target(), None);
+        let end = tg.setpoint();
+        assert_eq!(end.position, 105.0);
+        assert_eq!(end.velocity, 0.0);
+        // Long move reaches the velocity limit.
+        let peak = trace.iter().map(|s| s.velocity).fold(0.0, f64::max);
+        assert!((peak - 100.0).abs() < 1e-6, "peak {peak}");
+        // Monotonic position (no overshoot).
+        assert!(trace.windows(2).all(|w| w[1].position >= w[0].position - TOL));
+    }
+
+    #[test]
+    fn short_move_has_no_cruise() {
+        let mut tg = TrajectoryGenerator::new();
+        assert!(tg.move_to(-0.5, limits()));
+        let mut phases = Vec::new();
+        while tg.is_active() {
+            tg.step(DT);
+            phases.push(tg.phase());
+        }
+        assert_eq!(tg.setpoint().position, -0.5);
+        assert!(!phases.contains(&ProfilePhase::ConstantVelocity));
+        assert!(phases.contains(&ProfilePhase::Accelerating));
+        assert!(phases.contains(&ProfilePhase::Decelerating));
+    }
+
+    #[test]
+    fn phases_follow_seven_segment_order() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.move_to(50.0, limits());
+        let mut seen = vec![tg.phase()];
+        while tg.is_active() {
+            tg.step(DT);
+            if seen.last() != Some(&tg.phase()) {
+                seen.push(tg.phase());
+            }
+        }
+        assert_eq!(
+            seen,
+            [
+                ProfilePhase::Accelerating,
+                ProfilePhase::ConstantVelocity,
+                ProfilePhase::Decelerating,
+                ProfilePhase::Idle,
+            ]
+        );
+    }
+
+    #[test]
+    fn retarget_further_while_moving() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.move_to(20.0, limits());
+        for _ in 0..80 {
+            tg.step(DT);
+        }
+        assert!(tg.setpoint().acceleration != 0.0 || tg.setpoint().velocity > 0.0);
+        assert!(tg.move_to(60.0, limits()));
+        run(&mut tg, 10_000);
+        assert_eq!(tg.setpoint().position, 60.0);
+    }
+
+    #[test]
+    fn retarget_behind_reverses_smoothly() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.move_to(100.0, limits());
+        for _ in 0..300 {
+            tg.step(DT);
+        }
+        let here = tg.setpoint().position;
+        assert!(tg.move_to(here - 10.0, limits()));
+        let trace = run(&mut tg, 10_000);
+        assert_eq!(tg.setpoint().position, here - 10.0);
+        // Stops (overshooting the new target) before reversing.
+        let max = trace.iter().map(|s| s.position).fold(f64::MIN, f64::max);
+        assert!(max > here);
+    }
+
+    #[test]
+    fn retarget_inside_stopping_distance_overshoots_and_returns() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.move_to(100.0, limits());
+        for _ in 0..300 {
+            tg.step(DT);
+        }
+        let sp = tg.setpoint();
+        assert!((sp.velocity - 100.0).abs() < TOL);
+        // Stopping from 100 at 500 mm/s² needs ~10 mm; ask for 1 mm.
+        assert!(tg.move_to(sp.position + 1.0, limits()));
+        let trace = run(&mut tg, 10_000);
+        assert_eq!(tg.setpoint().position, sp.position + 1.0);
+        assert!(trace.iter().any(|s| s.velocity < 0.0));
+    }
+
+    #[test]
+    fn velocity_mode_cruises_until_stop() {
+        let mut tg = TrajectoryGenerator::new();
+        assert!(tg.move_velocity(-40.0, limits()));
+        for _ in 0..1000 {
+            tg.step(DT);
+        }
+        assert!(tg.is_active());
+        assert_eq!(tg.phase(), ProfilePhase::ConstantVelocity);
+        assert!((tg.setpoint().velocity + 40.0).abs() < TOL);
+
+        tg.stop();
+        assert_eq!(tg.phase(), ProfilePhase::Decelerating);
+        run(&mut tg, 10_000);
+        assert!(!tg.is_active());
+        assert_eq!(tg.setpoint().velocity, 0.0);
+        assert_eq!(tg.setpoint().acceleration, 0.0);
+    }
+
+    #[test]
+    fn stop_from_takes_over_moving_state() {
+        let mut tg = TrajectoryGenerator::new();
+        let from = Setpoint { position: 10.0, velocity: 40.0, acceleration: 300.0 };
+        assert!(tg.stop_from(from, limits()));
+        assert!(tg.is_active());
+        let trace = run(&mut tg, 10_000);
+        // Continuous hand-over: the first step starts from `from`.
+        assert!((trace[0].velocity - 40.0).abs() < 1.0);
+        assert!(trace.windows(2).all(|w| w[1].position >= w[0].position));
+        assert!(!tg.is_active());
+        assert!(tg.setpoint().position > 10.0);
+        assert_eq!(tg.setpoint().velocity, 0.0);
+
+        // At rest there is nothing to stop.
+        let rest = Setpoint { position: 1.0, velocity: 0.0, acceleration: 0.0 };
+        assert!(tg.stop_from(rest, limits()));
+        assert!(!tg.is_active());
+        assert_eq!(tg.setpoint().position, 1.0);
+        assert!(!tg.stop_from(from, TrajectoryLimits { max_jerk: 0.0, ..limits() }));
+    }
+
+    #[test]
+    fn velocity_is_clamped_to_limit() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.move_velocity(1e6, limits());
+        for _ in 0..2000 {
+            tg.step(DT);
+        }
+        assert!((tg.setpoint().velocity - 100.0).abs() < TOL);
+    }
+
+    #[test]
+    fn limit_velocity_replans_active_profile() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.move_to(500.0, limits());
+        for _ in 0..500 {
+            tg.step(DT);
+        }
+        tg.limit_velocity(20.0);
+        for _ in 0..1000 {
+            tg.step(DT);
+        }
+        assert!((tg.setpoint().velocity - 20.0).abs() < TOL);
+        run(&mut tg, 100_000);
+        assert_eq!(tg.setpoint().position, 500.0);
+    }
+
+    #[test]
+    fn invalid_limits_rejected() {
+        let mut tg = TrajectoryGenerator::new();
+        let bad = TrajectoryLimits { max_jerk: 0.0, ..limits() };
+        assert!(!tg.move_to(10.0, bad));
+        assert!(!tg.is_active());
+        assert!(!tg.move_velocity(10.0, TrajectoryLimits { max_velocity: f64::NAN, ..limits() }));
+    }
+
+    #[test]
+    fn move_to_current_position_is_noop() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.hold(3.0);
+        assert!(tg.move_to(3.0, limits()));
+        assert!(!tg.is_active());
+        assert_eq!(tg.setpoint().position, 3.0);
+    }
+
+    #[test]
+    fn cancel_holds_commanded_position() {
+        let mut tg = TrajectoryGenerator::new();
+        tg.move_to(10.0, limits());
+        for _ in 0..50 {
+            tg.step(DT);
+        }
+        let p = tg.setpoint().position;
+        tg.cancel();
+        assert!(!tg.is_active());
+        assert_eq!(tg.step(DT), Setpoint { position: p, velocity: 0.0, acceleration: 0.0 });
+    }
+}
@@ -15,21 +15,82 @@
 //! Single cycle overrun → `ERR_CYCLE_OVERRUN` → `SAFETY_STOP` (FR-138).
 //!
 //! ## Cycle Body (T033)
-//! Read inbound SHM → process (placeholder) → write outbound SHM.
+//! Read inbound SHM (HAL feedback, RE/RPC commands) → process (safety
+//! pipeline, control engine, lag monitoring) → write outbound SHM.
+//!
+//! ## Command Processing (T043)
+//! RE and RPC commands are deduplicated on `sequence_id`, checked against
+//! the per-axis source lock (FR-135) and routed to the axis state. The
+//! outcome of the last command of each source is acknowledged in
+//! `evo_cu_re` (`last_ack_seq_id` for RE, `rpc_ack_seq_id` for RPC).
+//! Motion commands are executed by a per-axis jerk-limited
+//! `TrajectoryGenerator` that feeds the control engine setpoints and
+//! drives `MotionState` through the profile phases.
+//! Path commands (`PathLinear` / `PathArcCw` / `PathArcCcw`) are queued
+//! on the `AxisGroup` owning the addressed axes; its look-ahead
+//! `PathPlanner` streams synchronized setpoints for all group members.
+//!
+//! ## Power Sequencing (T038)
+//! `EnableAxis` / `DisableAxis` start the per-axis `PowerStateMachine`
+//! POWERING_ON / POWERING_OFF sequence (drive enable, brake release /
+//! engage, control state reset). Their ack is held back until every
+//! addressed axis has finished its sequence.
+//!
+//! ## Lockstep Mode
+//! `CycleRunner` implements `LockstepModule`: a shared `VirtualClock`
+//! advances HAL and CU one cycle at a time, without sleeping or reading
+//! the wall clock, for fast bit-identical regression runs.
 //!
 //! ## Runtime State (T034)
 //! Pre-allocated `[AxisRuntimeState; MAX_AXES]` + global machine/safety state.
 
-use evo_common::consts::{MAX_AI, MAX_AXES};
-use evo_common::control_unit::state::{MachineState, SafetyState};
+use evo_common::consts::{MAX_AI, MAX_AXES, MAX_GROUP_AXES};
+use evo_common::control_unit::command::AxisSourceLock;
+use evo_common::control_unit::config::CuAxisConfig;
+use evo_common::control_unit::control::{ControlOutputVector, UniversalControlParameters};
+use evo_common::control_unit::error::{MotionError, PowerError};
+use evo_common::control_unit::safety::{AxisSafetyState, BrakeConfig};
+use evo_common::control_unit::state::{
+    CouplingState, MachineState, MotionState, OperationalMode, PowerState, SafetyState,
+};
 use evo_common::io::registry::IoRegistry;
+use evo_common::io::role::IoRole;
+use evo_common::lockstep::{LockstepModule, VirtualClock};
 use evo_common::shm::io_helpers::BANK_WORDS;
 use evo_common::shm::p2p::ShmError;
-use evo_common::shm::segments::{CuToHalSegment, CuToMqtSegment, CuToReSegment};
-
+use evo_common::shm::request::RequestEdge;
+use evo_common::shm::segments::{
+    AckStatus, CuToHalSegment, CuToMqtSegment, CuToReSegment, ReCommand, ReCommandType, RpcCommand,
+};
+
+use crate::command::arbitration::{
+    AxisCommand, CommandOrigin, command_axis_id, decode_re_command, decode_rpc_command,
+    dispatch_path_command, dispatch_re_command, dispatch_rpc_command, is_motion_command,
+    is_path_command, machine_event_for_target, requires_source_lock,
+};
+use crate::command::source_lock::{LockResult, check_authority, try_acquire, try_release};
 use crate::config::LoadedConfig;
-use crate::control::output::AxisControlState;
+use crate::control::lag::evaluate_lag;
+use crate::control::output::{
+    AxisControlState, ControlInput, build_axis_command, compute_control_output,
+};
+use crate::control::path::AxisGroup;
+use crate::control::trajectory::{ProfilePhase, TrajectoryGenerator, TrajectoryLimits};
+use crate::safety::flags::{SafetyFlagInput, evaluate_axis_safety};
+use crate::safety::peripherals::AxisPeripherals;
+use crate::safety::recovery::{RecoveryManager, RecoveryStep};
+use crate::safety::stop::{SafeStopExecutor, StopAction};
 use crate::shm::segments::{CuSegments, SegmentThresholds};
+use crate::state::machine::{MachineStateMachine, TransitionResult};
+use crate::state::motion::{
+    MotionEvent, MotionStateMachine, check_unreferenced_policy, clamp_unreferenced_velocity,
+};
+use crate::state::operational::{ModeTransition, OperationalModeMachine};
+use crate::state::power::{
+    PowerEvent, PowerOffStep, PowerOnStep, PowerStateMachine, PowerTransition,
+    power_off_step_from_u8, power_on_step_from_u8,
+};
+use crate::state::safety::{SafetyEvent, SafetyStateMachine, clamp_velocity_for_safety};
 
 // ─── Cycle Statistics (T032) ────────────────────────────────────────
 
@@ -55,6 +116,10 @@ pub struct CycleStats {
     pub overruns: u64,
     /// Maximum wake-up latency [ns] (time between expected and actual wake).
     pub max_latency_ns: i64,
+    /// HAL→CU age of the last read [ns] (HAL commit to CU read).
+    pub last_hal_age_ns: i64,
+    /// Maximum HAL→CU age [ns].
+    pub max_hal_age_ns: i64,
 }
 
 impl CycleStats {
@@ -69,6 +134,8 @@ impl CycleStats {
             sum_sq_cycle_ns: 0,
             overruns: 0,
             max_latency_ns: 0,
+            last_hal_age_ns: 0,
+            max_hal_age_ns: 0,
         }
     }
 
@@ -90,6 +157,15 @@ impl CycleStats {
         }
     }
 
+    /// Record the HAL→CU age of a read. O(1), no allocation.
+    #[inline]
+    pub fn record_hal_age(&mut self, age_ns: i64) {
+        self.last_hal_age_ns = age_ns;
+        if age_ns > self.max_hal_age_ns {
+            self.max_hal_age_ns = age_ns;
+        }
+    }
+
     /// Average cycle time [ns] (returns 0 if no cycles).
     #[inline]
     pub fn avg_cycle_ns(&self) -> i64 {
@@ -126,9 +202,8 @@ pub struct AxisRuntimeState {
     pub loading_state: u8,
     /// Homing state (0 = not homed).
     pub homing_state: u8,
-
-    /// Padding for alignment.
-    pub _pad: u8,
+    /// Axis referenced flag from HAL (enables soft-limit checks).
+    pub referenced: u8,
 
     // ── Error flags ──
     /// Power error bitflags.
@@ -149,6 +224,8 @@ pub struct AxisRuntimeState {
     pub lag: f64,
     /// Estimated torque [% of rated].
     pub torque_estimate: f64,
+    /// Motor current [A].
+    pub actual_current: f64,
 
     // ── Command data (computed by CU) ──
     /// Target position [user units].
@@ -382,6 +459,45 @@ pub struct CycleRunner {
     pub io_registry: IoRegistry,
     /// Per-axis control engine state (PID, DOB, filters).
     pub control_states: [AxisControlState; MAX_AXES as usize],
+    /// Per-axis control parameters (from `CuAxisConfig::control`).
+    pub control_params: [UniversalControlParameters; MAX_AXES as usize],
+    /// Per-axis S-curve trajectory generators for motion commands.
+    pub trajectories: [TrajectoryGenerator; MAX_AXES as usize],
+    /// Coordinated axis groups with their path planners (from config).
+    pub axis_groups: Vec<AxisGroup>,
+    /// Per-axis power state machines (enable / disable sequences).
+    pub power: [PowerStateMachine; MAX_AXES as usize],
+    /// Bit per axis: power sequence started by the command being processed.
+    power_wait: u64,
+    /// RE enable / disable ack waiting for its power sequences. A newer RE
+    /// command supersedes it.
+    pending_re_ack: Option<PendingPowerAck>,
+    /// RPC enable / disable ack waiting for its power sequences.
+    pending_rpc_ack: Option<PendingPowerAck>,
+    /// Power state seen by the control engine in the previous cycle.
+    prev_power_state: [u8; MAX_AXES as usize],
+    /// Operational mode seen by the control engine in the previous cycle.
+    prev_operational_mode: [u8; MAX_AXES as usize],
+    /// Per-axis safety peripheral monitors (tailstock, lock pin, brake, guard).
+    pub peripherals: [AxisPeripherals; MAX_AXES as usize],
+    /// Per-axis safety flags from the last evaluation (FR-080).
+    pub safety_flags: [AxisSafetyState; MAX_AXES as usize],
+    /// Per-axis SAFETY_STOP executors (STO / SS1 / SS2).
+    pub stop_executors: [SafeStopExecutor; MAX_AXES as usize],
+    /// Per-axis stop action applied in the current cycle.
+    stop_actions: [StopAction; MAX_AXES as usize],
+    /// Global safety state machine (Safe / SafeReducedSpeed / SafetyStop).
+    pub safety_sm: SafetyStateMachine,
+    /// Reset + authorization sequence after SAFETY_STOP (FR-122).
+    pub recovery: RecoveryManager,
+    /// Per-axis command source locks (FR-135).
+    pub source_locks: [AxisSourceLock; MAX_AXES as usize],
+    /// Bit per axis: manual mode authorized by `AllowManualMode` (FR-004).
+    pub manual_allowed: u64,
+    /// Processed RE `sequence_id`s (exactly once each).
+    re_edge: RequestEdge,
+    /// Processed RPC `sequence_id`s (exactly once each).
+    rpc_edge: RequestEdge,
     /// Cycles between RE/RPC late-attach attempts.
     attach_interval_cycles: u64,
 }
@@ -393,11 +509,26 @@ impl CycleRunner {
     pub fn new(config: LoadedConfig) -> Result<Self, CycleError> {
         let thresholds = SegmentThresholds {
             hal_stale: config.cu_config.hal_stale_threshold,
+            hal_max_age: config
+                .cu_config
+                .hal_max_age_us
+                .map(|us| std::time::Duration::from_micros(us as u64)),
             re_stale: config.cu_config.re_stale_threshold,
             rpc_stale: config.cu_config.rpc_stale_threshold,
         };
 
-        let segments = CuSegments::init(&thresholds)?;
+        let mut segments = CuSegments::init_in(&config.shm_ns, &thresholds)?;
+        // Requests already in RE/RPC segments that outlived a CU restart were
+        // meant for the previous instance. Late-attached segments belong to a
+        // client started after the CU and are served from the first request.
+        let re_edge = segments
+            .re_to_cu
+            .as_mut()
+            .map_or(RequestEdge::new(), RequestEdge::attached);
+        let rpc_edge = segments
+            .rpc_to_cu
+            .as_mut()
+            .map_or(RequestEdge::new(), RequestEdge::attached);
         let axis_count = config.machine.axes.len() as u8;
         let state = RuntimeState::new(axis_count);
         let cycle_time_ns = config.cu_config.cycle_time_us as i64 * 1000;
@@ -406,8 +537,63 @@ impl CycleRunner {
         // IoRegistry from loaded config.
         let io_registry = config.io_registry.clone();
 
-        // Pre-allocate per-axis control states.
-        let control_states = core::array::from_fn(|_| AxisControlState::default());
+        // Per-axis control parameters and pre-initialized control states.
+        let control_params: [UniversalControlParameters; MAX_AXES as usize] =
+            core::array::from_fn(|i| {
+                config
+                    .machine
+                    .axes
+                    .get(i)
+                    .map(|a| a.control)
+                    .unwrap_or_default()
+            });
+        let sample_rate = 1.0e9 / cycle_time_ns.max(1) as f64;
+        let control_states = core::array::from_fn(|i| {
+            let mut cs = AxisControlState::default();
+            cs.init_filters(&control_params[i], sample_rate);
+            cs
+        });
+
+        // Per-axis safety monitors and safe-stop executors.
+        let cycle_time_us = config.cu_config.cycle_time_us;
+        let global_safety = &config.machine.global_safety;
+        let peripherals = core::array::from_fn(|i| match config.machine.axes.get(i) {
+            Some(a) => AxisPeripherals::from_config(
+                a.tailstock.as_ref(),
+                a.index.as_ref(),
+                a.brake.as_ref(),
+                a.guard.as_ref(),
+                a.axis_id,
+                cycle_time_us,
+            ),
+            None => AxisPeripherals::from_config(None, None, None, None, 0, cycle_time_us),
+        });
+        let stop_executors = core::array::from_fn(|i| {
+            let stop_cfg = config
+                .machine
+                .axes
+                .get(i)
+                .map(|a| a.safe_stop)
+                .unwrap_or_default();
+            SafeStopExecutor::new(&stop_cfg, cycle_time_us, global_safety.safety_stop_timeout)
+        });
+        let recovery = RecoveryManager::new(global_safety.recovery_authorization_required);
+        let power = core::array::from_fn(|i| match config.machine.axes.get(i) {
+            Some(a) => PowerStateMachine::new(
+                a.brake.as_ref().is_some_and(|b| !b.always_free),
+                a.index.is_some(),
+                false,
+            ),
+            None => PowerStateMachine::new(false, false, false),
+        });
+
+        // Axis groups (members validated at config load).
+        let axis_groups = config
+            .machine
+            .axis_groups
+            .iter()
+            .filter_map(|g| AxisGroup::from_config(g, &config.machine.axes))
+            .collect();
 
         // Compute attach interval: once per second (cycle_time_us→cycles).
         let attach_interval_cycles = if config.cu_config.cycle_time_us > 0 {
@@ -424,10 +610,35 @@ impl CycleRunner {
             mqt_interval,
             io_registry,
             control_states,
+            control_params,
+            trajectories: core::array::from_fn(|_| TrajectoryGenerator::new()),
+            axis_groups,
+            power,
+            power_wait: 0,
+            pending_re_ack: None,
+            pending_rpc_ack: None,
+            prev_power_state: [0u8; MAX_AXES as usize],
+            prev_operational_mode: [0u8; MAX_AXES as usize],
+            peripherals,
+            safety_flags: [AxisSafetyState::default(); MAX_AXES as usize],
+            stop_executors,
+            stop_actions: [StopAction::None; MAX_AXES as usize],
+            safety_sm: SafetyStateMachine::new(),
+            recovery,
+            source_locks: [AxisSourceLock::default(); MAX_AXES as usize],
+            manual_allowed: 0,
+            re_edge,
+            rpc_edge,
             attach_interval_cycles,
         })
     }
 
+    /// Leave `Stopped` before the first cycle (done by `run()`; lockstep
+    /// harnesses call it before the first tick).
+    pub fn start(&mut self) {
+        self.state.machine_state = MachineState::Idle;
+    }
+
     /// Enter the deterministic cycle loop (T032).
     ///
     /// This method never returns under normal operation. It uses
@@ -440,7 +651,7 @@ impl CycleRunner {
     /// Returns `CycleError::CycleOverrun` on the first overrun detected
     /// (FR-138: hard real-time deadline).
     pub fn run(&mut self) -> Result<(), CycleError> {
-        self.state.machine_state = MachineState::Idle;
+        self.start();
 
         #[cfg(feature = "rt")]
         {
@@ -527,9 +738,6 @@ impl CycleRunner {
     }
 
     /// Three-phase cycle body: read → process → write (T033).
-    ///
-    /// Each phase is a placeholder for future state machine,
-    /// control, and safety logic (T036+).
     fn cycle_body(&mut self) -> Result<(), CycleError> {
         // ═══ READ PHASE ═══
         // Read mandatory HAL→CU feedback.
@@ -540,27 +748,56 @@ impl CycleRunner {
         for i in 0..n {
             self.state.axes[i].actual_position = hal.axes[i].position;
             self.state.axes[i].actual_velocity = hal.axes[i].velocity;
+            self.state.axes[i].torque_estimate = hal.axes[i].torque_estimate;
+            self.state.axes[i].actual_current = hal.axes[i].current;
             self.state.axes[i].drive_status = hal.axes[i].drive_ready;
             self.state.axes[i].drive_fault_code = hal.axes[i].drive_fault as u16;
+            self.state.axes[i].referenced = hal.axes[i].referenced;
         }
 
         // Copy DI bank and AI values for state machine and safety logic.
         self.state.di_bank = hal.di_bank;
         self.state.ai_values = hal.ai_values;
+        if let Some(age) = self.segments.hal_to_cu.age() {
+            self.state.stats.record_hal_age(age.as_nanos() as i64);
+        }
 
-        // Read optional RE→CU commands.
+        // Read optional RE→CU commands (each sequence_id processed once).
+        let mut re_cmd = None;
         if let Some(ref mut re_reader) = self.segments.re_to_cu {
             if re_reader.has_changed() {
-                let _re = re_reader.read()?;
-                // TODO (T036+): Process recipe commands → command arbitration.
+                let cmd = re_reader.read()?.command;
+                if self.re_edge.accept(u64::from(cmd.sequence_id)) {
+                    re_cmd = Some(cmd);
+                }
+            }
+        }
+        self.sync_power_machines();
+        if let Some(cmd) = re_cmd {
+            let status = self.process_re_command(&cmd);
+            self.pending_re_ack = self.defer_power_ack(cmd.sequence_id, status);
+            if self.pending_re_ack.is_none() {
+                self.state.out_re.last_ack_seq_id = cmd.sequence_id;
+                self.state.out_re.ack_status = status as u8;
             }
         }
 
-        // Read optional RPC→CU commands.
+        // Read optional RPC→CU commands (each sequence_id processed once).
+        let mut rpc_cmd = None;
         if let Some(ref mut rpc_reader) = self.segments.rpc_to_cu {
             if rpc_reader.has_changed() {
-                let _rpc = rpc_reader.read()?;
-                // TODO (T036+): Process RPC commands → command arbitration.
+                let cmd = rpc_reader.read()?.command;
+                if self.rpc_edge.accept(u64::from(cmd.sequence_id)) {
+                    rpc_cmd = Some(cmd);
+                }
+            }
+        }
+        if let Some(cmd) = rpc_cmd {
+            let status = self.process_rpc_command(&cmd);
+            self.pending_rpc_ack = self.defer_power_ack(cmd.sequence_id, status);
+            if self.pending_rpc_ack.is_none() {
+                self.state.out_re.rpc_ack_seq_id = cmd.sequence_id;
+                self.state.out_re.rpc_ack_status = status as u8;
             }
         }
 
@@ -578,74 +815,30 @@ impl CycleRunner {
 
         // ═══ PROCESS PHASE ═══
         // Phase 4 Integration: Safety evaluation (T053)
-        //
-        // The safety evaluation pipeline runs here, between SHM read and
-        // state machine processing:
-        //
-        // 1. Evaluate all safety peripherals per axis (tailstock, lock pin, brake, guard)
-        //    via `AxisPeripherals::evaluate()` using DI bank from HAL feedback.
-        //
-        // 2. Aggregate into `AxisSafetyState` flags via `evaluate_axis_safety()`,
-        //    combining peripheral results with limit switch, soft limit, motion enable,
-        //    and gearbox checks.
-        //
-        // 3. If any CRITICAL error → `SafetyStateMachine::force_safety_stop()`
-        //    → trigger SafeStopExecutor per axis.
-        //
-        // 4. If SafetyState == SafeReducedSpeed → clamp all target velocities
-        //    via `clamp_velocity_for_safety()`.
-        //
-        // 5. Tick SafeStopExecutors and apply StopActions (Decelerate/DisableAndBrake/HoldTorque).
-        //
-        // 6. If SafetyState == SafetyStop and recovery requested → tick RecoveryManager.
-        //
-        // Full wiring requires IoRegistry and peripherals array on CycleRunner,
-        // which will be added when the runtime orchestration is consolidated (T060+).
+        self.process_safety();
+
+        // Power sequences (T038), after safety so a stop interrupts them.
+        self.process_power();
 
         // Phase 6 Integration: Control engine (T067)
-        //
-        // For each axis in PowerState::Motion, compute control output:
-        //
-        // 1. Build `ControlInput` from AxisRuntimeState feedback + target commands.
-        //
-        // 2. Call `compute_control_output(&mut control_state, &params, &input)`
-        //    which runs the full PID + FF + DOB + notch → lowpass → clamp pipeline.
-        //
-        // 3. Evaluate lag error via `evaluate_lag(target, actual, limit, policy)`.
-        //    - LagPolicy::Critical → trigger SAFETY_STOP for all axes.
-        //    - LagPolicy::Unwanted → axis-local MotionError.
-        //    - LagPolicy::Neutral → flag only.
-        //    - LagPolicy::Desired → suppress.
-        //
-        // 4. On axis disable or mode change → `AxisControlState::reset()`
-        //    to zero PID integral, DOB state, filter state (I-PW-4 / I-OM-4).
-        //
-        // 5. Store resulting `ControlOutputVector` in AxisRuntimeState::control_output.
-        //
-        // 6. In WRITE phase, use `build_axis_command(power_state, mode, output)`
-        //    to construct CuAxisCommand with the computed ControlOutputVector.
-        //
-        // Per-axis AxisControlState instances and UniversalControlParameters are stored
-        // on CycleRunner, initialized from config at startup. Full runtime wiring
-        // will be added when the complete control loop orchestration is integrated.
-
-        // Compute lag for diagnostics.
-        for i in 0..n {
-            self.state.axes[i].lag =
-                self.state.axes[i].target_position - self.state.axes[i].actual_position;
-        }
+        self.process_control();
 
         // ═══ WRITE PHASE ═══
         // Build CU→HAL axis commands.
         self.state.out_hal.axis_count = self.state.axis_count;
         for i in 0..n {
-            self.state.out_hal.axes[i].enable =
-                if self.state.machine_state == MachineState::Active {
-                    1
-                } else {
-                    0
-                };
-            // TODO (T036+): Fill control output from PID/control pipeline.
+            let ax = &self.state.axes[i];
+            let power = PowerState::from_u8(ax.power_state).unwrap_or_default();
+            let mut cmd = build_axis_command(
+                power,
+                ax.operational_mode,
+                output_from_array(&ax.control_output),
+            );
+            // Enable and brake outputs follow the power sequence step.
+            cmd.enable = self.power[i].drive_enabled() as u8;
+            cmd.brake_release = self.power[i].brake_released() as u8;
+            self.state.out_hal.axes[i] = cmd;
+            self.peripherals[i].write_brake_command(&self.io_registry, &mut self.state.out_hal.do_bank);
         }
         self.segments.cu_to_hal.commit(&self.state.out_hal)?;
 
@@ -677,12 +870,1192 @@ impl CycleRunner {
             self.segments.cu_to_mqt.commit(&self.state.out_mqt)?;
         }
 
-        // Build CU→RE acknowledgement.
-        // TODO (T036+): Fill ack_seq_id and ack_status from command processing.
+        // Build CU→RE acknowledgement + axis availability.
+        let mut in_position = 0u64;
+        let mut in_error = 0u64;
+        let path_active = self
+            .axis_groups
+            .iter()
+            .filter(|g| g.planner.is_active())
+            .fold(0u64, |m, g| m | g.mask());
+        for i in 0..n {
+            let ax = &self.state.axes[i];
+            let window = self.config.machine.axes[i].in_position_window;
+            if !self.trajectories[i].is_active()
+                && path_active & (1 << i) == 0
+                && (ax.target_position - ax.actual_position).abs() <= window
+            {
+                in_position |= 1 << i;
+            }
+            if ax.power_errors | ax.motion_errors | ax.gearbox_errors | ax.coupling_errors != 0
+                || ax.motion_state == MotionState::MotionError as u8
+                || ax.power_state == PowerState::PowerError as u8
+            {
+                in_error |= 1 << i;
+            }
+        }
+        if let Some((seq, status)) = resolve_power_ack(&mut self.pending_re_ack, &self.state.axes) {
+            self.state.out_re.last_ack_seq_id = seq;
+            self.state.out_re.ack_status = status as u8;
+        }
+        if let Some((seq, status)) = resolve_power_ack(&mut self.pending_rpc_ack, &self.state.axes)
+        {
+            self.state.out_re.rpc_ack_seq_id = seq;
+            self.state.out_re.rpc_ack_status = status as u8;
+        }
+        self.state.out_re.axes_in_position = in_position;
+        self.state.out_re.axes_in_error = in_error;
         self.segments.cu_to_re.commit(&self.state.out_re)?;
 
         Ok(())
     }
+
+    /// Process one RE command across all axes in its `axis_mask` (T043).
+    ///
+    /// Source-lock authority and the axis preconditions are checked for
+    /// every addressed axis before any of them is touched, so one axis that
+    /// cannot take the command rejects it for all. Path segments check every
+    /// member of their group (see `start_path`).
+    fn process_re_command(&mut self, re: &ReCommand) -> AckStatus {
+        if decode_re_command(re.command_type).is_none() {
+            return AckStatus::Error;
+        }
+        let n = self.state.axis_count as usize;
+        if n < 64 && re.axis_mask >> n != 0 {
+            return AckStatus::Rejected; // Mask addresses unconfigured axes.
+        }
+
+        if ReCommandType::from_u8(re.command_type).is_some_and(is_path_command) {
+            return self.start_path(re);
+        }
+
+        let source = CommandOrigin::RecipeExecutor.source();
+
+        for i in 0..n {
+            let Some(cmd) = dispatch_re_command(re, i) else {
+                continue;
+            };
+            let locked = requires_source_lock(&cmd)
+                && check_authority(&self.source_locks[i], source).is_err();
+            if locked || !self.admits(CommandOrigin::RecipeExecutor, i, &cmd) {
+                return AckStatus::Rejected;
+            }
+        }
+
+        let mut status = AckStatus::Ok;
+        for i in 0..n {
+            if let Some(cmd) = dispatch_re_command(re, i)
+                && self.apply_command(CommandOrigin::RecipeExecutor, &cmd) != AckStatus::Ok
+            {
+                status = AckStatus::Rejected;
+            }
+        }
+        status
+    }
+
+    /// Process one RPC command (T078).
+    fn process_rpc_command(&mut self, rpc: &RpcCommand) -> AckStatus {
+        match dispatch_rpc_command(rpc) {
+            Some(cmd) => self.apply_command(CommandOrigin::RpcApi, &cmd),
+            // Nop decodes but yields no command.
+            None if decode_rpc_command(rpc.command_type).is_some() => AckStatus::Ok,
+            None => AckStatus::Error,
+        }
+    }
+
+    /// Route a decoded command through source locking and the axis
+    /// state machines (FR-135, contracts/state-machines.md).
+    fn apply_command(&mut self, origin: CommandOrigin, cmd: &AxisCommand) -> AckStatus {
+        let source = origin.source();
+
+        // ── Global commands ──
+        match *cmd {
+            AxisCommand::Nop => return AckStatus::Ok,
+            AxisCommand::SetMachineState { target_state } => {
+                return self.apply_machine_state(target_state);
+            }
+            // Config reload parses TOML and is served outside the RT cycle (FR-147).
+            AxisCommand::ReloadConfig => return AckStatus::Rejected,
+            // Only meaningful once reset and flags clear are done (FR-122).
+            AxisCommand::AuthorizeRecovery => {
+                if self.recovery.step() != RecoveryStep::WaitingAuthorization {
+                    return AckStatus::Rejected;
+                }
+                self.recovery.authorize();
+                return AckStatus::Ok;
+            }
+            _ => {}
+        }
+
+        let Some(i) = command_axis_id(cmd)
+            .filter(|&id| id >= 1 && id <= self.state.axis_count)
+            .map(|id| id as usize - 1)
+        else {
+            return AckStatus::Rejected;
+        };
+
+        // ── Source lock (FR-135) ──
+        match *cmd {
+            AxisCommand::AcquireLock { .. } => {
+                let (result, _) = try_acquire(&mut self.source_locks[i], source, origin.lock_reason());
+                return ack_if(result == LockResult::Acquired);
+            }
+            AxisCommand::ReleaseLock { .. } => {
+                let result = try_release(&mut self.source_locks[i], source);
+                return ack_if(matches!(result, LockResult::Released | LockResult::NotHeld));
+            }
+            _ => {}
+        }
+        if requires_source_lock(cmd) && check_authority(&self.source_locks[i], source).is_err() {
+            return AckStatus::Rejected;
+        }
+
+        if is_motion_command(cmd) {
+            return self.start_motion(i, origin, cmd);
+        }
+        match *cmd {
+            AxisCommand::Stop { .. } | AxisCommand::JogStop { .. } if self.stop_path(i) => {
+                return AckStatus::Ok;
+            }
+            AxisCommand::DisableAxis { .. } => {
+                cancel_group_path(
+                    &mut self.axis_groups,
+                    &mut self.state.axes,
+                    &mut self.trajectories,
+                    &self.config.machine.axes,
+                    i,
+                );
+            }
+            _ => {}
+        }
+
+        let safety_stop = self.safety_sm.requires_emergency_stop();
+        let manual_allowed = self.manual_allowed_for(i);
+        let ax = &mut self.state.axes[i];
+        let power = PowerState::from_u8(ax.power_state).unwrap_or_default();
+
+        match *cmd {
+            AxisCommand::EnableAxis { .. } => {
+                let critical = PowerError::from_bits_truncate(ax.power_errors as u16).has_critical();
+                if safety_stop || critical {
+                    return AckStatus::Rejected;
+                }
+                let sm = &mut self.power[i];
+                match sm.handle_event(PowerEvent::Enable, false) {
+                    PowerTransition::Ok(_) => ax.power_state = sm.state() as u8,
+                    _ if power == PowerState::PoweringOn => {}
+                    _ => return ack_if(matches!(power, PowerState::Standby | PowerState::Motion)),
+                }
+                self.power_wait |= 1 << i;
+                AckStatus::Ok
+            }
+            AxisCommand::DisableAxis { .. } => {
+                let sm = &mut self.power[i];
+                match sm.handle_event(PowerEvent::Disable, false) {
+                    PowerTransition::Ok(_) => ax.power_state = sm.state() as u8,
+                    _ if power == PowerState::PoweringOff => {}
+                    // The drive is already off.
+                    _ if matches!(power, PowerState::PowerOff | PowerState::PowerError) => {
+                        return AckStatus::Ok;
+                    }
+                    _ => return AckStatus::Rejected,
+                }
+                self.trajectories[i].cancel();
+                ax.motion_state = MotionState::Standstill as u8;
+                ax.target_velocity = 0.0;
+                self.power_wait |= 1 << i;
+                AckStatus::Ok
+            }
+            AxisCommand::Stop { .. } | AxisCommand::JogStop { .. } => {
+                let traj = &mut self.trajectories[i];
+                if traj.is_active() {
+                    // Controlled, jerk-limited stop along the profile.
+                    traj.stop();
+                    let mut sm = MotionStateMachine::with_state(
+                        MotionState::from_u8(ax.motion_state).unwrap_or_default(),
+                    );
+                    let _ = sm.handle_event(MotionEvent::Stop);
+                    ax.motion_state = sm.state() as u8;
+                    return AckStatus::Ok;
+                }
+                ax.target_position = ax.actual_position;
+                ax.target_velocity = 0.0;
+                if ax.motion_state != MotionState::EmergencyStop as u8
+                    && ax.motion_state != MotionState::MotionError as u8
+                {
+                    ax.motion_state = MotionState::Standstill as u8;
+                }
+                AckStatus::Ok
+            }
+            AxisCommand::EmergencyStop { .. } => {
+                self.safety_sm.force_safety_stop();
+                self.state.safety_state = SafetyState::SafetyStop;
+                AckStatus::Ok
+            }
+            AxisCommand::SetMode { mode, .. } => match mode_change(ax, mode, manual_allowed) {
+                Some(new_mode) => {
+                    ax.operational_mode = new_mode as u8;
+                    AckStatus::Ok
+                }
+                None => AckStatus::Rejected,
+            },
+            AxisCommand::AllowManualMode { .. } => {
+                self.manual_allowed |= 1 << i;
+                AckStatus::Ok
+            }
+            AxisCommand::ResetError { .. } => {
+                // Latched safety errors are cleared by the recovery sequence.
+                if safety_stop {
+                    return AckStatus::Rejected;
+                }
+                ax.power_errors = 0;
+                ax.motion_errors = 0;
+                ax.gearbox_errors = 0;
+                ax.coupling_errors = 0;
+                if ax.motion_state == MotionState::MotionError as u8 {
+                    ax.motion_state = MotionState::Standstill as u8;
+                }
+                if power == PowerState::PowerError {
+                    let _ = self.power[i].handle_event(PowerEvent::ErrorReset, false);
+                    ax.power_state = self.power[i].state() as u8;
+                }
+                AckStatus::Ok
+            }
+            // Coupling and gearbox supervisors are not wired into the cycle yet.
+            _ => AckStatus::Rejected,
+        }
+    }
+
+    /// Whether axis command `cmd` meets the preconditions `apply_command`
+    /// checks on axis `i`, without applying it. Source locks are left to
+    /// the caller.
+    fn admits(&self, origin: CommandOrigin, i: usize, cmd: &AxisCommand) -> bool {
+        if is_motion_command(cmd) {
+            return self.plan_motion(i, origin, cmd).is_some();
+        }
+        let safety_stop = self.safety_sm.requires_emergency_stop();
+        let ax = &self.state.axes[i];
+        let power = PowerState::from_u8(ax.power_state).unwrap_or_default();
+        let accepts = |event| {
+            matches!(self.power[i].clone().handle_event(event, false), PowerTransition::Ok(_))
+        };
+        match *cmd {
+            AxisCommand::EnableAxis { .. } => {
+                !safety_stop
+                    && !PowerError::from_bits_truncate(ax.power_errors as u16).has_critical()
+                    && (accepts(PowerEvent::Enable)
+                        || matches!(
+                            power,
+                            PowerState::PoweringOn | PowerState::Standby | PowerState::Motion
+                        ))
+            }
+            AxisCommand::DisableAxis { .. } => {
+                accepts(PowerEvent::Disable)
+                    || matches!(
+                        power,
+                        PowerState::PoweringOff | PowerState::PowerOff | PowerState::PowerError
+                    )
+            }
+            AxisCommand::Stop { .. }
+            | AxisCommand::JogStop { .. }
+            | AxisCommand::EmergencyStop { .. }
+            | AxisCommand::AllowManualMode { .. } => true,
+            AxisCommand::SetMode { mode, .. } => {
+                mode_change(ax, mode, self.manual_allowed_for(i)).is_some()
+            }
+            AxisCommand::ResetError { .. } => !safety_stop,
+            _ => false,
+        }
+    }
+
+    /// Whether axis `i` may enter manual mode: during a running recipe
+    /// only once authorized (FR-004).
+    fn manual_allowed_for(&self, i: usize) -> bool {
+        self.state.machine_state != MachineState::Active || self.manual_allowed & (1 << i) != 0
+    }
+
+    /// Start a motion command on axis `i` (FR-035, FR-081, I-MS-3).
+    ///
+    /// Requires a powered axis with all safety flags OK, no motion error,
+    /// a mode permitted by the unreferenced-axis policy, and a machine state
+    /// that allows motion (`Idle` is promoted by the origin's motion event).
+    ///
+    /// Accepted commands (re)plan the axis trajectory from its current
+    /// commanded state; command limits of 0 fall back to the axis config.
+    fn start_motion(&mut self, i: usize, origin: CommandOrigin, cmd: &AxisCommand) -> AckStatus {
+        let Some(plan) = self.plan_motion(i, origin, cmd) else {
+            return AckStatus::Rejected;
+        };
+        if !self.promote_for_motion(origin) {
+            return AckStatus::Rejected;
+        }
+
+        let ax = &mut self.state.axes[i];
+        let traj = &mut self.trajectories[i];
+        if !traj.is_active() {
+            traj.hold(ax.target_position);
+        }
+        match plan.position {
+            Some(p) => traj.move_to(p, plan.limits),
+            None => traj.move_velocity(plan.velocity, plan.limits),
+        };
+        let _ = self.power[i].handle_event(PowerEvent::MotionCommand, false);
+        ax.power_state = self.power[i].state() as u8;
+        let mut sm =
+            MotionStateMachine::with_state(MotionState::from_u8(ax.motion_state).unwrap_or_default());
+        let _ = sm.handle_event(MotionEvent::StartMotion);
+        ax.motion_state = profile_motion_state(sm.state(), traj.phase()) as u8;
+        AckStatus::Ok
+    }
+
+    /// Check the preconditions of motion command `cmd` on axis `i` and
+    /// resolve its trajectory target, without touching any state.
+    fn plan_motion(
+        &self,
+        i: usize,
+        origin: CommandOrigin,
+        cmd: &AxisCommand,
+    ) -> Option<MotionPlan> {
+        let cfg = &self.config.machine.axes[i];
+        let ax = &self.state.axes[i];
+        let power = PowerState::from_u8(ax.power_state).unwrap_or_default();
+        let mode = OperationalMode::from_u8(ax.operational_mode).unwrap_or_default();
+        let referenced = ax.referenced != 0;
+
+        if self.safety_sm.requires_emergency_stop()
+            || !matches!(power, PowerState::Standby | PowerState::Motion)
+            || !self.safety_flags[i].all_ok()
+            || ax.motion_state == MotionState::MotionError as u8
+            || ax.motion_state == MotionState::EmergencyStop as u8
+            || check_unreferenced_policy(referenced, mode).is_err()
+            || self.path_group(i).is_some()
+        {
+            return None;
+        }
+
+        // Resolve the new targets before touching any state.
+        let (position, velocity, acceleration, deceleration) = match *cmd {
+            AxisCommand::MoveAbsolute { position, velocity, acceleration, deceleration, .. } => {
+                (Some(position), velocity, acceleration, deceleration)
+            }
+            AxisCommand::MoveRelative { distance, velocity, acceleration, deceleration, .. } => {
+                // Relative to the pending move target, if any.
+                let base = self.trajectories[i].target().unwrap_or(ax.target_position);
+                (Some(base + distance), velocity, acceleration, deceleration)
+            }
+            AxisCommand::MoveVelocity { velocity, acceleration, .. } => {
+                (None, velocity, acceleration, acceleration)
+            }
+            AxisCommand::JogPositive { speed, .. } => (None, speed.abs(), 0.0, 0.0),
+            AxisCommand::JogNegative { speed, .. } => (None, -speed.abs(), 0.0, 0.0),
+            // Homing supervision is not wired into the cycle yet.
+            _ => return None,
+        };
+        if let Some(p) = position
+            && referenced
+            && !(cfg.min_pos..=cfg.max_pos).contains(&p)
+        {
+            return None;
+        }
+        let velocity = clamp_unreferenced_velocity(
+            velocity.clamp(-cfg.max_velocity, cfg.max_velocity),
+            cfg.max_velocity,
+            referenced,
+        );
+        // Position moves use |velocity| as cruise limit (0 → axis max).
+        let max_velocity = match position {
+            Some(_) if velocity != 0.0 => velocity.abs(),
+            Some(_) => clamp_unreferenced_velocity(cfg.max_velocity, cfg.max_velocity, referenced),
+            None => velocity.abs().max(f64::MIN_POSITIVE),
+        };
+        let limits = TrajectoryLimits {
+            max_velocity,
+            max_acceleration: command_limit(acceleration, cfg.max_acceleration),
+            max_deceleration: command_limit(deceleration, cfg.max_acceleration),
+            max_jerk: cfg.max_jerk,
+        };
+        if !limits.is_valid() {
+            return None;
+        }
+        self.motion_machine_state(origin)?;
+        Some(MotionPlan { position, velocity, limits })
+    }
+
+    /// Queue an RE path segment on the axis group owning `re.axis_mask`.
+    ///
+    /// Every group member, masked or not (the path drives all of them), must
+    /// be powered, referenced, safety-clear, not locked by another source
+    /// and not running a single-axis profile; masked end points must lie
+    /// within the soft limits. The first segment starts from the current
+    /// targets.
+    fn start_path(&mut self, re: &ReCommand) -> AckStatus {
+        let Some(g) = self
+            .axis_groups
+            .iter()
+            .position(|g| re.axis_mask != 0 && re.axis_mask & !g.mask() == 0)
+        else {
+            return AckStatus::Rejected;
+        };
+        let Some(cmd) = dispatch_path_command(re, &self.axis_groups[g]) else {
+            return AckStatus::Rejected;
+        };
+
+        let safety_stop = self.safety_sm.requires_emergency_stop();
+        let source = CommandOrigin::RecipeExecutor.source();
+        for (k, &i) in self.axis_groups[g].members().iter().enumerate() {
+            let ax = &self.state.axes[i];
+            let cfg = &self.config.machine.axes[i];
+            if safety_stop
+                || !matches!(
+                    PowerState::from_u8(ax.power_state),
+                    Some(PowerState::Standby | PowerState::Motion)
+                )
+                || !self.safety_flags[i].all_ok()
+                || check_authority(&self.source_locks[i], source).is_err()
+                || ax.motion_state == MotionState::MotionError as u8
+                || ax.motion_state == MotionState::EmergencyStop as u8
+                || ax.referenced == 0
+                || self.trajectories[i].is_active()
+                || (cmd.axis_mask & (1 << k) != 0
+                    && !(cfg.min_pos..=cfg.max_pos).contains(&cmd.end[k]))
+            {
+                return AckStatus::Rejected;
+            }
+        }
+        if !self.promote_for_motion(CommandOrigin::RecipeExecutor) {
+            return AckStatus::Rejected;
+        }
+
+        let group = &mut self.axis_groups[g];
+        if !group.planner.is_active() {
+            let mut start = [0.0; MAX_GROUP_AXES];
+            for (k, &i) in group.members().iter().enumerate() {
+                start[k] = self.state.axes[i].target_position;
+            }
+            group.planner.hold(&start[..group.planner.axis_count()]);
+        }
+        if group.planner.push(&cmd).is_err() {
+            return AckStatus::Rejected;
+        }
+        for &i in group.members() {
+            let ax = &mut self.state.axes[i];
+            let _ = self.power[i].handle_event(PowerEvent::MotionCommand, false);
+            ax.power_state = self.power[i].state() as u8;
+            let mut sm = MotionStateMachine::with_state(
+                MotionState::from_u8(ax.motion_state).unwrap_or_default(),
+            );
+            let _ = sm.handle_event(MotionEvent::StartMotion);
+            ax.motion_state = sm.state() as u8;
+        }
+        AckStatus::Ok
+    }
+
+    /// Index of the group with an active path containing axis `i`.
+    fn path_group(&self, i: usize) -> Option<usize> {
+        self.axis_groups
+            .iter()
+            .position(|g| g.planner.is_active() && g.mask() & (1 << i) != 0)
+    }
+
+    /// Controlled stop of the active path containing axis `i`.
+    ///
+    /// Returns `false` if the axis is not moving on a path.
+    fn stop_path(&mut self, i: usize) -> bool {
+        let Some(g) = self.path_group(i) else {
+            return false;
+        };
+        let group = &mut self.axis_groups[g];
+        group.planner.stop();
+        for &m in group.members() {
+            let ax = &mut self.state.axes[m];
+            let mut sm = MotionStateMachine::with_state(
+                MotionState::from_u8(ax.motion_state).unwrap_or_default(),
+            );
+            let _ = sm.handle_event(MotionEvent::Stop);
+            ax.motion_state = sm.state() as u8;
+        }
+        true
+    }
+
+    /// Check that the machine allows motion from `origin`; `Idle` (or
+    /// `Manual` for RE) is promoted by the origin's motion event.
+    fn promote_for_motion(&mut self, origin: CommandOrigin) -> bool {
+        let Some(next) = self.motion_machine_state(origin) else {
+            return false;
+        };
+        self.state.machine_state = next;
+        true
+    }
+
+    /// Machine state that motion from `origin` runs in, or `None` if the
+    /// machine does not allow it (see `promote_for_motion`).
+    fn motion_machine_state(&self, origin: CommandOrigin) -> Option<MachineState> {
+        let current = self.state.machine_state;
+        let promote = match origin {
+            CommandOrigin::RecipeExecutor => {
+                matches!(current, MachineState::Idle | MachineState::Manual)
+            }
+            CommandOrigin::RpcApi => current == MachineState::Idle,
+        };
+        let mut machine = MachineStateMachine::with_state(current);
+        if !promote {
+            return machine.allows_motion().then_some(current);
+        }
+        match machine.handle_event(origin.motion_event()) {
+            TransitionResult::Ok(next) => Some(next),
+            TransitionResult::Rejected(_) => None,
+        }
+    }
+
+    /// Handle `SetMachineState` via the machine state machine (T036).
+    fn apply_machine_state(&mut self, target_state: u8) -> AckStatus {
+        let current = self.state.machine_state;
+        let Some(target) = MachineState::from_u8(target_state) else {
+            return AckStatus::Rejected;
+        };
+        if target == current {
+            return AckStatus::Ok;
+        }
+        let Some(event) = machine_event_for_target(current, target) else {
+            return AckStatus::Rejected;
+        };
+        match MachineStateMachine::with_state(current).handle_event(event) {
+            TransitionResult::Ok(next) => {
+                self.state.machine_state = next;
+                AckStatus::Ok
+            }
+            TransitionResult::Rejected(_) => AckStatus::Rejected,
+        }
+    }
+
+    /// Run the safety pipeline for every active axis (T053).
+    ///
+    /// 1. Evaluate peripherals and aggregate `AxisSafetyState` flags from
+    ///    the DI bank; error flags are latched until recovery.
+    /// 2. E-Stop, safety gate and CRITICAL errors drive the
+    ///    `SafetyStateMachine` (SafetyStop forces `SystemError`).
+    /// 3. During SAFETY_STOP, tick the per-axis `SafeStopExecutor` and
+    ///    apply its `StopAction`; once all axes are stopped, run the
+    ///    reset → flags clear → authorization recovery sequence.
+    /// 4. In SAFE_REDUCED_SPEED, clamp every target velocity (FR-011).
+    fn process_safety(&mut self) {
+        let n = self.state.axis_count as usize;
+        let dt = self.cycle_time_ns as f64 * 1.0e-9;
+        let di_bank = self.state.di_bank;
+        let registry = &self.io_registry;
+
+        // ── 1. Per-axis flags ──
+        let mut critical = false;
+        let mut all_axes_safe = true;
+        for i in 0..n {
+            let ax = &mut self.state.axes[i];
+            let Some(cfg) = self.config.machine.axes.get(i) else {
+                continue;
+            };
+            let power = PowerState::from_u8(ax.power_state).unwrap_or_default();
+            let is_powered = matches!(power, PowerState::Standby | PowerState::Motion);
+
+            let eval = self.peripherals[i].evaluate(
+                registry,
+                &di_bank,
+                is_powered,
+                ax.actual_velocity.abs(),
+            );
+            let input = SafetyFlagInput {
+                position: ax.actual_position,
+                min_pos: cfg.min_pos,
+                max_pos: cfg.max_pos,
+                in_position_window: cfg.in_position_window,
+                referenced: ax.referenced != 0,
+                gearbox_ok: ax.gearbox_errors == 0,
+            };
+            let (flags, power_errors, motion_errors) = evaluate_axis_safety(
+                &eval,
+                &input,
+                registry,
+                &di_bank,
+                cfg.axis_id,
+                cfg.motion_enable_input.is_some(),
+            );
+            self.safety_flags[i] = flags;
+            ax.power_errors |= power_errors.bits() as u32;
+            ax.motion_errors |= motion_errors.bits() as u32;
+
+            critical |= PowerError::from_bits_truncate(ax.power_errors as u16).has_critical()
+                || MotionError::from_bits_truncate(ax.motion_errors as u16).has_critical();
+            all_axes_safe &= flags.all_ok();
+
+            // FR-081: motion is blocked while any flag is false.
+            if !flags.all_ok() && power == PowerState::Motion {
+                if self.trajectories[i].is_active() {
+                    self.trajectories[i].cancel();
+                    ax.motion_state = MotionState::Standstill as u8;
+                }
+                ax.target_position = ax.actual_position;
+                ax.target_velocity = 0.0;
+                cancel_group_path(
+                    &mut self.axis_groups,
+                    &mut self.state.axes,
+                    &mut self.trajectories,
+                    &self.config.machine.axes,
+                    i,
+                );
+            }
+        }
+
+        // ── 2. Global safety inputs ──
+        let estop = registry.read_di(&IoRole::EStop, &di_bank).unwrap_or(false);
+        let gate_open = registry
+            .read_di(&IoRole::SafetyGate, &di_bank)
+            .unwrap_or(false);
+        all_axes_safe &= !estop;
+
+        if estop || critical || (gate_open && self.state.machine_state != MachineState::Service) {
+            self.safety_sm.handle_event(SafetyEvent::SafetyStop);
+        } else if gate_open {
+            self.safety_sm.handle_event(SafetyEvent::ReducedSpeed);
+        } else if self.safety_sm.requires_reduced_speed() {
+            self.safety_sm.handle_event(SafetyEvent::AllOk);
+        }
+
+        // ── 3. SAFETY_STOP execution + recovery ──
+        if self.safety_sm.requires_emergency_stop() {
+            self.state.machine_state = MachineState::SystemError;
+
+            for group in &mut self.axis_groups {
+                group.planner.cancel();
+            }
+            let mut all_stopped = true;
+            for i in 0..n {
+                let ax = &mut self.state.axes[i];
+                let exec = &mut self.stop_executors[i];
+                self.trajectories[i].cancel();
+                exec.trigger();
+                let action = exec.tick(ax.actual_velocity);
+                self.stop_actions[i] = action;
+                all_stopped &= exec.is_complete();
+
+                match action {
+                    StopAction::None => {}
+                    StopAction::Decelerate(rate) => {
+                        // Ramp starts from the measured velocity.
+                        let v = if ax.motion_state == MotionState::EmergencyStop as u8 {
+                            ax.target_velocity
+                        } else {
+                            ax.actual_velocity
+                        };
+                        let v_next = v - v.signum() * (rate * dt).min(v.abs());
+                        ax.target_velocity = v_next;
+                        ax.target_position = ax.actual_position + v_next * dt;
+                        ax.motion_state = MotionState::EmergencyStop as u8;
+                    }
+                    StopAction::DisableAndBrake => {
+                        ax.power_state = PowerState::PowerOff as u8;
+                        ax.target_velocity = 0.0;
+                        ax.motion_state = MotionState::EmergencyStop as u8;
+                    }
+                    StopAction::HoldTorque(_) => {
+                        ax.target_position = ax.actual_position;
+                        ax.target_velocity = 0.0;
+                        ax.motion_state = MotionState::Standstill as u8;
+                    }
+                }
+            }
+
+            if all_stopped {
+                self.recovery.begin();
+                let reset = RecoveryManager::read_reset_button(registry, &di_bank);
+                if self.recovery.tick(reset, all_axes_safe) == RecoveryStep::Complete {
+                    self.complete_recovery();
+                }
+            }
+        }
+
+        // ── 4. SAFE_REDUCED_SPEED clamping ──
+        if self.safety_sm.requires_reduced_speed() {
+            for i in 0..n {
+                let limit = self.config.machine.axes[i].safe_reduced_speed_limit;
+                let ax = &mut self.state.axes[i];
+                ax.target_velocity = clamp_velocity_for_safety(ax.target_velocity, limit);
+            }
+        }
+
+        self.state.safety_state = self.safety_sm.state();
+    }
+
+    /// Leave SAFETY_STOP after a completed recovery sequence (FR-122).
+    ///
+    /// Clears latched error flags, re-arms the stop executors and returns
+    /// the machine to `Idle`. Axes stay in their stopped power state until
+    /// re-enabled by a command.
+    fn complete_recovery(&mut self) {
+        self.safety_sm.handle_event(SafetyEvent::Recovery);
+        self.recovery.reset();
+        for i in 0..self.state.axis_count as usize {
+            let ax = &mut self.state.axes[i];
+            ax.power_errors = 0;
+            ax.motion_errors = 0;
+            ax.motion_state = MotionState::Standstill as u8;
+            self.stop_executors[i].reset();
+            self.stop_actions[i] = StopAction::None;
+        }
+        self.state.machine_state = MachineState::Idle;
+    }
+
+    /// Adopt power states written outside the power state machines (safe
+    /// stop executor, test harness) before the machines are driven.
+    fn sync_power_machines(&mut self) {
+        for i in 0..self.state.axis_count as usize {
+            let state = PowerState::from_u8(self.state.axes[i].power_state).unwrap_or_default();
+            self.power[i].force_state(state);
+        }
+    }
+
+    /// Hold back the ack of an accepted command that started power
+    /// sequences; `None` acks it right away.
+    fn defer_power_ack(&mut self, sequence_id: u32, status: AckStatus) -> Option<PendingPowerAck> {
+        let mask = core::mem::take(&mut self.power_wait);
+        (status == AckStatus::Ok && mask != 0).then_some(PendingPowerAck { sequence_id, mask })
+    }
+
+    /// Advance the POWERING_ON / POWERING_OFF sequences (T038, FR-021/022).
+    ///
+    /// At most one step completes per cycle, so each output change reaches
+    /// the drive before the next step checks its feedback. A drive fault or
+    /// a step exceeding its timeout (brake steps: `BrakeConfig`, others:
+    /// [`POWER_STEP_TIMEOUT_S`]) ends in `PowerError`. The brake monitor
+    /// follows the brake output of the sequence.
+    fn process_power(&mut self) {
+        self.sync_power_machines();
+        let cycles_per_s = 1.0e9 / self.cycle_time_ns.max(1) as f64;
+        let di_bank = self.state.di_bank;
+
+        for i in 0..self.state.axis_count as usize {
+            let sm = &mut self.power[i];
+            let ax = &mut self.state.axes[i];
+            let cfg = &self.config.machine.axes[i];
+            let peripherals = &mut self.peripherals[i];
+            let state = sm.state();
+
+            if matches!(state, PowerState::PoweringOn | PowerState::PoweringOff) {
+                sm.tick_sequence();
+                let brake_timeout = |s: fn(&BrakeConfig) -> f64| {
+                    cfg.brake.as_ref().map_or(POWER_STEP_TIMEOUT_S, s)
+                };
+                let brake_released = || {
+                    peripherals
+                        .brake
+                        .as_ref()
+                        .is_none_or(|b| b.is_released(&self.io_registry, &di_bank))
+                };
+                // Position held: axis at standstill.
+                let stable = ax.actual_velocity.abs() < cfg.standstill_velocity;
+
+                let (done, timeout_s, timeout_error) = if state == PowerState::PoweringOn {
+                    match power_on_step_from_u8(sm.sequence.step) {
+                        Some(PowerOnStep::CheckSafety) => (
+                            self.safety_flags[i].all_ok(),
+                            POWER_STEP_TIMEOUT_S,
+                            PowerError::DRIVE_NOT_READY,
+                        ),
+                        // HAL reports `ready` for referenced axes only.
+                        Some(PowerOnStep::WaitDriveReady) => (
+                            ax.drive_status != 0 || ax.referenced == 0,
+                            POWER_STEP_TIMEOUT_S,
+                            PowerError::DRIVE_NOT_READY,
+                        ),
+                        Some(PowerOnStep::WaitBrakeReleased) => (
+                            brake_released(),
+                            brake_timeout(|b| b.release_timeout),
+                            PowerError::BRAKE_TIMEOUT,
+                        ),
+                        Some(PowerOnStep::CheckPositionStable) => {
+                            (stable, POWER_STEP_TIMEOUT_S, PowerError::DRIVE_NOT_READY)
+                        }
+                        Some(PowerOnStep::ResetControlState) => {
+                            // I-PW-4: bumpless start from zeroed controller state.
+                            self.control_states[i].reset();
+                            (true, POWER_STEP_TIMEOUT_S, PowerError::DRIVE_NOT_READY)
+                        }
+                        _ => (true, POWER_STEP_TIMEOUT_S, PowerError::DRIVE_NOT_READY),
+                    }
+                } else {
+                    match power_off_step_from_u8(sm.sequence.step) {
+                        Some(PowerOffStep::EngageBrake) => (
+                            !brake_released(),
+                            brake_timeout(|b| b.engage_timeout),
+                            PowerError::BRAKE_TIMEOUT,
+                        ),
+                        Some(PowerOffStep::VerifyPosition) => {
+                            (stable, POWER_STEP_TIMEOUT_S, PowerError::DRIVE_NOT_READY)
+                        }
+                        Some(PowerOffStep::ExtendLockPin) => {
+                            if let Some(pin) = peripherals.lock_pin.as_mut() {
+                                pin.start_insert();
+                            }
+                            (true, POWER_STEP_TIMEOUT_S, PowerError::LOCK_PIN_TIMEOUT)
+                        }
+                        _ => (true, POWER_STEP_TIMEOUT_S, PowerError::DRIVE_NOT_READY),
+                    }
+                };
+
+                let timeout_cycles = (timeout_s * cycles_per_s).ceil() as u32;
+                let event = if ax.drive_fault_code != 0 {
+                    ax.power_errors |= PowerError::DRIVE_FAULT.bits() as u32;
+                    Some(PowerEvent::DriveFault)
+                } else if done {
+                    Some(PowerEvent::StepComplete)
+                } else if sm.sequence.timed_out(timeout_cycles) {
+                    ax.power_errors |= timeout_error.bits() as u32;
+                    Some(PowerEvent::StepTimeout)
+                } else {
+                    None
+                };
+                if let Some(event) = event {
+                    let _ = sm.handle_event(event, false);
+                    ax.power_state = sm.state() as u8;
+                }
+            }
+
+            if let Some(brake) = peripherals.brake.as_mut() {
+                if sm.brake_released() {
+                    brake.command_release();
+                } else {
+                    brake.command_engage();
+                }
+            }
+        }
+    }
+
+    /// Run the control engine for every active axis (T067).
+    ///
+    /// Axes in `PowerState::Motion` get the full PID + FF + DOB + filter
+    /// pipeline followed by lag monitoring. All other axes output zero and
+    /// hold their target at the actual position so the next enable is
+    /// bumpless. Controller state is reset on disable or mode change
+    /// (I-PW-4 / I-OM-4).
+    fn process_control(&mut self) {
+        let n = self.state.axis_count as usize;
+        let dt = self.cycle_time_ns as f64 * 1.0e-9;
+
+        // Coordinated paths write the setpoints of all group members.
+        let mut path_acceleration = [0.0; MAX_AXES as usize];
+        let reduced_speed = self.safety_sm.requires_reduced_speed();
+        for g in 0..self.axis_groups.len() {
+            let group = &self.axis_groups[g];
+            if !group.planner.is_active() {
+                continue;
+            }
+            let members = group.members();
+            if let Some(&off) = members
+                .iter()
+                .find(|&&i| self.state.axes[i].power_state != PowerState::Motion as u8)
+            {
+                cancel_group_path(
+                    &mut self.axis_groups,
+                    &mut self.state.axes,
+                    &mut self.trajectories,
+                    &self.config.machine.axes,
+                    off,
+                );
+                continue;
+            }
+            let limit = if reduced_speed {
+                members
+                    .iter()
+                    .map(|&i| self.config.machine.axes[i].safe_reduced_speed_limit)
+                    .fold(f64::INFINITY, f64::min)
+            } else {
+                f64::INFINITY
+            };
+            let group = &mut self.axis_groups[g];
+            group.planner.set_velocity_limit(limit);
+            group.planner.step(dt);
+            for (k, &i) in group.members().iter().enumerate() {
+                let sp = group.planner.setpoint(k);
+                let ax = &mut self.state.axes[i];
+                ax.target_position = sp.position;
+                ax.target_velocity = sp.velocity;
+                path_acceleration[i] = sp.acceleration;
+                let current = MotionState::from_u8(ax.motion_state).unwrap_or_default();
+                ax.motion_state = profile_motion_state(current, group.planner.phase()) as u8;
+            }
+        }
+
+        for (i, &path_acceleration) in path_acceleration.iter().enumerate().take(n) {
+            let ax = &mut self.state.axes[i];
+            let power = PowerState::from_u8(ax.power_state).unwrap_or_default();
+
+            // Reset on leaving Motion or on any operational mode change.
+            let was_motion = self.prev_power_state[i] == PowerState::Motion as u8;
+            let mode_changed = self.prev_operational_mode[i] != ax.operational_mode;
+            if (was_motion && power != PowerState::Motion) || mode_changed {
+                self.control_states[i].reset();
+            }
+            self.prev_power_state[i] = ax.power_state;
+            self.prev_operational_mode[i] = ax.operational_mode;
+
+            if power != PowerState::Motion {
+                self.trajectories[i].cancel();
+                ax.target_position = ax.actual_position;
+                ax.target_velocity = 0.0;
+                // An enabled drive (Standby, power sequences) holds in CSP.
+                ax.control_output = if self.power[i].drive_enabled() {
+                    [0.0, 0.0, ax.target_position, 0.0]
+                } else {
+                    [0.0; 4]
+                };
+                ax.lag = 0.0;
+                continue;
+            }
+
+            // Active profile drives the setpoints; otherwise targets are
+            // used as written (direct setpoint / stop executor).
+            let mut target_acceleration = path_acceleration;
+            let traj = &mut self.trajectories[i];
+            if traj.is_active() {
+                if self.safety_sm.requires_reduced_speed() {
+                    traj.limit_velocity(self.config.machine.axes[i].safe_reduced_speed_limit);
+                }
+                let sp = traj.step(dt);
+                ax.target_position = sp.position;
+                ax.target_velocity = sp.velocity;
+                target_acceleration = sp.acceleration;
+                let current = MotionState::from_u8(ax.motion_state).unwrap_or_default();
+                ax.motion_state = profile_motion_state(current, traj.phase()) as u8;
+            }
+
+            let params = &self.control_params[i];
+            let input = ControlInput {
+                target_position: ax.target_position,
+                actual_position: ax.actual_position,
+                target_velocity: ax.target_velocity,
+                actual_velocity: ax.actual_velocity,
+                target_acceleration,
+                dt,
+            };
+            let mut out = compute_control_output(&mut self.control_states[i], params, &input);
+            if let StopAction::HoldTorque(limit) = self.stop_actions[i] {
+                out.calculated_torque = out.calculated_torque.clamp(-limit, limit);
+            }
+            ax.control_output = [
+                out.calculated_torque,
+                out.target_velocity,
+                out.target_position,
+                out.torque_offset,
+            ];
+            ax.lag = ax.target_position - ax.actual_position;
+
+            // Lag monitoring (FR-103), independent of the control algorithm.
+            let lag = evaluate_lag(
+                ax.target_position,
+                ax.actual_position,
+                params.lag_error_limit,
+                params.lag_policy,
+            );
+            if !lag.exceeded {
+                continue;
+            }
+            ax.motion_errors |= lag.motion_error.bits() as u32;
+            if lag.trigger_safety_stop {
+                ax.motion_errors |= MotionError::LAG_CRITICAL.bits() as u32;
+                self.safety_sm.force_safety_stop();
+                self.state.safety_state = SafetyState::SafetyStop;
+            }
+            if lag.trigger_axis_stop {
+                // Axis-local stop: hold at the current position; a running
+                // path is aborted and the other members ramp down.
+                self.trajectories[i].cancel();
+                cancel_group_path(
+                    &mut self.axis_groups,
+                    &mut self.state.axes,
+                    &mut self.trajectories,
+                    &self.config.machine.axes,
+                    i,
+                );
+                let ax = &mut self.state.axes[i];
+                ax.motion_state = MotionState::MotionError as u8;
+                ax.target_position = ax.actual_position;
+                ax.target_velocity = 0.0;
+            }
+        }
+    }
+}
+
+// ─── Lockstep ───────────────────────────────────────────────────────
+
+/// Virtual-time execution: one cycle body per clock tick.
+///
+/// Virtual cycles take no time, so cycle statistics record zero duration
+/// and overrun detection is skipped.
+impl LockstepModule for CycleRunner {
+    fn name(&self) -> &str {
+        "cu"
+    }
+
+    fn step(&mut self, _clock: &VirtualClock) -> Result<(), String> {
+        self.cycle_body().map_err(|e| e.to_string())?;
+        self.state.stats.record(0, 0);
+        Ok(())
+    }
+}
+
+// ─── Helpers ────────────────────────────────────────────────────────
+
+/// Timeout of a power sequence step without its own configured timeout
+/// (FR-021: drive ready within 5 s).
+const POWER_STEP_TIMEOUT_S: f64 = 5.0;
+
+/// Enable / disable command whose ack waits for its power sequences.
+#[derive(Debug, Clone, Copy)]
+struct PendingPowerAck {
+    sequence_id: u32,
+    /// Axes still expected to finish their sequence.
+    mask: u64,
+}
+
+/// Take a pending power ack once none of its axes is sequencing any more.
+///
+/// Resolves to `Ok`, or `Error` if an axis ended in `PowerError`.
+fn resolve_power_ack(
+    pending: &mut Option<PendingPowerAck>,
+    axes: &[AxisRuntimeState],
+) -> Option<(u32, AckStatus)> {
+    let ack = (*pending)?;
+    let mut status = AckStatus::Ok;
+    for (i, ax) in axes.iter().enumerate() {
+        if ack.mask & (1 << i) == 0 {
+            continue;
+        }
+        match PowerState::from_u8(ax.power_state) {
+            Some(PowerState::PoweringOn | PowerState::PoweringOff) => return None,
+            Some(PowerState::PowerError) => status = AckStatus::Error,
+            _ => {}
+        }
+    }
+    *pending = None;
+    Some((ack.sequence_id, status))
+}
+
+/// Trajectory target of an admitted motion command (see `plan_motion`).
+#[derive(Debug, Clone, Copy)]
+struct MotionPlan {
+    /// Target position, `None` for velocity moves.
+    position: Option<f64>,
+    /// Signed velocity of velocity moves.
+    velocity: f64,
+    limits: TrajectoryLimits,
+}
+
+/// Operational mode `mode` requested for `ax`, or `None` if it is unknown,
+/// manual mode is not `manual_allowed`, or the mode machine rejects it.
+fn mode_change(ax: &AxisRuntimeState, mode: u8, manual_allowed: bool) -> Option<OperationalMode> {
+    let new_mode = OperationalMode::from_u8(mode)?;
+    if new_mode == OperationalMode::Manual && !manual_allowed {
+        return None;
+    }
+    let mut om = OperationalModeMachine::new();
+    om.force_mode(OperationalMode::from_u8(ax.operational_mode).unwrap_or_default());
+    let result = om.set_mode(
+        new_mode,
+        MotionState::from_u8(ax.motion_state).unwrap_or_default(),
+        PowerState::from_u8(ax.power_state).unwrap_or_default(),
+        CouplingState::from_u8(ax.coupling_state).unwrap_or_default(),
+    );
+    matches!(result, ModeTransition::Ok(_)).then(|| om.mode())
+}
+
+/// `AckStatus::Ok` if `accepted`, otherwise `AckStatus::Rejected`.
+#[inline]
+fn ack_if(accepted: bool) -> AckStatus {
+    if accepted { AckStatus::Ok } else { AckStatus::Rejected }
+}
+
+/// Command limit, or the axis limit when the command leaves it at 0.
+/// Never exceeds the axis limit.
+#[inline]
+fn command_limit(commanded: f64, axis: f64) -> f64 {
+    if commanded > 0.0 { commanded.min(axis) } else { axis }
+}
+
+/// Abort the active path of the group containing axis `i`, if any.
+///
+/// Axis `i` holds its last commanded position. The other members still
+/// moving in `Motion` hand their path setpoint to their trajectory
+/// generator and ramp down with their axis limits (`Stopping`); members at
+/// rest drop to `Standstill` unless in an error or emergency stop state.
+fn cancel_group_path(
+    groups: &mut [AxisGroup],
+    axes: &mut [AxisRuntimeState],
+    trajectories: &mut [TrajectoryGenerator],
+    configs: &[CuAxisConfig],
+    i: usize,
+) {
+    for group in groups
+        .iter_mut()
+        .filter(|g| g.planner.is_active() && g.mask() & (1 << i) != 0)
+    {
+        for (k, &m) in group.members().iter().enumerate() {
+            let sp = group.planner.setpoint(k);
+            let ax = &mut axes[m];
+            let cfg = &configs[m];
+            let limits = TrajectoryLimits {
+                max_velocity: cfg.max_velocity,
+                max_acceleration: cfg.max_acceleration,
+                max_deceleration: cfg.max_acceleration,
+                max_jerk: cfg.max_jerk,
+            };
+            if m != i
+                && ax.power_state == PowerState::Motion as u8
+                && (sp.velocity != 0.0 || sp.acceleration != 0.0)
+                && trajectories[m].stop_from(sp, limits)
+            {
+                let mut sm = MotionStateMachine::with_state(
+                    MotionState::from_u8(ax.motion_state).unwrap_or_default(),
+                );
+                let _ = sm.handle_event(MotionEvent::Stop);
+                ax.motion_state = sm.state() as u8;
+                continue;
+            }
+            ax.target_velocity = 0.0;
+            if ax.motion_state != MotionState::MotionError as u8
+                && ax.motion_state != MotionState::EmergencyStop as u8
+            {
+                ax.motion_state = MotionState::Standstill as u8;
+            }
+        }
+        group.planner.cancel();
+    }
+}
+
+/// Follow the trajectory phase with the motion state machine (T039).
+///
+/// Rejected transitions keep the current state, so `Stopping` holds
+/// until the profile ends.
+fn profile_motion_state(current: MotionState, phase: ProfilePhase) -> MotionState {
+    let events: &[MotionEvent] = match phase {
+        ProfilePhase::Accelerating => &[MotionEvent::StartMotion],
+        ProfilePhase::ConstantVelocity => &[MotionEvent::ReachedVelocity],
+        ProfilePhase::Decelerating => &[MotionEvent::Decelerating],
+        ProfilePhase::Idle => &[MotionEvent::Decelerating, MotionEvent::Standstill],
+    };
+    let mut sm = MotionStateMachine::with_state(current);
+    for &event in events {
+        let _ = sm.handle_event(event);
+    }
+    sm.state()
+}
+
+/// Rebuild a [`ControlOutputVector`] from its `AxisRuntimeState` array form
+/// `[calculated_torque, target_velocity, target_position, torque_offset]`.
+#[inline]
+fn output_from_array(v: &[f64; 4]) -> ControlOutputVector {
+    ControlOutputVector {
+        calculated_torque: v[0],
+        target_velocity: v[1],
+        target_position: v[2],
+        torque_offset: v[3],
+    }
 }
 
 // ─── Time Helpers ───────────────────────────────────────────────────
@@ -741,6 +2114,15 @@ mod tests {
         assert_eq!(stats.avg_cycle_ns(), 550_000);
     }
 
+    #[test]
+    fn cycle_stats_hal_age() {
+        let mut stats = CycleStats::new();
+        stats.record_hal_age(40_000);
+        stats.record_hal_age(15_000);
+        assert_eq!(stats.last_hal_age_ns, 15_000);
+        assert_eq!(stats.max_hal_age_ns, 40_000);
+    }
+
     #[test]
     fn axis_runtime_state_default_is_zeroed() {
         let state = AxisRuntimeState::default();
@@ -16,7 +16,8 @@ use clap::Parser;
 use evo_common::config::load_config_dir;
 use evo_common::io::config::IoConfig;
 use evo_common::io::registry::IoRegistry;
-use evo_control_unit::config::{load_config, LoadedConfig};
+use evo_common::shm::p2p::ShmNamespace;
+use evo_control_unit::config::{load_config, CuSection, LoadedConfig};
 use evo_control_unit::cycle::{rt_setup, CycleRunner};
 use std::path::PathBuf;
 use std::process;
@@ -42,6 +43,11 @@ struct Args {
     #[arg(default_value = "config/cu.toml")]
     config: PathBuf,
 
+    /// EVO instance id selecting the SHM namespace (overrides `instance`
+    /// in config.toml; default: `evo_*` segments).
+    #[arg(long, value_name = "ID")]
+    instance: Option<String>,
+
     /// CPU core to pin the RT thread to (default: 1).
     #[arg(long, default_value_t = 1)]
     cpu_core: usize,
@@ -74,7 +80,7 @@ fn main() {
 }
 
 fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
-    let loaded = if let Some(ref config_dir) = args.config_dir {
+    let mut loaded = if let Some(ref config_dir) = args.config_dir {
         // ── Unified config-dir path ──
         info!("Loading unified config from {:?}", config_dir);
         let full = load_config_dir(config_dir)?;
@@ -88,7 +94,9 @@ fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
         let io_registry = load_io_registry(config_dir);
 
         // Adapt FullConfig → CU LoadedConfig.
-        adapt_full_config(&full, io_registry)?
+        let mut loaded = adapt_full_config(&full, io_registry)?;
+        loaded.shm_ns = full.system.shm_namespace()?;
+        loaded
     } else {
         // ── Legacy single-file path ──
         warn!(
@@ -98,10 +106,15 @@ fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
         load_config(&args.config).map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?
     };
 
+    if let Some(ref id) = args.instance {
+        loaded.shm_ns = ShmNamespace::new(id)?;
+    }
+
     info!(
-        "Config OK: cycle_time={}µs, axes={}",
+        "Config OK: cycle_time={}µs, axes={}, shm namespace={}",
         loaded.cu_config.cycle_time_us,
         loaded.machine.axes.len(),
+        loaded.shm_ns,
     );
 
     // RT setup (mlockall, affinity, scheduler).
@@ -136,8 +149,9 @@ fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
 
 /// Adapt a `FullConfig` (from `load_config_dir`) to the CU's `LoadedConfig`.
 ///
-/// Maps the unified config structs into the CU-specific types. If `IoRegistry`
-/// is `None`, creates a default empty registry.
+/// Maps the unified config structs into the CU-specific types; CU settings
+/// come from the `[cu]` section of `config.toml`. If `IoRegistry` is `None`,
+/// creates a default empty registry.
 fn adapt_full_config(
     full: &evo_common::config::FullConfig,
     io_registry: Option<IoRegistry>,
@@ -148,7 +162,9 @@ fn adapt_full_config(
     };
     use evo_common::config::DEFAULT_CYCLE_TIME_US;
 
-    // Build ControlUnitConfig from defaults.
+    let section = CuSection::from_value(full.system.cu.as_ref())?;
+
+    // Build ControlUnitConfig from defaults and the `[cu]` section.
     let cu_config = ControlUnitConfig {
         cycle_time_us: DEFAULT_CYCLE_TIME_US,
         max_axes: full.axes.len() as u8,
@@ -156,6 +172,7 @@ fn adapt_full_config(
         io_config_path: String::new(),      // Not used in unified mode
         manual_timeout: MANUAL_TIMEOUT_DEFAULT,
         hal_stale_threshold: HAL_STALE_THRESHOLD_DEFAULT,
+        hal_max_age_us: section.hal_max_age_us,
         re_stale_threshold: NON_RT_STALE_THRESHOLD_DEFAULT,
         rpc_stale_threshold: NON_RT_STALE_THRESHOLD_DEFAULT,
         mqt_update_interval: MQT_UPDATE_INTERVAL_DEFAULT,
@@ -170,6 +187,7 @@ fn adapt_full_config(
 
     let machine = CuMachineConfig {
         axes,
+        axis_groups: full.machine.axis_groups.clone(),
         ..Default::default()
     };
 
@@ -179,6 +197,7 @@ fn adapt_full_config(
         cu_config,
         machine,
         io_registry: registry,
+        shm_ns: ShmNamespace::default(),
     })
 }
 
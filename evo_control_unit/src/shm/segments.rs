@@ -4,7 +4,8 @@
 //! attaches inbound reader segments (HAL→CU, RE→CU, RPC→CU) using
 //! `TypedP2pWriter` / `TypedP2pReader` from `evo_common::shm::p2p`.
 
-use evo_common::shm::p2p::{ModuleAbbrev, ShmError, TypedP2pReader, TypedP2pWriter};
+use evo_common::shm::p2p::{ModuleAbbrev, ShmError, ShmNamespace, TypedP2pReader, TypedP2pWriter};
+use std::time::Duration;
 use evo_common::shm::segments::{
     CuToHalSegment, CuToMqtSegment, CuToReSegment, HalToCuSegment, ReToCuSegment,
     RpcToCuSegment, SEG_CU_HAL, SEG_CU_MQT, SEG_CU_RE, SEG_HAL_CU, SEG_RE_CU, SEG_RPC_CU,
@@ -15,11 +16,14 @@ use evo_common::shm::segments::{
 /// Staleness thresholds for inbound segment readers.
 ///
 /// These control how many consecutive reads without a heartbeat change
-/// trigger a staleness error.
+/// trigger a staleness error, or, for HAL, optionally how old the last
+/// commit may be.
 #[derive(Debug, Clone, Copy)]
 pub struct SegmentThresholds {
     /// HAL heartbeat staleness threshold [cycles] (FR-130c: RT, default 3).
     pub hal_stale: u32,
+    /// HAL commit age limit; replaces `hal_stale` when set (default none).
+    pub hal_max_age: Option<Duration>,
     /// RE heartbeat staleness threshold [cycles] (configurable, default 1000).
     pub re_stale: u32,
     /// RPC heartbeat staleness threshold [cycles] (configurable, default 1000).
@@ -30,6 +34,7 @@ impl Default for SegmentThresholds {
     fn default() -> Self {
         Self {
             hal_stale: 3,
+            hal_max_age: None,
             re_stale: 1000,
             rpc_stale: 1000,
         }
@@ -59,6 +64,9 @@ pub struct CuSegments {
     pub re_to_cu: Option<TypedP2pReader<ReToCuSegment>>,
     /// RPC → CU: API commands. **Optional** (API may start later).
     pub rpc_to_cu: Option<TypedP2pReader<RpcToCuSegment>>,
+
+    /// SHM namespace of the segments (used for late attach).
+    namespace: ShmNamespace,
 }
 
 impl CuSegments {
@@ -68,37 +76,49 @@ impl CuSegments {
     /// - HAL→CU is required; failure to attach is a fatal error.
     /// - RE→CU and RPC→CU are optional; `SegmentNotFound` is not an error.
     pub fn init(thresholds: &SegmentThresholds) -> Result<Self, ShmError> {
+        Self::init_in(&ShmNamespace::default(), thresholds)
+    }
+
+    /// Same as [`init`](Self::init), in namespace `ns`.
+    pub fn init_in(ns: &ShmNamespace, thresholds: &SegmentThresholds) -> Result<Self, ShmError> {
         // ── Create outbound writer segments ──
-        let cu_to_hal = TypedP2pWriter::<CuToHalSegment>::create(
+        let cu_to_hal = TypedP2pWriter::<CuToHalSegment>::create_in(
+            ns,
             SEG_CU_HAL,
             ModuleAbbrev::Cu,
             ModuleAbbrev::Hal,
         )?;
-        let cu_to_mqt = TypedP2pWriter::<CuToMqtSegment>::create(
+        let cu_to_mqt = TypedP2pWriter::<CuToMqtSegment>::create_in(
+            ns,
             SEG_CU_MQT,
             ModuleAbbrev::Cu,
             ModuleAbbrev::Mqt,
         )?;
-        let cu_to_re = TypedP2pWriter::<CuToReSegment>::create(
+        let cu_to_re = TypedP2pWriter::<CuToReSegment>::create_in(
+            ns,
             SEG_CU_RE,
             ModuleAbbrev::Cu,
             ModuleAbbrev::Re,
         )?;
 
         // ── Attach inbound reader segments ──
-        let hal_to_cu =
-            TypedP2pReader::<HalToCuSegment>::attach(SEG_HAL_CU, thresholds.hal_stale)?;
+        let mut hal_to_cu =
+            TypedP2pReader::<HalToCuSegment>::attach_in(ns, SEG_HAL_CU, thresholds.hal_stale)?;
+        if let Some(max_age) = thresholds.hal_max_age {
+            hal_to_cu = hal_to_cu.with_max_age(max_age);
+        }
 
         // RE and RPC are optional — they may not be running yet.
         let re_to_cu =
-            match TypedP2pReader::<ReToCuSegment>::attach(SEG_RE_CU, thresholds.re_stale) {
+            match TypedP2pReader::<ReToCuSegment>::attach_in(ns, SEG_RE_CU, thresholds.re_stale) {
                 Ok(r) => Some(r),
                 Err(ShmError::SegmentNotFound { .. }) => None,
                 Err(e) => return Err(e),
             };
 
         let rpc_to_cu =
-            match TypedP2pReader::<RpcToCuSegment>::attach(SEG_RPC_CU, thresholds.rpc_stale) {
+            match TypedP2pReader::<RpcToCuSegment>::attach_in(ns, SEG_RPC_CU, thresholds.rpc_stale)
+            {
                 Ok(r) => Some(r),
                 Err(ShmError::SegmentNotFound { .. }) => None,
                 Err(e) => return Err(e),
@@ -111,6 +131,7 @@ impl CuSegments {
             hal_to_cu,
             re_to_cu,
             rpc_to_cu,
+            namespace: ns.clone(),
         })
     }
 
@@ -119,7 +140,7 @@ impl CuSegments {
         if self.re_to_cu.is_some() {
             return Ok(true);
         }
-        match TypedP2pReader::<ReToCuSegment>::attach(SEG_RE_CU, stale_threshold) {
+        match TypedP2pReader::<ReToCuSegment>::attach_in(&self.namespace, SEG_RE_CU, stale_threshold) {
             Ok(r) => {
                 self.re_to_cu = Some(r);
                 Ok(true)
@@ -134,7 +155,8 @@ impl CuSegments {
         if self.rpc_to_cu.is_some() {
             return Ok(true);
         }
-        match TypedP2pReader::<RpcToCuSegment>::attach(SEG_RPC_CU, stale_threshold) {
+        match TypedP2pReader::<RpcToCuSegment>::attach_in(&self.namespace, SEG_RPC_CU, stale_threshold)
+        {
             Ok(r) => {
                 self.rpc_to_cu = Some(r);
                 Ok(true)
@@ -153,6 +175,7 @@ mod tests {
     fn segment_thresholds_defaults() {
         let t = SegmentThresholds::default();
         assert_eq!(t.hal_stale, 3);
+        assert_eq!(t.hal_max_age, None);
         assert_eq!(t.re_stale, 1000);
         assert_eq!(t.rpc_stale, 1000);
     }
@@ -8,10 +8,10 @@
 
 use evo_common::consts::MAX_AXES;
 use evo_common::control_unit::error::AxisErrorState;
-use evo_common::control_unit::shm::AxisStateSnapshot;
 use evo_common::control_unit::state::{
     CouplingState, GearboxState, LoadingState,
 };
+use evo_common::shm::segments::CuAxisStatus;
 
 use super::motion::MotionStateMachine;
 use super::operational::OperationalModeMachine;
@@ -62,28 +62,16 @@ impl AxisState {
         }
     }
 
-    /// Produce a diagnostic snapshot of this axis for the MQT segment.
-    pub fn snapshot(&self) -> AxisStateSnapshot {
-        AxisStateSnapshot {
-            axis_id: self.axis_id,
-            power: self.power.state() as u8,
-            motion: self.motion.state() as u8,
-            operational: self.operational.mode() as u8,
-            coupling: self.coupling as u8,
-            gearbox: self.gearbox as u8,
-            loading: self.loading as u8,
-            locked_by: self.locked_by,
+    /// Produce the `evo_cu_mqt` / `evo_cu_rpc` status entry of this axis.
+    pub fn snapshot(&self) -> CuAxisStatus {
+        CuAxisStatus {
+            axis_state: self.power.state() as u8,
+            motion_state: self.motion.state() as u8,
+            error_state: self.errors.has_any_error() as u8,
+            enable_state: self.operational.mode() as u8,
+            error_code: self.errors.power.bits(),
             safety_flags: 0xFF, // TODO: from AxisSafetyState (T042+)
-            error_power: self.errors.power.bits(),
-            error_motion: self.errors.motion.bits(),
-            error_command: self.errors.command.bits(),
-            error_gearbox: self.errors.gearbox.bits(),
-            error_coupling: self.errors.coupling.bits(),
-            _pad: [0u8; 5],
-            position: 0.0,  // filled from HAL feedback in cycle_body
-            velocity: 0.0,
-            lag: 0.0,
-            torque: 0.0,
+            ..CuAxisStatus::default()
         }
     }
 }
@@ -180,9 +168,9 @@ mod tests {
     fn axis_state_snapshot_fields() {
         let ax = AxisState::new(5, true, false, false);
         let snap = ax.snapshot();
-        assert_eq!(snap.axis_id, 5);
-        assert_eq!(snap.power, PowerState::PowerOff as u8);
-        assert_eq!(snap.coupling, CouplingState::Uncoupled as u8);
+        assert_eq!(snap.axis_state, PowerState::PowerOff as u8);
+        assert_eq!(snap.enable_state, OperationalMode::Position as u8);
+        assert_eq!(snap.error_state, 0);
     }
 
     #[test]
@@ -61,6 +61,12 @@ impl MachineStateMachine {
         }
     }
 
+    /// Resume the machine at a known state (the cycle stores the state
+    /// in `RuntimeState` and evaluates transitions on demand).
+    pub const fn with_state(state: MachineState) -> Self {
+        Self { state }
+    }
+
     /// Current state.
     #[inline]
     pub const fn state(&self) -> MachineState {
@@ -68,6 +68,11 @@ impl MotionStateMachine {
         }
     }
 
+    /// Resume from a stored state (runtime state is kept as `u8`).
+    pub const fn with_state(state: MotionState) -> Self {
+        Self { state }
+    }
+
     #[inline]
     pub const fn state(&self) -> MotionState {
         self.state
@@ -109,6 +114,14 @@ impl MotionStateMachine {
             // Decelerating → Standstill
             (S::Decelerating, E::Standstill) => S::Standstill,
 
+            // Retarget while moving or stopping → Accelerating
+            (S::ConstantVelocity | S::Decelerating | S::Stopping, E::StartMotion) => {
+                S::Accelerating
+            }
+
+            // Decelerating → ConstantVelocity (slowed to a new cruise velocity)
+            (S::Decelerating, E::ReachedVelocity) => S::ConstantVelocity,
+
             // Any moving → Stopping (controlled stop)
             (S::Accelerating | S::ConstantVelocity | S::Decelerating, E::Stop) => S::Stopping,
 
@@ -261,6 +274,20 @@ mod tests {
         assert_eq!(sm.handle_event(E::Decelerating), MotionTransition::Ok(S::Decelerating));
     }
 
+    #[test]
+    fn retarget_while_moving() {
+        for state in [S::ConstantVelocity, S::Decelerating, S::Stopping] {
+            let mut sm = MotionStateMachine::with_state(state);
+            assert_eq!(
+                sm.handle_event(E::StartMotion),
+                MotionTransition::Ok(S::Accelerating),
+                "StartMotion from {state:?}"
+            );
+        }
+        let mut sm = MotionStateMachine::with_state(S::Decelerating);
+        assert_eq!(sm.handle_event(E::ReachedVelocity), MotionTransition::Ok(S::ConstantVelocity));
+    }
+
     #[test]
     fn controlled_stop() {
         let mut sm = MotionStateMachine { state: S::ConstantVelocity };
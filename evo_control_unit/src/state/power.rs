@@ -334,6 +334,41 @@ impl PowerStateMachine {
         self.state = PowerState::PowerError;
     }
 
+    /// Adopt a state set outside the machine (e.g., STO by the safe-stop
+    /// executor). Any running sequence is abandoned.
+    pub fn force_state(&mut self, state: PowerState) {
+        if self.state != state {
+            self.state = state;
+            self.sequence = SequenceTracker::new();
+        }
+    }
+
+    /// Whether the drive enable output is set.
+    ///
+    /// POWERING_ON enables the drive from `EnableDrive`; POWERING_OFF keeps
+    /// it enabled until `DisableDrive`.
+    pub const fn drive_enabled(&self) -> bool {
+        match self.state {
+            PowerState::Standby | PowerState::Motion => true,
+            PowerState::PoweringOn => self.sequence.step >= PowerOnStep::EnableDrive as u8,
+            PowerState::PoweringOff => self.sequence.step < PowerOffStep::DisableDrive as u8,
+            _ => false,
+        }
+    }
+
+    /// Whether the brake release output is set.
+    ///
+    /// POWERING_ON releases the brake from `ReleaseBrake`; POWERING_OFF
+    /// engages it at `EngageBrake`.
+    pub const fn brake_released(&self) -> bool {
+        match self.state {
+            PowerState::Standby | PowerState::Motion | PowerState::NoBrake => true,
+            PowerState::PoweringOn => self.sequence.step >= PowerOnStep::ReleaseBrake as u8,
+            PowerState::PoweringOff => self.sequence.step < PowerOffStep::EngageBrake as u8,
+            _ => false,
+        }
+    }
+
     /// Skip inapplicable POWERING_ON steps.
     fn skip_inapplicable_on_steps(&mut self) {
         loop {
@@ -369,7 +404,8 @@ impl PowerStateMachine {
     }
 }
 
-fn power_on_step_from_u8(v: u8) -> Option<PowerOnStep> {
+/// Decode a POWERING_ON step index.
+pub const fn power_on_step_from_u8(v: u8) -> Option<PowerOnStep> {
     match v {
         0 => Some(PowerOnStep::CheckSafety),
         1 => Some(PowerOnStep::EnableDrive),
@@ -383,7 +419,8 @@ fn power_on_step_from_u8(v: u8) -> Option<PowerOnStep> {
     }
 }
 
-fn power_off_step_from_u8(v: u8) -> Option<PowerOffStep> {
+/// Decode a POWERING_OFF step index.
+pub const fn power_off_step_from_u8(v: u8) -> Option<PowerOffStep> {
     match v {
         0 => Some(PowerOffStep::CheckLockPosition),
         1 => Some(PowerOffStep::EngageBrake),
@@ -569,6 +606,42 @@ mod tests {
         assert!(sm.allows_motion_output());
     }
 
+    #[test]
+    fn outputs_follow_sequence_steps() {
+        let mut sm = simple_axis();
+        sm.handle_event(Enable, false);
+        assert!(!sm.drive_enabled() && !sm.brake_released()); // CheckSafety
+        sm.handle_event(StepComplete, false);
+        assert!(sm.drive_enabled() && !sm.brake_released()); // EnableDrive
+        while sm.sequence.step < PowerOnStep::ReleaseBrake as u8 {
+            sm.handle_event(StepComplete, false);
+        }
+        assert!(sm.drive_enabled() && sm.brake_released());
+
+        let mut sm = PowerStateMachine { state: Standby, ..simple_axis() };
+        sm.handle_event(Disable, false);
+        while sm.sequence.step < PowerOffStep::EngageBrake as u8 {
+            assert!(sm.brake_released());
+            sm.handle_event(StepComplete, false);
+        }
+        assert!(sm.drive_enabled() && !sm.brake_released());
+        while sm.sequence.step < PowerOffStep::DisableDrive as u8 {
+            sm.handle_event(StepComplete, false);
+        }
+        assert!(!sm.drive_enabled());
+    }
+
+    #[test]
+    fn force_state_abandons_sequence() {
+        let mut sm = simple_axis();
+        sm.handle_event(Enable, false);
+        sm.handle_event(StepComplete, false);
+        sm.force_state(PowerOff);
+        assert_eq!(sm.state(), PowerOff);
+        assert_eq!(sm.sequence.step, 0);
+        assert!(!sm.drive_enabled());
+    }
+
     #[test]
     fn sequence_tracker_tick_and_timeout() {
         let mut t = SequenceTracker::new();
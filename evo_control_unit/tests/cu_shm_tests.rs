@@ -252,9 +252,10 @@ fn test_cu_to_re_segment_roundtrip() {
             .expect("writer create failed");
 
     let mut payload: CuToReSegment = unsafe { core::mem::zeroed() };
-    // CuToReSegment is reserved — write a pattern into the reserved bytes.
-    payload._reserved[0] = 0xAA;
-    payload._reserved[255] = 0xBB;
+    payload.last_ack_seq_id = 42;
+    payload.ack_status = 1;
+    payload.axes_in_position = 0b101;
+    payload.axes_in_error = 1 << 63;
 
     writer.commit(&payload).expect("commit failed");
 
@@ -262,7 +263,9 @@ fn test_cu_to_re_segment_roundtrip() {
         TypedP2pReader::<CuToReSegment>::attach(&seg_name, 10).expect("reader attach failed");
     let read = reader.read().expect("read failed");
 
-    assert_eq!(read._reserved[0], 0xAA);
-    assert_eq!(read._reserved[255], 0xBB);
-    assert_eq!(read._reserved[1], 0x00); // Untouched bytes are zero.
+    assert_eq!(read.last_ack_seq_id, 42);
+    assert_eq!(read.ack_status, 1);
+    assert_eq!(read.axes_in_position, 0b101);
+    assert_eq!(read.axes_in_error, 1 << 63);
+    assert_eq!(read._reserved[0], 0x00); // Untouched bytes are zero.
 }
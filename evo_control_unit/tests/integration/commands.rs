@@ -0,0 +1,298 @@
+//! Integration test: RE / RPC command processing in the cycle body (T043).
+//!
+//! Publishes commands on `evo_re_cu` / `evo_rpc_cu` and checks:
+//! 1. Commands reach the axis state and are acknowledged in `evo_cu_re`,
+//!    each source in its own ack field pair
+//! 2. Each `sequence_id` is processed exactly once
+//! 3. Source locks reject commands from the non-owning origin (FR-135)
+//! 4. Unreferenced-axis policy and SAFETY_STOP reject motion (FR-035)
+//! 5. `axes_in_position` / `axes_in_error` follow the axis state
+//! 6. Enable / disable run the power sequences and are acked at their end
+//! 7. A restarted CU does not re-execute the commands left in `evo_re_cu` /
+//!    `evo_rpc_cu`
+//! 8. A multi-axis command that one axis cannot take leaves every axis
+//!    untouched
+
+use evo_common::control_unit::state::{MachineState, MotionState, PowerState, SafetyState};
+use evo_common::shm::segments::{AckStatus, ReCommandType, RpcCommand, RpcCommandType};
+
+use super::sim_loop::{PIN_ESTOP, PIN_LIMIT_MAX_1, SimLoop, re, two_axis_machine};
+
+const CONTROL: &str = "kp = 100.0\nki = 10.0\nkd = 1.0\nout_max = 50.0\n\
+                       lag_error_limit = 50.0\nlag_policy = \"Neutral\"";
+
+fn machine() -> String {
+    two_axis_machine(CONTROL, "")
+}
+
+fn rpc(command_type: RpcCommandType, axis_id: u8, sequence_id: u32) -> RpcCommand {
+    RpcCommand { command_type: command_type as u8, axis_id, sequence_id, ..RpcCommand::default() }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[test]
+fn re_move_is_acknowledged_and_reaches_target() {
+    let mut sim = SimLoop::new(&machine());
+    sim.ready(0b01);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::Standby as u8);
+
+    let mut mv = re(ReCommandType::MoveAbsolute, 0b01, 3);
+    mv.targets[0].target_position = 5.0;
+    assert_eq!(sim.exec_re(mv), AckStatus::Ok);
+    assert_eq!(sim.runner.state.machine_state, MachineState::Active);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::Motion as u8);
+    assert_eq!(sim.ack().axes_in_position & 0b01, 0);
+
+    sim.ticks(2000);
+    let x = &sim.runner.state.axes[0];
+    assert!((x.actual_position - 5.0).abs() < 0.01, "X at {}", x.actual_position);
+    assert_eq!(x.motion_state, MotionState::Standstill as u8);
+    let ack = sim.ack();
+    assert_eq!(ack.axes_in_position & 0b11, 0b11);
+    assert_eq!(ack.axes_in_error, 0);
+}
+
+#[test]
+fn duplicate_sequence_id_is_processed_once() {
+    let mut sim = SimLoop::new(&machine());
+    sim.ready(0b01);
+
+    let mut mv = re(ReCommandType::MoveRelative, 0b01, 3);
+    mv.targets[0].target_position = 1.0;
+    assert_eq!(sim.exec_re(mv), AckStatus::Ok);
+    assert_eq!(sim.runner.trajectories[0].target(), Some(1.0));
+
+    // Re-publishing the same sequence_id must not move the target again.
+    sim.send_re(mv);
+    sim.ticks(3);
+    assert_eq!(sim.runner.trajectories[0].target(), Some(1.0));
+
+    mv.sequence_id = 4;
+    assert_eq!(sim.exec_re(mv), AckStatus::Ok);
+    assert_eq!(sim.runner.trajectories[0].target(), Some(2.0));
+}
+
+#[test]
+fn re_and_rpc_acks_in_same_cycle_are_kept_apart() {
+    let mut sim = SimLoop::new(&machine());
+
+    // Both sources number from 1; they land in the same cycle. The RPC
+    // rejection is acked at once, the RE enable after its power sequence.
+    sim.send_re(re(ReCommandType::EnableAxis, 0b01, 1));
+    sim.send_rpc(rpc(RpcCommandType::EnableAxis, 3, 1));
+    sim.tick();
+    let ack = sim.ack();
+    assert_eq!(ack.last_ack_seq_id, 0);
+    assert_eq!(ack.rpc_ack_seq_id, 1);
+    assert_eq!(AckStatus::from_u8(ack.rpc_ack_status), Some(AckStatus::Rejected));
+    assert_eq!(sim.await_re_ack(1), AckStatus::Ok);
+    assert_eq!(sim.ack().rpc_ack_seq_id, 1);
+
+    // A later RPC command leaves the RE ack untouched.
+    sim.send_rpc(rpc(RpcCommandType::EnableAxis, 2, 2));
+    assert_eq!(sim.await_rpc_ack(2), AckStatus::Ok);
+    let ack = sim.ack();
+    assert_eq!((ack.last_ack_seq_id, ack.rpc_ack_seq_id), (1, 2));
+    assert_eq!(AckStatus::from_u8(ack.ack_status), Some(AckStatus::Ok));
+    assert_eq!(AckStatus::from_u8(ack.rpc_ack_status), Some(AckStatus::Ok));
+}
+
+#[test]
+fn source_lock_rejects_other_origin() {
+    let mut sim = SimLoop::new(&machine());
+    sim.ready(0b01);
+
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::AcquireLock, 1, 10)), AckStatus::Ok);
+
+    let mut mv = re(ReCommandType::MoveAbsolute, 0b01, 3);
+    mv.targets[0].target_position = 2.0;
+    assert_eq!(sim.exec_re(mv), AckStatus::Rejected);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::Standby as u8);
+
+    // A multi-axis command touching the locked axis is rejected as a whole.
+    assert_eq!(sim.exec_re(re(ReCommandType::DisableAxis, 0b11, 4)), AckStatus::Rejected);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::Standby as u8);
+
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::ReleaseLock, 1, 11)), AckStatus::Ok);
+    mv.sequence_id = 5;
+    assert_eq!(sim.exec_re(mv), AckStatus::Ok);
+    assert_eq!(sim.runner.trajectories[0].target(), Some(2.0));
+}
+
+#[test]
+fn invalid_commands_are_not_applied() {
+    let mut sim = SimLoop::new(&machine());
+
+    let mut bad = re(ReCommandType::EnableAxis, 0b01, 1);
+    bad.command_type = 99;
+    assert_eq!(sim.exec_re(bad), AckStatus::Error);
+
+    // Axis 3 is not configured.
+    assert_eq!(sim.exec_re(re(ReCommandType::EnableAxis, 0b100, 2)), AckStatus::Rejected);
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::EnableAxis, 3, 1)), AckStatus::Rejected);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::PowerOff as u8);
+}
+
+#[test]
+fn unreferenced_axis_rejects_position_mode_motion() {
+    let mut sim = SimLoop::unreferenced(&machine());
+    sim.tick();
+    assert_eq!(sim.runner.state.axes[0].referenced, 0);
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::EnableAxis, 1, 1)), AckStatus::Ok);
+
+    let mut mv = rpc(RpcCommandType::MoveAbsolute, 1, 2);
+    mv.param_f64 = 3.0;
+    assert_eq!(sim.exec_rpc(mv), AckStatus::Rejected);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::Standby as u8);
+    assert_eq!(sim.runner.state.machine_state, MachineState::Idle);
+}
+
+#[test]
+fn jog_is_clamped_for_unreferenced_axis() {
+    let mut sim = SimLoop::unreferenced(&machine());
+    sim.ready(0b01);
+
+    let mut jog = rpc(RpcCommandType::JogPositive, 1, 1);
+    jog.param_f64 = 100.0;
+    assert_eq!(sim.exec_rpc(jog), AckStatus::Ok);
+    assert_eq!(sim.runner.state.machine_state, MachineState::Manual);
+
+    sim.ticks(200);
+    // 5 % of max_velocity = 500.0 (FR-035).
+    assert!((sim.runner.state.axes[0].target_velocity - 25.0).abs() < 1e-9);
+    assert!(sim.runner.state.axes[0].actual_position > 2.0);
+
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::JogStop, 1, 2)), AckStatus::Ok);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::Stopping as u8);
+    sim.ticks(200);
+    let x = &sim.runner.state.axes[0];
+    assert_eq!(x.motion_state, MotionState::Standstill as u8);
+    assert_eq!(x.target_velocity, 0.0);
+}
+
+#[test]
+fn safety_stop_rejects_enable_and_reports_errors() {
+    let mut sim = SimLoop::new(&machine());
+    sim.ready(0b01);
+
+    sim.set_di(PIN_ESTOP, false);
+    sim.tick();
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::EnableAxis, 2, 1)), AckStatus::Rejected);
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::ResetError, 1, 2)), AckStatus::Rejected);
+
+    // RE-triggered emergency stop on a healthy machine.
+    drop(sim);
+    let mut sim = SimLoop::new(&machine());
+    assert_eq!(sim.exec_re(re(ReCommandType::EmergencyStop, 0b01, 1)), AckStatus::Ok);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+}
+
+#[test]
+fn axes_in_error_tracks_motion_errors() {
+    let mut sim = SimLoop::new(&two_axis_machine(
+        "kp = 100.0\nout_max = 50.0\nlag_error_limit = 0.5\nlag_policy = \"Unwanted\"",
+        "",
+    ));
+    sim.ready(0b01);
+
+    let mut mv = re(ReCommandType::MoveAbsolute, 0b01, 3);
+    mv.targets[0].target_position = 50.0;
+    sim.exec_re(mv);
+    // Commanded 500 mm/s, simulated drive limited to 100 mm/s → lag grows.
+    for _ in 0..1000 {
+        if sim.ack().axes_in_error != 0 {
+            break;
+        }
+        sim.tick();
+    }
+    assert_eq!(sim.ack().axes_in_error, 0b01);
+
+    // Motion rejected while the axis is in MotionError.
+    mv.sequence_id = 4;
+    assert_eq!(sim.exec_re(mv), AckStatus::Rejected);
+
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::ResetError, 1, 1)), AckStatus::Ok);
+    sim.tick();
+    assert_eq!(sim.ack().axes_in_error, 0);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::Standstill as u8);
+}
+
+#[test]
+fn enable_and_disable_run_power_sequences() {
+    let mut sim = SimLoop::new(&machine());
+
+    sim.send_re(re(ReCommandType::EnableAxis, 0b01, 1));
+    sim.tick();
+    // Safety checked; the drive is enabled, the brake still held.
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::PoweringOn as u8);
+    assert_eq!(sim.ack().last_ack_seq_id, 0);
+    assert_eq!(sim.runner.state.out_hal.axes[0].enable, 1);
+    assert_eq!(sim.runner.state.out_hal.axes[0].brake_release, 0);
+
+    assert_eq!(sim.await_re_ack(1), AckStatus::Ok);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::Standby as u8);
+    assert_eq!(sim.runner.state.out_hal.axes[0].brake_release, 1);
+
+    sim.send_re(re(ReCommandType::DisableAxis, 0b01, 2));
+    sim.tick();
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::PoweringOff as u8);
+    assert_eq!(sim.runner.state.out_hal.axes[0].enable, 1);
+    assert_eq!(sim.await_re_ack(2), AckStatus::Ok);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::PowerOff as u8);
+    assert_eq!(sim.runner.state.out_hal.axes[0].enable, 0);
+    assert_eq!(sim.runner.state.out_hal.axes[0].brake_release, 0);
+}
+
+#[test]
+fn enable_with_failed_safety_check_times_out() {
+    let mut sim = SimLoop::new(&machine());
+    sim.set_di(PIN_LIMIT_MAX_1, true);
+
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::EnableAxis, 1, 1)), AckStatus::Error);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::PowerError as u8);
+    assert_eq!(sim.runner.state.out_hal.axes[0].enable, 0);
+}
+
+#[test]
+fn restarted_cu_skips_commands_left_in_segments() {
+    let mut sim = SimLoop::new(&machine());
+    assert_eq!(sim.exec_re(re(ReCommandType::EnableAxis, 0b01, 1)), AckStatus::Ok);
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::EnableAxis, 2, 1)), AckStatus::Ok);
+
+    // Both enables are still in the command segments of the new instance.
+    let mut sim = sim.restart_cu();
+    sim.ticks(50);
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::PowerOff as u8);
+    assert_eq!(sim.runner.state.axes[1].power_state, PowerState::PowerOff as u8);
+    let ack = sim.ack();
+    assert_eq!((ack.last_ack_seq_id, ack.rpc_ack_seq_id), (0, 0));
+
+    // The next command of each origin is served.
+    assert_eq!(sim.exec_re(re(ReCommandType::EnableAxis, 0b01, 2)), AckStatus::Ok);
+    assert_eq!(sim.exec_rpc(rpc(RpcCommandType::EnableAxis, 2, 2)), AckStatus::Ok);
+    assert_eq!(sim.runner.state.axes[1].power_state, PowerState::Standby as u8);
+}
+
+#[test]
+fn multi_axis_move_with_disabled_axis_moves_neither() {
+    let mut sim = SimLoop::new(&machine());
+    sim.ready(0b01);
+    assert_eq!(sim.runner.state.axes[1].power_state, PowerState::PowerOff as u8);
+
+    let mut mv = re(ReCommandType::MoveAbsolute, 0b11, 3);
+    mv.targets[0].target_position = 5.0;
+    mv.targets[1].target_position = 5.0;
+    assert_eq!(sim.exec_re(mv), AckStatus::Rejected);
+    assert!(!sim.runner.trajectories[0].is_active());
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::Standby as u8);
+    assert_eq!(sim.runner.state.machine_state, MachineState::Idle);
+
+    sim.ticks(200);
+    for ax in &sim.runner.state.axes[..2] {
+        assert!(ax.actual_position.abs() < 1e-3, "axis moved to {}", ax.actual_position);
+        assert_eq!(ax.motion_state, MotionState::Standstill as u8);
+    }
+}
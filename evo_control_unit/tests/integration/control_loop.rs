@@ -0,0 +1,177 @@
+//! Integration test: control pipeline in the cycle body (T067).
+//!
+//! Closes the loop between `CycleRunner` and the HAL simulation driver
+//! over the real P2P segments:
+//! 1. Axes in Motion run PID + FF + DOB + filters and reach their target
+//! 2. Axes outside Motion output zero and are not enabled
+//! 3. Disable / mode change resets the controller state (I-PW-4 / I-OM-4)
+//! 4. LagPolicy::Critical → SAFETY_STOP, LagPolicy::Unwanted → axis stop
+//! 5. On a dynamic plant the CU gains shape the simulated response
+
+use evo_common::control_unit::error::MotionError;
+use evo_common::control_unit::state::{MotionState, OperationalMode, PowerState, SafetyState};
+
+use evo_control_unit::control::pid::PidState;
+
+use super::sim_loop::{SimLoop, two_axis_machine};
+
+fn machine(lag_limit: f64, lag_policy: &str) -> String {
+    two_axis_machine(
+        &format!(
+            "kp = 100.0\nki = 10.0\nkd = 1.0\nkvff = 0.5\nout_max = 50.0\n\
+             lag_error_limit = {lag_limit}\nlag_policy = \"{lag_policy}\""
+        ),
+        "",
+    )
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[test]
+fn motion_axis_reaches_target_through_sim_hal() {
+    let mut sim = SimLoop::new(&machine(50.0, "Neutral"));
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 10.0;
+
+    let mut peak_torque: f64 = 0.0;
+    for _ in 0..2000 {
+        sim.tick();
+        peak_torque = peak_torque.max(sim.runner.state.axes[0].control_output[0].abs());
+    }
+
+    let x = &sim.runner.state.axes[0];
+    assert!((x.actual_position - 10.0).abs() < 0.01, "X at {}", x.actual_position);
+    assert!(peak_torque > 0.0, "control engine never produced torque");
+    assert!(peak_torque <= 50.0, "output not clamped to out_max: {peak_torque}");
+
+    // Motion axis is enabled, PowerOff axis is not.
+    let out = sim.runner.state.out_hal;
+    assert_eq!(out.axes[0].enable, 1);
+    assert_eq!(out.axes[0].target_position, 10.0);
+    assert_eq!(out.axes[1].enable, 0);
+    assert_eq!(sim.runner.state.axes[1].control_output, [0.0; 4]);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::Safe);
+}
+
+#[test]
+fn disable_resets_controller_state() {
+    let mut sim = SimLoop::new(&machine(50.0, "Neutral"));
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 5.0;
+    for _ in 0..20 {
+        sim.tick();
+    }
+    assert_ne!(sim.runner.control_states[0].prev_applied_torque, 0.0);
+
+    sim.set_power(0, PowerState::Standby);
+    sim.tick();
+
+    let cs = &sim.runner.control_states[0];
+    assert_eq!(cs.prev_applied_torque, 0.0);
+    assert_eq!(format!("{:?}", cs.pid), format!("{:?}", PidState::default()));
+    let x = &sim.runner.state.axes[0];
+    // No torque / velocity output; the enabled drive holds in CSP.
+    assert_eq!(x.control_output, [0.0, 0.0, x.actual_position, 0.0]);
+    // Target follows actual outside Motion → bumpless re-enable.
+    assert_eq!(x.target_position, x.actual_position);
+    assert_eq!(sim.runner.state.out_hal.axes[0].calculated_torque, 0.0);
+}
+
+#[test]
+fn mode_change_resets_controller_state() {
+    let mut sim = SimLoop::new(&machine(50.0, "Neutral"));
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 5.0;
+    for _ in 0..20 {
+        sim.tick();
+    }
+    let before = format!("{:?}", sim.runner.control_states[0].pid);
+    assert_ne!(before, format!("{:?}", PidState::default()));
+
+    // Switch mode while staying in Motion; hold the target at actual so
+    // the first cycle after the reset starts from zero error.
+    sim.runner.state.axes[0].operational_mode = OperationalMode::Manual as u8;
+    sim.runner.state.axes[0].target_position = sim.hal.feedback.axes[0].position;
+    sim.tick();
+
+    // Fresh integrator + zero error → PID state identical to default.
+    assert_eq!(
+        format!("{:?}", sim.runner.control_states[0].pid),
+        format!("{:?}", PidState::default())
+    );
+}
+
+#[test]
+fn critical_lag_triggers_safety_stop() {
+    let mut sim = SimLoop::new(&machine(0.5, "Critical"));
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 50.0;
+    sim.tick();
+
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+    let flags = MotionError::from_bits_truncate(sim.runner.state.axes[0].motion_errors as u16);
+    assert!(flags.contains(MotionError::LAG_EXCEED));
+    assert!(flags.contains(MotionError::LAG_CRITICAL));
+}
+
+#[test]
+fn unwanted_lag_stops_axis_only() {
+    let mut sim = SimLoop::new(&machine(0.5, "Unwanted"));
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 50.0;
+    sim.tick();
+
+    let x = &sim.runner.state.axes[0];
+    assert_eq!(x.motion_state, MotionState::MotionError as u8);
+    assert_eq!(x.target_position, x.actual_position);
+    assert!(MotionError::from_bits_truncate(x.motion_errors as u16).contains(MotionError::LAG_EXCEED));
+    assert_eq!(sim.runner.state.safety_state, SafetyState::Safe);
+}
+
+#[test]
+fn neutral_lag_flags_without_stopping() {
+    let mut sim = SimLoop::new(&machine(0.5, "Neutral"));
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 50.0;
+    sim.tick();
+
+    let x = &sim.runner.state.axes[0];
+    assert!(MotionError::from_bits_truncate(x.motion_errors as u16).contains(MotionError::LAG_EXCEED));
+    assert_eq!(x.target_position, 50.0);
+    assert_ne!(x.motion_state, MotionState::MotionError as u8);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::Safe);
+}
+
+/// Position trace of axis X on a dynamic plant stepping to 1.0 with `kp`.
+fn dynamic_step_response(kp: f64) -> Vec<f64> {
+    let control = format!("kp = {kp}\nki = 0.0\nkd = 0.5\nout_max = 50.0\nlag_error_limit = 50.0");
+    let mut sim = SimLoop::dynamic(
+        &two_axis_machine(&control, ""),
+        "inertia = 0.01\nviscous_friction = 0.01\nencoder_resolution = 1e6",
+    );
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 1.0;
+    (0..1500)
+        .map(|_| {
+            sim.tick();
+            sim.hal.feedback.axes[0].position
+        })
+        .collect()
+}
+
+#[test]
+fn cu_gains_shape_dynamic_plant_response() {
+    let soft = dynamic_step_response(10.0);
+    let stiff = dynamic_step_response(40.0);
+
+    // The plant follows the CU torque: stiffer gains rise faster.
+    assert_ne!(soft, stiff);
+    assert!(stiff[20] > 2.0 * soft[20], "soft {} stiff {}", soft[20], stiff[20]);
+    let peak = |trace: &[f64]| trace.iter().copied().fold(f64::MIN, f64::max);
+    assert!(peak(&stiff) > peak(&soft) + 0.05, "overshoot {} vs {}", peak(&stiff), peak(&soft));
+    // Both loops still settle on the target.
+    for trace in [&soft, &stiff] {
+        let x = trace.last().unwrap();
+        assert!((x - 1.0).abs() < 0.01, "X at {x}");
+    }
+}
@@ -0,0 +1,216 @@
+//! Integration test: HAL Core + CU in virtual-time lockstep.
+//!
+//! A real `HalCore` (simulation driver, production SHM segments) and the
+//! `CycleRunner` are advanced on a shared `VirtualClock`, one HAL cycle
+//! followed by one CU cycle per tick, checked for:
+//! 1. Machine time running much faster than wall time
+//! 2. Bit-identical results across runs
+//! 3. Closed-loop motion through the HAL command / feedback path
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use evo_common::control_unit::state::{MachineState, MotionState, OperationalMode};
+use evo_common::hal::config::MachineConfig;
+use evo_common::lockstep::VirtualClock;
+use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
+use evo_common::shm::segments::{
+    AckStatus, CuToReSegment, ReCommand, ReCommandType, ReToCuSegment, SEG_CU_RE, SEG_RE_CU,
+};
+use evo_hal::HalCore;
+use tempfile::TempDir;
+
+use evo_control_unit::config::load_config_from_strings;
+use evo_control_unit::cycle::CycleRunner;
+
+use super::sim_loop::{CU_TOML, IO_TOML, SHM_LOCK, two_axis_machine};
+
+const CONTROL: &str = "kp = 100.0\nki = 10.0\nkd = 1.0\nkvff = 0.01\nfriction = 0.05\nout_max = 50.0\n\
+                       lag_error_limit = 50.0\nlag_policy = \"Neutral\"";
+
+/// Seven simulated DIs matching [`IO_TOML`]; the NC E-Stop starts healthy.
+const HAL_MACHINE_TOML: &str = r#"
+cycle_time_us = 1000
+axes = ["x.toml", "y.toml"]
+digital_inputs = [
+    { name = "EStop", initial_value = true },
+    { name = "LimitMin1" },
+    { name = "LimitMax1" },
+    { name = "LimitMin2" },
+    { name = "LimitMax2" },
+    { name = "EStopReset" },
+    { name = "SafetyGate" },
+]
+"#;
+
+const HAL_X_TOML: &str = r#"
+name = "X"
+axis_type = "positioning"
+encoder_resolution = 1000000.0
+max_velocity = 100.0
+max_acceleration = 1000.0
+lag_error_limit = 50.0
+"#;
+
+/// Y runs the dynamic plant model.
+const HAL_Y_TOML: &str = r#"
+name = "Y"
+axis_type = "positioning"
+encoder_resolution = 1000000.0
+max_velocity = 100.0
+max_acceleration = 1000.0
+lag_error_limit = 50.0
+
+[dynamics]
+inertia = 0.01
+viscous_friction = 0.01
+coulomb_friction = 0.05
+resonance_frequency = 80.0
+"#;
+
+/// HAL Core + CU on one virtual clock, with an RE command source.
+struct Lockstep {
+    clock: VirtualClock,
+    hal: HalCore,
+    cu: CycleRunner,
+    re_writer: TypedP2pWriter<ReToCuSegment>,
+    ack_reader: TypedP2pReader<CuToReSegment>,
+    _dir: TempDir,
+}
+
+impl Lockstep {
+    fn new() -> Self {
+        let dir = TempDir::new().expect("tempdir");
+        fs::write(dir.path().join("x.toml"), HAL_X_TOML).unwrap();
+        fs::write(dir.path().join("y.toml"), HAL_Y_TOML).unwrap();
+
+        let hal_config: MachineConfig = toml::from_str(HAL_MACHINE_TOML).expect("hal machine");
+        let mut hal = HalCore::new(hal_config).expect("hal");
+        hal.load_axis_configs(dir.path()).expect("hal axes");
+        hal.init("simulation").expect("hal init");
+
+        let clock = VirtualClock::new(Duration::from_micros(1000));
+        // First HAL feedback exists before the CU attaches.
+        hal.step(clock.cycle_time()).expect("initial hal cycle");
+
+        let re_writer =
+            TypedP2pWriter::<ReToCuSegment>::create(SEG_RE_CU, ModuleAbbrev::Re, ModuleAbbrev::Cu)
+                .expect("create re_cu");
+
+        let config = load_config_from_strings(CU_TOML, &two_axis_machine(CONTROL, ""), IO_TOML)
+            .expect("config");
+        let mut cu = CycleRunner::new(config).expect("runner");
+        cu.start();
+        hal.retry_p2p_readers();
+
+        let ack_reader =
+            TypedP2pReader::<CuToReSegment>::attach(SEG_CU_RE, 1000).expect("attach cu_re");
+
+        Self { clock, hal, cu, re_writer, ack_reader, _dir: dir }
+    }
+
+    fn run(&mut self, ticks: u64) {
+        self.clock.run(&mut [&mut self.hal, &mut self.cu], ticks).expect("lockstep");
+    }
+
+    fn send(&mut self, command_type: ReCommandType, sequence_id: u32, fill: impl Fn(&mut ReCommand)) {
+        let mut cmd = ReCommand {
+            command_type: command_type as u8,
+            axis_mask: 0b11,
+            sequence_id,
+            ..ReCommand::default()
+        };
+        fill(&mut cmd);
+        self.re_writer.commit(&ReToCuSegment { command: cmd }).expect("commit re_cu");
+        // Enable is acked once the power sequence ends.
+        let mut ack = CuToReSegment::default();
+        for _ in 0..100 {
+            self.run(1);
+            ack = *self.ack_reader.read().expect("read cu_re");
+            if ack.last_ack_seq_id == sequence_id {
+                break;
+            }
+        }
+        assert_eq!(ack.last_ack_seq_id, sequence_id);
+        assert_eq!(AckStatus::from_u8(ack.ack_status), Some(AckStatus::Ok), "{command_type:?}");
+    }
+
+    /// Enable both axes in Manual mode.
+    fn ready(&mut self) {
+        self.run(10);
+        assert_eq!(self.cu.state.machine_state, MachineState::Idle);
+        self.send(ReCommandType::EnableAxis, 1, |_| {});
+        self.send(ReCommandType::SetMode, 2, |cmd| {
+            cmd.targets[0].mode = OperationalMode::Manual as u8;
+            cmd.targets[1].mode = OperationalMode::Manual as u8;
+        });
+    }
+
+    /// Alternate both axes between two targets for `moves` moves, sampling
+    /// the feedback bits every 100 ticks.
+    fn back_and_forth(&mut self, moves: u32, ticks_per_move: u64) -> Vec<u64> {
+        let mut trace = Vec::new();
+        for k in 0..moves {
+            let target = if k % 2 == 0 { 40.0 } else { 5.0 };
+            self.send(ReCommandType::MoveAbsolute, 10 + k, |cmd| {
+                for t in &mut cmd.targets[..2] {
+                    t.target_position = target;
+                    t.target_velocity = 50.0;
+                }
+            });
+            for _ in 0..ticks_per_move / 100 {
+                self.run(100);
+                for ax in &self.cu.state.axes[..2] {
+                    trace.push(ax.actual_position.to_bits());
+                    trace.push(ax.actual_velocity.to_bits());
+                    trace.push(ax.torque_estimate.to_bits());
+                }
+            }
+        }
+        trace
+    }
+}
+
+/// Run the scenario once; the harness holds the SHM lock for its lifetime.
+fn scenario(moves: u32, ticks_per_move: u64) -> (Vec<u64>, Duration) {
+    let _guard = SHM_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut rig = Lockstep::new();
+    rig.ready();
+    let trace = rig.back_and_forth(moves, ticks_per_move);
+
+    for ax in &rig.cu.state.axes[..2] {
+        assert_eq!(ax.motion_state, MotionState::Standstill as u8);
+        let target = if moves % 2 == 1 { 40.0 } else { 5.0 };
+        assert!((ax.actual_position - target).abs() < 0.01, "at {}", ax.actual_position);
+    }
+    assert_eq!(rig.cu.state.machine_state, MachineState::Active);
+    (trace, rig.clock.now())
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[test]
+fn lockstep_runs_faster_than_real_time() {
+    let start = Instant::now();
+    let (_, machine_time) = scenario(30, 2000);
+    let wall = start.elapsed();
+    assert!(machine_time >= Duration::from_secs(60), "machine time {machine_time:?}");
+    assert!(wall < machine_time, "wall {wall:?} ≥ machine {machine_time:?}");
+}
+
+#[test]
+fn lockstep_is_bit_identical() {
+    let (first, t1) = scenario(6, 2000);
+    let (second, t2) = scenario(6, 2000);
+    assert_eq!(t1, t2);
+    assert!(!first.is_empty());
+    assert!(first == second, "lockstep runs diverged");
+}
+
+/// One hour of machine time (run with `--ignored`).
+#[test]
+#[ignore]
+fn lockstep_one_machine_hour() {
+    let (_, machine_time) = scenario(1800, 2000);
+    assert!(machine_time >= Duration::from_secs(3600));
+}
@@ -5,3 +5,10 @@ mod control_accuracy;
 mod startup_timing;
 mod soak_24h;
 mod hot_reload;
+mod sim_loop;
+mod control_loop;
+mod safety_cycle;
+mod commands;
+mod trajectory;
+mod path;
+mod lockstep;
@@ -0,0 +1,241 @@
+//! Integration test: coordinated path interpolation in the cycle body.
+//!
+//! X and Y form an axis group; `PathLinear` / `PathArc*` commands on
+//! `evo_re_cu` are queued on its `PathPlanner` and checked for:
+//! 1. Linear + arc segments reaching their end points on the geometry
+//! 2. Corner blending within the look-ahead queue
+//! 3. Rejection of malformed segments and single-axis moves on a busy group
+//! 4. Rejection while any group member is locked by another source
+//! 5. Controlled path stop, and a ramped stop of the other members when
+//!    one member drops out
+
+use evo_common::control_unit::state::{MotionState, PowerState};
+use evo_common::shm::segments::{AckStatus, ReCommand, ReCommandType, RpcCommand, RpcCommandType};
+
+use super::sim_loop::{SimLoop, re, two_axis_machine};
+
+const CONTROL: &str = "kp = 100.0\nki = 10.0\nkd = 1.0\nkvff = 1.0\nout_max = 50.0\n\
+                       lag_error_limit = 50.0\nlag_policy = \"Neutral\"";
+
+const FEED: f64 = 50.0;
+const TOL: f64 = 1e-6;
+
+fn machine() -> String {
+    let mut s = two_axis_machine(CONTROL, "max_acceleration = 1000.0");
+    s.push_str("\n[[axis_groups]]\nname = \"XY\"\naxes = [1, 2]\ncorner_tolerance = 0.5\n");
+    s
+}
+
+fn linear(sequence_id: u32, x: f64, y: f64) -> ReCommand {
+    let mut cmd = re(ReCommandType::PathLinear, 0b11, sequence_id);
+    cmd.targets[0].target_position = x;
+    cmd.targets[1].target_position = y;
+    cmd.targets[0].target_velocity = FEED;
+    cmd
+}
+
+fn arc_ccw(sequence_id: u32, end: [f64; 2], center: [f64; 2]) -> ReCommand {
+    let mut cmd = re(ReCommandType::PathArcCcw, 0b11, sequence_id);
+    for (t, &e) in cmd.targets.iter_mut().zip(&end) {
+        t.target_position = e;
+        t.target_velocity = FEED;
+    }
+    cmd.arc.plane = [0, 1];
+    cmd.arc.center = center;
+    cmd
+}
+
+/// Enabled X and Y in Manual mode.
+fn ready_sim() -> SimLoop {
+    let mut sim = SimLoop::new(&machine());
+    assert_eq!(sim.runner.axis_groups.len(), 1);
+    sim.ready(0b11);
+    sim
+}
+
+/// Current XY setpoint.
+fn xy(sim: &SimLoop) -> (f64, f64) {
+    let axes = &sim.runner.state.axes;
+    (axes[0].target_position, axes[1].target_position)
+}
+
+/// Tick until the group path completes, calling `check` after every cycle.
+fn run_path(sim: &mut SimLoop, mut check: impl FnMut(&SimLoop)) {
+    for _ in 0..20_000 {
+        if !sim.runner.axis_groups[0].planner.is_active() {
+            return;
+        }
+        sim.tick();
+        check(sim);
+    }
+    panic!("path did not complete");
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[test]
+fn linear_and_arc_reach_end_points() {
+    let mut sim = ready_sim();
+    assert_eq!(sim.exec_re(linear(3, 10.0, 0.0)), AckStatus::Ok);
+    assert_eq!(sim.exec_re(arc_ccw(4, [20.0, 10.0], [10.0, 10.0])), AckStatus::Ok);
+    assert_eq!(sim.ack().axes_in_position & 0b11, 0);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::Accelerating as u8);
+
+    run_path(&mut sim, |sim| {
+        let (x, y) = xy(sim);
+        assert!(sim.runner.axis_groups[0].planner.path_velocity() <= FEED + TOL);
+        if x <= 10.0 - 0.5 {
+            // Linear segment (before the blend zone).
+            assert!(y.abs() < TOL, "Y left the line: {y}");
+        } else if y >= 0.5 {
+            // Arc: radius 10 around (10, 10).
+            let r = ((x - 10.0).powi(2) + (y - 10.0).powi(2)).sqrt();
+            assert!((r - 10.0).abs() < 1e-3, "radius {r}");
+        }
+    });
+
+    sim.ticks(500);
+    assert_eq!(xy(&sim), (20.0, 10.0));
+    for ax in &sim.runner.state.axes[..2] {
+        assert_eq!(ax.target_velocity, 0.0);
+        assert_eq!(ax.motion_state, MotionState::Standstill as u8);
+    }
+    assert!((sim.runner.state.axes[0].actual_position - 20.0).abs() < 0.01);
+    assert!((sim.runner.state.axes[1].actual_position - 10.0).abs() < 0.01);
+    assert_eq!(sim.ack().axes_in_position & 0b11, 0b11);
+}
+
+#[test]
+fn corner_is_blended() {
+    let mut sim = ready_sim();
+    assert_eq!(sim.exec_re(linear(3, 10.0, 0.0)), AckStatus::Ok);
+    assert_eq!(sim.exec_re(linear(4, 10.0, 10.0)), AckStatus::Ok);
+
+    let mut v_min = f64::INFINITY;
+    run_path(&mut sim, |sim| {
+        let (x, y) = xy(sim);
+        if x > 5.0 && y < 5.0 {
+            v_min = v_min.min(sim.runner.axis_groups[0].planner.path_velocity());
+        }
+    });
+    assert!(v_min > 1.0, "corner not blended: v_min = {v_min}");
+    assert_eq!(xy(&sim), (10.0, 10.0));
+}
+
+#[test]
+fn malformed_paths_and_busy_group_rejected() {
+    let mut sim = ready_sim();
+
+    // Arc with a degenerate plane.
+    let mut arc = arc_ccw(3, [20.0, 10.0], [10.0, 10.0]);
+    arc.arc.plane = [0, 0];
+    assert_eq!(sim.exec_re(arc), AckStatus::Rejected);
+    // End point outside the soft limits (min_pos = 0).
+    assert_eq!(sim.exec_re(linear(4, -5.0, 0.0)), AckStatus::Rejected);
+    // Arc end not on the circle.
+    assert_eq!(sim.exec_re(arc_ccw(5, [30.0, 10.0], [10.0, 10.0])), AckStatus::Rejected);
+    assert!(!sim.runner.axis_groups[0].planner.is_active());
+
+    // Single-axis moves are locked out while the group runs a path.
+    assert_eq!(sim.exec_re(linear(6, 20.0, 20.0)), AckStatus::Ok);
+    let mut mv = re(ReCommandType::MoveAbsolute, 0b01, 7);
+    mv.targets[0].target_position = 1.0;
+    assert_eq!(sim.exec_re(mv), AckStatus::Rejected);
+    assert!(sim.runner.axis_groups[0].planner.is_active());
+}
+
+#[test]
+fn locked_member_rejects_path() {
+    let mut sim = ready_sim();
+    // Y is locked by the RPC source; the segment only addresses X.
+    let lock = RpcCommand {
+        command_type: RpcCommandType::AcquireLock as u8,
+        axis_id: 2,
+        sequence_id: 1,
+        ..RpcCommand::default()
+    };
+    sim.send_rpc(lock);
+    sim.tick();
+    assert_eq!(AckStatus::from_u8(sim.ack().rpc_ack_status), Some(AckStatus::Ok));
+
+    let mut cmd = linear(3, 20.0, 0.0);
+    cmd.axis_mask = 0b01;
+    assert_eq!(sim.exec_re(cmd), AckStatus::Rejected);
+    assert!(!sim.runner.axis_groups[0].planner.is_active());
+
+    let release = RpcCommand {
+        command_type: RpcCommandType::ReleaseLock as u8,
+        sequence_id: 2,
+        ..lock
+    };
+    sim.send_rpc(release);
+    sim.tick();
+    cmd.sequence_id = 4;
+    assert_eq!(sim.exec_re(cmd), AckStatus::Ok);
+    assert!(sim.runner.axis_groups[0].planner.is_active());
+}
+
+#[test]
+fn stop_decelerates_along_path() {
+    let mut sim = ready_sim();
+    assert_eq!(sim.exec_re(linear(3, 100.0, 100.0)), AckStatus::Ok);
+    sim.ticks(500);
+    assert_eq!(sim.runner.state.axes[1].motion_state, MotionState::ConstantVelocity as u8);
+
+    // Stop addressed to X halts the whole group on the line.
+    assert_eq!(sim.exec_re(re(ReCommandType::Stop, 0b01, 4)), AckStatus::Ok);
+    assert_eq!(sim.runner.state.axes[1].motion_state, MotionState::Stopping as u8);
+    assert_eq!(sim.exec_re(linear(5, 0.0, 0.0)), AckStatus::Rejected);
+    run_path(&mut sim, |sim| {
+        let (x, y) = xy(sim);
+        assert!((x - y).abs() < TOL, "left the path at ({x}, {y})");
+    });
+
+    let (x, y) = xy(&sim);
+    assert!(x > 10.0 && x < 100.0, "stopped at {x}");
+    assert_eq!(x, y);
+    for ax in &sim.runner.state.axes[..2] {
+        assert_eq!(ax.target_velocity, 0.0);
+        assert_eq!(ax.motion_state, MotionState::Standstill as u8);
+    }
+}
+
+#[test]
+fn disabled_member_ramps_others_down() {
+    let mut sim = ready_sim();
+    assert_eq!(sim.exec_re(linear(3, 100.0, 100.0)), AckStatus::Ok);
+    sim.ticks(500);
+    let v_path = sim.runner.state.axes[0].target_velocity;
+    assert!((v_path - FEED / 2f64.sqrt()).abs() < TOL);
+
+    // Disabling Y aborts the path; X stops under control instead of dead.
+    sim.send_re(re(ReCommandType::DisableAxis, 0b10, 4));
+    sim.tick();
+    assert!(!sim.runner.axis_groups[0].planner.is_active());
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::Stopping as u8);
+    let mut v_prev = sim.runner.state.axes[0].target_velocity;
+    assert!(v_prev > v_path - 1.0, "X stopped dead: {v_prev}");
+
+    let x_abort = xy(&sim).0;
+    let mut cycles = 0;
+    while sim.runner.trajectories[0].is_active() {
+        sim.tick();
+        cycles += 1;
+        let v = sim.runner.state.axes[0].target_velocity;
+        assert!(v <= v_prev + TOL && v >= 0.0, "X not ramping down: {v}");
+        // Axis deceleration limit: 1000 mm/s².
+        assert!(v_prev - v <= 1000.0 * 0.001 + TOL);
+        v_prev = v;
+        assert!(cycles < 1000, "X did not stop");
+    }
+    assert!(cycles > 20, "ramp took {cycles} cycles");
+    assert!(xy(&sim).0 > x_abort);
+    assert_eq!(sim.runner.state.axes[0].target_velocity, 0.0);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::Standstill as u8);
+
+    // Y has finished its power-off sequence meanwhile.
+    let ack = sim.ack();
+    assert_eq!(ack.last_ack_seq_id, 4);
+    assert_eq!(AckStatus::from_u8(ack.ack_status), Some(AckStatus::Ok));
+    assert_eq!(sim.runner.state.axes[1].power_state, PowerState::PowerOff as u8);
+}
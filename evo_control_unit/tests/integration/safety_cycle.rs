@@ -0,0 +1,210 @@
+//! Integration test: safety pipeline in the cycle body (T053).
+//!
+//! Drives `CycleRunner` against the HAL simulation driver and toggles
+//! DI pins to exercise:
+//! 1. E-Stop → SAFETY_STOP → per-axis STO / SS1 / SS2 execution
+//! 2. Reset button → flags clear → `AuthorizeRecovery` command → Safe
+//! 3. Safety gate in Service → SAFE_REDUCED_SPEED velocity clamping
+//! 4. Limit switch → non-critical flag, axis motion blocked
+
+use evo_common::control_unit::error::MotionError;
+use evo_common::control_unit::state::{MachineState, MotionState, PowerState, SafetyState};
+use evo_common::shm::segments::{AckStatus, RpcCommand, RpcCommandType};
+
+use evo_control_unit::safety::recovery::RecoveryStep;
+use evo_control_unit::safety::stop::StopPhase;
+
+use super::sim_loop::{
+    PIN_ESTOP, PIN_LIMIT_MAX_1, PIN_RESET, PIN_SAFETY_GATE, SimLoop, two_axis_machine,
+};
+
+const CONTROL: &str = "kp = 100.0\nout_max = 50.0\nlag_error_limit = 50.0\nlag_policy = \"Neutral\"";
+
+fn machine(category: &str) -> String {
+    two_axis_machine(
+        CONTROL,
+        &format!(
+            "safe_reduced_speed_limit = 20.0\n\n[axes.safe_stop]\ncategory = \"{category}\"\n\
+             sto_brake_delay = 0.005"
+        ),
+    )
+}
+
+/// Bring axis X up to speed towards a distant target.
+fn moving_sim(category: &str) -> SimLoop {
+    let mut sim = SimLoop::new(&machine(category));
+    sim.set_power(0, PowerState::Motion);
+    sim.runner.state.axes[0].target_position = 500.0;
+    sim.ticks(150);
+    assert!(sim.runner.state.axes[0].actual_velocity > 50.0);
+    sim
+}
+
+/// Tick until the X stop executor reports completion (bounded).
+fn run_until_stopped(sim: &mut SimLoop) {
+    for _ in 0..2000 {
+        if sim.runner.stop_executors[0].is_complete() {
+            return;
+        }
+        sim.tick();
+    }
+    panic!("stop sequence did not complete");
+}
+
+/// Send the RPC `AuthorizeRecovery` command, run one cycle and return
+/// the ack status.
+fn authorize(sim: &mut SimLoop, sequence_id: u32) -> AckStatus {
+    sim.send_rpc(RpcCommand {
+        command_type: RpcCommandType::AuthorizeRecovery as u8,
+        sequence_id,
+        ..RpcCommand::default()
+    });
+    sim.tick();
+    let ack = sim.ack();
+    assert_eq!(ack.rpc_ack_seq_id, sequence_id);
+    AckStatus::from_u8(ack.rpc_ack_status).expect("ack status")
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[test]
+fn healthy_inputs_keep_safe() {
+    let mut sim = SimLoop::new(&machine("SS1"));
+    sim.ticks(10);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::Safe);
+    assert!(sim.runner.safety_flags[0].all_ok());
+    assert!(sim.runner.safety_flags[1].all_ok());
+}
+
+#[test]
+fn estop_ss1_decelerates_then_disables() {
+    let mut sim = moving_sim("SS1");
+
+    sim.set_di(PIN_ESTOP, false); // NC circuit opened
+    sim.tick();
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+    assert_eq!(sim.runner.state.machine_state, MachineState::SystemError);
+    assert_eq!(sim.runner.stop_executors[0].phase(), StopPhase::Decelerating);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::EmergencyStop as u8);
+
+    run_until_stopped(&mut sim);
+    let x = &sim.runner.state.axes[0];
+    assert_eq!(x.power_state, PowerState::PowerOff as u8);
+    assert!(x.actual_velocity.abs() < 0.01);
+    assert_eq!(sim.runner.state.out_hal.axes[0].enable, 0);
+    assert_eq!(sim.runner.state.out_hal.axes[0].brake_release, 0);
+}
+
+#[test]
+fn estop_sto_disables_immediately() {
+    let mut sim = moving_sim("STO");
+
+    sim.set_di(PIN_ESTOP, false);
+    sim.tick();
+    assert_eq!(sim.runner.state.axes[0].power_state, PowerState::PowerOff as u8);
+    assert_eq!(sim.runner.state.out_hal.axes[0].enable, 0);
+    assert_eq!(sim.runner.state.axes[0].control_output, [0.0; 4]);
+}
+
+#[test]
+fn estop_ss2_holds_with_drive_enabled() {
+    let mut sim = moving_sim("SS2");
+
+    sim.set_di(PIN_ESTOP, false);
+    run_until_stopped(&mut sim);
+    sim.ticks(5);
+
+    let x = &sim.runner.state.axes[0];
+    assert_eq!(x.power_state, PowerState::Motion as u8);
+    assert_eq!(sim.runner.state.out_hal.axes[0].enable, 1);
+    assert!(x.control_output[0].abs() <= 20.0, "torque above SS2 hold limit");
+    assert!(x.actual_velocity.abs() < 0.01);
+}
+
+#[test]
+fn recovery_requires_reset_and_authorization() {
+    let mut sim = moving_sim("SS1");
+    sim.set_di(PIN_ESTOP, false);
+    run_until_stopped(&mut sim);
+
+    // E-Stop released, no reset yet → still stopped.
+    sim.set_di(PIN_ESTOP, true);
+    sim.ticks(5);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+    assert_eq!(sim.runner.recovery.step(), RecoveryStep::WaitingReset);
+
+    // Reset pressed → flags clear → waiting for authorization (default).
+    sim.set_di(PIN_RESET, true);
+    sim.ticks(2);
+    sim.set_di(PIN_RESET, false);
+    assert_eq!(sim.runner.recovery.step(), RecoveryStep::WaitingAuthorization);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+
+    assert_eq!(authorize(&mut sim, 1), AckStatus::Ok);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::Safe);
+    assert_eq!(sim.runner.state.machine_state, MachineState::Idle);
+    assert_eq!(sim.runner.state.axes[0].motion_errors, 0);
+    assert_eq!(sim.runner.stop_executors[0].phase(), StopPhase::Idle);
+    assert_eq!(sim.runner.recovery.step(), RecoveryStep::Idle);
+}
+
+#[test]
+fn recovery_blocked_while_estop_active() {
+    let mut sim = moving_sim("STO");
+    sim.set_di(PIN_ESTOP, false);
+    run_until_stopped(&mut sim);
+
+    sim.set_di(PIN_RESET, true);
+    sim.ticks(5);
+    // Authorization is only accepted once the flags have cleared.
+    assert_eq!(authorize(&mut sim, 1), AckStatus::Rejected);
+    assert_eq!(sim.runner.recovery.step(), RecoveryStep::WaitingFlagsClear);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+}
+
+#[test]
+fn safety_gate_in_service_clamps_velocity() {
+    let mut sim = SimLoop::new(&machine("SS1"));
+    sim.runner.state.machine_state = MachineState::Service;
+    sim.set_power(0, PowerState::Motion);
+    sim.tick();
+
+    sim.set_di(PIN_SAFETY_GATE, true);
+    sim.runner.state.axes[0].target_velocity = 300.0;
+    sim.tick();
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafeReducedSpeed);
+    assert_eq!(sim.runner.state.out_hal.axes[0].target_velocity, 20.0);
+
+    sim.runner.state.axes[0].target_velocity = -300.0;
+    sim.tick();
+    assert_eq!(sim.runner.state.out_hal.axes[0].target_velocity, -20.0);
+
+    // Gate closed again → back to Safe.
+    sim.set_di(PIN_SAFETY_GATE, false);
+    sim.tick();
+    assert_eq!(sim.runner.state.safety_state, SafetyState::Safe);
+}
+
+#[test]
+fn safety_gate_outside_service_stops() {
+    let mut sim = SimLoop::new(&machine("SS1"));
+    sim.tick();
+    sim.set_di(PIN_SAFETY_GATE, true);
+    sim.tick();
+    assert_eq!(sim.runner.state.safety_state, SafetyState::SafetyStop);
+}
+
+#[test]
+fn limit_switch_blocks_axis_without_safety_stop() {
+    let mut sim = moving_sim("SS1");
+
+    sim.set_di(PIN_LIMIT_MAX_1, true);
+    sim.tick();
+
+    assert!(!sim.runner.safety_flags[0].limit_switch_ok);
+    assert!(sim.runner.safety_flags[1].all_ok());
+    let x = &sim.runner.state.axes[0];
+    assert!(MotionError::from_bits_truncate(x.motion_errors as u16).contains(MotionError::HARD_LIMIT));
+    assert_eq!(x.target_position, x.actual_position);
+    assert_eq!(sim.runner.state.safety_state, SafetyState::Safe);
+}
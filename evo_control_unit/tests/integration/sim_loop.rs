@@ -0,0 +1,337 @@
+//! Shared closed-loop harness: `CycleRunner` + HAL simulation driver.
+//!
+//! Both sides are `LockstepModule`s advanced on one `VirtualClock`. The
+//! runner binds the production segment names (`hal_cu`, `cu_hal`, …), so
+//! every harness instance holds a process-wide lock for its lifetime.
+
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use evo_common::control_unit::state::{OperationalMode, PowerState};
+use evo_common::hal::config::{AxisConfig, MachineConfig};
+use evo_common::hal::driver::HalDriver;
+use evo_common::lockstep::{LockstepModule, VirtualClock};
+use evo_common::shm::conversions::{hal_status_to_segment, segment_to_hal_commands};
+use evo_common::shm::io_helpers::BANK_WORDS;
+use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
+use evo_common::shm::segments::{
+    AckStatus, CuToHalSegment, CuToReSegment, HalToCuSegment, ReCommand, ReCommandType,
+    ReToCuSegment, RpcCommand, RpcToCuSegment, SEG_CU_HAL, SEG_CU_RE, SEG_HAL_CU, SEG_RE_CU, SEG_RPC_CU,
+};
+use evo_hal::drivers::simulation::SimulationDriver;
+
+use evo_control_unit::config::load_config_from_strings;
+use evo_control_unit::cycle::CycleRunner;
+
+pub static SHM_LOCK: Mutex<()> = Mutex::new(());
+
+// ── Config ──────────────────────────────────────────────────────────
+
+pub const CU_TOML: &str = r#"
+cycle_time_us = 1000
+max_axes = 2
+machine_config_path = "test_machine.toml"
+io_config_path = "test_io.toml"
+hal_stale_threshold = 1000
+"#;
+
+/// E-Stop (NC) on pin 0, limit switches on pins 1–4, reset button on
+/// pin 5, safety gate on pin 6.
+pub const IO_TOML: &str = r#"
+[Safety]
+name = "Safety circuits"
+io = [
+    { type = "di", role = "EStop", pin = 0, logic = "NC" },
+    { type = "di", role = "EStopReset", pin = 5, logic = "NO" },
+    { type = "di", role = "SafetyGate", pin = 6, logic = "NO" },
+]
+
+[Axes]
+name = "Axis limit switches"
+io = [
+    { type = "di", role = "LimitMin1", pin = 1, logic = "NO" },
+    { type = "di", role = "LimitMax1", pin = 2, logic = "NO" },
+    { type = "di", role = "LimitMin2", pin = 3, logic = "NO" },
+    { type = "di", role = "LimitMax2", pin = 4, logic = "NO" },
+]
+"#;
+
+/// DI pin numbers from [`IO_TOML`].
+pub const PIN_ESTOP: usize = 0;
+pub const PIN_LIMIT_MAX_1: usize = 2;
+pub const PIN_RESET: usize = 5;
+pub const PIN_SAFETY_GATE: usize = 6;
+
+/// Upper bound on cycles waited for an ack; covers the 5 s power step
+/// timeout at 1 ms cycles.
+const MAX_ACK_CYCLES: usize = 10_000;
+
+/// Two identical axes (X, Y) with the given `[axes.control]` body and
+/// any extra per-axis TOML appended.
+pub fn two_axis_machine(control: &str, extra: &str) -> String {
+    let mut s = String::new();
+    for (id, name) in [(1, "X"), (2, "Y")] {
+        s.push_str(&format!(
+            r#"
+[[axes]]
+axis_id = {id}
+name = "{name}"
+max_velocity = 500.0
+{extra}
+
+[axes.control]
+{control}
+
+[axes.homing]
+method = "NoHoming"
+"#
+        ));
+    }
+    s
+}
+
+const HAL_AXIS_TOML: &str = r#"
+name = "X"
+axis_type = "positioning"
+max_velocity = 100.0
+max_acceleration = 1000.0
+"#;
+
+/// HAL axis that must be referenced before it reports `referenced`.
+const HAL_AXIS_UNREFERENCED_TOML: &str = r#"
+name = "X"
+axis_type = "positioning"
+max_velocity = 100.0
+max_acceleration = 1000.0
+
+[referencing]
+required = "yes"
+mode = "IndexOnly"
+"#;
+
+// ── Commands ────────────────────────────────────────────────────────
+
+/// RE command `command_type` on the axes of `axis_mask`.
+pub fn re(command_type: ReCommandType, axis_mask: u64, sequence_id: u32) -> ReCommand {
+    ReCommand { command_type: command_type as u8, axis_mask, sequence_id, ..ReCommand::default() }
+}
+
+// ── Harness ─────────────────────────────────────────────────────────
+
+/// Simulated HAL: one `SimulationDriver` cycle on the latest CU commands
+/// per tick, published on `hal_cu`.
+pub struct SimHal {
+    writer: TypedP2pWriter<HalToCuSegment>,
+    reader: TypedP2pReader<CuToHalSegment>,
+    driver: SimulationDriver,
+    /// Last HAL→CU payload committed.
+    pub feedback: HalToCuSegment,
+    /// Raw DI bank injected into every HAL→CU payload (E-Stop healthy).
+    pub di_raw: [u64; BANK_WORDS],
+}
+
+impl LockstepModule for SimHal {
+    fn name(&self) -> &str {
+        "hal"
+    }
+
+    fn step(&mut self, clock: &VirtualClock) -> Result<(), String> {
+        let out = self.reader.read().map_err(|e| e.to_string())?;
+        let commands = segment_to_hal_commands(out);
+        let status = self.driver.cycle(&commands, clock.cycle_time());
+        self.feedback = hal_status_to_segment(&status, 2);
+        self.feedback.di_bank = self.di_raw;
+        self.writer.commit(&self.feedback).map_err(|e| e.to_string())
+    }
+}
+
+/// CU + simulated HAL on one virtual clock, one HAL cycle per CU cycle.
+pub struct SimLoop {
+    pub runner: CycleRunner,
+    pub hal: SimHal,
+    clock: VirtualClock,
+    machine_toml: String,
+    re_writer: TypedP2pWriter<ReToCuSegment>,
+    rpc_writer: TypedP2pWriter<RpcToCuSegment>,
+    ack_reader: TypedP2pReader<CuToReSegment>,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl SimLoop {
+    pub fn new(machine_toml: &str) -> Self {
+        Self::with_hal_axis(machine_toml, HAL_AXIS_TOML)
+    }
+
+    /// Like [`SimLoop::new`], but the simulated axes start unreferenced.
+    pub fn unreferenced(machine_toml: &str) -> Self {
+        Self::with_hal_axis(machine_toml, HAL_AXIS_UNREFERENCED_TOML)
+    }
+
+    /// Like [`SimLoop::new`], but the simulated axes are torque-driven
+    /// plants with the given `[dynamics]` body, driven by the CU torque.
+    pub fn dynamic(machine_toml: &str, dynamics_toml: &str) -> Self {
+        Self::with_hal_axis(machine_toml, &format!("{HAL_AXIS_TOML}\n[dynamics]\n{dynamics_toml}\n"))
+    }
+
+    fn with_hal_axis(machine_toml: &str, hal_axis_toml: &str) -> Self {
+        let guard = SHM_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let config = load_config_from_strings(CU_TOML, machine_toml, IO_TOML).expect("config");
+
+        let mut di_raw = [0u64; BANK_WORDS];
+        di_raw[0] |= 1 << PIN_ESTOP;
+
+        let mut hal_writer =
+            TypedP2pWriter::<HalToCuSegment>::create(SEG_HAL_CU, ModuleAbbrev::Hal, ModuleAbbrev::Cu)
+                .expect("create hal_cu");
+        let feedback = HalToCuSegment { axis_count: 2, di_bank: di_raw, ..Default::default() };
+        hal_writer.commit(&feedback).expect("initial hal_cu commit");
+
+        // Command sources exist before the CU so it attaches them at init.
+        let re_writer =
+            TypedP2pWriter::<ReToCuSegment>::create(SEG_RE_CU, ModuleAbbrev::Re, ModuleAbbrev::Cu)
+                .expect("create re_cu");
+        let rpc_writer =
+            TypedP2pWriter::<RpcToCuSegment>::create(SEG_RPC_CU, ModuleAbbrev::Rpc, ModuleAbbrev::Cu)
+                .expect("create rpc_cu");
+
+        let mut runner = CycleRunner::new(config).expect("runner");
+        runner.start();
+        let cu_reader =
+            TypedP2pReader::<CuToHalSegment>::attach(SEG_CU_HAL, 1000).expect("attach cu_hal");
+        let ack_reader =
+            TypedP2pReader::<CuToReSegment>::attach(SEG_CU_RE, 1000).expect("attach cu_re");
+
+        let mut driver = SimulationDriver::new();
+        let hal_machine: MachineConfig = toml::from_str("").expect("hal machine");
+        driver.init(&hal_machine).expect("driver init");
+        let axis: AxisConfig = toml::from_str(hal_axis_toml).expect("hal axis");
+        driver.set_axis_configs(&[axis.clone(), AxisConfig { name: "Y".into(), ..axis }]);
+
+        Self {
+            runner,
+            hal: SimHal { writer: hal_writer, reader: cu_reader, driver, feedback, di_raw },
+            clock: VirtualClock::new(Duration::from_millis(1)),
+            machine_toml: machine_toml.to_string(),
+            re_writer,
+            rpc_writer,
+            ack_reader,
+            _guard: guard,
+        }
+    }
+
+    /// Replace the CU with a fresh instance. HAL, RE and RPC segments keep
+    /// their contents, as when only the CU process restarts.
+    pub fn restart_cu(self) -> Self {
+        let Self { runner, machine_toml, mut hal, ack_reader, .. } = self;
+        // The old instance must release its writer segments first.
+        drop((runner, ack_reader));
+        let config =
+            load_config_from_strings(CU_TOML, &machine_toml, IO_TOML).expect("config");
+        let mut runner = CycleRunner::new(config).expect("runner");
+        runner.start();
+        hal.reader = TypedP2pReader::attach(SEG_CU_HAL, 1000).expect("attach cu_hal");
+        Self {
+            runner,
+            hal,
+            machine_toml,
+            ack_reader: TypedP2pReader::attach(SEG_CU_RE, 1000).expect("attach cu_re"),
+            ..self
+        }
+    }
+
+    /// One CU cycle followed by one HAL cycle. The feedback committed at
+    /// construction stands in for the HAL cycle before the first tick.
+    pub fn tick(&mut self) {
+        self.clock.tick(&mut [&mut self.runner, &mut self.hal]).expect("lockstep tick");
+    }
+
+    pub fn ticks(&mut self, n: usize) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    /// Publish an RE command; the CU picks it up on its next cycle.
+    pub fn send_re(&mut self, command: ReCommand) {
+        self.re_writer.commit(&ReToCuSegment { command }).expect("commit re_cu");
+    }
+
+    /// Publish an RPC command; the CU picks it up on its next cycle.
+    pub fn send_rpc(&mut self, command: RpcCommand) {
+        let seg = RpcToCuSegment { command, ..Default::default() };
+        self.rpc_writer.commit(&seg).expect("commit rpc_cu");
+    }
+
+    /// Publish an RE command and return its ack status.
+    pub fn exec_re(&mut self, command: ReCommand) -> AckStatus {
+        self.send_re(command);
+        self.await_re_ack(command.sequence_id)
+    }
+
+    /// Publish an RPC command and return its ack status.
+    pub fn exec_rpc(&mut self, command: RpcCommand) -> AckStatus {
+        self.send_rpc(command);
+        self.await_rpc_ack(command.sequence_id)
+    }
+
+    /// Enable the axes of `axis_mask` and switch them to Manual (also
+    /// allowed while unreferenced), as RE commands 1 and 2.
+    pub fn ready(&mut self, axis_mask: u64) {
+        assert_eq!(self.exec_re(re(ReCommandType::EnableAxis, axis_mask, 1)), AckStatus::Ok);
+        let mut set_mode = re(ReCommandType::SetMode, axis_mask, 2);
+        for (i, target) in set_mode.targets.iter_mut().enumerate() {
+            if axis_mask & (1 << i) != 0 {
+                target.mode = OperationalMode::Manual as u8;
+            }
+        }
+        assert_eq!(self.exec_re(set_mode), AckStatus::Ok);
+    }
+
+    /// Latest CU→RE acknowledgement.
+    pub fn ack(&mut self) -> CuToReSegment {
+        *self.ack_reader.read().expect("read cu_re")
+    }
+
+    /// Tick until the RE command `sequence_id` is acknowledged and return
+    /// its status. Enable / disable are acked once their power sequence ends.
+    pub fn await_re_ack(&mut self, sequence_id: u32) -> AckStatus {
+        for _ in 0..MAX_ACK_CYCLES {
+            self.tick();
+            let ack = self.ack();
+            if ack.last_ack_seq_id == sequence_id {
+                return AckStatus::from_u8(ack.ack_status).expect("ack status");
+            }
+        }
+        panic!("RE command {sequence_id} not acknowledged");
+    }
+
+    /// Like [`SimLoop::await_re_ack`], for the RPC command `sequence_id`.
+    pub fn await_rpc_ack(&mut self, sequence_id: u32) -> AckStatus {
+        for _ in 0..MAX_ACK_CYCLES {
+            self.tick();
+            let ack = self.ack();
+            if ack.rpc_ack_seq_id == sequence_id {
+                return AckStatus::from_u8(ack.rpc_ack_status).expect("ack status");
+            }
+        }
+        panic!("RPC command {sequence_id} not acknowledged");
+    }
+
+    pub fn set_power(&mut self, axis: usize, state: PowerState) {
+        self.runner.state.axes[axis].power_state = state as u8;
+    }
+
+    /// Change a raw DI pin and publish it immediately, so the next CU
+    /// cycle sees the new value.
+    pub fn set_di(&mut self, pin: usize, raw: bool) {
+        let hal = &mut self.hal;
+        if raw {
+            hal.di_raw[pin / 64] |= 1 << (pin % 64);
+        } else {
+            hal.di_raw[pin / 64] &= !(1 << (pin % 64));
+        }
+        hal.feedback.di_bank = hal.di_raw;
+        hal.writer.commit(&hal.feedback).expect("commit hal_cu");
+    }
+}
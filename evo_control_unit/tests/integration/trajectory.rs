@@ -0,0 +1,130 @@
+//! Integration test: S-curve trajectory generation in the cycle body.
+//!
+//! Motion commands published on `evo_re_cu` are executed through the
+//! per-axis `TrajectoryGenerator` and checked for:
+//! 1. Target reached with velocity / acceleration / jerk within limits
+//! 2. MotionState following the profile phases (T039)
+//! 3. On-the-fly retargeting without setpoint discontinuities
+//! 4. Controlled stop of a velocity move
+
+use evo_common::control_unit::state::{MotionState, PowerState};
+use evo_common::shm::segments::{AckStatus, ReCommand, ReCommandType};
+
+use super::sim_loop::{SimLoop, re, two_axis_machine};
+
+const CONTROL: &str = "kp = 100.0\nki = 10.0\nkd = 1.0\nkvff = 1.0\nout_max = 50.0\n\
+                       lag_error_limit = 50.0\nlag_policy = \"Neutral\"";
+
+/// Axis jerk limit (`max_jerk` in the axis config).
+const JERK: f64 = 5000.0;
+const DT: f64 = 0.001;
+const TOL: f64 = 1e-6;
+
+fn machine() -> String {
+    two_axis_machine(CONTROL, &format!("max_acceleration = 1000.0\nmax_jerk = {JERK}"))
+}
+
+fn move_abs(sequence_id: u32, position: f64, velocity: f64, accel: f64, decel: f64) -> ReCommand {
+    let mut cmd = re(ReCommandType::MoveAbsolute, 0b01, sequence_id);
+    cmd.targets[0].target_position = position;
+    cmd.targets[0].target_velocity = velocity;
+    cmd.targets[0].acceleration = accel;
+    cmd.targets[0].deceleration = decel;
+    cmd
+}
+
+/// Enabled axis X in Manual mode.
+fn ready_sim() -> SimLoop {
+    let mut sim = SimLoop::new(&machine());
+    sim.ready(0b01);
+    sim
+}
+
+/// Tick until the X profile completes, checking setpoint limits.
+///
+/// Returns the distinct motion states seen, in order.
+fn run_profile(sim: &mut SimLoop, v_max: f64, a_max: f64) -> Vec<u8> {
+    let mut prev = sim.runner.trajectories[0].setpoint();
+    let mut states = vec![sim.runner.state.axes[0].motion_state];
+    for _ in 0..20_000 {
+        if !sim.runner.trajectories[0].is_active() {
+            return states;
+        }
+        sim.tick();
+        let sp = sim.runner.trajectories[0].setpoint();
+        assert!(sp.velocity.abs() <= v_max + TOL, "velocity {}", sp.velocity);
+        assert!(sp.acceleration.abs() <= a_max + TOL, "acceleration {}", sp.acceleration);
+        assert!((sp.acceleration - prev.acceleration).abs() <= JERK * DT + TOL, "jerk limit");
+        assert!((sp.velocity - prev.velocity).abs() <= a_max * DT + TOL, "velocity step");
+        prev = sp;
+
+        let state = sim.runner.state.axes[0].motion_state;
+        if states.last() != Some(&state) {
+            states.push(state);
+        }
+    }
+    panic!("profile did not complete");
+}
+
+// ── Tests ───────────────────────────────────────────────────────────
+
+#[test]
+fn s_curve_move_respects_limits_and_reaches_target() {
+    let mut sim = ready_sim();
+    assert_eq!(sim.exec_re(move_abs(3, 20.0, 50.0, 200.0, 400.0)), AckStatus::Ok);
+    assert_eq!(sim.ack().axes_in_position & 0b01, 0);
+
+    let states = run_profile(&mut sim, 50.0, 400.0);
+    assert_eq!(
+        states,
+        [
+            MotionState::Accelerating as u8,
+            MotionState::ConstantVelocity as u8,
+            MotionState::Decelerating as u8,
+            MotionState::Standstill as u8,
+        ]
+    );
+
+    sim.ticks(500);
+    let x = &sim.runner.state.axes[0];
+    assert_eq!(x.target_position, 20.0);
+    assert_eq!(x.target_velocity, 0.0);
+    assert!((x.actual_position - 20.0).abs() < 0.01, "X at {}", x.actual_position);
+    assert_eq!(sim.ack().axes_in_position & 0b01, 0b01);
+}
+
+#[test]
+fn retarget_while_moving_is_continuous() {
+    let mut sim = ready_sim();
+    assert_eq!(sim.exec_re(move_abs(3, 100.0, 50.0, 200.0, 200.0)), AckStatus::Ok);
+    sim.ticks(400);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::ConstantVelocity as u8);
+    let here = sim.runner.trajectories[0].setpoint().position;
+
+    // New target behind the axis: decelerate, reverse, arrive.
+    assert_eq!(sim.exec_re(move_abs(4, here - 5.0, 50.0, 200.0, 200.0)), AckStatus::Ok);
+    assert_eq!(sim.runner.trajectories[0].target(), Some(here - 5.0));
+    run_profile(&mut sim, 50.0, 200.0);
+    assert_eq!(sim.runner.state.axes[0].target_position, here - 5.0);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::Standstill as u8);
+}
+
+#[test]
+fn stop_decelerates_velocity_move() {
+    let mut sim = ready_sim();
+    let mut mv = re(ReCommandType::MoveVelocity, 0b01, 3);
+    mv.targets[0].target_velocity = 40.0;
+    assert_eq!(sim.exec_re(mv), AckStatus::Ok);
+    sim.ticks(500);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::ConstantVelocity as u8);
+    assert!((sim.runner.state.axes[0].target_velocity - 40.0).abs() < TOL);
+
+    assert_eq!(sim.exec_re(re(ReCommandType::Stop, 0b01, 4)), AckStatus::Ok);
+    assert_eq!(sim.runner.state.axes[0].motion_state, MotionState::Stopping as u8);
+    let states = run_profile(&mut sim, 40.0, 1000.0);
+    assert_eq!(states.last(), Some(&(MotionState::Standstill as u8)));
+
+    let x = &sim.runner.state.axes[0];
+    assert_eq!(x.target_velocity, 0.0);
+    assert_eq!(x.power_state, PowerState::Motion as u8);
+}
@@ -22,28 +22,52 @@
 //! | `evo_hal_rpc` | HalToRpcSegment | HAL    |
 //! | `evo_re_rpc`  | ReToRpcSegment  | RE     |
 
-use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
+use clap::Parser;
+use evo_common::shm::layout::ShmLayout;
+use evo_common::shm::p2p::{ModuleAbbrev, ShmNamespace, TypedP2pReader, TypedP2pWriter};
 use evo_common::shm::segments::{
     CuToRpcSegment, HalToRpcSegment, ReToRpcSegment,
     RpcToCuSegment, RpcToHalSegment, RpcToReSegment,
     SEG_CU_RPC, SEG_HAL_RPC, SEG_RE_RPC,
     SEG_RPC_CU, SEG_RPC_HAL, SEG_RPC_RE,
 };
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// EVO gRPC Liaison
+#[derive(Parser, Debug)]
+#[command(name = "evo_grpc")]
+#[command(author = "RTS007")]
+#[command(version)]
+#[command(about = "gRPC API liaison between external clients and the RT domain")]
+struct Args {
+    /// EVO instance id selecting the SHM namespace (default: `evo_*` segments).
+    #[arg(long, value_name = "ID")]
+    instance: Option<String>,
+}
 
 fn main() {
+    let args = Args::parse();
     tracing_subscriber::fmt().compact().init();
     info!("EVO gRPC Liaison starting...");
 
+    let ns = match ShmNamespace::from_instance(args.instance.as_deref()) {
+        Ok(ns) => ns,
+        Err(e) => {
+            error!("{e}");
+            std::process::exit(1);
+        }
+    };
+
     // ── Writers: gRPC → RT ──────────────────────────────────────────
     let writer_rpc_cu = try_create_writer::<RpcToCuSegment>(
-        SEG_RPC_CU, ModuleAbbrev::Rpc, ModuleAbbrev::Cu,
+        &ns, SEG_RPC_CU, ModuleAbbrev::Rpc, ModuleAbbrev::Cu,
     );
     let writer_rpc_hal = try_create_writer::<RpcToHalSegment>(
-        SEG_RPC_HAL, ModuleAbbrev::Rpc, ModuleAbbrev::Hal,
+        &ns, SEG_RPC_HAL, ModuleAbbrev::Rpc, ModuleAbbrev::Hal,
     );
     let writer_rpc_re = try_create_writer::<RpcToReSegment>(
-        SEG_RPC_RE, ModuleAbbrev::Rpc, ModuleAbbrev::Re,
+        &ns, SEG_RPC_RE, ModuleAbbrev::Rpc, ModuleAbbrev::Re,
     );
 
     info!(
@@ -52,10 +76,10 @@ fn main() {
     );
 
     // ── Readers: RT → gRPC ──────────────────────────────────────────
-    let stale_threshold: u32 = 1000;
-    let reader_cu_rpc = try_attach::<CuToRpcSegment>(SEG_CU_RPC, stale_threshold);
-    let reader_hal_rpc = try_attach::<HalToRpcSegment>(SEG_HAL_RPC, stale_threshold);
-    let reader_re_rpc = try_attach::<ReToRpcSegment>(SEG_RE_RPC, stale_threshold);
+    let max_age = Duration::from_secs(1); // writer silent this long = stale
+    let reader_cu_rpc = try_attach::<CuToRpcSegment>(&ns, SEG_CU_RPC, max_age);
+    let reader_hal_rpc = try_attach::<HalToRpcSegment>(&ns, SEG_HAL_RPC, max_age);
+    let reader_re_rpc = try_attach::<ReToRpcSegment>(&ns, SEG_RE_RPC, max_age);
 
     info!(
         "gRPC readers: cu_rpc={}, hal_rpc={}, re_rpc={}",
@@ -66,12 +90,13 @@ fn main() {
     info!("gRPC Liaison initialized — placeholder (not yet implemented)");
 }
 
-fn try_create_writer<T: Default + Copy>(
+fn try_create_writer<T: Default + ShmLayout>(
+    ns: &ShmNamespace,
     seg_name: &str,
     src: ModuleAbbrev,
     dst: ModuleAbbrev,
 ) -> Option<TypedP2pWriter<T>> {
-    match TypedP2pWriter::<T>::create(seg_name, src, dst) {
+    match TypedP2pWriter::<T>::create_in(ns, seg_name, src, dst) {
         Ok(w) => {
             info!("Created writer: evo_{seg_name}");
             Some(w)
@@ -83,14 +108,16 @@ fn try_create_writer<T: Default + Copy>(
     }
 }
 
-fn try_attach<T: Default + Copy>(
+fn try_attach<T: Default + ShmLayout>(
+    ns: &ShmNamespace,
     seg_name: &str,
-    stale_threshold: u32,
+    max_age: Duration,
 ) -> Option<TypedP2pReader<T>> {
-    match TypedP2pReader::<T>::attach(seg_name, stale_threshold) {
+    // Read-count threshold unused: `max_age` replaces it.
+    match TypedP2pReader::<T>::attach_in(ns, seg_name, 0) {
         Ok(r) => {
             info!("Attached reader: evo_{seg_name}");
-            Some(r)
+            Some(r.with_max_age(max_age))
         }
         Err(e) => {
             debug!("Could not attach evo_{seg_name} (will retry later): {e}");
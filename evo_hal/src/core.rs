@@ -9,9 +9,13 @@ use evo_common::hal::config::{AxisConfig, MachineConfig};
 use evo_common::hal::consts::HAL_SERVICE_NAME;
 use evo_common::hal::driver::{HalDriver, HalError};
 use evo_common::hal::types::{HalCommands, HalStatus};
+use evo_common::io::config::IoConfig;
 use evo_common::io::registry::IoRegistry;
-use evo_common::shm::conversions::{hal_status_to_segment, segment_to_hal_commands};
-use evo_common::shm::p2p::{ModuleAbbrev, ShmError, TypedP2pReader, TypedP2pWriter};
+use evo_common::lockstep::{LockstepModule, VirtualClock};
+use evo_common::shm::conversions::{
+    hal_axis_feedback, hal_status_to_segment, segment_to_hal_commands,
+};
+use evo_common::shm::p2p::{ModuleAbbrev, ShmError, ShmNamespace, TypedP2pReader, TypedP2pWriter};
 use evo_common::shm::segments::*;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -22,7 +26,9 @@ use tracing::{debug, error, info, warn};
 
 use crate::driver_registry::create_driver;
 use crate::drivers::register_all_drivers;
+use crate::io_conditioning::IoConditioner;
 use crate::module_status::ModuleStatusPublisher;
+use crate::rpc::RpcCommandHandler;
 
 /// Default stale threshold (heartbeats) for P2P readers.
 /// Readers detect staleness if writer heartbeat hasn't advanced in N reads.
@@ -46,6 +52,8 @@ pub struct HalCore {
     module_status: ModuleStatusPublisher,
     /// Number of active axes (set from config)
     axis_count: u8,
+    /// SHM namespace of the EVO instance (P2P segment names)
+    shm_ns: ShmNamespace,
 
     // ── P2P SHM writers (HAL is the writer/producer) ──
     /// Writer: HAL → CU (`evo_hal_cu`)
@@ -67,6 +75,15 @@ pub struct HalCore {
 
     /// I/O Registry for role-based ownership enforcement (FR-036).
     io_registry: Option<IoRegistry>,
+    /// `io.toml` conditioning between driver and segments (None = raw I/O).
+    io_conditioner: Option<IoConditioner>,
+    /// gRPC direct command processing and last ack (FR-030b).
+    rpc: RpcCommandHandler,
+
+    /// Commands applied in the last cycle (kept on read contention).
+    commands: HalCommands,
+    /// Axes disabled for an unsupported drive mode (logged on change).
+    unsupported_mode_axes: u64,
 }
 
 /// Timing statistics for RT loop monitoring.
@@ -111,6 +128,7 @@ impl HalCore {
             stats: TimingStats::default(),
             module_status,
             axis_count: 0,
+            shm_ns: ShmNamespace::default(),
             writer_hal_cu: None,
             writer_hal_mqt: None,
             writer_hal_rpc: None,
@@ -118,22 +136,41 @@ impl HalCore {
             reader_cu_hal: None,
             reader_rpc_hal: None,
             reader_re_hal: None,
+            commands: HalCommands::default(),
+            unsupported_mode_axes: 0,
             io_registry: None,
+            io_conditioner: None,
+            rpc: RpcCommandHandler::new(),
         })
     }
 
     /// Create a new HalCore instance from unified config (new path via --config-dir).
     ///
     /// Uses `FullConfig` from `load_config_dir()` and optional `IoRegistry`.
+    /// Each `axis_NN_*.toml` becomes a simulated Positioning axis; its
+    /// `[dynamics]` section selects the dynamic plant model.
     pub fn from_full_config(
         full: FullConfig,
         io_registry: Option<IoRegistry>,
     ) -> Result<Self, HalError> {
         let cycle_time_us = DEFAULT_CYCLE_TIME_US;
         let cycle_time = Duration::from_micros(cycle_time_us as u64);
-        let axis_count = full.axes.len().min(64) as u8;
         let module_status = ModuleStatusPublisher::new(HAL_SERVICE_NAME);
 
+        let mut axis_configs: Vec<AxisConfig> = Vec::with_capacity(full.axes.len());
+        for (idx, axis) in full.axes.iter().enumerate() {
+            let axis_config = AxisConfig::from_new_axis_config(axis);
+            axis_config.validate(idx, &axis_configs)?;
+            info!(
+                "  Axis {}: {} ({})",
+                idx,
+                axis_config.name,
+                if axis_config.dynamics.is_some() { "dynamic" } else { "kinematic" }
+            );
+            axis_configs.push(axis_config);
+        }
+        let axis_count = axis_configs.len().min(64) as u8;
+
         // Build a legacy MachineConfig from the new format for driver compatibility.
         let mut config = MachineConfig::default();
         config.cycle_time_us = cycle_time_us;
@@ -146,13 +183,14 @@ impl HalCore {
 
         Ok(Self {
             config,
-            axis_configs: Vec::new(),
+            axis_configs,
             driver: None,
             running: Arc::new(AtomicBool::new(false)),
             cycle_time,
             stats: TimingStats::default(),
             module_status,
             axis_count,
+            shm_ns: ShmNamespace::default(),
             writer_hal_cu: None,
             writer_hal_mqt: None,
             writer_hal_rpc: None,
@@ -160,10 +198,32 @@ impl HalCore {
             reader_cu_hal: None,
             reader_rpc_hal: None,
             reader_re_hal: None,
+            commands: HalCommands::default(),
+            unsupported_mode_axes: 0,
             io_registry,
+            io_conditioner: None,
+            rpc: RpcCommandHandler::new(),
         })
     }
 
+    /// Select the SHM namespace of the EVO instance.
+    ///
+    /// Must be called before [`init`](Self::init), which creates and
+    /// attaches the P2P segments.
+    pub fn set_shm_namespace(&mut self, ns: ShmNamespace) {
+        self.shm_ns = ns;
+    }
+
+    /// Enable `io.toml` I/O conditioning (debounce, averaging, pulse,
+    /// E-Stop reset) and apply the `init` output states.
+    ///
+    /// Millisecond parameters are converted with the configured cycle time.
+    pub fn set_io_conditioning(&mut self, io_config: &IoConfig) {
+        let conditioner = IoConditioner::from_config(io_config, self.cycle_time);
+        conditioner.apply_initial_outputs(&mut self.commands);
+        self.io_conditioner = Some(conditioner);
+    }
+
     /// Load machine configuration from a TOML file (legacy path).
     pub fn load_config(config_path: &Path) -> Result<MachineConfig, HalError> {
         info!("Loading configuration from {:?}", config_path);
@@ -256,7 +316,8 @@ impl HalCore {
     /// Create P2P writers for HAL outbound segments (T042).
     fn init_p2p_writers(&mut self) {
         // HAL → CU (active — critical for RT loop).
-        match TypedP2pWriter::<HalToCuSegment>::create(
+        match TypedP2pWriter::<HalToCuSegment>::create_in(
+            &self.shm_ns,
             SEG_HAL_CU,
             ModuleAbbrev::Hal,
             ModuleAbbrev::Cu,
@@ -269,7 +330,8 @@ impl HalCore {
         }
 
         // HAL → MQTT (skeleton).
-        match TypedP2pWriter::<HalToMqtSegment>::create(
+        match TypedP2pWriter::<HalToMqtSegment>::create_in(
+            &self.shm_ns,
             SEG_HAL_MQT,
             ModuleAbbrev::Hal,
             ModuleAbbrev::Mqt,
@@ -281,8 +343,9 @@ impl HalCore {
             Err(e) => warn!("Failed to create evo_{}: {} (non-critical)", SEG_HAL_MQT, e),
         }
 
-        // HAL → gRPC (placeholder).
-        match TypedP2pWriter::<HalToRpcSegment>::create(
+        // HAL → gRPC (acks for direct commands).
+        match TypedP2pWriter::<HalToRpcSegment>::create_in(
+            &self.shm_ns,
             SEG_HAL_RPC,
             ModuleAbbrev::Hal,
             ModuleAbbrev::Rpc,
@@ -295,7 +358,8 @@ impl HalCore {
         }
 
         // HAL → RE (placeholder).
-        match TypedP2pWriter::<HalToReSegment>::create(
+        match TypedP2pWriter::<HalToReSegment>::create_in(
+            &self.shm_ns,
             SEG_HAL_RE,
             ModuleAbbrev::Hal,
             ModuleAbbrev::Re,
@@ -313,7 +377,11 @@ impl HalCore {
     /// Non-blocking — segments may not exist yet (CU/RE/gRPC not started).
     fn init_p2p_readers(&mut self) {
         // CU → HAL (active).
-        match TypedP2pReader::<CuToHalSegment>::attach(SEG_CU_HAL, READER_STALE_THRESHOLD) {
+        match TypedP2pReader::<CuToHalSegment>::attach_in(
+            &self.shm_ns,
+            SEG_CU_HAL,
+            READER_STALE_THRESHOLD,
+        ) {
             Ok(r) => {
                 info!("P2P reader attached: evo_{}", SEG_CU_HAL);
                 self.reader_cu_hal = Some(r);
@@ -325,9 +393,15 @@ impl HalCore {
         }
 
         // gRPC → HAL (skeleton).
-        match TypedP2pReader::<RpcToHalSegment>::attach(SEG_RPC_HAL, READER_STALE_THRESHOLD) {
-            Ok(r) => {
+        match TypedP2pReader::<RpcToHalSegment>::attach_in(
+            &self.shm_ns,
+            SEG_RPC_HAL,
+            READER_STALE_THRESHOLD,
+        ) {
+            Ok(mut r) => {
                 info!("P2P reader attached: evo_{}", SEG_RPC_HAL);
+                // Segment outlived a HAL restart: skip the request still in it.
+                self.rpc = RpcCommandHandler::attached(&mut r);
                 self.reader_rpc_hal = Some(r);
             }
             Err(ShmError::SegmentNotFound { .. }) => {
@@ -337,7 +411,11 @@ impl HalCore {
         }
 
         // RE → HAL (skeleton).
-        match TypedP2pReader::<ReToHalSegment>::attach(SEG_RE_HAL, READER_STALE_THRESHOLD) {
+        match TypedP2pReader::<ReToHalSegment>::attach_in(
+            &self.shm_ns,
+            SEG_RE_HAL,
+            READER_STALE_THRESHOLD,
+        ) {
             Ok(r) => {
                 info!("P2P reader attached: evo_{}", SEG_RE_HAL);
                 self.reader_re_hal = Some(r);
@@ -349,10 +427,14 @@ impl HalCore {
         }
     }
 
-    /// Periodically retry attaching P2P readers that weren't available at startup.
-    fn retry_p2p_readers(&mut self) {
+    /// Retry attaching P2P readers that weren't available at startup.
+    ///
+    /// Called once per second by [`HalCore::step`]; lockstep harnesses call
+    /// it directly once the CU segments exist.
+    pub fn retry_p2p_readers(&mut self) {
         if self.reader_cu_hal.is_none() {
-            if let Ok(r) = TypedP2pReader::<CuToHalSegment>::attach(
+            if let Ok(r) = TypedP2pReader::<CuToHalSegment>::attach_in(
+                &self.shm_ns,
                 SEG_CU_HAL,
                 READER_STALE_THRESHOLD,
             ) {
@@ -361,7 +443,8 @@ impl HalCore {
             }
         }
         if self.reader_rpc_hal.is_none() {
-            if let Ok(r) = TypedP2pReader::<RpcToHalSegment>::attach(
+            if let Ok(r) = TypedP2pReader::<RpcToHalSegment>::attach_in(
+                &self.shm_ns,
                 SEG_RPC_HAL,
                 READER_STALE_THRESHOLD,
             ) {
@@ -370,7 +453,8 @@ impl HalCore {
             }
         }
         if self.reader_re_hal.is_none() {
-            if let Ok(r) = TypedP2pReader::<ReToHalSegment>::attach(
+            if let Ok(r) = TypedP2pReader::<ReToHalSegment>::attach_in(
+                &self.shm_ns,
                 SEG_RE_HAL,
                 READER_STALE_THRESHOLD,
             ) {
@@ -384,9 +468,9 @@ impl HalCore {
     ///
     /// This method blocks until shutdown is requested via signal or error.
     pub fn run(&mut self) -> Result<(), HalError> {
-        let mut driver = self.driver.take().ok_or_else(|| {
-            HalError::InitFailed("Driver not initialized".to_string())
-        })?;
+        if self.driver.is_none() {
+            return Err(HalError::InitFailed("Driver not initialized".to_string()));
+        }
 
         info!(
             "Starting HalCore RT loop (cycle_time={}us, axes={})...",
@@ -403,101 +487,17 @@ impl HalCore {
         }
 
         let mut last_cycle = Instant::now();
-        let mut commands = HalCommands::default();
 
         while self.running.load(Ordering::SeqCst) {
             let cycle_start = Instant::now();
             let dt = cycle_start.duration_since(last_cycle);
             last_cycle = cycle_start;
 
-            // ── Read commands from SHM (T045) ──
-            if let Some(ref mut reader) = self.reader_cu_hal {
-                match reader.read() {
-                    Ok(seg) => {
-                        commands = segment_to_hal_commands(seg);
-                    }
-                    Err(ShmError::HeartbeatStale { .. }) => {
-                        // CU heartbeat stale — zero out commands for safety.
-                        commands = HalCommands::default();
-                        if self.stats.cycle_count % 1000 == 0 {
-                            warn!("CU heartbeat stale — using default zero commands");
-                        }
-                    }
-                    Err(ShmError::ReadContention { .. }) => {
-                        // Writer mid-write — use previous commands (acceptable).
-                    }
-                    Err(e) => {
-                        debug!("evo_{} read error: {}", SEG_CU_HAL, e);
-                    }
-                }
-            }
-
-            // ── Apply I/O role ownership enforcement for RE commands (T048/FR-036) ──
-            if let Some(ref mut reader) = self.reader_re_hal {
-                match reader.read() {
-                    Ok(re_seg) => {
-                        use evo_common::io::role::IoPointType;
-                        // Only apply if request_id > 0 (non-zero = valid command).
-                        if re_seg.request_id > 0 {
-                            let do_pin = re_seg.set_do_pin;
-                            let ao_pin = re_seg.set_ao_pin;
-                            let owned_do = self.io_registry.as_ref()
-                                .is_some_and(|reg| reg.pin_is_role_owned(IoPointType::Do, do_pin));
-                            let owned_ao = self.io_registry.as_ref()
-                                .is_some_and(|reg| reg.pin_is_role_owned(IoPointType::Ao, ao_pin));
-
-                            if owned_do {
-                                debug!(
-                                    "RE DO command rejected: pin {} is role-owned (req_id={})",
-                                    do_pin, re_seg.request_id
-                                );
-                            } else if re_seg.set_do_value != 0 {
-                                // Apply DO command (non-role pin).
-                                commands.digital_outputs[do_pin as usize] = true;
-                            }
-
-                            if owned_ao {
-                                debug!(
-                                    "RE AO command rejected: pin {} is role-owned (req_id={})",
-                                    ao_pin, re_seg.request_id
-                                );
-                            } else {
-                                // Apply AO command (non-role pin).
-                                commands.analog_outputs[ao_pin as usize] = re_seg.set_ao_value;
-                            }
-                        }
-                    }
-                    Err(ShmError::HeartbeatStale { .. }) | Err(ShmError::ReadContention { .. }) => {
-                        // RE stale or contention — ignore.
-                    }
-                    Err(e) => {
-                        debug!("evo_{} RE read error: {}", SEG_RE_HAL, e);
-                    }
-                }
-            }
-
-            // ── Execute driver cycle ──
-            let status: HalStatus = driver.cycle(&commands, dt);
-
-            // ── Write status to SHM (T044, T046, T047) ──
-            if let Some(ref mut writer) = self.writer_hal_cu {
-                let seg = hal_status_to_segment(&status, self.axis_count);
-                if let Err(e) = writer.commit(&seg) {
-                    debug!("evo_{} write error: {}", SEG_HAL_CU, e);
-                }
-            }
-
-            // Write to HAL → MQT segment (superset of hal_cu plus outputs and timing).
-            if let Some(ref mut writer) = self.writer_hal_mqt {
-                let seg = build_hal_mqt_segment(&status, &commands, self.axis_count, dt);
-                if let Err(e) = writer.commit(&seg) {
-                    debug!("evo_{} write error: {}", SEG_HAL_MQT, e);
-                }
-            }
+            // ── Execute cycle body ──
+            self.step(dt)?;
 
             // Update timing stats.
             let cycle_time_us = cycle_start.elapsed().as_micros() as u64;
-            self.stats.cycle_count += 1;
             self.stats.total_cycle_time_us += cycle_time_us;
             if cycle_time_us > self.stats.max_cycle_time_us {
                 self.stats.max_cycle_time_us = cycle_time_us;
@@ -522,12 +522,6 @@ impl HalCore {
                 std::thread::sleep(self.cycle_time - elapsed);
             }
 
-            // Periodic tasks — once per second at 1kHz.
-            let cycles_per_second = 1_000_000u64 / self.cycle_time.as_micros().max(1) as u64;
-            if self.stats.cycle_count % cycles_per_second.max(1) == 0 {
-                self.retry_p2p_readers();
-            }
-
             // Update module status for EVO supervisor (every 100 cycles ≈ 100ms at 1kHz).
             if self.stats.cycle_count % 100 == 0 {
                 let avg_cycle = if self.stats.cycle_count > 0 {
@@ -558,9 +552,6 @@ impl HalCore {
             }
         }
 
-        // Put driver back for shutdown.
-        self.driver = Some(driver);
-
         info!(
             "HalCore RT loop stopped after {} cycles (violations: {})",
             self.stats.cycle_count, self.stats.timing_violations
@@ -568,6 +559,171 @@ impl HalCore {
         Ok(())
     }
 
+    /// Execute exactly one HAL cycle: read commands → driver cycle → write
+    /// status.
+    ///
+    /// `dt` is the time since the previous cycle; the driver integrates
+    /// with it. Called by [`HalCore::run`] with wall-clock deltas and by
+    /// lockstep harnesses with the virtual cycle time. Never sleeps.
+    ///
+    /// # Errors
+    /// Returns `HalError::InitFailed` if the driver is not initialized.
+    pub fn step(&mut self, dt: Duration) -> Result<(), HalError> {
+        let driver = self.driver.as_deref_mut().ok_or_else(|| {
+            HalError::InitFailed("Driver not initialized".to_string())
+        })?;
+
+        // ── Read commands from SHM (T045) ──
+        if let Some(ref mut reader) = self.reader_cu_hal {
+            match reader.read() {
+                Ok(seg) => {
+                    self.commands = segment_to_hal_commands(seg);
+                    if let Some(ref conditioner) = self.io_conditioner {
+                        conditioner.apply_default_outputs(&mut self.commands);
+                    }
+                    let mask =
+                        disable_unsupported_modes(&mut self.commands, driver, self.axis_count);
+                    if mask != self.unsupported_mode_axes && mask != 0 {
+                        warn!(
+                            "Drive mode not supported by driver {} — axes disabled (mask {:#x})",
+                            driver.name(),
+                            mask
+                        );
+                    }
+                    self.unsupported_mode_axes = mask;
+                }
+                Err(ShmError::HeartbeatStale { .. }) | Err(ShmError::CommitTooOld { .. }) => {
+                    // CU heartbeat stale — zero out commands for safety;
+                    // outputs without a role fall back to their `init` level.
+                    self.commands = HalCommands::default();
+                    if let Some(ref conditioner) = self.io_conditioner {
+                        conditioner.apply_default_outputs(&mut self.commands);
+                    }
+                    if self.stats.cycle_count % 1000 == 0 {
+                        warn!("CU heartbeat stale — using default zero commands");
+                    }
+                }
+                Err(ShmError::ReadContention { .. }) => {
+                    // Writer mid-write — use previous commands (acceptable).
+                }
+                Err(e) => {
+                    debug!("evo_{} read error: {}", SEG_CU_HAL, e);
+                }
+            }
+        }
+
+        // ── Apply I/O role ownership enforcement for RE commands (T048/FR-036) ──
+        if let Some(ref mut reader) = self.reader_re_hal {
+            match reader.read() {
+                Ok(re_seg) => {
+                    use evo_common::io::role::IoPointType;
+                    // Only apply if request_id > 0 (non-zero = valid command).
+                    if re_seg.request_id > 0 {
+                        let do_pin = re_seg.set_do_pin;
+                        let ao_pin = re_seg.set_ao_pin;
+                        let owned_do = self.io_registry.as_ref()
+                            .is_some_and(|reg| reg.pin_is_role_owned(IoPointType::Do, do_pin));
+                        let owned_ao = self.io_registry.as_ref()
+                            .is_some_and(|reg| reg.pin_is_role_owned(IoPointType::Ao, ao_pin));
+
+                        if owned_do {
+                            debug!(
+                                "RE DO command rejected: pin {} is role-owned (req_id={})",
+                                do_pin, re_seg.request_id
+                            );
+                        } else if re_seg.set_do_value != 0 {
+                            // Apply DO command (non-role pin).
+                            self.commands.digital_outputs[do_pin as usize] = true;
+                        }
+
+                        if owned_ao {
+                            debug!(
+                                "RE AO command rejected: pin {} is role-owned (req_id={})",
+                                ao_pin, re_seg.request_id
+                            );
+                        } else {
+                            // Apply AO command (non-role pin).
+                            self.commands.analog_outputs[ao_pin as usize] = re_seg.set_ao_value;
+                        }
+                    }
+                }
+                Err(ShmError::HeartbeatStale { .. })
+                | Err(ShmError::CommitTooOld { .. })
+                | Err(ShmError::ReadContention { .. }) => {
+                    // RE stale or contention — ignore.
+                }
+                Err(e) => {
+                    debug!("evo_{} RE read error: {}", SEG_RE_HAL, e);
+                }
+            }
+        }
+
+        // ── gRPC direct commands with acks (FR-030b/FR-036) ──
+        if let Some(ref mut reader) = self.reader_rpc_hal {
+            match reader.read() {
+                Ok(rpc_seg) => {
+                    self.rpc.process(rpc_seg, self.io_registry.as_ref(), &mut self.commands, driver);
+                }
+                Err(ShmError::HeartbeatStale { .. })
+                | Err(ShmError::CommitTooOld { .. })
+                | Err(ShmError::ReadContention { .. }) => {
+                    // gRPC stale or contention — ignore.
+                }
+                Err(e) => {
+                    debug!("evo_{} RPC read error: {}", SEG_RPC_HAL, e);
+                }
+            }
+        }
+
+        // ── Output conditioning: pulse watchdog, E-Stop reset ──
+        // `self.commands` stays unconditioned so refreshes are detected.
+        let mut outputs = self.commands.clone();
+        if let Some(ref mut conditioner) = self.io_conditioner {
+            conditioner.condition_outputs(&mut outputs);
+        }
+
+        // ── Execute driver cycle ──
+        let mut status: HalStatus = driver.cycle(&outputs, dt);
+
+        // ── Input conditioning: debounce, moving average ──
+        if let Some(ref mut conditioner) = self.io_conditioner {
+            conditioner.condition_inputs(&mut status);
+        }
+
+        // ── Write status to SHM (T044, T046, T047) ──
+        if let Some(ref mut writer) = self.writer_hal_cu {
+            let seg = hal_status_to_segment(&status, self.axis_count);
+            if let Err(e) = writer.commit(&seg) {
+                debug!("evo_{} write error: {}", SEG_HAL_CU, e);
+            }
+        }
+
+        // Write to HAL → MQT segment (superset of hal_cu plus outputs and timing).
+        if let Some(ref mut writer) = self.writer_hal_mqt {
+            let seg = build_hal_mqt_segment(&status, &outputs, self.axis_count, dt);
+            if let Err(e) = writer.commit(&seg) {
+                debug!("evo_{} write error: {}", SEG_HAL_MQT, e);
+            }
+        }
+
+        // Ack the last gRPC request (committed every cycle for the heartbeat).
+        if let Some(ref mut writer) = self.writer_hal_rpc
+            && let Err(e) = writer.commit(self.rpc.response())
+        {
+            debug!("evo_{} write error: {}", SEG_HAL_RPC, e);
+        }
+
+        self.stats.cycle_count += 1;
+
+        // Periodic tasks — once per second at 1kHz.
+        let cycles_per_second = 1_000_000u64 / self.cycle_time.as_micros().max(1) as u64;
+        if self.stats.cycle_count % cycles_per_second.max(1) == 0 {
+            self.retry_p2p_readers();
+        }
+
+        Ok(())
+    }
+
     /// Request shutdown of the RT loop.
     pub fn shutdown(&mut self) -> Result<(), HalError> {
         info!("Shutdown requested");
@@ -610,6 +766,19 @@ impl HalCore {
     }
 }
 
+// ─── Lockstep ───────────────────────────────────────────────────────
+
+/// Virtual-time execution: one HAL cycle per clock tick, `dt` = cycle time.
+impl LockstepModule for HalCore {
+    fn name(&self) -> &str {
+        HAL_SERVICE_NAME
+    }
+
+    fn step(&mut self, clock: &VirtualClock) -> Result<(), String> {
+        HalCore::step(self, clock.cycle_time()).map_err(|e| e.to_string())
+    }
+}
+
 // ─── HAL → MQT segment builder (T044, T046, T047) ──────────────────
 
 /// Build the `HalToMqtSegment` — superset of `HalToCuSegment` plus output
@@ -630,16 +799,7 @@ fn build_hal_mqt_segment(
     // Axis feedback (same as HalToCuSegment).
     let count = (axis_count as usize).min(64);
     for i in 0..count {
-        let src = &status.axes[i];
-        seg.axes[i] = HalAxisFeedback {
-            position: src.actual_position,
-            velocity: src.actual_velocity,
-            torque_estimate: src.lag_error,
-            drive_ready: src.ready as u8,
-            drive_fault: src.error as u8,
-            referenced: src.referenced as u8,
-            active: (src.ready || src.moving || src.referencing) as u8,
-        };
+        seg.axes[i] = hal_axis_feedback(&status.axes[i]);
     }
 
     // DI bank.
@@ -660,6 +820,24 @@ fn build_hal_mqt_segment(
     seg
 }
 
+/// Disable axes commanded in a drive mode the driver cannot run, so a
+/// setpoint is never misinterpreted. Returns the mask of disabled axes.
+fn disable_unsupported_modes(
+    commands: &mut HalCommands,
+    driver: &dyn HalDriver,
+    axis_count: u8,
+) -> u64 {
+    let count = (axis_count as usize).min(commands.axes.len()).min(64);
+    let mut mask = 0u64;
+    for (i, axis) in commands.axes[..count].iter_mut().enumerate() {
+        if axis.enable && !driver.supports_axis_command_mode(i, axis.mode) {
+            axis.enable = false;
+            mask |= 1 << i;
+        }
+    }
+    mask
+}
+
 /// Resolve a possibly relative path against a base directory.
 fn resolve_path(base: &Path, path: &PathBuf) -> PathBuf {
     if path.is_absolute() {
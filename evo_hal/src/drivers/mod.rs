@@ -14,15 +14,22 @@
 pub mod simulation;
 
 use crate::driver_registry::register_driver;
+use std::sync::Once;
+
+/// Guards the one-time global registration.
+static REGISTER: Once = Once::new();
 
 /// Initialize and register all built-in drivers.
 ///
-/// This function should be called once at startup before any drivers are requested.
+/// Must be called before any drivers are requested. Repeated calls (e.g.
+/// several `HalCore` instances in one lockstep process) are no-ops.
 pub fn register_all_drivers() {
-    // Register simulation driver
-    register_driver("simulation", simulation::create_driver);
+    REGISTER.call_once(|| {
+        // Register simulation driver
+        register_driver("simulation", simulation::create_driver);
 
-    // Future drivers will be registered here:
-    // register_driver("ethercat", ethercat::create_driver);
-    // register_driver("canopen", canopen::create_driver);
+        // Future drivers will be registered here:
+        // register_driver("ethercat", ethercat::create_driver);
+        // register_driver("canopen", canopen::create_driver);
+    });
 }
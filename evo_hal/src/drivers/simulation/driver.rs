@@ -9,7 +9,7 @@ use super::physics::AxisSimulator;
 use super::state::{PersistedAxisState, PersistedState, StatePersistence, needs_referencing};
 use evo_common::hal::config::{AxisConfig, MachineConfig};
 use evo_common::hal::driver::{HalDriver, HalError};
-use evo_common::hal::types::{HalCommands, HalStatus};
+use evo_common::hal::types::{CommandMode, HalCommands, HalStatus};
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
@@ -31,6 +31,8 @@ pub struct SimulationDriver {
     persisted_state: Option<PersistedState>,
     /// Simulation start time (for timestamping)
     start_time: Option<Instant>,
+    /// Simulated time since start (sum of cycle `dt`)
+    sim_time: Duration,
 }
 
 impl SimulationDriver {
@@ -45,6 +47,7 @@ impl SimulationDriver {
             state_persistence: None,
             persisted_state: None,
             start_time: None,
+            sim_time: Duration::ZERO,
         }
     }
 
@@ -162,6 +165,7 @@ impl HalDriver for SimulationDriver {
         }
 
         self.start_time = Some(Instant::now());
+        self.sim_time = Duration::ZERO;
         self.initialized = true;
 
         info!("Simulation driver initialized (axis simulators will be set via set_axis_configs)");
@@ -187,7 +191,10 @@ impl HalDriver for SimulationDriver {
     fn cycle(&mut self, commands: &HalCommands, dt: Duration) -> HalStatus {
         debug!("Simulation driver cycle, dt={:?}", dt);
 
-        let now = self.start_time.map(|_| Instant::now()).unwrap_or_else(Instant::now);
+        // Simulated time: delayed I/O reactions follow the cycle `dt`, so a
+        // lockstep run is independent of the wall clock.
+        self.sim_time += dt;
+        let now = self.start_time.unwrap_or_else(Instant::now) + self.sim_time;
         let mut status = HalStatus::default();
 
         // Process I/O
@@ -258,6 +265,17 @@ impl HalDriver for SimulationDriver {
         Ok(())
     }
 
+    fn supports_command_mode(&self, mode: CommandMode) -> bool {
+        // Kinematic axis model: no torque path (CST).
+        mode != CommandMode::CyclicSyncTorque
+    }
+
+    fn supports_axis_command_mode(&self, axis: usize, mode: CommandMode) -> bool {
+        // Dynamic axes integrate the commanded torque.
+        self.supports_command_mode(mode)
+            || self.axis_sims.get(axis).is_some_and(AxisSimulator::is_dynamic)
+    }
+
     fn supports_hot_swap(&self) -> bool {
         false
     }
@@ -2,15 +2,19 @@
 //!
 //! The `AxisSimulator` provides realistic motion simulation for different axis types:
 //! - Simple: On/off without position feedback
-//! - Positioning: Full kinematics with velocity/acceleration limits
+//! - Positioning: Full kinematics with velocity/acceleration limits, tracking
+//!   the position (CSP) or velocity (CSV) setpoint; with `[dynamics]` the
+//!   axis is a torque-driven `PlantModel` instead, driven by the CU torque
+//!   or by the simulated drive cascade (`drive_loop`)
 //! - Slave: Coupled to master axis with offset
 //! - Measurement: Encoder-only without drive
 
-use evo_common::hal::config::{AxisConfig, AxisType};
-use evo_common::hal::types::{AxisCommand, AxisStatus};
+use evo_common::hal::config::{AxisConfig, AxisType, DriveLoop};
+use evo_common::hal::types::{AxisCommand, AxisStatus, CommandMode};
 use std::time::Duration;
 use tracing::{debug, trace};
 
+use super::dynamics::PlantModel;
 use super::referencing::{ReferencingState, ReferencingStateMachine};
 
 /// Error codes for axis status
@@ -32,6 +36,16 @@ pub struct AxisSimulator {
     velocity: f64,
     /// Target position from command
     target_position: f64,
+    /// Target velocity from command (CSV setpoint + velocity offset)
+    target_velocity: f64,
+    /// Velocity feedforward from command (CSP velocity offset)
+    velocity_feedforward: f64,
+    /// Target torque from command (CST)
+    target_torque: f64,
+    /// Torque feedforward from command (CSP / CSV torque offset)
+    torque_feedforward: f64,
+    /// Drive mode from command
+    mode: CommandMode,
     /// Is axis enabled?
     enabled: bool,
     /// Is axis referenced?
@@ -50,6 +64,8 @@ pub struct AxisSimulator {
     master_index: Option<usize>,
     /// Coupling offset for Slave type (captured at coupling time)
     coupling_offset: f64,
+    /// Dynamic plant (Positioning with `[dynamics]`)
+    plant: Option<PlantModel>,
 }
 
 impl AxisSimulator {
@@ -72,12 +88,25 @@ impl AxisSimulator {
 
         let coupling_offset = config.coupling_offset.unwrap_or(0.0);
 
+        let plant = match (config.axis_type, &config.dynamics) {
+            (AxisType::Positioning, Some(dynamics)) => Some(PlantModel::new(
+                dynamics.clone(),
+                config.encoder_resolution.unwrap_or(1.0),
+            )),
+            _ => None,
+        };
+
         Self {
             referencing_sm: ReferencingStateMachine::new(&config.referencing),
             config,
             position: 0.0,
             velocity: 0.0,
             target_position: 0.0,
+            target_velocity: 0.0,
+            velocity_feedforward: 0.0,
+            target_torque: 0.0,
+            torque_feedforward: 0.0,
+            mode: CommandMode::CyclicSyncPosition,
             enabled: false,
             referenced: initial_referenced,
             error_code: ERROR_NONE,
@@ -86,6 +115,7 @@ impl AxisSimulator {
             moving: false,
             master_index,
             coupling_offset,
+            plant,
         }
     }
 
@@ -120,8 +150,13 @@ impl AxisSimulator {
             self.start_referencing();
         }
 
-        // Store target position
+        // Store setpoints
         self.target_position = command.target_position;
+        self.target_velocity = command.target_velocity + command.velocity_offset;
+        self.velocity_feedforward = command.velocity_offset;
+        self.target_torque = command.target_torque;
+        self.torque_feedforward = command.torque_offset;
+        self.mode = command.mode;
 
         // Update based on axis type
         match self.config.axis_type {
@@ -139,8 +174,11 @@ impl AxisSimulator {
             }
         }
 
-        // Calculate lag error for Positioning axes
-        if self.config.axis_type == AxisType::Positioning && self.enabled {
+        // Calculate lag error for Positioning axes under position control
+        if self.config.axis_type == AxisType::Positioning
+            && self.enabled
+            && self.mode == CommandMode::CyclicSyncPosition
+        {
             self.lag_error = self.target_position - self.position;
             self.check_lag_error();
         } else {
@@ -164,8 +202,64 @@ impl AxisSimulator {
         }
     }
 
-    /// Update for Positioning axis type (full kinematics)
+    /// Update for Positioning axis type
     fn update_positioning(&mut self, dt: f64) {
+        if self.plant.is_some() && !self.referencing_sm.is_active() {
+            self.update_dynamic(dt);
+        } else {
+            self.update_kinematic(dt);
+            // Keep the plant on the kinematic (referencing) trajectory
+            if let Some(plant) = &mut self.plant {
+                plant.reset(self.position, self.velocity);
+            }
+        }
+    }
+
+    /// Update a Positioning axis through its dynamic plant.
+    ///
+    /// With the CU running the loop the commanded torque is applied in
+    /// every mode. Otherwise CST applies the commanded torque and CSP / CSV
+    /// run the drive cascade with the velocity / torque offsets as
+    /// feedforward. A disabled or faulted drive quick-stops and holds on
+    /// the brake.
+    fn update_dynamic(&mut self, dt: f64) {
+        let Some(plant) = &mut self.plant else {
+            return;
+        };
+        let drive_loop = self.config.dynamics.as_ref().map_or(DriveLoop::Cu, |d| d.drive_loop);
+        if !self.enabled || self.error_code != ERROR_NONE {
+            plant.brake(dt);
+        } else if drive_loop == DriveLoop::Cu {
+            plant.apply_torque(self.target_torque, dt);
+        } else {
+            match self.mode {
+                CommandMode::CyclicSyncTorque => plant.apply_torque(self.target_torque, dt),
+                CommandMode::CyclicSyncVelocity => {
+                    plant.track_velocity(self.target_velocity, self.torque_feedforward, dt)
+                }
+                CommandMode::CyclicSyncPosition => plant.track_position(
+                    self.target_position,
+                    self.velocity_feedforward,
+                    self.torque_feedforward,
+                    dt,
+                ),
+            }
+        }
+        self.position = plant.position();
+        self.velocity = plant.velocity();
+
+        self.check_soft_limits();
+        if let Some(plant) = &mut self.plant
+            && (self.position != plant.position() || self.velocity != plant.velocity())
+        {
+            plant.reset(self.position, self.velocity);
+        }
+
+        self.moving = self.velocity.abs() > 0.001;
+    }
+
+    /// Update for Positioning axis type (full kinematics)
+    fn update_kinematic(&mut self, dt: f64) {
         if !self.enabled || self.has_error() {
             // Decelerate to stop
             self.decelerate_to_stop(dt);
@@ -178,20 +272,31 @@ impl AxisSimulator {
             return;
         }
 
+        // Kinematic model has no torque path: CST holds the axis
+        if self.mode == CommandMode::CyclicSyncTorque {
+            self.decelerate_to_stop(dt);
+            return;
+        }
+
         // Normal positioning motion
         let position_error = self.target_position - self.position;
         let max_vel = self.config.max_velocity.unwrap_or(100.0);
         let max_acc = self.config.max_acceleration.unwrap_or(1000.0);
 
-        // Calculate desired velocity based on position error
-        // Use triangular velocity profile for smooth motion
-        let stopping_distance = self.velocity.abs() * self.velocity.abs() / (2.0 * max_acc);
-        let desired_velocity = if position_error.abs() <= stopping_distance {
-            // Deceleration phase
-            position_error.signum() * (2.0 * max_acc * position_error.abs()).sqrt().min(max_vel)
+        let desired_velocity = if self.mode == CommandMode::CyclicSyncVelocity {
+            // Velocity setpoint tracked directly
+            self.target_velocity
         } else {
-            // Acceleration/cruise phase
-            position_error.signum() * max_vel
+            // Calculate desired velocity based on position error
+            // Use triangular velocity profile for smooth motion
+            let stopping_distance = self.velocity.abs() * self.velocity.abs() / (2.0 * max_acc);
+            if position_error.abs() <= stopping_distance {
+                // Deceleration phase
+                position_error.signum() * (2.0 * max_acc * position_error.abs()).sqrt().min(max_vel)
+            } else {
+                // Acceleration/cruise phase
+                position_error.signum() * max_vel
+            }
         };
 
         // Apply acceleration limits
@@ -347,6 +452,9 @@ impl AxisSimulator {
             self.referenced = true;
             // Set position to reference point (typically 0)
             self.position = 0.0;
+            if let Some(plant) = &mut self.plant {
+                plant.reset(0.0, 0.0);
+            }
         } else if self.referencing_sm.state() == ReferencingState::Error {
             debug!("Axis {} referencing failed", self.config.name);
             self.set_error(ERROR_REFERENCING);
@@ -377,6 +485,10 @@ impl AxisSimulator {
             actual_position: self.position,
             actual_velocity: self.velocity,
             lag_error: self.lag_error,
+            // Torque / current feedback only from the dynamic plant
+            actual_torque: self.plant.as_ref().map_or(0.0, PlantModel::torque),
+            actual_current: self.plant.as_ref().map_or(0.0, PlantModel::current),
+            mode: self.mode,
             ready: self.enabled && !self.has_error() && self.referenced,
             error: self.has_error(),
             referenced: self.referenced,
@@ -395,6 +507,14 @@ impl AxisSimulator {
     /// Set position externally (for Measurement axes or state restore)
     pub fn set_position(&mut self, pos: f64) {
         self.position = pos;
+        if let Some(plant) = &mut self.plant {
+            plant.reset(pos, 0.0);
+        }
+    }
+
+    /// Check if the axis runs the dynamic plant model (supports CST)
+    pub fn is_dynamic(&self) -> bool {
+        self.plant.is_some()
     }
 
     /// Get axis name
@@ -435,7 +555,9 @@ impl AxisSimulator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use evo_common::hal::config::{ReferencingConfig, ReferencingMode, ReferencingRequired};
+    use evo_common::hal::config::{
+        DriveLoop, DynamicsConfig, ReferencingConfig, ReferencingMode, ReferencingRequired,
+    };
 
     fn make_simple_axis() -> AxisConfig {
         AxisConfig {
@@ -451,6 +573,7 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         }
     }
 
@@ -472,6 +595,7 @@ mod tests {
             },
             soft_limit_positive: Some(1000.0),
             soft_limit_negative: Some(-1000.0),
+            dynamics: None,
         }
     }
 
@@ -489,6 +613,7 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         }
     }
 
@@ -506,6 +631,7 @@ mod tests {
             referencing: ReferencingConfig::default(),
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         }
     }
 
@@ -521,6 +647,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         let status = sim.update(&cmd, dt, None);
@@ -544,6 +671,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         // First update - should start moving
@@ -572,6 +700,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         // Run until velocity stabilizes
@@ -584,6 +713,31 @@ mod tests {
         assert!(status.actual_velocity.abs() <= 100.1);
     }
 
+    #[test]
+    fn test_positioning_axis_velocity_mode() {
+        let config = make_positioning_axis();
+        let mut sim = AxisSimulator::new(config);
+        let dt = Duration::from_millis(1);
+
+        let cmd = AxisCommand {
+            mode: CommandMode::CyclicSyncVelocity,
+            target_position: 0.0, // Ignored in CSV
+            target_velocity: 40.0,
+            enable: true,
+            ..Default::default()
+        };
+
+        // 500 mm/s² → 40 mm/s after 80 ms
+        for _ in 0..200 {
+            sim.update(&cmd, dt, None);
+        }
+        let status = sim.update(&cmd, dt, None);
+        assert!((status.actual_velocity - 40.0).abs() < 1e-9);
+        assert!(status.actual_position > 4.0);
+        assert_eq!(status.mode, CommandMode::CyclicSyncVelocity);
+        assert!(!status.error, "no lag error in velocity mode");
+    }
+
     #[test]
     fn test_slave_axis_follows_master() {
         let config = make_slave_axis(0);
@@ -595,6 +749,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         // Master at position 50
@@ -618,6 +773,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         // Master at 50, slave should be at 50 + 10 = 60
@@ -639,6 +795,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         let status = sim.update(&cmd, dt, None);
@@ -660,6 +817,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         // Run for a while
@@ -684,6 +842,7 @@ mod tests {
             enable: false,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         let status = sim.update(&cmd, dt, None);
@@ -695,6 +854,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         let status = sim.update(&cmd, dt, None);
@@ -713,6 +873,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         let status = sim.update(&cmd, dt, None);
@@ -744,6 +905,7 @@ mod tests {
             },
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         };
         let mut sim = AxisSimulator::new(config);
         let dt = Duration::from_millis(10);
@@ -754,6 +916,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         // Run a few cycles, position will approach target
@@ -790,6 +953,7 @@ mod tests {
             },
             soft_limit_positive: None,
             soft_limit_negative: None,
+            dynamics: None,
         };
         let mut sim = AxisSimulator::new(config);
         let dt = Duration::from_millis(10);
@@ -803,6 +967,7 @@ mod tests {
             enable: true,
             reset: false,
             reference: false,
+            ..Default::default()
         };
 
         // This should trigger lag error since position error > lag limit
@@ -818,6 +983,7 @@ mod tests {
                 enable: true, // Still enabled
                 reset: true,  // Try reset
                 reference: false,
+                ..Default::default()
             };
             let status = sim.update(&cmd_reset_enabled, dt, None);
             assert!(status.error); // Error should persist
@@ -828,6 +994,7 @@ mod tests {
                 enable: false, // Disable first
                 reset: false,
                 reference: false,
+                ..Default::default()
             };
             let status = sim.update(&cmd_disable, dt, None);
             assert!(status.error); // Error still present
@@ -839,10 +1006,116 @@ mod tests {
                 enable: false, // Still disabled
                 reset: true,   // Now reset
                 reference: false,
+                ..Default::default()
             };
             let status = sim.update(&cmd_reset, dt, None);
             assert!(!status.error); // Error should be cleared
             assert_eq!(status.error_code, 0); // Error code reset
         }
     }
+
+    fn make_dynamic_axis() -> AxisConfig {
+        AxisConfig {
+            name: "dynamic_axis".to_string(),
+            dynamics: Some(DynamicsConfig {
+                viscous_friction: 0.01,
+                coulomb_friction: 0.02,
+                gravity_load: 0.1,
+                resonance_frequency: Some(150.0),
+                encoder_resolution: Some(1e6),
+                drive_loop: DriveLoop::Drive,
+                ..DynamicsConfig::rigid(0.01)
+            }),
+            ..make_positioning_axis()
+        }
+    }
+
+    #[test]
+    fn test_dynamic_axis_torque_mode() {
+        let mut sim = AxisSimulator::new(make_dynamic_axis());
+        assert!(sim.is_dynamic());
+        assert!(!AxisSimulator::new(make_positioning_axis()).is_dynamic());
+        let dt = Duration::from_millis(1);
+
+        // Net torque 0.5 − 0.1 gravity − 0.02 Coulomb accelerates positive.
+        let cmd = AxisCommand {
+            mode: CommandMode::CyclicSyncTorque,
+            target_torque: 0.5,
+            enable: true,
+            ..Default::default()
+        };
+        let mut status = sim.update(&cmd, dt, None);
+        for _ in 0..100 {
+            status = sim.update(&cmd, dt, None);
+        }
+        // a ≈ 38 → v ≈ 3.8 after 0.1 s (viscous friction slightly less)
+        assert!((3.5..3.9).contains(&status.actual_velocity), "v = {}", status.actual_velocity);
+        assert!(status.actual_position > 0.15);
+        assert_eq!(status.actual_torque, 0.5);
+        assert_eq!(status.actual_current, 0.5);
+        assert!(status.moving);
+        assert!(!status.error, "no lag check in torque mode");
+    }
+
+    #[test]
+    fn test_dynamic_axis_position_mode_settles() {
+        let mut sim = AxisSimulator::new(make_dynamic_axis());
+        let dt = Duration::from_millis(1);
+        let cmd = AxisCommand {
+            target_position: 2.0,
+            enable: true,
+            ..Default::default()
+        };
+        let mut status = sim.update(&cmd, dt, None);
+        for _ in 0..2000 {
+            status = sim.update(&cmd, dt, None);
+        }
+        assert!((status.actual_position - 2.0).abs() < 1e-3, "x = {}", status.actual_position);
+        assert!(status.in_position);
+        assert!(!status.error);
+        // Holding against gravity.
+        assert!(status.actual_torque > 0.05, "τ = {}", status.actual_torque);
+
+        // Disabled: quick stop and brake, no torque.
+        let disable = AxisCommand {
+            target_position: 2.0,
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            status = sim.update(&disable, dt, None);
+        }
+        assert_eq!(status.actual_torque, 0.0);
+        assert!((status.actual_position - 2.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_dynamic_axis_cu_loop_applies_torque_in_csp() {
+        let mut config = make_dynamic_axis();
+        config.dynamics.as_mut().unwrap().drive_loop = DriveLoop::Cu;
+        let mut sim = AxisSimulator::new(config);
+        let dt = Duration::from_millis(1);
+
+        // The position setpoint is ignored; only the CU torque moves the axis.
+        let cmd = AxisCommand {
+            target_position: 2.0,
+            target_torque: 0.5,
+            torque_offset: 0.3,
+            enable: true,
+            ..Default::default()
+        };
+        let mut status = sim.update(&cmd, dt, None);
+        for _ in 0..100 {
+            status = sim.update(&cmd, dt, None);
+        }
+        assert_eq!(status.actual_torque, 0.5);
+        assert!((3.5..3.9).contains(&status.actual_velocity), "v = {}", status.actual_velocity);
+
+        // Zero torque: gravity wins and the axis falls back.
+        let hold = AxisCommand { target_torque: 0.0, ..cmd };
+        for _ in 0..500 {
+            status = sim.update(&hold, dt, None);
+        }
+        assert_eq!(status.actual_torque, 0.0);
+        assert!(status.actual_velocity < 0.0);
+    }
 }
@@ -0,0 +1,424 @@
+//! Dynamic plant model.
+//!
+//! Torque-driven two-mass model of a positioning axis (motor + load coupled
+//! by a damped spring) with viscous and Coulomb friction, a constant
+//! gravity load and a quantized motor-side encoder. The commanded torque is
+//! applied directly (CST, or any mode when the CU closes the loop); with
+//! `drive_loop = "drive"` the simulated drive closes its own cascade
+//! (P position → PI velocity) for CSP / CSV.
+
+use evo_common::hal::config::DynamicsConfig;
+use std::f64::consts::PI;
+
+/// Velocity below which Coulomb friction holds a mass at rest [user units/s].
+const STICTION_VELOCITY: f64 = 1e-6;
+
+/// Integration substeps per resonance period.
+const SUBSTEPS_PER_PERIOD: f64 = 20.0;
+
+/// Upper bound on integration substeps per cycle.
+const MAX_SUBSTEPS: u32 = 100;
+
+/// Encoder velocity below which the brake engages on disable [user units/s].
+const BRAKE_VELOCITY: f64 = 0.001;
+
+/// Torque-driven axis plant with its simulated drive loops.
+#[derive(Debug, Clone)]
+pub struct PlantModel {
+    config: DynamicsConfig,
+    /// Encoder increments per user unit.
+    resolution: f64,
+    /// Motor-side inertia (total inertia when rigid).
+    motor_inertia: f64,
+    /// Load-side inertia (0 when rigid).
+    load_inertia: f64,
+    /// Coupling stiffness [torque / user unit].
+    stiffness: f64,
+    /// Coupling damping [torque / (user units/s)].
+    damping: f64,
+    motor_position: f64,
+    motor_velocity: f64,
+    load_position: f64,
+    load_velocity: f64,
+    /// Torque applied in the last cycle (after the torque limit).
+    torque: f64,
+    /// Quantized encoder position.
+    encoder_position: f64,
+    /// Encoder velocity (position difference per cycle).
+    encoder_velocity: f64,
+    /// Velocity loop integrator [torque].
+    integral: f64,
+}
+
+impl PlantModel {
+    /// Create a plant at rest at position 0.
+    ///
+    /// `resolution` is the encoder quantization [increments per user unit];
+    /// `config.encoder_resolution` takes precedence.
+    pub fn new(config: DynamicsConfig, resolution: f64) -> Self {
+        let resolution = config.encoder_resolution.unwrap_or(resolution);
+        let (motor_inertia, load_inertia, stiffness, damping) = match config.resonance_frequency {
+            Some(f) => {
+                let load = config.inertia * config.load_ratio;
+                let motor = config.inertia - load;
+                let reduced = motor * load / config.inertia;
+                let omega = 2.0 * PI * f;
+                let stiffness = omega * omega * reduced;
+                let damping = 2.0 * config.resonance_damping * (stiffness * reduced).sqrt();
+                (motor, load, stiffness, damping)
+            }
+            None => (config.inertia, 0.0, 0.0, 0.0),
+        };
+        Self {
+            config,
+            resolution,
+            motor_inertia,
+            load_inertia,
+            stiffness,
+            damping,
+            motor_position: 0.0,
+            motor_velocity: 0.0,
+            load_position: 0.0,
+            load_velocity: 0.0,
+            torque: 0.0,
+            encoder_position: 0.0,
+            encoder_velocity: 0.0,
+            integral: 0.0,
+        }
+    }
+
+    /// Measured (quantized) position.
+    pub fn position(&self) -> f64 {
+        self.encoder_position
+    }
+
+    /// Measured velocity.
+    pub fn velocity(&self) -> f64 {
+        self.encoder_velocity
+    }
+
+    /// Load-side position (equals the motor position when rigid).
+    #[cfg(test)]
+    pub(crate) fn load_position(&self) -> f64 {
+        if self.is_rigid() { self.motor_position } else { self.load_position }
+    }
+
+    /// Torque applied in the last cycle.
+    pub fn torque(&self) -> f64 {
+        self.torque
+    }
+
+    /// Motor current for the applied torque.
+    pub fn current(&self) -> f64 {
+        self.torque / self.config.torque_constant
+    }
+
+    /// Place both masses at `position` moving with `velocity`.
+    ///
+    /// Used after kinematic referencing, soft-limit clamping and external
+    /// position changes.
+    pub fn reset(&mut self, position: f64, velocity: f64) {
+        self.motor_position = position;
+        self.load_position = position;
+        self.motor_velocity = velocity;
+        self.load_velocity = velocity;
+        self.encoder_position = self.quantize(position);
+        self.encoder_velocity = velocity;
+        self.integral = 0.0;
+    }
+
+    /// CST / CU loop: apply `torque` for one cycle.
+    pub fn apply_torque(&mut self, torque: f64, dt: f64) {
+        self.step(torque, dt);
+    }
+
+    /// CSV: track `velocity` with the drive PI velocity loop.
+    pub fn track_velocity(&mut self, velocity: f64, torque_feedforward: f64, dt: f64) {
+        let torque = self.velocity_loop(velocity, torque_feedforward, dt);
+        self.step(torque, dt);
+    }
+
+    /// CSP: track `position` with the drive P position loop on top of the
+    /// velocity loop.
+    pub fn track_position(
+        &mut self,
+        position: f64,
+        velocity_feedforward: f64,
+        torque_feedforward: f64,
+        dt: f64,
+    ) {
+        let kp = self.velocity_bandwidth() / 4.0;
+        let velocity = velocity_feedforward + kp * (position - self.encoder_position);
+        self.track_velocity(velocity, torque_feedforward, dt);
+    }
+
+    /// Disabled drive: quick stop, then hold on the brake.
+    ///
+    /// Gravity is carried by the brake, so a braked axis does not drift.
+    pub fn brake(&mut self, dt: f64) {
+        if self.encoder_velocity.abs() < BRAKE_VELOCITY && self.torque == 0.0 {
+            return;
+        }
+        if self.encoder_velocity.abs() < BRAKE_VELOCITY {
+            let position = self.motor_position;
+            self.reset(position, 0.0);
+            self.torque = 0.0;
+            return;
+        }
+        self.track_velocity(0.0, 0.0, dt);
+    }
+
+    // ── Internals ───────────────────────────────────────────────────
+
+    fn is_rigid(&self) -> bool {
+        self.config.resonance_frequency.is_none()
+    }
+
+    fn velocity_bandwidth(&self) -> f64 {
+        2.0 * PI * self.config.drive_bandwidth
+    }
+
+    fn limit(&self, torque: f64) -> f64 {
+        match self.config.max_torque {
+            Some(max) => torque.clamp(-max, max),
+            None => torque,
+        }
+    }
+
+    /// PI velocity controller tuned to `drive_bandwidth` for the total inertia.
+    fn velocity_loop(&mut self, velocity: f64, torque_feedforward: f64, dt: f64) -> f64 {
+        let omega = self.velocity_bandwidth();
+        let kv = self.config.inertia * omega;
+        let error = velocity - self.encoder_velocity;
+        self.integral += kv * omega / 4.0 * error * dt;
+        if let Some(max) = self.config.max_torque {
+            self.integral = self.integral.clamp(-max, max);
+        }
+        kv * error + self.integral + torque_feedforward
+    }
+
+    fn quantize(&self, position: f64) -> f64 {
+        (position * self.resolution).round() / self.resolution
+    }
+
+    /// Integrate the plant for one cycle under constant `torque`.
+    fn step(&mut self, torque: f64, dt: f64) {
+        self.torque = self.limit(torque);
+        let substeps = match self.config.resonance_frequency {
+            Some(f) => ((dt * f * SUBSTEPS_PER_PERIOD).ceil() as u32).clamp(1, MAX_SUBSTEPS),
+            None => 1,
+        };
+        let h = dt / f64::from(substeps);
+        for _ in 0..substeps {
+            if self.is_rigid() {
+                let external = self.torque - self.config.gravity_load;
+                self.motor_velocity =
+                    self.friction_step(self.motor_velocity, external, self.motor_inertia, h);
+            } else {
+                let spring = self.stiffness * (self.motor_position - self.load_position)
+                    + self.damping * (self.motor_velocity - self.load_velocity);
+                self.motor_velocity =
+                    self.friction_step(self.motor_velocity, self.torque - spring, self.motor_inertia, h);
+                self.load_velocity +=
+                    (spring - self.config.gravity_load) / self.load_inertia * h;
+                self.load_position += self.load_velocity * h;
+            }
+            self.motor_position += self.motor_velocity * h;
+        }
+
+        let previous = self.encoder_position;
+        self.encoder_position = self.quantize(self.motor_position);
+        self.encoder_velocity = (self.encoder_position - previous) / dt;
+    }
+
+    /// Semi-implicit velocity update of a mass with friction.
+    ///
+    /// Coulomb friction holds the mass while the external torque stays
+    /// below it and never reverses the direction of motion.
+    fn friction_step(&self, velocity: f64, external: f64, inertia: f64, h: f64) -> f64 {
+        let coulomb = self.config.coulomb_friction;
+        if velocity.abs() < STICTION_VELOCITY && external.abs() <= coulomb {
+            return 0.0;
+        }
+        let direction = if velocity.abs() < STICTION_VELOCITY {
+            external.signum()
+        } else {
+            velocity.signum()
+        };
+        let friction = coulomb * direction + self.config.viscous_friction * velocity;
+        let next = velocity + (external - friction) / inertia * h;
+        if velocity.abs() >= STICTION_VELOCITY && next.signum() != velocity.signum() {
+            0.0
+        } else {
+            next
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 0.001;
+
+    fn rigid() -> DynamicsConfig {
+        DynamicsConfig {
+            encoder_resolution: Some(1e6),
+            ..DynamicsConfig::rigid(0.01)
+        }
+    }
+
+    #[test]
+    fn constant_torque_accelerates() {
+        let mut plant = PlantModel::new(rigid(), 1e6);
+        for _ in 0..100 {
+            plant.apply_torque(0.5, DT);
+        }
+        // a = τ / J = 50, t = 0.1 s → v = 5, x ≈ 0.25
+        assert!((plant.velocity() - 5.0).abs() < 0.01, "v = {}", plant.velocity());
+        assert!((plant.position() - 0.25).abs() < 0.01, "x = {}", plant.position());
+        assert_eq!(plant.torque(), 0.5);
+        assert_eq!(plant.current(), 0.5);
+    }
+
+    #[test]
+    fn viscous_friction_limits_speed() {
+        let config = DynamicsConfig {
+            viscous_friction: 0.1,
+            ..rigid()
+        };
+        let mut plant = PlantModel::new(config, 1e6);
+        for _ in 0..2000 {
+            plant.apply_torque(1.0, DT);
+        }
+        // Steady state v = τ / b = 10.
+        assert!((plant.velocity() - 10.0).abs() < 0.01, "v = {}", plant.velocity());
+    }
+
+    #[test]
+    fn coulomb_friction_sticks_and_breaks_away() {
+        let config = DynamicsConfig {
+            coulomb_friction: 0.2,
+            ..rigid()
+        };
+        let mut plant = PlantModel::new(config, 1e6);
+        for _ in 0..100 {
+            plant.apply_torque(0.15, DT);
+        }
+        assert_eq!(plant.position(), 0.0);
+        assert_eq!(plant.velocity(), 0.0);
+
+        for _ in 0..100 {
+            plant.apply_torque(0.3, DT);
+        }
+        assert!(plant.velocity() > 0.9, "v = {}", plant.velocity());
+
+        // Friction stops the coasting axis without reversing it.
+        for _ in 0..1000 {
+            plant.apply_torque(0.0, DT);
+        }
+        assert_eq!(plant.velocity(), 0.0);
+        assert!(plant.position() > 0.0);
+    }
+
+    #[test]
+    fn gravity_pulls_negative_and_brake_holds() {
+        let config = DynamicsConfig {
+            gravity_load: 0.1,
+            ..rigid()
+        };
+        let mut plant = PlantModel::new(config, 1e6);
+        for _ in 0..100 {
+            plant.apply_torque(0.0, DT);
+        }
+        assert!(plant.velocity() < 0.0);
+
+        for _ in 0..500 {
+            plant.brake(DT);
+        }
+        let held = plant.position();
+        assert_eq!(plant.velocity(), 0.0);
+        assert_eq!(plant.torque(), 0.0);
+        for _ in 0..100 {
+            plant.brake(DT);
+        }
+        assert_eq!(plant.position(), held);
+    }
+
+    #[test]
+    fn torque_limit_applied() {
+        let config = DynamicsConfig {
+            max_torque: Some(0.2),
+            ..rigid()
+        };
+        let mut plant = PlantModel::new(config, 1e6);
+        plant.apply_torque(5.0, DT);
+        assert_eq!(plant.torque(), 0.2);
+        plant.apply_torque(-5.0, DT);
+        assert_eq!(plant.torque(), -0.2);
+    }
+
+    #[test]
+    fn encoder_quantizes_position() {
+        let mut plant = PlantModel::new(DynamicsConfig::rigid(0.01), 100.0);
+        plant.reset(1.2345, 0.0);
+        assert_eq!(plant.position(), 1.23);
+        for _ in 0..10 {
+            plant.apply_torque(0.001, DT);
+        }
+        assert_eq!((plant.position() * 100.0).fract(), 0.0);
+    }
+
+    #[test]
+    fn resonance_oscillates_at_configured_frequency() {
+        let config = DynamicsConfig {
+            resonance_frequency: Some(50.0),
+            resonance_damping: 0.0,
+            ..rigid()
+        };
+        let mut plant = PlantModel::new(config, 1e6);
+        // Torque impulse excites the two-mass mode.
+        plant.apply_torque(1.0, DT);
+        let mut previous = plant.position() - plant.load_position();
+        let mut crossings = 0;
+        for _ in 0..1000 {
+            plant.apply_torque(0.0, DT);
+            let twist = plant.position() - plant.load_position();
+            if twist.signum() != previous.signum() {
+                crossings += 1;
+            }
+            previous = twist;
+        }
+        // 50 Hz for 1 s → ~100 zero crossings of the shaft twist.
+        assert!((95..=105).contains(&crossings), "crossings = {crossings}");
+    }
+
+    #[test]
+    fn velocity_loop_tracks_setpoint() {
+        let config = DynamicsConfig {
+            viscous_friction: 0.05,
+            coulomb_friction: 0.05,
+            ..rigid()
+        };
+        let mut plant = PlantModel::new(config, 1e6);
+        for _ in 0..500 {
+            plant.track_velocity(20.0, 0.0, DT);
+        }
+        assert!((plant.velocity() - 20.0).abs() < 0.05, "v = {}", plant.velocity());
+    }
+
+    #[test]
+    fn position_loop_settles_against_gravity() {
+        let config = DynamicsConfig {
+            gravity_load: 0.2,
+            resonance_frequency: Some(200.0),
+            ..rigid()
+        };
+        let mut plant = PlantModel::new(config, 1e6);
+        for _ in 0..2000 {
+            plant.track_position(5.0, 0.0, 0.0, DT);
+        }
+        assert!((plant.position() - 5.0).abs() < 1e-3, "x = {}", plant.position());
+        assert!((plant.torque() - 0.2).abs() < 0.01, "τ = {}", plant.torque());
+    }
+}
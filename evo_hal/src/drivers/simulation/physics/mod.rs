@@ -1,9 +1,11 @@
 //! Physics simulation module.
 //!
 //! This module provides physics-based simulation for axis motion,
-//! including kinematics, referencing, and error detection.
+//! including kinematics, an optional dynamic plant model, referencing,
+//! and error detection.
 
 mod axis;
+mod dynamics;
 mod referencing;
 
 pub use axis::AxisSimulator;
@@ -0,0 +1,565 @@
+//! I/O conditioning stage between the driver and the P2P segments.
+//!
+//! Applies the per-point `io.toml` parameters at runtime:
+//!
+//! | Parameter    | Type   | Effect                                                  |
+//! |--------------|--------|---------------------------------------------------------|
+//! | `debounce`   | DI     | Level must be stable for N ms before it is reported     |
+//! | `average`    | AI     | Moving average over the last N samples                  |
+//! | `init`       | DO/AO  | Output state at startup and of role-less outputs        |
+//! | `inverted`   | DO     | Logical OFF is physical high (init / pulse / E-Stop)    |
+//! | `pulse`      | DO/AO  | Output auto-OFF N ms after the last refresh             |
+//! | `keep_estop` | DO/AO  | Output is NOT reset while the `EStop` role is active    |
+//!
+//! Digital banks carry physical pin levels: the CU applies `inverted` to
+//! role outputs itself (FR-152), so HAL only uses it to derive the
+//! physical OFF level. Analog outputs are normalized (0.0–1.0); `init` is
+//! given in engineering units and normalized against `min`/`max`.
+//!
+//! The CU only drives role outputs. Outputs without a role keep their
+//! `init` level in every CU command image, including the zero image used
+//! while the CU is stale; direct RE / gRPC commands apply on top.
+//!
+//! A DO pulse is refreshed by a rising edge of the commanded logical state
+//! and an AO pulse by any change of the commanded value. Once expired, the
+//! output stays OFF until the next refresh.
+
+use evo_common::consts::{MAX_AI, MAX_AO, MAX_DI, MAX_DO};
+use evo_common::hal::types::{AnalogValue, HalCommands, HalStatus};
+use evo_common::io::config::{InitValue, IoConfig};
+use evo_common::io::role::{DiLogic, IoPointType};
+use std::time::Duration;
+
+/// Default DI debounce time [ms].
+const DEFAULT_DEBOUNCE_MS: u16 = 15;
+
+/// Default AI moving-average window [samples].
+const DEFAULT_AVERAGE: u16 = 5;
+
+/// Maximum AI moving-average window [samples].
+const MAX_AVERAGE: u16 = 1000;
+
+/// Role name of the emergency stop input.
+const ESTOP_ROLE: &str = "EStop";
+
+// ─── Per-Point State ────────────────────────────────────────────────
+
+/// Debounce filter of one digital input.
+#[derive(Debug, Clone)]
+struct DebouncedInput {
+    pin: usize,
+    /// Cycles the raw level must be stable before it is accepted.
+    cycles: u32,
+    /// Accepted (debounced) level.
+    state: bool,
+    /// Consecutive cycles the raw level has differed from `state`.
+    pending: u32,
+    /// First sample not yet seen — adopt it without delay.
+    primed: bool,
+}
+
+impl DebouncedInput {
+    fn update(&mut self, raw: bool) -> bool {
+        if !self.primed {
+            self.primed = true;
+            self.state = raw;
+        } else if raw == self.state {
+            self.pending = 0;
+        } else {
+            self.pending += 1;
+            if self.pending >= self.cycles {
+                self.state = raw;
+                self.pending = 0;
+            }
+        }
+        self.state
+    }
+}
+
+/// Moving-average filter of one analog input.
+#[derive(Debug, Clone)]
+struct AveragedInput {
+    pin: usize,
+    /// Ring buffer of the last samples.
+    window: Vec<AnalogValue>,
+    /// Next slot to overwrite.
+    next: usize,
+    /// Number of valid samples (≤ window length).
+    filled: usize,
+}
+
+impl AveragedInput {
+    fn update(&mut self, sample: AnalogValue) -> AnalogValue {
+        self.window[self.next] = sample;
+        self.next = (self.next + 1) % self.window.len();
+        self.filled = (self.filled + 1).min(self.window.len());
+
+        let samples = &self.window[..self.filled];
+        let n = self.filled as f64;
+        AnalogValue::new(
+            samples.iter().map(|v| v.normalized).sum::<f64>() / n,
+            samples.iter().map(|v| v.scaled).sum::<f64>() / n,
+        )
+    }
+}
+
+/// Conditioning state of one digital output.
+#[derive(Debug, Clone)]
+struct ConditionedOutput {
+    pin: usize,
+    inverted: bool,
+    keep_estop: bool,
+    /// Pulse length in cycles (0 = no watchdog).
+    pulse_cycles: u32,
+    /// Commanded logical state in the previous cycle.
+    previous: bool,
+    /// Cycles left until the pulse expires.
+    remaining: u32,
+}
+
+impl ConditionedOutput {
+    /// Physical pin level for the logical OFF state.
+    fn off(&self) -> bool {
+        self.inverted
+    }
+
+    fn update(&mut self, physical: bool, estop: bool) -> bool {
+        let logical = physical != self.inverted;
+        if logical && !self.previous {
+            self.remaining = self.pulse_cycles;
+        }
+        self.previous = logical;
+
+        if estop && !self.keep_estop {
+            return self.off();
+        }
+        if logical && self.pulse_cycles > 0 {
+            if self.remaining == 0 {
+                return self.off();
+            }
+            self.remaining -= 1;
+        }
+        physical
+    }
+}
+
+/// Conditioning state of one analog output.
+#[derive(Debug, Clone)]
+struct ConditionedAnalogOutput {
+    pin: usize,
+    keep_estop: bool,
+    /// Pulse length in cycles (0 = no watchdog).
+    pulse_cycles: u32,
+    /// Commanded value in the previous cycle.
+    previous: f64,
+    /// Cycles left until the pulse expires.
+    remaining: u32,
+}
+
+impl ConditionedAnalogOutput {
+    fn update(&mut self, value: f64, estop: bool) -> f64 {
+        if value != self.previous {
+            self.remaining = self.pulse_cycles;
+        }
+        self.previous = value;
+
+        if estop && !self.keep_estop {
+            return 0.0;
+        }
+        if value != 0.0 && self.pulse_cycles > 0 {
+            if self.remaining == 0 {
+                return 0.0;
+            }
+            self.remaining -= 1;
+        }
+        value
+    }
+}
+
+// ─── IoConditioner ──────────────────────────────────────────────────
+
+/// Runtime I/O conditioning built from `io.toml`.
+///
+/// Pins not listed in `io.toml` pass through unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct IoConditioner {
+    inputs: Vec<DebouncedInput>,
+    analog_inputs: Vec<AveragedInput>,
+    outputs: Vec<ConditionedOutput>,
+    analog_outputs: Vec<ConditionedAnalogOutput>,
+    /// Physical DO levels applied at startup.
+    initial_outputs: Vec<(usize, bool)>,
+    /// Normalized AO values applied at startup.
+    initial_analog_outputs: Vec<(usize, f64)>,
+    /// Physical DO levels of outputs without a role (not driven by the CU).
+    default_outputs: Vec<(usize, bool)>,
+    /// Normalized AO values of outputs without a role.
+    default_analog_outputs: Vec<(usize, f64)>,
+    /// `EStop` role input: pin and contact logic.
+    estop_input: Option<(usize, DiLogic)>,
+    /// `EStop` active in the last conditioned input image.
+    estop_active: bool,
+}
+
+impl IoConditioner {
+    /// Build the conditioning stage for the given RT cycle time.
+    ///
+    /// Millisecond parameters are rounded up to whole cycles. Points with
+    /// out-of-range pins are ignored.
+    pub fn from_config(config: &IoConfig, cycle_time: Duration) -> Self {
+        let cycle_us = cycle_time.as_micros().max(1);
+        let to_cycles = |ms: u32| (u128::from(ms) * 1000).div_ceil(cycle_us) as u32;
+
+        let mut conditioner = Self::default();
+        for (_, _, point) in config.all_points() {
+            let pin = point.pin as usize;
+            match point.io_type {
+                IoPointType::Di if pin < MAX_DI => {
+                    if point.role.as_deref() == Some(ESTOP_ROLE) {
+                        conditioner.estop_input = Some((pin, point.logic.unwrap_or(DiLogic::NO)));
+                    }
+                    let debounce = point.debounce.unwrap_or(DEFAULT_DEBOUNCE_MS);
+                    if debounce > 0 {
+                        conditioner.inputs.push(DebouncedInput {
+                            pin,
+                            cycles: to_cycles(u32::from(debounce)),
+                            state: false,
+                            pending: 0,
+                            primed: false,
+                        });
+                    }
+                }
+                IoPointType::Ai if pin < MAX_AI => {
+                    let average = point
+                        .average
+                        .unwrap_or(DEFAULT_AVERAGE)
+                        .clamp(1, MAX_AVERAGE);
+                    if average > 1 {
+                        conditioner.analog_inputs.push(AveragedInput {
+                            pin,
+                            window: vec![AnalogValue::default(); average as usize],
+                            next: 0,
+                            filled: 0,
+                        });
+                    }
+                }
+                IoPointType::Do if pin < MAX_DO => {
+                    let inverted = point.inverted.unwrap_or(false);
+                    let init = point.init.is_some_and(InitValue::as_bool);
+                    conditioner.initial_outputs.push((pin, init != inverted));
+                    if point.role.is_none() {
+                        conditioner.default_outputs.push((pin, init != inverted));
+                    }
+                    conditioner.outputs.push(ConditionedOutput {
+                        pin,
+                        inverted,
+                        keep_estop: point.keep_estop.unwrap_or(false),
+                        pulse_cycles: to_cycles(point.pulse.unwrap_or(0)),
+                        previous: false,
+                        remaining: 0,
+                    });
+                }
+                IoPointType::Ao if pin < MAX_AO => {
+                    if let Some(init) = point.init {
+                        let min = point.min.unwrap_or(0.0);
+                        let range = point.max.unwrap_or(0.0) - min;
+                        let normalized = if range.abs() < f64::EPSILON {
+                            0.0
+                        } else {
+                            (init.as_f64() - min) / range
+                        };
+                        conditioner.initial_analog_outputs.push((pin, normalized));
+                        if point.role.is_none() {
+                            conditioner.default_analog_outputs.push((pin, normalized));
+                        }
+                    }
+                    conditioner.analog_outputs.push(ConditionedAnalogOutput {
+                        pin,
+                        keep_estop: point.keep_estop.unwrap_or(false),
+                        pulse_cycles: to_cycles(point.pulse.unwrap_or(0)),
+                        previous: 0.0,
+                        remaining: 0,
+                    });
+                }
+                _ => {}
+            }
+        }
+        conditioner
+    }
+
+    /// Apply the `init` output states to a command image.
+    ///
+    /// Called once at startup, before the first CU command is read.
+    pub fn apply_initial_outputs(&self, commands: &mut HalCommands) {
+        for &(pin, level) in &self.initial_outputs {
+            commands.digital_outputs[pin] = level;
+        }
+        for &(pin, value) in &self.initial_analog_outputs {
+            commands.analog_outputs[pin] = value;
+        }
+    }
+
+    /// Apply the `init` states of outputs without a role to a CU command
+    /// image.
+    ///
+    /// Called for every image read from the CU and for the zero image used
+    /// while the CU is stale; role outputs keep the CU's levels.
+    pub fn apply_default_outputs(&self, commands: &mut HalCommands) {
+        for &(pin, level) in &self.default_outputs {
+            commands.digital_outputs[pin] = level;
+        }
+        for &(pin, value) in &self.default_analog_outputs {
+            commands.analog_outputs[pin] = value;
+        }
+    }
+
+    /// Debounce digital inputs and average analog inputs in place, then
+    /// latch the `EStop` state for the next output pass.
+    pub fn condition_inputs(&mut self, status: &mut HalStatus) {
+        for input in &mut self.inputs {
+            status.digital_inputs[input.pin] = input.update(status.digital_inputs[input.pin]);
+        }
+        for input in &mut self.analog_inputs {
+            status.analog_inputs[input.pin] = input.update(status.analog_inputs[input.pin]);
+        }
+        self.estop_active = self.estop_input.is_some_and(|(pin, logic)| {
+            let raw = status.digital_inputs[pin];
+            match logic {
+                DiLogic::NO => raw,
+                DiLogic::NC => !raw,
+            }
+        });
+    }
+
+    /// Apply pulse watchdogs and the E-Stop reset to a command image.
+    ///
+    /// `commands` is the image sent to the driver this cycle; the caller
+    /// keeps the unconditioned commands so a refresh is seen as such.
+    pub fn condition_outputs(&mut self, commands: &mut HalCommands) {
+        let estop = self.estop_active;
+        for output in &mut self.outputs {
+            let level = commands.digital_outputs[output.pin];
+            commands.digital_outputs[output.pin] = output.update(level, estop);
+        }
+        for output in &mut self.analog_outputs {
+            let value = commands.analog_outputs[output.pin];
+            commands.analog_outputs[output.pin] = output.update(value, estop);
+        }
+    }
+
+    /// `EStop` role input active in the last conditioned input image.
+    pub fn estop_active(&self) -> bool {
+        self.estop_active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CYCLE: Duration = Duration::from_millis(1);
+
+    fn conditioner(io: &str) -> IoConditioner {
+        IoConditioner::from_config(&IoConfig::from_toml(io).unwrap(), CYCLE)
+    }
+
+    #[test]
+    fn debounce_rejects_short_glitches() {
+        let mut c = conditioner(
+            r#"
+[In]
+io = [{ type = "di", pin = 3, debounce = 5 }]
+"#,
+        );
+        let mut status = HalStatus::default();
+        c.condition_inputs(&mut status);
+        assert!(!status.digital_inputs[3]);
+
+        // 4 ms glitch is filtered out.
+        for _ in 0..4 {
+            status.digital_inputs[3] = true;
+            c.condition_inputs(&mut status);
+            assert!(!status.digital_inputs[3]);
+        }
+        status.digital_inputs[3] = false;
+        c.condition_inputs(&mut status);
+        assert!(!status.digital_inputs[3]);
+
+        // Level stable for 5 ms is accepted.
+        for cycle in 1..=5 {
+            status.digital_inputs[3] = true;
+            c.condition_inputs(&mut status);
+            assert_eq!(status.digital_inputs[3], cycle == 5, "cycle {cycle}");
+        }
+    }
+
+    #[test]
+    fn unlisted_inputs_pass_through() {
+        let mut c = conditioner(
+            r#"
+[In]
+io = [{ type = "di", pin = 0 }, { type = "ai", pin = 0, max = 10.0 }]
+"#,
+        );
+        let mut status = HalStatus::default();
+        c.condition_inputs(&mut status);
+        status.digital_inputs[1] = true;
+        status.analog_inputs[1] = AnalogValue::new(0.5, 5.0);
+        c.condition_inputs(&mut status);
+        assert!(status.digital_inputs[1]);
+        assert_eq!(status.analog_inputs[1].scaled, 5.0);
+    }
+
+    #[test]
+    fn moving_average_over_window() {
+        let mut c = conditioner(
+            r#"
+[In]
+io = [{ type = "ai", pin = 2, max = 10.0, average = 4 }]
+"#,
+        );
+        let mut status = HalStatus::default();
+        let mut out = Vec::new();
+        for sample in [4.0, 8.0, 0.0, 4.0, 8.0] {
+            status.analog_inputs[2] = AnalogValue::new(sample / 10.0, sample);
+            c.condition_inputs(&mut status);
+            out.push(status.analog_inputs[2].scaled);
+        }
+        // Partial window at startup, then the last 4 samples.
+        assert_eq!(out, vec![4.0, 6.0, 4.0, 4.0, 5.0]);
+        assert!((status.analog_inputs[2].normalized - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn initial_outputs_respect_inversion_and_scaling() {
+        let c = conditioner(
+            r#"
+[Out]
+io = [
+    { type = "do", pin = 1, init = true },
+    { type = "do", pin = 2, inverted = true },
+    { type = "do", pin = 3, init = true, inverted = true },
+    { type = "ao", pin = 0, min = 2.0, max = 12.0, init = 7.0 },
+]
+"#,
+        );
+        let mut commands = HalCommands::default();
+        c.apply_initial_outputs(&mut commands);
+        assert!(commands.digital_outputs[1]);
+        assert!(commands.digital_outputs[2], "inverted OFF is physical high");
+        assert!(!commands.digital_outputs[3]);
+        assert_eq!(commands.analog_outputs[0], 0.5);
+    }
+
+    #[test]
+    fn default_outputs_skip_role_outputs() {
+        let c = conditioner(
+            r#"
+[Out]
+io = [
+    { type = "do", pin = 1, init = true },
+    { type = "do", pin = 2, inverted = true },
+    { type = "do", pin = 4, role = "BrakeOut1", init = true },
+    { type = "ao", pin = 0, min = 2.0, max = 12.0, init = 7.0 },
+]
+"#,
+        );
+        // CU image: brake released, everything else zero.
+        let mut commands = HalCommands::default();
+        commands.digital_outputs[4] = false;
+        commands.digital_outputs[7] = true;
+        c.apply_default_outputs(&mut commands);
+        assert!(commands.digital_outputs[1]);
+        assert!(commands.digital_outputs[2], "inverted OFF is physical high");
+        assert!(!commands.digital_outputs[4], "role output follows the CU");
+        assert!(commands.digital_outputs[7], "unlisted pin passes through");
+        assert_eq!(commands.analog_outputs[0], 0.5);
+    }
+
+    #[test]
+    fn pulse_drops_output_until_refreshed() {
+        let mut c = conditioner(
+            r#"
+[Out]
+io = [{ type = "do", pin = 5, pulse = 3 }, { type = "ao", pin = 1, max = 10.0, pulse = 2 }]
+"#,
+        );
+        let mut on = Vec::new();
+        let mut ao = Vec::new();
+        for cycle in 0..6 {
+            let mut commands = HalCommands::default();
+            commands.digital_outputs[5] = cycle != 4;
+            commands.analog_outputs[1] = if cycle < 4 { 0.3 } else { 0.6 };
+            c.condition_outputs(&mut commands);
+            on.push(commands.digital_outputs[5]);
+            ao.push(commands.analog_outputs[1]);
+        }
+        // ON for 3 ms, dropped, re-armed by the OFF → ON edge.
+        assert_eq!(on, vec![true, true, true, false, false, true]);
+        // Reset to 0 after 2 ms, refreshed by the new value.
+        assert_eq!(ao, vec![0.3, 0.3, 0.0, 0.0, 0.6, 0.6]);
+    }
+
+    #[test]
+    fn estop_resets_outputs_except_keep_estop() {
+        let mut c = conditioner(
+            r#"
+[Safety]
+io = [{ type = "di", role = "EStop", pin = 0, logic = "NC", debounce = 0 }]
+
+[Out]
+io = [
+    { type = "do", pin = 10 },
+    { type = "do", pin = 11, keep_estop = true },
+    { type = "do", pin = 12, inverted = true },
+    { type = "ao", pin = 0, max = 10.0 },
+]
+"#,
+        );
+        let command = || {
+            let mut commands = HalCommands::default();
+            commands.digital_outputs[10] = true;
+            commands.digital_outputs[11] = true;
+            commands.digital_outputs[12] = false;
+            commands.analog_outputs[0] = 0.8;
+            commands
+        };
+
+        // NC contact closed — healthy.
+        let mut status = HalStatus::default();
+        status.digital_inputs[0] = true;
+        c.condition_inputs(&mut status);
+        assert!(!c.estop_active());
+        let mut commands = command();
+        c.condition_outputs(&mut commands);
+        assert!(commands.digital_outputs[10]);
+
+        // Contact opens — E-Stop.
+        status.digital_inputs[0] = false;
+        c.condition_inputs(&mut status);
+        assert!(c.estop_active());
+        let mut commands = command();
+        c.condition_outputs(&mut commands);
+        assert!(!commands.digital_outputs[10]);
+        assert!(commands.digital_outputs[11]);
+        assert!(
+            commands.digital_outputs[12],
+            "inverted OFF is physical high"
+        );
+        assert_eq!(commands.analog_outputs[0], 0.0);
+    }
+
+    #[test]
+    fn debounce_rounds_up_to_whole_cycles() {
+        let config = IoConfig::from_toml(
+            r#"
+[In]
+io = [{ type = "di", pin = 0 }]
+"#,
+        )
+        .unwrap();
+        let c = IoConditioner::from_config(&config, Duration::from_micros(4000));
+        // Default 15 ms at 4 ms/cycle → 4 cycles.
+        assert_eq!(c.inputs[0].cycles, 4);
+    }
+}
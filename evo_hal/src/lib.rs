@@ -10,7 +10,9 @@
 //! - [`core`] - HalCore struct, RT loop management
 //! - [`driver_registry`] - Driver factory registration
 //! - [`drivers`] - HAL driver implementations
+//! - [`io_conditioning`] - `io.toml` debounce, averaging, pulse and E-Stop handling
 //! - [`module_status`] - Module status publishing (stub)
+//! - [`rpc`] - gRPC → HAL direct commands with acknowledgements
 //!
 //! # Architecture
 //!
@@ -36,7 +38,9 @@
 pub mod core;
 pub mod driver_registry;
 pub mod drivers;
+pub mod io_conditioning;
 pub mod module_status;
+pub mod rpc;
 
 // Re-export key types for convenience
 pub use crate::core::HalCore;
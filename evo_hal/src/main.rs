@@ -15,6 +15,9 @@
 //! # Verbose logging
 //! evo_hal --config-dir config/ -s -v
 //!
+//! # Second EVO instance on the same host (segments `evo-b_*`)
+//! evo_hal --config-dir config/ -s --instance b
+//!
 //! # Legacy mode (single machine.toml)
 //! evo_hal --config config/machine.toml -s
 //! ```
@@ -25,6 +28,7 @@ use clap::Parser;
 use evo_common::config::load_config_dir;
 use evo_common::io::config::IoConfig;
 use evo_common::io::registry::IoRegistry;
+use evo_common::shm::p2p::ShmNamespace;
 use evo_hal::core::HalCore;
 use std::path::PathBuf;
 use std::sync::atomic::Ordering;
@@ -53,6 +57,11 @@ struct Args {
     #[arg(short = 's', long)]
     simulate: bool,
 
+    /// EVO instance id selecting the SHM namespace (overrides `instance`
+    /// in config.toml; default: `evo_*` segments)
+    #[arg(long, value_name = "ID")]
+    instance: Option<String>,
+
     /// Load specific driver (can be specified multiple times)
     #[arg(short, long = "driver", action = clap::ArgAction::Append)]
     drivers: Vec<String>,
@@ -97,6 +106,10 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     if let Some(ref config_dir) = args.config_dir {
         info!("Loading unified config from {:?}", config_dir);
         let full_config = load_config_dir(config_dir)?;
+        let shm_ns = match args.instance.as_deref() {
+            Some(id) => ShmNamespace::new(id)?,
+            None => full_config.system.shm_namespace()?,
+        };
         info!(
             "Loaded {} axes from {}",
             full_config.axes.len(),
@@ -104,10 +117,16 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         );
 
         // Load I/O config (io.toml) and build IoRegistry.
-        let io_registry = load_io_registry(config_dir);
+        let io_config = load_io_config(config_dir);
+        let io_registry = io_config.as_ref().and_then(build_io_registry);
 
         // Create HalCore from unified config.
         let mut hal_core = HalCore::from_full_config(full_config, io_registry)?;
+        info!("SHM namespace: {}", shm_ns);
+        hal_core.set_shm_namespace(shm_ns);
+        if let Some(ref io_config) = io_config {
+            hal_core.set_io_conditioning(io_config);
+        }
 
         // Setup signal handler.
         let running = hal_core.running_flag();
@@ -134,6 +153,7 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
 
         let mut hal_core = HalCore::new(config)?;
         hal_core.load_axis_configs(config_dir_legacy)?;
+        hal_core.set_shm_namespace(ShmNamespace::from_instance(args.instance.as_deref())?);
 
         let running = hal_core.running_flag();
         ctrlc::set_handler(move || {
@@ -152,25 +172,12 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// Load io.toml and build IoRegistry. Returns None if io.toml is missing.
-fn load_io_registry(config_dir: &std::path::Path) -> Option<IoRegistry> {
+/// Load io.toml. Returns None if io.toml is missing or invalid.
+fn load_io_config(config_dir: &std::path::Path) -> Option<IoConfig> {
     let io_path = config_dir.join("io.toml");
     match std::fs::read_to_string(&io_path) {
         Ok(content) => match IoConfig::from_toml(&content) {
-            Ok(io_config) => match IoRegistry::from_config(&io_config) {
-                Ok(registry) => {
-                    info!(
-                        "IoRegistry built: {} DI, {} DO, {} AI, {} AO",
-                        registry.di_count, registry.do_count,
-                        registry.ai_count, registry.ao_count,
-                    );
-                    Some(registry)
-                }
-                Err(e) => {
-                    warn!("IoRegistry validation failed: {e}. Continuing without I/O roles.");
-                    None
-                }
-            },
+            Ok(io_config) => Some(io_config),
             Err(e) => {
                 warn!("Failed to parse io.toml: {e}. Continuing without I/O roles.");
                 None
@@ -183,6 +190,24 @@ fn load_io_registry(config_dir: &std::path::Path) -> Option<IoRegistry> {
     }
 }
 
+/// Build IoRegistry from io.toml. Returns None if validation fails.
+fn build_io_registry(io_config: &IoConfig) -> Option<IoRegistry> {
+    match IoRegistry::from_config(io_config) {
+        Ok(registry) => {
+            info!(
+                "IoRegistry built: {} DI, {} DO, {} AI, {} AO",
+                registry.di_count, registry.do_count,
+                registry.ai_count, registry.ao_count,
+            );
+            Some(registry)
+        }
+        Err(e) => {
+            warn!("IoRegistry validation failed: {e}. Continuing without I/O roles.");
+            None
+        }
+    }
+}
+
 /// Setup tracing subscriber based on CLI arguments.
 fn setup_tracing(args: &Args) {
     let level = if args.verbose {
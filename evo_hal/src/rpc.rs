@@ -0,0 +1,413 @@
+//! gRPC → HAL direct command channel (FR-030b, FR-036).
+//!
+//! Reads `RpcToHalSegment` commands (set DO, set AO, driver command) and
+//! answers each `request_id` once in `HalToRpcSegment` with a
+//! [`HalRpcResult`] and error text.
+//!
+//! Like RE I/O commands, set DO / set AO are re-applied every cycle while
+//! the request stays in the segment, so they hold against the CU output
+//! image. Driver commands run once per `request_id`. Pins assigned to an
+//! `IoRole` are rejected with `ERR_IO_ROLE_OWNED`.
+//!
+//! A request found in the segment when HAL attaches at startup was issued
+//! to a previous HAL instance; it is neither applied nor answered.
+
+use evo_common::consts::{MAX_AO, MAX_DO};
+use evo_common::hal::driver::HalDriver;
+use evo_common::hal::types::HalCommands;
+use evo_common::io::registry::IoRegistry;
+use evo_common::io::role::IoPointType;
+use evo_common::shm::p2p::TypedP2pReader;
+use evo_common::shm::request::RequestEdge;
+use evo_common::shm::segments::{
+    HalRpcResult, HalToRpcSegment, RpcHalCommandType, RpcToHalSegment,
+};
+use tracing::debug;
+
+/// Processes direct gRPC commands and holds the last response.
+#[derive(Default)]
+pub struct RpcCommandHandler {
+    /// Answered requests (exactly once per `request_id`).
+    edge: RequestEdge,
+    /// Request left in the segment by a previous HAL instance (0 = none).
+    seed: u64,
+    /// Response committed to `evo_hal_rpc` every cycle.
+    response: HalToRpcSegment,
+}
+
+impl RpcCommandHandler {
+    /// Create a handler with no answered request.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a handler for a freshly attached command segment, ignoring
+    /// the request it already holds.
+    pub fn attached(reader: &mut TypedP2pReader<RpcToHalSegment>) -> Self {
+        let edge = RequestEdge::attached(reader);
+        Self {
+            edge,
+            seed: edge.last(),
+            ..Self::default()
+        }
+    }
+
+    /// Response for the last answered request.
+    pub fn response(&self) -> &HalToRpcSegment {
+        &self.response
+    }
+
+    /// Apply one read of the command segment.
+    ///
+    /// `request_id` 0 means no command. A new `request_id` is answered
+    /// in [`response`](Self::response).
+    pub fn process(
+        &mut self,
+        seg: &RpcToHalSegment,
+        registry: Option<&IoRegistry>,
+        commands: &mut HalCommands,
+        driver: &mut dyn HalDriver,
+    ) {
+        if seg.request_id == 0 || seg.request_id == self.seed {
+            return;
+        }
+        self.seed = 0;
+        let is_new = self.edge.accept(seg.request_id);
+        let pin = seg.target;
+        let owned = |io_type| registry.is_some_and(|reg| reg.pin_is_role_owned(io_type, pin));
+
+        let result = match RpcHalCommandType::from_u8(seg.command_type) {
+            Some(RpcHalCommandType::SetDo) => {
+                if pin as usize >= MAX_DO {
+                    HalRpcResult::PinOutOfRange
+                } else if owned(IoPointType::Do) {
+                    HalRpcResult::IoRoleOwned
+                } else {
+                    commands.digital_outputs[pin as usize] = seg.value != 0.0;
+                    HalRpcResult::Ok
+                }
+            }
+            Some(RpcHalCommandType::SetAo) => {
+                if pin as usize >= MAX_AO {
+                    HalRpcResult::PinOutOfRange
+                } else if owned(IoPointType::Ao) {
+                    HalRpcResult::IoRoleOwned
+                } else {
+                    commands.analog_outputs[pin as usize] = seg.value;
+                    HalRpcResult::Ok
+                }
+            }
+            Some(RpcHalCommandType::DriverCommand) if is_new => {
+                let mut payload = [0u8; 10];
+                payload[..2].copy_from_slice(&pin.to_le_bytes());
+                payload[2..].copy_from_slice(&seg.value.to_le_bytes());
+                match driver.handle_custom_command(&payload) {
+                    Some(_) => HalRpcResult::Ok,
+                    None => HalRpcResult::DriverRejected,
+                }
+            }
+            // Driver command already executed for this request.
+            Some(RpcHalCommandType::DriverCommand) => return,
+            Some(RpcHalCommandType::Nop) | None => HalRpcResult::InvalidCommand,
+        };
+
+        // Held requests are re-applied above every cycle; the response
+        // (and its error text) is built once, when the request is new.
+        if !is_new {
+            return;
+        }
+        if result == HalRpcResult::Ok {
+            self.response = HalToRpcSegment::response(seg.request_id, result, "");
+            return;
+        }
+        let message = rejection_message(seg, result, driver);
+        debug!(
+            "RPC HAL command rejected: {} (req_id={})",
+            message, seg.request_id
+        );
+        let text = format!("{}: {}", result.code_name(), message);
+        self.response = HalToRpcSegment::response(seg.request_id, result, &text);
+    }
+}
+
+/// Error text for a rejected request.
+fn rejection_message(
+    seg: &RpcToHalSegment,
+    result: HalRpcResult,
+    driver: &dyn HalDriver,
+) -> String {
+    let pin = seg.target;
+    let (kind, count) = if seg.command_type == RpcHalCommandType::SetAo as u8 {
+        ("AO", MAX_AO)
+    } else {
+        ("DO", MAX_DO)
+    };
+    match result {
+        HalRpcResult::PinOutOfRange => format!("{kind} pin {pin} out of range (max {})", count - 1),
+        HalRpcResult::IoRoleOwned => format!("{kind} pin {pin} is role-owned"),
+        HalRpcResult::DriverRejected => {
+            format!("driver {} rejected command for target {pin}", driver.name())
+        }
+        HalRpcResult::InvalidCommand => format!("invalid command type {}", seg.command_type),
+        HalRpcResult::Ok => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evo_common::hal::config::MachineConfig;
+    use evo_common::hal::driver::HalError;
+    use evo_common::hal::types::HalStatus;
+    use evo_common::io::config::IoConfig;
+    use std::time::Duration;
+
+    /// Driver accepting custom commands for target 1 only.
+    #[derive(Default)]
+    struct TestDriver {
+        received: Vec<Vec<u8>>,
+    }
+
+    impl HalDriver for TestDriver {
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        fn version(&self) -> &'static str {
+            "0.1.0"
+        }
+
+        fn init(&mut self, _config: &MachineConfig) -> Result<(), HalError> {
+            Ok(())
+        }
+
+        fn cycle(&mut self, _commands: &HalCommands, _dt: Duration) -> HalStatus {
+            HalStatus::default()
+        }
+
+        fn shutdown(&mut self) -> Result<(), HalError> {
+            Ok(())
+        }
+
+        fn handle_custom_command(&mut self, cmd: &[u8]) -> Option<Vec<u8>> {
+            self.received.push(cmd.to_vec());
+            (cmd[..2] == [1, 0]).then(Vec::new)
+        }
+    }
+
+    fn registry() -> IoRegistry {
+        let config = IoConfig::from_toml(
+            r#"
+[Outputs]
+io = [
+    { type = "do", role = "BrakeOut1", pin = 4 },
+    { type = "ao", role = "SpindleSpeed", pin = 2, max = 10.0 },
+]
+"#,
+        )
+        .unwrap();
+        IoRegistry::from_config(&config).unwrap()
+    }
+
+    fn command(
+        request_id: u64,
+        command_type: RpcHalCommandType,
+        target: u16,
+        value: f64,
+    ) -> RpcToHalSegment {
+        RpcToHalSegment {
+            request_id,
+            command_type: command_type as u8,
+            target,
+            value,
+            ..RpcToHalSegment::default()
+        }
+    }
+
+    fn result(handler: &RpcCommandHandler) -> HalRpcResult {
+        HalRpcResult::from_u32(handler.response().result_code).unwrap()
+    }
+
+    #[test]
+    fn set_do_and_ao_on_free_pins() {
+        let mut handler = RpcCommandHandler::new();
+        let mut driver = TestDriver::default();
+        let mut commands = HalCommands::default();
+        let reg = registry();
+
+        handler.process(
+            &command(1, RpcHalCommandType::SetDo, 5, 1.0),
+            Some(&reg),
+            &mut commands,
+            &mut driver,
+        );
+        assert!(commands.digital_outputs[5]);
+        assert_eq!(handler.response().request_id, 1);
+        assert_eq!(result(&handler), HalRpcResult::Ok);
+
+        handler.process(
+            &command(2, RpcHalCommandType::SetAo, 3, 0.25),
+            Some(&reg),
+            &mut commands,
+            &mut driver,
+        );
+        assert_eq!(commands.analog_outputs[3], 0.25);
+        assert_eq!(handler.response().request_id, 2);
+
+        // Held while the request stays in the segment.
+        let mut fresh = HalCommands::default();
+        handler.process(
+            &command(2, RpcHalCommandType::SetAo, 3, 0.25),
+            Some(&reg),
+            &mut fresh,
+            &mut driver,
+        );
+        assert_eq!(fresh.analog_outputs[3], 0.25);
+    }
+
+    #[test]
+    fn role_owned_pins_rejected() {
+        let mut handler = RpcCommandHandler::new();
+        let mut driver = TestDriver::default();
+        let mut commands = HalCommands::default();
+        let reg = registry();
+
+        handler.process(
+            &command(1, RpcHalCommandType::SetDo, 4, 1.0),
+            Some(&reg),
+            &mut commands,
+            &mut driver,
+        );
+        assert!(!commands.digital_outputs[4]);
+        assert_eq!(result(&handler), HalRpcResult::IoRoleOwned);
+        assert_eq!(
+            handler.response().error_text(),
+            "ERR_IO_ROLE_OWNED: DO pin 4 is role-owned"
+        );
+
+        handler.process(
+            &command(2, RpcHalCommandType::SetAo, 2, 5.0),
+            Some(&reg),
+            &mut commands,
+            &mut driver,
+        );
+        assert_eq!(commands.analog_outputs[2], 0.0);
+        assert_eq!(result(&handler), HalRpcResult::IoRoleOwned);
+
+        // Without a registry no pin is role-owned.
+        handler.process(
+            &command(3, RpcHalCommandType::SetDo, 4, 1.0),
+            None,
+            &mut commands,
+            &mut driver,
+        );
+        assert!(commands.digital_outputs[4]);
+    }
+
+    #[test]
+    fn out_of_range_and_invalid_commands() {
+        let mut handler = RpcCommandHandler::new();
+        let mut driver = TestDriver::default();
+        let mut commands = HalCommands::default();
+
+        handler.process(
+            &command(1, RpcHalCommandType::SetDo, MAX_DO as u16, 1.0),
+            None,
+            &mut commands,
+            &mut driver,
+        );
+        assert_eq!(result(&handler), HalRpcResult::PinOutOfRange);
+        assert!(
+            handler
+                .response()
+                .error_text()
+                .starts_with("ERR_PIN_OUT_OF_RANGE")
+        );
+
+        let mut seg = command(2, RpcHalCommandType::Nop, 0, 0.0);
+        handler.process(&seg, None, &mut commands, &mut driver);
+        assert_eq!(result(&handler), HalRpcResult::InvalidCommand);
+
+        seg.request_id = 3;
+        seg.command_type = 99;
+        handler.process(&seg, None, &mut commands, &mut driver);
+        assert_eq!(
+            handler.response().error_text(),
+            "ERR_INVALID_COMMAND: invalid command type 99"
+        );
+    }
+
+    #[test]
+    fn driver_command_runs_once_per_request() {
+        let mut handler = RpcCommandHandler::new();
+        let mut driver = TestDriver::default();
+        let mut commands = HalCommands::default();
+
+        let seg = command(1, RpcHalCommandType::DriverCommand, 1, 2.5);
+        for _ in 0..3 {
+            handler.process(&seg, None, &mut commands, &mut driver);
+        }
+        assert_eq!(driver.received.len(), 1);
+        assert_eq!(driver.received[0][..2], [1, 0]);
+        assert_eq!(driver.received[0][2..], 2.5f64.to_le_bytes());
+        assert_eq!(result(&handler), HalRpcResult::Ok);
+
+        handler.process(
+            &command(2, RpcHalCommandType::DriverCommand, 7, 0.0),
+            None,
+            &mut commands,
+            &mut driver,
+        );
+        assert_eq!(result(&handler), HalRpcResult::DriverRejected);
+        assert_eq!(
+            handler.response().error_text(),
+            "ERR_DRIVER_REJECTED: driver test rejected command for target 7"
+        );
+    }
+
+    #[test]
+    fn zero_request_id_ignored() {
+        let mut handler = RpcCommandHandler::new();
+        let mut driver = TestDriver::default();
+        let mut commands = HalCommands::default();
+        handler.process(
+            &command(0, RpcHalCommandType::SetDo, 1, 1.0),
+            None,
+            &mut commands,
+            &mut driver,
+        );
+        assert!(!commands.digital_outputs[1]);
+        assert_eq!(handler.response().request_id, 0);
+    }
+
+    #[test]
+    fn restarted_handler_skips_surviving_request() {
+        use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pWriter};
+
+        let name = format!("test_rpc_hal_restart_{}", std::process::id());
+        let mut writer =
+            TypedP2pWriter::<RpcToHalSegment>::create(&name, ModuleAbbrev::Rpc, ModuleAbbrev::Hal)
+                .unwrap();
+        writer
+            .commit(&command(3, RpcHalCommandType::DriverCommand, 1, 1.0))
+            .unwrap();
+
+        // HAL restarts while the gRPC segment still holds request 3.
+        let mut reader = TypedP2pReader::<RpcToHalSegment>::attach(&name, 1000).unwrap();
+        let mut handler = RpcCommandHandler::attached(&mut reader);
+        let mut driver = TestDriver::default();
+        let mut commands = HalCommands::default();
+        let seg = *reader.read().unwrap();
+        handler.process(&seg, None, &mut commands, &mut driver);
+        assert!(driver.received.is_empty(), "old request re-executed");
+        assert_eq!(handler.response().request_id, 0);
+
+        writer
+            .commit(&command(4, RpcHalCommandType::SetDo, 1, 1.0))
+            .unwrap();
+        let seg = *reader.read().unwrap();
+        handler.process(&seg, None, &mut commands, &mut driver);
+        assert!(commands.digital_outputs[1]);
+        assert_eq!(handler.response().request_id, 4);
+    }
+}
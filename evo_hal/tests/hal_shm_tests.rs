@@ -11,8 +11,11 @@ use evo_common::shm::conversions::{
     segment_to_hal_status,
 };
 use evo_common::shm::io_helpers::{get_di, pack_bools, set_do, unpack_bools, BANK_WORDS};
-use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
+use evo_common::io::config::IoConfig;
+use evo_common::shm::p2p::{ModuleAbbrev, ShmNamespace, TypedP2pReader, TypedP2pWriter};
 use evo_common::shm::segments::*;
+use evo_hal::HalCore;
+use std::time::Duration;
 
 /// Helper: cleanup SHM segment if it exists.
 fn cleanup_segment(name: &str) {
@@ -270,3 +273,60 @@ fn test_hal_mqt_segment_has_both_input_and_output() {
     drop(writer);
     cleanup_segment(seg_name);
 }
+
+/// Test: `init` levels of outputs without a role survive CU command images
+/// and the stale-CU fallback; role outputs follow the CU.
+#[test]
+fn test_init_outputs_persist_for_pins_without_role() {
+    let ns = ShmNamespace::new(&format!("halinit{}", std::process::id())).unwrap();
+    let io = IoConfig::from_toml(
+        r#"
+[Out]
+io = [
+    { type = "do", pin = 1, init = true },
+    { type = "do", pin = 2, inverted = true },
+    { type = "do", pin = 4, role = "BrakeOut1" },
+    { type = "ao", pin = 0, min = 2.0, max = 12.0, init = 7.0 },
+]
+"#,
+    )
+    .unwrap();
+
+    // CU exists before HAL so the reader attaches at init.
+    let mut cu = TypedP2pWriter::<CuToHalSegment>::create_in(
+        &ns,
+        SEG_CU_HAL,
+        ModuleAbbrev::Cu,
+        ModuleAbbrev::Hal,
+    )
+    .expect("create cu_hal");
+    let mut commands = CuToHalSegment::default();
+    set_do(&mut commands.do_bank, 4, true);
+    cu.commit(&commands).unwrap();
+
+    let mut hal = HalCore::new(toml::from_str("").unwrap()).expect("hal");
+    hal.set_shm_namespace(ns.clone());
+    hal.set_io_conditioning(&io);
+    hal.init("simulation").expect("hal init");
+    let mut mqt = TypedP2pReader::<HalToMqtSegment>::attach_in(&ns, SEG_HAL_MQT, 10_000)
+        .expect("attach hal_mqt");
+    let mut outputs = |hal: &mut HalCore| {
+        hal.step(Duration::from_millis(1)).expect("step");
+        let seg = mqt.read().expect("read hal_mqt");
+        let bit = |pin: usize| get_di(&seg.do_bank, pin);
+        (bit(1), bit(2), bit(4), seg.ao_values[0])
+    };
+
+    // CU image zeroes everything but the brake output.
+    for _ in 0..3 {
+        cu.commit(&commands).unwrap();
+        assert_eq!(outputs(&mut hal), (true, true, true, 0.5));
+    }
+
+    // CU stops committing: zero image, role-less pins keep `init`.
+    let mut stale = (true, true, true, 0.5);
+    for _ in 0..200 {
+        stale = outputs(&mut hal);
+    }
+    assert_eq!(stale, (true, true, false, 0.5));
+}
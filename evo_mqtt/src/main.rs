@@ -12,23 +12,48 @@
 //! | `evo_hal_mqt` | HalToMqtSegment | HAL    |
 //! | `evo_re_mqt`  | ReToMqtSegment  | RE     |
 
-use evo_common::shm::p2p::TypedP2pReader;
+use clap::Parser;
+use evo_common::shm::layout::ShmLayout;
+use evo_common::shm::p2p::{ShmNamespace, TypedP2pReader};
 use evo_common::shm::segments::{
     CuToMqtSegment, HalToMqtSegment, ReToMqtSegment,
     SEG_CU_MQT, SEG_HAL_MQT, SEG_RE_MQT,
 };
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// EVO MQTT Bridge
+#[derive(Parser, Debug)]
+#[command(name = "evo_mqtt")]
+#[command(author = "RTS007")]
+#[command(version)]
+#[command(about = "MQTT bridge publishing HAL/CU/RE status snapshots")]
+struct Args {
+    /// EVO instance id selecting the SHM namespace (default: `evo_*` segments).
+    #[arg(long, value_name = "ID")]
+    instance: Option<String>,
+}
 
 fn main() {
+    let args = Args::parse();
     tracing_subscriber::fmt().compact().init();
     info!("EVO MQTT Bridge starting...");
 
+    let ns = match ShmNamespace::from_instance(args.instance.as_deref()) {
+        Ok(ns) => ns,
+        Err(e) => {
+            error!("{e}");
+            std::process::exit(1);
+        }
+    };
+
     // Attach readers — non-fatal if segments don't exist yet.
-    let stale_threshold: u32 = 100; // cycles before marking stale
+    // Writer silent this long = stale, however often the bridge polls.
+    let max_age = Duration::from_millis(100);
 
-    let reader_cu_mqt = try_attach::<CuToMqtSegment>(SEG_CU_MQT, stale_threshold);
-    let reader_hal_mqt = try_attach::<HalToMqtSegment>(SEG_HAL_MQT, stale_threshold);
-    let reader_re_mqt = try_attach::<ReToMqtSegment>(SEG_RE_MQT, stale_threshold);
+    let reader_cu_mqt = try_attach::<CuToMqtSegment>(&ns, SEG_CU_MQT, max_age);
+    let reader_hal_mqt = try_attach::<HalToMqtSegment>(&ns, SEG_HAL_MQT, max_age);
+    let reader_re_mqt = try_attach::<ReToMqtSegment>(&ns, SEG_RE_MQT, max_age);
 
     info!(
         "MQTT readers: cu_mqt={}, hal_mqt={}, re_mqt={}",
@@ -41,14 +66,16 @@ fn main() {
     info!("MQTT Bridge initialized — placeholder read loop (not yet implemented)");
 }
 
-fn try_attach<T: Default + Copy>(
+fn try_attach<T: Default + ShmLayout>(
+    ns: &ShmNamespace,
     seg_name: &str,
-    stale_threshold: u32,
+    max_age: Duration,
 ) -> Option<TypedP2pReader<T>> {
-    match TypedP2pReader::<T>::attach(seg_name, stale_threshold) {
+    // Read-count threshold unused: `max_age` replaces it.
+    match TypedP2pReader::<T>::attach_in(ns, seg_name, 0) {
         Ok(r) => {
             info!("Attached reader: evo_{seg_name}");
-            Some(r)
+            Some(r.with_max_age(max_age))
         }
         Err(e) => {
             debug!("Could not attach evo_{seg_name} (will retry later): {e}");
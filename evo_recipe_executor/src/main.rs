@@ -23,31 +23,55 @@
 //! | `evo_hal_re`  | HalToReSegment  | HAL    |
 //! | `evo_rpc_re`  | RpcToReSegment  | gRPC   |
 
-use evo_common::shm::p2p::{ModuleAbbrev, TypedP2pReader, TypedP2pWriter};
+use clap::Parser;
+use evo_common::shm::layout::ShmLayout;
+use evo_common::shm::p2p::{ModuleAbbrev, ShmNamespace, TypedP2pReader, TypedP2pWriter};
 use evo_common::shm::segments::{
     CuToReSegment, HalToReSegment, RpcToReSegment,
     ReToCuSegment, ReToHalSegment, ReToMqtSegment, ReToRpcSegment,
     SEG_CU_RE, SEG_HAL_RE, SEG_RPC_RE,
     SEG_RE_CU, SEG_RE_HAL, SEG_RE_MQT, SEG_RE_RPC,
 };
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// EVO Recipe Executor
+#[derive(Parser, Debug)]
+#[command(name = "evo_recipe_executor")]
+#[command(author = "RTS007")]
+#[command(version)]
+#[command(about = "Recipe Executor: runs motion recipes via P2P SHM")]
+struct Args {
+    /// EVO instance id selecting the SHM namespace (default: `evo_*` segments).
+    #[arg(long, value_name = "ID")]
+    instance: Option<String>,
+}
 
 fn main() {
+    let args = Args::parse();
     tracing_subscriber::fmt().compact().init();
     info!("EVO Recipe Executor starting...");
 
+    let ns = match ShmNamespace::from_instance(args.instance.as_deref()) {
+        Ok(ns) => ns,
+        Err(e) => {
+            error!("{e}");
+            std::process::exit(1);
+        }
+    };
+
     // ── Writers: RE → others ────────────────────────────────────────
     let writer_re_cu = try_create_writer::<ReToCuSegment>(
-        SEG_RE_CU, ModuleAbbrev::Re, ModuleAbbrev::Cu,
+        &ns, SEG_RE_CU, ModuleAbbrev::Re, ModuleAbbrev::Cu,
     );
     let writer_re_hal = try_create_writer::<ReToHalSegment>(
-        SEG_RE_HAL, ModuleAbbrev::Re, ModuleAbbrev::Hal,
+        &ns, SEG_RE_HAL, ModuleAbbrev::Re, ModuleAbbrev::Hal,
     );
     let writer_re_mqt = try_create_writer::<ReToMqtSegment>(
-        SEG_RE_MQT, ModuleAbbrev::Re, ModuleAbbrev::Mqt,
+        &ns, SEG_RE_MQT, ModuleAbbrev::Re, ModuleAbbrev::Mqt,
     );
     let writer_re_rpc = try_create_writer::<ReToRpcSegment>(
-        SEG_RE_RPC, ModuleAbbrev::Re, ModuleAbbrev::Rpc,
+        &ns, SEG_RE_RPC, ModuleAbbrev::Re, ModuleAbbrev::Rpc,
     );
 
     info!(
@@ -57,10 +81,10 @@ fn main() {
     );
 
     // ── Readers: others → RE ────────────────────────────────────────
-    let stale_threshold: u32 = 1000;
-    let reader_cu_re = try_attach::<CuToReSegment>(SEG_CU_RE, stale_threshold);
-    let reader_hal_re = try_attach::<HalToReSegment>(SEG_HAL_RE, stale_threshold);
-    let reader_rpc_re = try_attach::<RpcToReSegment>(SEG_RPC_RE, stale_threshold);
+    let max_age = Duration::from_secs(1); // writer silent this long = stale
+    let reader_cu_re = try_attach::<CuToReSegment>(&ns, SEG_CU_RE, max_age);
+    let reader_hal_re = try_attach::<HalToReSegment>(&ns, SEG_HAL_RE, max_age);
+    let reader_rpc_re = try_attach::<RpcToReSegment>(&ns, SEG_RPC_RE, max_age);
 
     info!(
         "RE readers: cu_re={}, hal_re={}, rpc_re={}",
@@ -71,12 +95,13 @@ fn main() {
     info!("Recipe Executor initialized — placeholder (not yet implemented)");
 }
 
-fn try_create_writer<T: Default + Copy>(
+fn try_create_writer<T: Default + ShmLayout>(
+    ns: &ShmNamespace,
     seg_name: &str,
     src: ModuleAbbrev,
     dst: ModuleAbbrev,
 ) -> Option<TypedP2pWriter<T>> {
-    match TypedP2pWriter::<T>::create(seg_name, src, dst) {
+    match TypedP2pWriter::<T>::create_in(ns, seg_name, src, dst) {
         Ok(w) => {
             info!("Created writer: evo_{seg_name}");
             Some(w)
@@ -88,14 +113,16 @@ fn try_create_writer<T: Default + Copy>(
     }
 }
 
-fn try_attach<T: Default + Copy>(
+fn try_attach<T: Default + ShmLayout>(
+    ns: &ShmNamespace,
     seg_name: &str,
-    stale_threshold: u32,
+    max_age: Duration,
 ) -> Option<TypedP2pReader<T>> {
-    match TypedP2pReader::<T>::attach(seg_name, stale_threshold) {
+    // Read-count threshold unused: `max_age` replaces it.
+    match TypedP2pReader::<T>::attach_in(ns, seg_name, 0) {
         Ok(r) => {
             info!("Attached reader: evo_{seg_name}");
-            Some(r)
+            Some(r.with_max_age(max_age))
         }
         Err(e) => {
             debug!("Could not attach evo_{seg_name} (will retry later): {e}");